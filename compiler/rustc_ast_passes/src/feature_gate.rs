@@ -179,6 +179,7 @@ macro_rules! gate_doc { ($($s:literal { $($name:ident => $feature:ident)* })*) =
                         auto_cfg => doc_cfg
                         masked => doc_masked
                         notable_trait => doc_notable_trait
+                        scrape_examples => doc_scrape_examples
                     }
                     "meant for internal use only" {
                         attribute => rustdoc_internals
@@ -2,7 +2,8 @@
 use rustc_feature::template;
 use rustc_hir::Target;
 use rustc_hir::attrs::{
-    AttributeKind, CfgEntry, CfgHideShow, CfgInfo, DocAttribute, DocInline, HideOrShow,
+    AttributeKind, CfgEntry, CfgHideShow, CfgInfo, DocAttribute, DocInline, DocScrapeExamples,
+    HideOrShow,
 };
 use rustc_hir::lints::AttributeLintKind;
 use rustc_span::{Span, Symbol, edition, sym};
@@ -253,6 +254,43 @@ fn parse_inline<S: Stage>(
         self.attribute.inline.push((inline, path.span()));
     }
 
+    fn parse_scrape_examples<S: Stage>(
+        &mut self,
+        cx: &mut AcceptContext<'_, '_, S>,
+        path: &OwnedPathParser,
+        args: &ArgParser,
+    ) {
+        let Some(nv) = args.name_value() else {
+            cx.expected_name_value(args.span().unwrap_or(path.span()), path.word_sym());
+            return;
+        };
+
+        let value = match nv.value_as_str() {
+            Some(sym::pin) => DocScrapeExamples::Pin,
+            Some(sym::exclude) => DocScrapeExamples::Exclude,
+            Some(_) => {
+                cx.expected_specific_argument_strings(nv.value_span, &[sym::pin, sym::exclude]);
+                return;
+            }
+            None => {
+                cx.expected_string_literal(nv.value_span, Some(nv.value_as_lit()));
+                return;
+            }
+        };
+
+        let span = path.span();
+        if self.attribute.scrape_examples.is_some() {
+            cx.duplicate_key(span, sym::scrape_examples);
+            return;
+        }
+
+        if !check_attr_not_crate_level(cx, span, sym::scrape_examples) {
+            return;
+        }
+
+        self.attribute.scrape_examples = Some((value, span));
+    }
+
     fn parse_cfg<S: Stage>(&mut self, cx: &mut AcceptContext<'_, '_, S>, args: &ArgParser) {
         // This function replaces cases like `cfg(all())` with `true`.
         fn simplify_cfg(cfg_entry: &mut CfgEntry) {
@@ -496,6 +534,7 @@ macro_rules! string_arg_and_crate_level {
             Some(sym::search_unbox) => no_args_and_not_crate_level!(search_unbox),
             Some(sym::rust_logo) => no_args_and_crate_level!(rust_logo),
             Some(sym::auto_cfg) => self.parse_auto_cfg(cx, path, args),
+            Some(sym::scrape_examples) => self.parse_scrape_examples(cx, path, args),
             Some(sym::test) => {
                 let Some(list) = args.list() else {
                     cx.emit_lint(
@@ -637,6 +676,7 @@ impl<S: Stage> AttributeParser<S> for DocParser {
                 "search_unbox",
                 "rust_logo",
                 "auto_cfg",
+                "scrape_examples",
                 "test",
                 "spotlight",
                 "include",
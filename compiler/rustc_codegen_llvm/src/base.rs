@@ -21,7 +21,7 @@
 use rustc_hir::attrs::Linkage;
 use rustc_middle::dep_graph;
 use rustc_middle::middle::codegen_fn_attrs::{CodegenFnAttrs, SanitizerFnAttrs};
-use rustc_middle::mir::mono::Visibility;
+use rustc_middle::mir::mono::{MonoItem, Visibility};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::{DebugInfo, Offload};
 use rustc_span::Symbol;
@@ -55,6 +55,23 @@ pub(crate) fn iter_globals(llmod: &llvm::Module) -> ValueIter<'_> {
     unsafe { ValueIter { cur: llvm::LLVMGetFirstGlobal(llmod), step: llvm::LLVMGetNextGlobal } }
 }
 
+/// Returns the number of instructions of the LLVM function `llfn`.
+fn count_instructions(llfn: &Value) -> usize {
+    if llvm::LLVMCountBasicBlocks(llfn) == 0 {
+        return 0;
+    }
+    let mut count = 0;
+    let mut block = Some(unsafe { llvm::LLVMGetFirstBasicBlock(llfn) });
+    while let Some(bb) = block {
+        let instructions = unsafe {
+            ValueIter { cur: llvm::LLVMGetFirstInstruction(bb), step: llvm::LLVMGetNextInstruction }
+        };
+        count += instructions.count();
+        block = unsafe { llvm::LLVMGetNextBasicBlock(bb) };
+    }
+    count
+}
+
 pub(crate) fn compile_codegen_unit(
     tcx: TyCtxt<'_>,
     cgu_name: Symbol,
@@ -119,6 +136,20 @@ fn module_codegen(tcx: TyCtxt<'_>, cgu_name: Symbol) -> ModuleCodegen<ModuleLlvm
                 mono_item.define::<Builder<'_, '_, '_>>(&mut cx, cgu_name.as_str(), item_data);
             }
 
+            // Measure the LLVM IR of the functions before it gets optimized, for the mono stats.
+            if tcx.sess.opts.unstable_opts.dump_mono_stats.enabled() {
+                for &(mono_item, _) in &mono_items {
+                    if let MonoItem::Fn(instance) = mono_item
+                        && mono_item.is_user_defined()
+                        && let Some(&llfn) = cx.instances.borrow().get(&instance)
+                    {
+                        tcx.sess
+                            .code_stats
+                            .record_llvm_ir_size(instance.def_id(), count_instructions(llfn));
+                    }
+                }
+            }
+
             // If this codegen unit contains the main function, also create the
             // wrapper here
             if let Some(entry) =
@@ -1098,6 +1098,9 @@ pub(crate) fn LLVMGetIntrinsicDeclaration<'a>(
 
     // Operations on basic blocks
     pub(crate) fn LLVMGetBasicBlockParent(BB: &BasicBlock) -> &Value;
+    pub(crate) safe fn LLVMCountBasicBlocks(Fn: &Value) -> c_uint;
+    pub(crate) fn LLVMGetNextBasicBlock(BB: &BasicBlock) -> Option<&BasicBlock>;
+    pub(crate) fn LLVMGetFirstInstruction(BB: &BasicBlock) -> Option<&Value>;
     pub(crate) fn LLVMAppendBasicBlockInContext<'a>(
         C: &'a Context,
         Fn: &'a Value,
@@ -1107,6 +1110,7 @@ pub(crate) fn LLVMAppendBasicBlockInContext<'a>(
     // Operations on instructions
     pub(crate) fn LLVMIsAInstruction(Val: &Value) -> Option<&Value>;
     pub(crate) fn LLVMGetFirstBasicBlock(Fn: &Value) -> &BasicBlock;
+    pub(crate) fn LLVMGetNextInstruction(Inst: &Value) -> Option<&Value>;
     pub(crate) fn LLVMGetOperand(Val: &Value, Index: c_uint) -> Option<&Value>;
 
     // Operations on call sites
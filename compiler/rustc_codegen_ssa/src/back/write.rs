@@ -3,6 +3,7 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::mpsc::{Receiver, Sender, channel};
+use std::time::Instant;
 use std::{fs, io, mem, str, thread};
 
 use rustc_abi::Size;
@@ -11,7 +12,8 @@
 use rustc_data_structures::jobserver::{self, Acquired};
 use rustc_data_structures::memmap::Mmap;
 use rustc_data_structures::profiling::{SelfProfilerRef, VerboseTimingGuard};
-use rustc_errors::emitter::Emitter;
+use rustc_errors::emitter::{Emitter, TimingEvent};
+use rustc_errors::timings::{TimingRecord, TimingSection};
 use rustc_errors::translation::Translator;
 use rustc_errors::{
     Diag, DiagArgMap, DiagCtxt, DiagCtxtHandle, DiagMessage, ErrCode, FatalError, FatalErrorMarker,
@@ -361,6 +363,9 @@ pub struct CodegenContext<B: WriteBackendMethods> {
     ///
     /// Depends on [`ExtraBackendMethods::supports_parallel()`] and `-Zno_parallel_backend`.
     pub parallel: bool,
+    /// The point in time that `--json=timings` timestamps are relative to, or `None` if timings
+    /// are not enabled.
+    pub timings_origin: Option<Instant>,
 }
 
 fn generate_thin_lto_work<B: ExtraBackendMethods>(
@@ -1004,13 +1009,15 @@ fn do_fat_lto<B: ExtraBackendMethods>(
         needs_fat_lto.push(FatLtoInput::Serialized { name: wp.cgu_name, buffer: module })
     }
 
-    let module = B::run_and_optimize_fat_lto(
-        cgcx,
-        &shared_emitter,
-        exported_symbols_for_lto,
-        each_linked_rlib_for_lto,
-        needs_fat_lto,
-    );
+    let module = time_lto(cgcx, &shared_emitter, None, || {
+        B::run_and_optimize_fat_lto(
+            cgcx,
+            &shared_emitter,
+            exported_symbols_for_lto,
+            each_linked_rlib_for_lto,
+            needs_fat_lto,
+        )
+    });
     B::codegen(cgcx, &shared_emitter, module, &cgcx.module_config)
 }
 
@@ -1164,10 +1171,32 @@ fn execute_thin_lto_work_item<B: ExtraBackendMethods>(
 ) -> CompiledModule {
     let _timer = cgcx.prof.generic_activity_with_arg("codegen_module_perform_lto", module.name());
 
-    let module = B::optimize_thin(cgcx, &shared_emitter, module);
+    let codegen_unit = module.name().to_owned();
+    let module = time_lto(cgcx, &shared_emitter, Some(codegen_unit), || {
+        B::optimize_thin(cgcx, &shared_emitter, module)
+    });
     B::codegen(cgcx, &shared_emitter, module, &cgcx.module_config)
 }
 
+/// Runs `f` in an LTO timing section, which the main thread reports if `--json=timings` is
+/// enabled.
+fn time_lto<B: WriteBackendMethods, R>(
+    cgcx: &CodegenContext<B>,
+    shared_emitter: &SharedEmitter,
+    codegen_unit: Option<String>,
+    f: impl FnOnce() -> R,
+) -> R {
+    let Some(origin) = cgcx.timings_origin else {
+        return f();
+    };
+    let start = TimingRecord::now(origin, TimingSection::Lto, codegen_unit.clone());
+    shared_emitter.timing(start, TimingEvent::Start);
+    let result = f();
+    let end = TimingRecord::now(origin, TimingSection::Lto, codegen_unit);
+    shared_emitter.timing(end, TimingEvent::End);
+    result
+}
+
 /// Messages sent to the coordinator.
 pub(crate) enum Message<B: WriteBackendMethods> {
     /// A jobserver token has become available. Sent from the jobserver helper
@@ -1331,6 +1360,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
         split_debuginfo: tcx.sess.split_debuginfo(),
         split_dwarf_kind: tcx.sess.opts.unstable_opts.split_dwarf_kind,
         parallel: backend.supports_parallel() && !sess.opts.unstable_opts.no_parallel_backend,
+        timings_origin: sess.timings.origin(),
         pointer_size: tcx.data_layout.pointer_size(),
         invocation_temp: sess.invocation_temp.clone(),
     };
@@ -1946,6 +1976,7 @@ enum SharedEmitterMessage {
     Diagnostic(Diagnostic),
     InlineAsmError(InlineAsmError),
     Fatal(String),
+    Timing(TimingRecord, TimingEvent),
 }
 
 pub struct InlineAsmError {
@@ -1978,6 +2009,10 @@ pub fn inline_asm_error(&self, err: InlineAsmError) {
     fn fatal(&self, msg: &str) {
         drop(self.sender.send(SharedEmitterMessage::Fatal(msg.to_string())));
     }
+
+    fn timing(&self, record: TimingRecord, event: TimingEvent) {
+        drop(self.sender.send(SharedEmitterMessage::Timing(record, event)));
+    }
 }
 
 impl Emitter for SharedEmitter {
@@ -2088,6 +2123,10 @@ fn check(&self, sess: &Session, blocking: bool) {
                 Ok(SharedEmitterMessage::Fatal(msg)) => {
                     sess.dcx().fatal(msg);
                 }
+                Ok(SharedEmitterMessage::Timing(record, event)) => match event {
+                    TimingEvent::Start => sess.dcx().emit_timing_section_start(record),
+                    TimingEvent::End => sess.dcx().emit_timing_section_end(record),
+                },
                 Err(_) => {
                     break;
                 }
@@ -1,3 +1,5 @@
+driver_impl_cant_dump_public_mir = could not dump public MIR to `{$path}`: {$error}
+
 driver_impl_cant_emit_mir = could not emit MIR: {$error}
 
 driver_impl_ice = the compiler unexpectedly panicked. this is a bug.
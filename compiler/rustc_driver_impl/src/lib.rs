@@ -51,9 +51,10 @@
 use rustc_middle::ty::TyCtxt;
 use rustc_parse::lexer::StripTokens;
 use rustc_parse::{new_parser_from_file, new_parser_from_source_str, unwrap_or_emit_fatal};
+use rustc_public::rustc_internal::pretty::write_public_mir_json;
 use rustc_session::config::{
-    CG_OPTIONS, CrateType, ErrorOutputType, Input, OptionDesc, OutFileName, OutputType, Sysroot,
-    UnstableOptions, Z_OPTIONS, nightly_options, parse_target_triple,
+    CG_OPTIONS, CrateType, ErrorOutputType, Input, OptionDesc, OutFileName, OutputType,
+    PublicMirFormat, Sysroot, UnstableOptions, Z_OPTIONS, nightly_options, parse_target_triple,
 };
 use rustc_session::getopts::{self, Matches};
 use rustc_session::lint::{Lint, LintId};
@@ -104,8 +105,9 @@ pub(super) fn install() {}
 }
 
 use crate::session_diagnostics::{
-    CantEmitMIR, RLinkEmptyVersionNumber, RLinkEncodingVersionMismatch, RLinkRustcVersionMismatch,
-    RLinkWrongFileType, RlinkCorruptFile, RlinkNotAFile, RlinkUnableToRead, UnstableFeatureUsage,
+    CantDumpPublicMir, CantEmitMIR, RLinkEmptyVersionNumber, RLinkEncodingVersionMismatch,
+    RLinkRustcVersionMismatch, RLinkWrongFileType, RlinkCorruptFile, RlinkNotAFile,
+    RlinkUnableToRead, UnstableFeatureUsage,
 };
 
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
@@ -382,6 +384,10 @@ pub fn run_compiler(at_args: &[String], callbacks: &mut (dyn Callbacks + Send))
                 return early_exit();
             }
 
+            if let Some(format) = sess.opts.unstable_opts.dump_public_mir {
+                dump_public_mir(tcx, format);
+            }
+
             if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
                 if let Err(error) = rustc_mir_transform::dump_mir::emit_mir(tcx) {
                     tcx.dcx().emit_fatal(CantEmitMIR { error });
@@ -399,6 +405,22 @@ pub fn run_compiler(at_args: &[String], callbacks: &mut (dyn Callbacks + Send))
     })
 }
 
+/// Write the MIR of the local crate in the interchange format of `rustc_public`.
+fn dump_public_mir(tcx: TyCtxt<'_>, format: PublicMirFormat) {
+    let crate_name = tcx.crate_name(LOCAL_CRATE);
+    let file_name = format!("{crate_name}.public_mir.{}", format.extension());
+    let path = tcx.output_filenames(()).out_directory.join(file_name);
+    let result = File::create_buffered(&path).and_then(|mut file| {
+        match format {
+            PublicMirFormat::Json => write_public_mir_json(tcx, &mut file)?,
+        }
+        file.flush()
+    });
+    if let Err(error) = result {
+        tcx.dcx().emit_fatal(CantDumpPublicMir { path, error });
+    }
+}
+
 fn dump_feature_usage_metrics(tcxt: TyCtxt<'_>, metrics_dir: &Path) {
     let hash = tcxt.crate_hash(LOCAL_CRATE);
     let crate_name = tcxt.crate_name(LOCAL_CRATE);
@@ -9,8 +9,9 @@
 
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_error_messages::{DiagArgName, DiagArgValue, IntoDiagArg};
-use rustc_lint_defs::{Applicability, LintExpectationId};
+use rustc_lint_defs::{Applicability, Lint, LintExpectationId};
 use rustc_macros::{Decodable, Encodable};
+use rustc_span::edition::Edition;
 use rustc_span::source_map::Spanned;
 use rustc_span::{DUMMY_SP, Span, Symbol};
 use tracing::debug;
@@ -178,6 +179,36 @@ pub struct IsLint {
     pub(crate) name: String,
     /// Indicates whether this lint should show up in cargo's future breakage report.
     has_future_breakage: bool,
+    /// What the JSON output says about the lint, see [`Diag::lint_metadata`].
+    pub(crate) metadata: Option<Box<LintMetadata>>,
+}
+
+/// Information about the lint that emitted a diagnostic, for tools that read the JSON output and
+/// want to explain the lint without their own copy of the lint definitions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct LintMetadata {
+    /// The lint groups that contain the lint, like `unused`.
+    pub groups: Vec<String>,
+    /// The level of the lint when no attribute or command-line flag sets it.
+    pub default_level: rustc_lint_defs::Level,
+    /// The edition from which the lint defaults to another level, and that level.
+    pub edition_level: Option<(Edition, rustc_lint_defs::Level)>,
+    /// See [`Lint::docs_url`].
+    pub docs_url: Option<String>,
+    /// Whether the lint is about code that will stop compiling or change meaning in the future.
+    pub future_incompat: bool,
+}
+
+impl LintMetadata {
+    pub fn new(lint: &Lint, groups: Vec<String>) -> Self {
+        LintMetadata {
+            groups,
+            default_level: lint.default_level,
+            edition_level: lint.edition_lint_opts,
+            docs_url: lint.docs_url(),
+            future_incompat: lint.future_incompatible.is_some(),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -1217,7 +1248,15 @@ pub fn span(&mut self, sp: impl Into<MultiSpan>) -> &mut Self {
     } }
 
     pub fn is_lint(&mut self, name: String, has_future_breakage: bool) -> &mut Self {
-        self.is_lint = Some(IsLint { name, has_future_breakage });
+        self.is_lint = Some(IsLint { name, has_future_breakage, metadata: None });
+        self
+    }
+
+    /// Adds information about the lint that emitted this diagnostic, which must already be marked
+    /// with [`Diag::is_lint`], to the JSON output.
+    pub fn lint_metadata(&mut self, metadata: LintMetadata) -> &mut Self {
+        let is_lint = self.is_lint.as_mut().expect("`lint_metadata` called before `is_lint`");
+        is_lint.metadata = Some(Box::new(metadata));
         self
     }
 
@@ -32,7 +32,7 @@
     should_show_source_code,
 };
 use crate::registry::Registry;
-use crate::timings::{TimingRecord, TimingSection};
+use crate::timings::TimingRecord;
 use crate::translation::{Translator, to_fluent_args};
 use crate::{CodeSuggestion, MultiSpan, SpanLabel, Subdiag, Suggestions, TerminalUrl};
 
@@ -128,11 +128,12 @@ fn emit_timing_section(&mut self, record: TimingRecord, event: TimingEvent) {
             TimingEvent::Start => "start",
             TimingEvent::End => "end",
         };
-        let name = match record.section {
-            TimingSection::Linking => "link",
-            TimingSection::Codegen => "codegen",
+        let data = SectionTimestamp {
+            name: record.section.name(),
+            event,
+            codegen_unit: record.codegen_unit.as_deref(),
+            timestamp: record.timestamp,
         };
-        let data = SectionTimestamp { name, event, timestamp: record.timestamp };
         let result = self.emit(EmitTyped::SectionTiming(data));
         if let Err(e) = result {
             panic!("failed to print timing section: {e:?}");
@@ -261,6 +262,33 @@ struct DiagnosticCode {
     code: String,
     /// An explanation for the code.
     explanation: Option<&'static str>,
+    /// The lint that emitted the diagnostic, if it comes from one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lint: Option<DiagnosticLint>,
+}
+
+#[derive(Serialize)]
+struct DiagnosticLint {
+    /// The lint name (e.g. "unused_variables" or "clippy::needless_return").
+    name: String,
+    /// The lint groups that contain the lint (e.g. "unused").
+    groups: Vec<String>,
+    /// The level of the lint when no attribute or command-line flag sets it:
+    /// "allow", "warn", "deny" or "forbid".
+    default_level: &'static str,
+    /// The edition from which the lint defaults to another level, if any.
+    edition: Option<DiagnosticLintEdition>,
+    /// The documentation of the lint, for the lints of rustc, rustdoc and Clippy.
+    docs_url: Option<String>,
+    /// Whether the lint is about code that will stop compiling or change
+    /// meaning in the future.
+    future_incompat: bool,
+}
+
+#[derive(Serialize)]
+struct DiagnosticLintEdition {
+    edition: String,
+    level: &'static str,
 }
 
 #[derive(Serialize)]
@@ -277,6 +305,9 @@ struct SectionTimestamp<'a> {
     name: &'a str,
     /// Start/end of the section
     event: &'a str,
+    /// The codegen unit that the section belongs to, if it runs once per codegen unit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    codegen_unit: Option<&'a str>,
     /// Opaque timestamp.
     timestamp: u128,
 }
@@ -348,13 +379,15 @@ fn flush(&mut self) -> io::Result<()> {
 
         let translated_message = je.translator.translate_messages(&diag.messages, &args);
 
+        let lint = diag.is_lint.as_ref().and_then(DiagnosticLint::from_is_lint);
         let code = if let Some(code) = diag.code {
             Some(DiagnosticCode {
                 code: code.to_string(),
                 explanation: registry.try_find_description(code).ok(),
+                lint,
             })
         } else if let Some(IsLint { name, .. }) = &diag.is_lint {
-            Some(DiagnosticCode { code: name.to_string(), explanation: None })
+            Some(DiagnosticCode { code: name.to_string(), explanation: None, lint })
         } else {
             None
         };
@@ -420,6 +453,24 @@ fn from_sub_diagnostic(
     }
 }
 
+impl DiagnosticLint {
+    /// Returns `None` for lints that were emitted without their metadata.
+    fn from_is_lint(is_lint: &IsLint) -> Option<DiagnosticLint> {
+        let metadata = is_lint.metadata.as_deref()?;
+        Some(DiagnosticLint {
+            name: is_lint.name.clone(),
+            groups: metadata.groups.clone(),
+            default_level: metadata.default_level.as_str(),
+            edition: metadata.edition_level.map(|(edition, level)| DiagnosticLintEdition {
+                edition: edition.to_string(),
+                level: level.as_str(),
+            }),
+            docs_url: metadata.docs_url.clone(),
+            future_incompat: metadata.future_incompat,
+        })
+    }
+}
+
 impl DiagnosticSpan {
     fn from_span_label(
         span: SpanLabel,
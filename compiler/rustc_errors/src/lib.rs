@@ -43,7 +43,8 @@
 pub use decorate_diag::{BufferedEarlyLint, DecorateDiagCompat, LintBuffer};
 pub use diagnostic::{
     BugAbort, Diag, DiagArgMap, DiagInner, DiagStyledString, Diagnostic, EmissionGuarantee,
-    FatalAbort, LintDiagnostic, LintDiagnosticBox, StringPart, Subdiag, Subdiagnostic,
+    FatalAbort, LintDiagnostic, LintDiagnosticBox, LintMetadata, StringPart, Subdiag,
+    Subdiagnostic,
 };
 pub use diagnostic_impls::{
     DiagSymbolList, ElidedLifetimeInPathSubdiag, ExpectedLifetimeParameter,
@@ -51,6 +52,7 @@
 };
 pub use emitter::ColorConfig;
 use emitter::{DynEmitter, Emitter};
+pub use lint_baseline::LintBaseline;
 use rustc_data_structures::fx::{FxHashSet, FxIndexMap, FxIndexSet};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::{DynSend, Lock};
@@ -82,9 +84,11 @@
 pub mod emitter;
 pub mod error;
 pub mod json;
+mod lint_baseline;
 mod lock;
 pub mod markdown;
 pub mod registry;
+pub mod sarif;
 #[cfg(test)]
 mod tests;
 pub mod timings;
@@ -367,6 +371,9 @@ struct DiagCtxtInner {
     /// The file where the ICE information is stored. This allows delayed_span_bug backtraces to be
     /// stored along side the main panic backtrace.
     ice_file: Option<PathBuf>,
+
+    /// The baseline of `-Z lint-baseline`, with the lint diagnostics that are not reported.
+    lint_baseline: Option<LintBaseline>,
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -487,6 +494,11 @@ pub fn with_registry(mut self, registry: Registry) -> Self {
         self
     }
 
+    pub fn with_lint_baseline(mut self, lint_baseline: LintBaseline) -> Self {
+        self.inner.get_mut().lint_baseline = Some(lint_baseline);
+        self
+    }
+
     pub fn new(emitter: Box<DynEmitter>) -> Self {
         Self { inner: Lock::new(DiagCtxtInner::new(emitter)) }
     }
@@ -556,6 +568,7 @@ pub fn reset_err_count(&self) {
             future_breakage_diagnostics,
             fulfilled_expectations,
             ice_file: _,
+            lint_baseline: _,
         } = inner.deref_mut();
 
         // For the `Vec`s and `HashMap`s, we overwrite with an empty container to free the
@@ -889,6 +902,16 @@ pub fn emit_future_breakage_report(&self) {
         }
     }
 
+    /// Writes the lint diagnostics of the compilation to the baseline, with
+    /// `-Z write-lint-baseline`. Does nothing otherwise.
+    pub fn write_lint_baseline(&self) -> std::io::Result<()> {
+        let inner = &mut *self.inner.borrow_mut();
+        match (&mut inner.lint_baseline, inner.emitter.source_map()) {
+            (Some(lint_baseline), Some(sm)) => lint_baseline.write(sm),
+            _ => Ok(()),
+        }
+    }
+
     pub fn emit_unused_externs(
         &self,
         lint_level: rustc_lint_defs::Level,
@@ -1203,6 +1226,7 @@ fn new(emitter: Box<DynEmitter>) -> Self {
             future_breakage_diagnostics: Vec::new(),
             fulfilled_expectations: Default::default(),
             ice_file: None,
+            lint_baseline: None,
         }
     }
 
@@ -1318,6 +1342,15 @@ fn emit_diagnostic(
         }
 
         TRACK_DIAGNOSTIC(diagnostic, &mut |mut diagnostic| {
+            // This happens after the diagnostic is tracked, so that it is checked against the
+            // baseline again when it is replayed by incremental compilation.
+            if let Some(lint_baseline) = &mut self.lint_baseline
+                && lint_baseline.suppresses(&diagnostic, self.emitter.source_map())
+            {
+                self.suppressed_expected_diag = true;
+                return None;
+            }
+
             if let Some(code) = diagnostic.code {
                 self.emitted_diagnostic_codes.insert(code);
             }
@@ -0,0 +1,168 @@
+//! Baselines of known lint diagnostics, which let a project turn on a lint without first fixing
+//! or allowing every place where it already fires.
+//!
+//! A baseline is a JSON file that lists lint diagnostics by the name of their lint, the file of
+//! their primary span, and a fingerprint of the text of that span. With `-Z lint-baseline`, the
+//! lint diagnostics that match an entry of the baseline are not reported, and an entry matches as
+//! many diagnostics as the number of times it is listed. Lines are not part of the entries, so that
+//! they keep matching when code above them changes, but changing the code that a diagnostic points
+//! to makes it reported again.
+//!
+//! With `-Z write-lint-baseline`, the lint diagnostics of the compilation are written to the
+//! baseline instead. They replace the entries of the files of the crate, and the entries of other
+//! files are kept, so that the crates of a project can share one baseline.
+
+use std::hash::Hash;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use rustc_data_structures::flock;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_hashes::Hash128;
+use rustc_span::source_map::SourceMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{DiagInner, Level};
+
+/// The version of the format of baselines, to be increased when entries stop matching the same
+/// diagnostics.
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    diagnostics: Vec<Entry>,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+struct Entry {
+    lint: String,
+    file: String,
+    fingerprint: String,
+}
+
+impl Entry {
+    /// Returns `None` for diagnostics without a primary span, or whose span has no source text.
+    fn new(lint: &str, diag: &DiagInner, sm: &SourceMap) -> Option<Self> {
+        // Diagnostics in macro expansions are attributed to the invocation of the macro, which is
+        // in the crate being compiled, unlike the definition of the macro.
+        let span = diag.span.primary_span()?.source_callsite();
+        if span.is_dummy() {
+            return None;
+        }
+        let text = sm.span_to_snippet(span).ok()?;
+        let file = sm.lookup_source_file(span.lo());
+
+        // Whitespace is not part of the fingerprint, so that reformatting the code does not make
+        // the diagnostic reported again.
+        let mut hasher = StableHasher::new();
+        text.split_whitespace().collect::<String>().hash(&mut hasher);
+        let fingerprint: Hash128 = hasher.finish();
+
+        Some(Entry {
+            lint: lint.to_owned(),
+            file: sm.filename_for_diagnostics(&file.name).to_string(),
+            fingerprint: format!("{:032x}", fingerprint.as_u128()),
+        })
+    }
+}
+
+pub struct LintBaseline {
+    path: PathBuf,
+    /// Whether the baseline is written at the end of the compilation, instead of being read.
+    write: bool,
+    /// The entries of the baseline, with the number of diagnostics that they can still match.
+    known: FxHashMap<Entry, usize>,
+    /// The lint diagnostics of the compilation, to be written to the baseline.
+    recorded: Vec<Entry>,
+}
+
+impl LintBaseline {
+    /// Reads the baseline at `path`, unless it is going to be written, in which case it does not
+    /// need to exist yet.
+    pub fn load(path: PathBuf, write: bool) -> io::Result<Self> {
+        let mut known = FxHashMap::default();
+        if !write {
+            for entry in read(&path)? {
+                *known.entry(entry).or_default() += 1;
+            }
+        }
+        Ok(LintBaseline { path, write, known, recorded: Vec::new() })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether `diag` is a lint diagnostic that must not be reported, because it is part of the
+    /// baseline, or because it is written to it.
+    pub(crate) fn suppresses(&mut self, diag: &DiagInner, sm: Option<&SourceMap>) -> bool {
+        let (Some(lint), Some(sm)) = (&diag.is_lint, sm) else { return false };
+        // Forced warnings and lints that are allowed or expected are left alone.
+        if !matches!(diag.level, Level::Error | Level::Warning) {
+            return false;
+        }
+        let Some(entry) = Entry::new(&lint.name, diag, sm) else { return false };
+        if self.write {
+            self.recorded.push(entry);
+            return true;
+        }
+        match self.known.get_mut(&entry) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Writes the recorded diagnostics to the baseline, in place of its entries for the files that
+    /// the compilation read from source.
+    pub(crate) fn write(&mut self, sm: &SourceMap) -> io::Result<()> {
+        if !self.write {
+            return Ok(());
+        }
+        let files: FxHashSet<String> = sm
+            .files()
+            .iter()
+            .filter(|file| !file.is_imported())
+            .map(|file| sm.filename_for_diagnostics(&file.name).to_string())
+            .collect();
+
+        // Crates that share the baseline can be compiled at the same time, so the baseline is
+        // locked while it is updated, and replaced at once for the compilations that read it.
+        let mut lock_path = self.path.clone().into_os_string();
+        lock_path.push(".lock");
+        let _lock = flock::Lock::new(Path::new(&lock_path), true, true, true)?;
+
+        let mut diagnostics = match read(&self.path) {
+            Ok(diagnostics) => diagnostics,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        diagnostics.retain(|entry| !files.contains(&entry.file));
+        diagnostics.append(&mut self.recorded);
+        diagnostics.sort();
+
+        let mut contents =
+            serde_json::to_string_pretty(&BaselineFile { version: VERSION, diagnostics })?;
+        contents.push('\n');
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        std::fs::write(&tmp_path, contents)?;
+        std::fs::rename(&tmp_path, &self.path)
+    }
+}
+
+fn read(path: &Path) -> io::Result<Vec<Entry>> {
+    let contents = std::fs::read_to_string(path)?;
+    let baseline: BaselineFile = serde_json::from_str(&contents)?;
+    if baseline.version != VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("version {} of the format is not supported", baseline.version),
+        ));
+    }
+    Ok(baseline.diagnostics)
+}
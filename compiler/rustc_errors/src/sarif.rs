@@ -0,0 +1,471 @@
+//! A SARIF emitter for errors.
+//!
+//! [SARIF] (Static Analysis Results Interchange Format) is the format consumed by most
+//! code-scanning services. Unlike the JSON emitter, which prints one object per diagnostic,
+//! a SARIF log is a single document, so this emitter collects every diagnostic it is given
+//! and writes out the whole log once it is dropped. Like the other emitters, it prints nothing
+//! if no diagnostics were emitted.
+//!
+//! Each diagnostic becomes a `result`. Error codes and lint names become `rules`,
+//! subdiagnostics with spans become `relatedLocations` and suggestions become `fixes`,
+//! with their [`Applicability`] kept in the fix's property bag.
+//!
+//! [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use derive_setters::Setters;
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::IntoDynSyncSend;
+use rustc_error_messages::FluentArgs;
+use rustc_lint_defs::Applicability;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{FileName, Span};
+use serde::Serialize;
+
+use crate::diagnostic::IsLint;
+use crate::emitter::{Emitter, should_show_source_code};
+use crate::registry::Registry;
+use crate::translation::{Translator, to_fluent_args};
+use crate::{CodeSuggestion, DiagInner, Level, MultiSpan, Suggestions};
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+#[derive(Setters)]
+pub struct SarifEmitter {
+    #[setters(skip)]
+    dst: IntoDynSyncSend<Box<dyn Write + Send>>,
+    #[setters(skip)]
+    sm: Option<Arc<SourceMap>>,
+    #[setters(skip)]
+    translator: Translator,
+    /// The version of the tool, reported as `tool.driver.version`.
+    #[setters(into)]
+    tool_version: Option<String>,
+    ignored_directories_in_source_blocks: Vec<String>,
+    #[setters(skip)]
+    rules: FxIndexMap<String, ReportingDescriptor>,
+    #[setters(skip)]
+    results: Vec<SarifResult>,
+}
+
+impl SarifEmitter {
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        sm: Option<Arc<SourceMap>>,
+        translator: Translator,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst: IntoDynSyncSend(dst),
+            sm,
+            translator,
+            tool_version: None,
+            ignored_directories_in_source_blocks: Vec::new(),
+            rules: FxIndexMap::default(),
+            results: Vec::new(),
+        }
+    }
+
+    fn write_log(&mut self) -> io::Result<()> {
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: [Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: "rustc",
+                        information_uri: "https://www.rust-lang.org/",
+                        version: self.tool_version.as_deref(),
+                        rules: self.rules.values().collect(),
+                    },
+                },
+                column_kind: "unicodeCodePoints",
+                results: &self.results,
+            }],
+        };
+        serde_json::to_writer_pretty(&mut *self.dst, &log)?;
+        self.dst.write_all(b"\n")?;
+        self.dst.flush()
+    }
+
+    /// Returns the index of the rule for `id` in `tool.driver.rules`, registering it first if
+    /// this is the first result that refers to it.
+    fn rule_index(&mut self, id: String, explanation: Option<&'static str>) -> usize {
+        let entry = self.rules.entry(id);
+        let index = entry.index();
+        entry.or_insert_with_key(|id| {
+            // Only error codes have an explanation and a page in the error index.
+            let help_uri =
+                explanation.map(|_| format!("https://doc.rust-lang.org/error_codes/{id}.html"));
+            ReportingDescriptor {
+                id: id.clone(),
+                full_description: explanation.map(|text| Message { text: text.to_owned() }),
+                help_uri,
+            }
+        });
+        index
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        if self.results.is_empty() {
+            return;
+        }
+        if let Err(e) = self.write_log() {
+            panic!("failed to print SARIF log: {e:?}");
+        }
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: DiagInner, registry: &Registry) {
+        // Failure notes ("aborting due to 2 previous errors", "for more information about
+        // this error, try ...") summarize other results rather than being results themselves.
+        if diag.level.is_failure_note() {
+            return;
+        }
+        let result = SarifResult::from_errors_diagnostic(diag, self, registry);
+        self.results.push(result);
+    }
+
+    fn source_map(&self) -> Option<&SourceMap> {
+        self.sm.as_deref()
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+
+    fn translator(&self) -> &Translator {
+        &self.translator
+    }
+}
+
+// The following data types are provided just for serialisation. Field names follow the
+// SARIF 2.1.0 specification.
+
+#[derive(Serialize)]
+struct SarifLog<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    /// rustc counts columns in characters, not in the UTF-16 code units SARIF defaults to.
+    column_kind: &'static str,
+    results: &'a [SarifResult],
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    driver: ToolComponent<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent<'a> {
+    name: &'static str,
+    information_uri: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<&'a str>,
+    rules: Vec<&'a ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    /// The error code (e.g. "E1234") or the lint name.
+    id: String,
+    /// The long-form explanation of an error code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning" or "note".
+    level: &'static str,
+    message: Message,
+    /// The primary spans of the diagnostic.
+    locations: Vec<Location>,
+    /// Labelled secondary spans, and the spans of subdiagnostics.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize, PartialEq)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    /// 1-based, character offset of the first character after the region.
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+    /// Source text covered by the region, if the source is available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+#[derive(Serialize)]
+struct FixProperties {
+    applicability: Applicability,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: Message,
+}
+
+impl SarifResult {
+    /// Converts from `rustc_errors::DiagInner` to `SarifResult`.
+    fn from_errors_diagnostic(
+        diag: DiagInner,
+        se: &mut SarifEmitter,
+        registry: &Registry,
+    ) -> SarifResult {
+        let args = to_fluent_args(diag.args.iter());
+
+        let (rule_id, rule_index) = if let Some(code) = diag.code {
+            let id = code.to_string();
+            let index = se.rule_index(id.clone(), registry.try_find_description(code).ok());
+            (Some(id), Some(index))
+        } else if let Some(IsLint { name, .. }) = &diag.is_lint {
+            (Some(name.clone()), Some(se.rule_index(name.clone(), None)))
+        } else {
+            (None, None)
+        };
+
+        let mut text = se.translator.translate_messages(&diag.messages, &args).into_owned();
+        let (locations, mut related_locations) = Location::from_multispan(&diag.span, &args, se);
+        for child in &diag.children {
+            let child_text = se.translator.translate_messages(&child.messages, &args);
+            let child_text = format!("{}: {child_text}", child.level.to_str());
+            if child.span.primary_spans().is_empty() {
+                // Spanless notes and helps are part of the message, as in the human output.
+                text.push('\n');
+                text.push_str(&child_text);
+            } else {
+                for span in child.span.primary_spans() {
+                    if let Some(physical_location) = PhysicalLocation::from_span(*span, se) {
+                        related_locations.push(Location {
+                            id: None,
+                            physical_location,
+                            message: Some(Message { text: child_text.clone() }),
+                        });
+                    }
+                }
+            }
+        }
+        for (id, location) in related_locations.iter_mut().enumerate() {
+            location.id = Some(id);
+        }
+
+        let suggestions = match &diag.suggestions {
+            Suggestions::Enabled(suggestions) => &suggestions[..],
+            Suggestions::Sealed(suggestions) => &suggestions[..],
+            Suggestions::Disabled => &[],
+        };
+        let fixes =
+            suggestions.iter().flat_map(|sugg| Fix::from_suggestion(sugg, &args, se)).collect();
+
+        SarifResult {
+            rule_id,
+            rule_index,
+            level: sarif_level(diag.level),
+            message: Message { text },
+            locations,
+            related_locations,
+            fixes,
+        }
+    }
+}
+
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::DelayedBug | Level::Fatal | Level::Error => "error",
+        Level::ForceWarning | Level::Warning => "warning",
+        Level::Note | Level::OnceNote | Level::Help | Level::OnceHelp | Level::FailureNote => {
+            "note"
+        }
+        Level::Allow | Level::Expect => unreachable!(),
+    }
+}
+
+impl Location {
+    /// Splits the labels of `msp` into primary `locations` and `relatedLocations`.
+    fn from_multispan(
+        msp: &MultiSpan,
+        args: &FluentArgs<'_>,
+        se: &SarifEmitter,
+    ) -> (Vec<Location>, Vec<Location>) {
+        let mut primary = vec![];
+        let mut related = vec![];
+        for span_label in msp.span_labels() {
+            let Some(physical_location) = PhysicalLocation::from_span(span_label.span, se) else {
+                continue;
+            };
+            let message = span_label.label.as_ref().map(|label| Message {
+                text: se.translator.translate_message(label, args).unwrap().to_string(),
+            });
+            let location = Location { id: None, physical_location, message };
+            if span_label.is_primary { primary.push(location) } else { related.push(location) }
+        }
+        (primary, related)
+    }
+}
+
+impl PhysicalLocation {
+    fn from_span(span: Span, se: &SarifEmitter) -> Option<PhysicalLocation> {
+        let (artifact_location, region) = Region::from_span(span, se)?;
+        Some(PhysicalLocation { artifact_location, region })
+    }
+}
+
+impl ArtifactLocation {
+    fn from_file_name(sm: &SourceMap, name: &FileName) -> ArtifactLocation {
+        // SARIF locations are URI references, which always use forward slashes.
+        let uri = sm.filename_for_diagnostics(name).to_string().replace('\\', "/");
+        ArtifactLocation { uri }
+    }
+}
+
+impl Region {
+    fn from_span(span: Span, se: &SarifEmitter) -> Option<(ArtifactLocation, Region)> {
+        let sm = se.sm.as_ref()?;
+        if span.is_dummy() {
+            return None;
+        }
+        let start = sm.lookup_char_pos(span.lo());
+        let end = sm.lookup_char_pos(span.hi());
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        let snippet =
+            if should_show_source_code(&se.ignored_directories_in_source_blocks, sm, &start.file) {
+                sm.span_to_snippet(span).ok().map(|text| Message { text })
+            } else {
+                None
+            };
+        let region = Region {
+            start_line: start.line,
+            start_column: start.col.0 + 1,
+            end_line: end.line,
+            end_column: end.col.0 + 1,
+            byte_offset: byte_start,
+            byte_length: byte_end - byte_start,
+            snippet,
+        };
+        Some((ArtifactLocation::from_file_name(sm, &start.file.name), region))
+    }
+}
+
+impl Fix {
+    /// Creates one fix per alternative substitution of the suggestion.
+    fn from_suggestion(
+        suggestion: &CodeSuggestion,
+        args: &FluentArgs<'_>,
+        se: &SarifEmitter,
+    ) -> Vec<Fix> {
+        let description = se.translator.translate_message(&suggestion.msg, args).unwrap();
+        suggestion
+            .substitutions
+            .iter()
+            .map(|substitution| {
+                let mut artifact_changes: Vec<ArtifactChange> = vec![];
+                for part in &substitution.parts {
+                    let Some((artifact_location, deleted_region)) =
+                        Region::from_span(part.span, se)
+                    else {
+                        continue;
+                    };
+                    let replacement = Replacement {
+                        deleted_region,
+                        inserted_content: Message { text: part.snippet.clone() },
+                    };
+                    match artifact_changes
+                        .iter_mut()
+                        .find(|change| change.artifact_location == artifact_location)
+                    {
+                        Some(change) => change.replacements.push(replacement),
+                        None => artifact_changes.push(ArtifactChange {
+                            artifact_location,
+                            replacements: vec![replacement],
+                        }),
+                    }
+                }
+                Fix {
+                    description: Message { text: description.to_string() },
+                    artifact_changes,
+                    properties: FixProperties { applicability: suggestion.applicability },
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+use std::str;
+use std::sync::Mutex;
+
+use rustc_span::BytePos;
+use rustc_span::source_map::FilePathMapping;
+use serde_json::{Value, json};
+
+use super::*;
+use crate::DiagCtxt;
+use crate::codes::E0308;
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+/// Runs `f` against a `DiagCtxt` emitting SARIF for a single `test.rs` containing `code`, and
+/// returns the log written once the `DiagCtxt` is dropped.
+fn test_sarif(code: &str, f: impl FnOnce(&DiagCtxt)) -> Value {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Arc::new(SourceMap::new(FilePathMapping::empty()));
+        let filename =
+            sm.path_mapping().to_real_filename(sm.working_dir(), PathBuf::from("test.rs"));
+        sm.new_source_file(FileName::Real(filename), code.to_owned());
+        let translator =
+            Translator::with_fallback_bundle(vec![crate::DEFAULT_LOCALE_RESOURCE], false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let se = SarifEmitter::new(Box::new(Shared { data: output.clone() }), Some(sm), translator);
+        let dcx = DiagCtxt::new(Box::new(se));
+        f(&dcx);
+        drop(dcx);
+
+        let bytes = output.lock().unwrap();
+        serde_json::from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+    })
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+#[test]
+fn no_diagnostics() {
+    rustc_span::create_default_session_globals_then(|| {
+        let translator =
+            Translator::with_fallback_bundle(vec![crate::DEFAULT_LOCALE_RESOURCE], false);
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let se = SarifEmitter::new(Box::new(Shared { data: output.clone() }), None, translator);
+        drop(DiagCtxt::new(Box::new(se)));
+        assert!(output.lock().unwrap().is_empty());
+    })
+}
+
+#[test]
+fn result_with_rule_and_fix() {
+    let log = test_sarif("let x: u32 = \"a\";\n", |dcx| {
+        dcx.handle()
+            .struct_span_err(span(13, 16), "mismatched types")
+            .with_code(E0308)
+            .with_span_suggestion(
+                span(13, 16),
+                "try using a number",
+                "0",
+                Applicability::MaybeIncorrect,
+            )
+            .emit();
+    });
+
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "rustc");
+    assert_eq!(run["tool"]["driver"]["rules"], json!([{ "id": "E0308" }]));
+
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "E0308");
+    assert_eq!(result["ruleIndex"], 0);
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["message"]["text"], "mismatched types");
+    assert_eq!(
+        result["locations"][0]["physicalLocation"],
+        json!({
+            "artifactLocation": { "uri": "test.rs" },
+            "region": {
+                "startLine": 1,
+                "startColumn": 14,
+                "endLine": 1,
+                "endColumn": 17,
+                "byteOffset": 13,
+                "byteLength": 3,
+                "snippet": { "text": "\"a\"" },
+            },
+        })
+    );
+    assert_eq!(
+        result["fixes"],
+        json!([{
+            "description": { "text": "try using a number" },
+            "artifactChanges": [{
+                "artifactLocation": { "uri": "test.rs" },
+                "replacements": [{
+                    "deletedRegion": {
+                        "startLine": 1,
+                        "startColumn": 14,
+                        "endLine": 1,
+                        "endColumn": 17,
+                        "byteOffset": 13,
+                        "byteLength": 3,
+                        "snippet": { "text": "\"a\"" },
+                    },
+                    "insertedContent": { "text": "0" },
+                }],
+            }],
+            "properties": { "applicability": "MaybeIncorrect" },
+        }])
+    );
+}
+
+#[test]
+fn related_locations_and_notes() {
+    let log = test_sarif("fn f() {}\nfn f() {}\n", |dcx| {
+        let mut msp = MultiSpan::from_span(span(13, 14));
+        msp.push_span_label(span(3, 4), "previous definition here");
+        dcx.handle()
+            .struct_span_err(msp, "the name `f` is defined multiple times")
+            .with_span_note(span(0, 9), "first defined in this item")
+            .with_note("`f` must be defined only once")
+            .emit();
+    });
+
+    let result = &log["runs"][0]["results"][0];
+    assert!(result.get("ruleId").is_none());
+    assert_eq!(
+        result["message"]["text"],
+        "the name `f` is defined multiple times\nnote: `f` must be defined only once"
+    );
+    assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startLine"], 2);
+
+    let related = result["relatedLocations"].as_array().unwrap();
+    assert_eq!(related.len(), 2);
+    assert_eq!(related[0]["id"], 0);
+    assert_eq!(related[0]["message"]["text"], "previous definition here");
+    assert_eq!(related[0]["physicalLocation"]["region"]["snippet"]["text"], "f");
+    assert_eq!(related[1]["id"], 1);
+    assert_eq!(related[1]["message"]["text"], "note: first defined in this item");
+    assert_eq!(related[1]["physicalLocation"]["region"]["snippet"]["text"], "fn f() {}");
+}
@@ -1,6 +1,6 @@
 use std::time::Instant;
 
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lock;
 
 use crate::DiagCtxtHandle;
@@ -8,23 +8,67 @@
 /// A high-level section of the compilation process.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum TimingSection {
+    /// Time spent parsing the crate.
+    Parse,
+    /// Time spent expanding macros, including the name resolution that is needed to do so.
+    MacroExpansion,
+    /// Time spent resolving names once all macros have been expanded.
+    NameResolution,
+    /// Time spent type checking the crate.
+    TypeCheck,
+    /// Time spent borrow checking, along with the other checks that are done on MIR.
+    Borrowck,
+    /// Time spent optimizing MIR. MIR is optimized on demand, mostly while collecting the items to
+    /// codegen, so this section is started and ended many times.
+    MirOptimization,
+    /// Time spent collecting the items to codegen and partitioning them into codegen units.
+    MonoItemCollection,
     /// Time spent doing codegen.
     Codegen,
+    /// Time spent doing link-time optimization, either of a single codegen unit or of all of them
+    /// at once.
+    Lto,
     /// Time spent linking.
     Linking,
 }
 
+impl TimingSection {
+    /// The name of the section in the output of `--json=timings`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TimingSection::Parse => "parse",
+            TimingSection::MacroExpansion => "expand",
+            TimingSection::NameResolution => "resolve",
+            TimingSection::TypeCheck => "typeck",
+            TimingSection::Borrowck => "borrowck",
+            TimingSection::MirOptimization => "mir_opt",
+            TimingSection::MonoItemCollection => "mono_items",
+            TimingSection::Codegen => "codegen",
+            TimingSection::Lto => "lto",
+            TimingSection::Linking => "link",
+        }
+    }
+}
+
 /// Section with attached timestamp
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct TimingRecord {
     pub section: TimingSection,
+    /// The codegen unit that the section belongs to, for sections that run once per codegen unit.
+    pub codegen_unit: Option<String>,
     /// Microseconds elapsed since some predetermined point in time (~start of the rustc process).
     pub timestamp: u128,
 }
 
 impl TimingRecord {
     fn from_origin(origin: Instant, section: TimingSection) -> Self {
-        Self { section, timestamp: Instant::now().duration_since(origin).as_micros() }
+        Self::now(origin, section, None)
+    }
+
+    /// Creates a record of `section` that is timestamped now, relative to `origin`, which is
+    /// obtained from [TimingSectionHandler::origin].
+    pub fn now(origin: Instant, section: TimingSection, codegen_unit: Option<String>) -> Self {
+        Self { section, codegen_unit, timestamp: Instant::now().duration_since(origin).as_micros() }
     }
 
     pub fn section(&self) -> TimingSection {
@@ -43,12 +87,18 @@ pub struct TimingSectionHandler {
     origin: Option<Instant>,
     /// Sanity check to ensure that we open and close sections correctly.
     opened_sections: Lock<FxHashSet<TimingSection>>,
+    /// How many guards of each reentrant section are alive.
+    reentrant_sections: Lock<FxHashMap<TimingSection, usize>>,
 }
 
 impl TimingSectionHandler {
     pub fn new(enabled: bool) -> Self {
         let origin = if enabled { Some(Instant::now()) } else { None };
-        Self { origin, opened_sections: Lock::new(FxHashSet::default()) }
+        Self {
+            origin,
+            opened_sections: Lock::new(FxHashSet::default()),
+            reentrant_sections: Lock::new(FxHashMap::default()),
+        }
     }
 
     /// Returns a RAII guard that will immediately emit a start the provided section, and then emit
@@ -66,6 +116,25 @@ pub fn section_guard<'a>(
         TimingSectionGuard::create(diag_ctxt, section, self.origin)
     }
 
+    /// Returns a RAII guard for a section that can be entered again before it ends, by a query
+    /// that it runs or on another thread. Only the first guard emits the start of the section,
+    /// and only the last one to be dropped emits its end.
+    pub fn reentrant_section_guard<'a>(
+        &'a self,
+        diag_ctxt: DiagCtxtHandle<'a>,
+        section: TimingSection,
+    ) -> ReentrantTimingSectionGuard<'a> {
+        if let Some(origin) = self.origin {
+            let mut active = self.reentrant_sections.borrow_mut();
+            let count = active.entry(section).or_default();
+            if *count == 0 {
+                diag_ctxt.emit_timing_section_start(TimingRecord::from_origin(origin, section));
+            }
+            *count += 1;
+        }
+        ReentrantTimingSectionGuard { handler: self, dcx: diag_ctxt, section }
+    }
+
     /// Start the provided section.
     pub fn start_section(&self, diag_ctxt: DiagCtxtHandle<'_>, section: TimingSection) {
         if let Some(origin) = self.origin {
@@ -94,6 +163,14 @@ pub fn end_section(&self, diag_ctxt: DiagCtxtHandle<'_>, section: TimingSection)
     fn is_enabled(&self) -> bool {
         self.origin.is_some()
     }
+
+    /// Returns the point in time that timestamps are relative to, if timings are enabled.
+    ///
+    /// Threads without access to the session can use this to create their own [TimingRecord]s,
+    /// which the main thread then emits.
+    pub fn origin(&self) -> Option<Instant> {
+        self.origin
+    }
 }
 
 /// RAII wrapper for starting and ending section timings.
@@ -119,3 +196,24 @@ fn drop(&mut self) {
         }
     }
 }
+
+/// RAII wrapper for sections that can be entered again before they end, see
+/// [TimingSectionHandler::reentrant_section_guard].
+pub struct ReentrantTimingSectionGuard<'a> {
+    handler: &'a TimingSectionHandler,
+    dcx: DiagCtxtHandle<'a>,
+    section: TimingSection,
+}
+
+impl<'a> Drop for ReentrantTimingSectionGuard<'a> {
+    fn drop(&mut self) {
+        if let Some(origin) = self.handler.origin {
+            let mut active = self.handler.reentrant_sections.borrow_mut();
+            let count = active.get_mut(&self.section).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.dcx.emit_timing_section_end(TimingRecord::from_origin(origin, self.section));
+            }
+        }
+    }
+}
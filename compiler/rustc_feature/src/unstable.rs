@@ -464,6 +464,8 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, doc_cfg, "1.21.0", Some(43781)),
     /// Allows `#[doc(masked)]`.
     (unstable, doc_masked, "1.21.0", Some(44027)),
+    /// Allows `#[doc(scrape_examples = "...")]`.
+    (unstable, doc_scrape_examples, "CURRENT_RUSTC_VERSION", Some(88791)),
     /// Allows features to allow target_feature to better interact with traits.
     (incomplete, effective_target_features, "1.91.0", Some(143352)),
     /// Allows the .use postfix syntax `x.use` and use closures `use |x| { ... }`
@@ -446,6 +446,17 @@ pub enum DocInline {
     NoInline,
 }
 
+/// What rustdoc does with the calls of an item that has `#[doc(scrape_examples = "...")]` when it
+/// scrapes examples.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(HashStable_Generic, Encodable, Decodable, PrintAttribute)]
+pub enum DocScrapeExamples {
+    /// The calls are shown before the other examples.
+    Pin,
+    /// The calls are never shown as examples.
+    Exclude,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[derive(HashStable_Generic, Encodable, Decodable, PrintAttribute)]
 pub enum HideOrShow {
@@ -489,6 +500,7 @@ pub struct DocAttribute {
     pub auto_cfg: ThinVec<(CfgHideShow, Span)>,
     /// This is for `#[doc(auto_cfg = false|true)]`/`#[doc(auto_cfg)]`.
     pub auto_cfg_change: ThinVec<(bool, Span)>,
+    pub scrape_examples: Option<(DocScrapeExamples, Span)>,
 
     // builtin
     pub fake_variadic: Option<Span>,
@@ -521,6 +533,7 @@ fn encode(&self, encoder: &mut E) {
             cfg,
             auto_cfg,
             auto_cfg_change,
+            scrape_examples,
             fake_variadic,
             keyword,
             attribute,
@@ -550,6 +563,7 @@ fn encode(&self, encoder: &mut E) {
         rustc_serialize::Encodable::<E>::encode(cfg, encoder);
         rustc_serialize::Encodable::<E>::encode(auto_cfg, encoder);
         rustc_serialize::Encodable::<E>::encode(auto_cfg_change, encoder);
+        rustc_serialize::Encodable::<E>::encode(scrape_examples, encoder);
         rustc_serialize::Encodable::<E>::encode(fake_variadic, encoder);
         rustc_serialize::Encodable::<E>::encode(keyword, encoder);
         rustc_serialize::Encodable::<E>::encode(attribute, encoder);
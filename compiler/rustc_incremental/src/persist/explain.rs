@@ -0,0 +1,167 @@
+//! Prints which changes caused the queries and codegen units of the previous session to be
+//! re-executed, with `-Z incremental-explain`.
+//!
+//! The changes are the changes of the inputs of the compilation that the dep-graph finds at the
+//! root of the re-executions, which are mostly the HIR and the spans of the items of the crate,
+//! grouped by the source file of the item, and the hashes of extern crates. Changes of the
+//! command-line options discard the whole previous session, which is reported when it is loaded.
+
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap, FxIndexSet};
+use rustc_middle::dep_graph::{DepNode, DepNodeExt, Deps, DepsType, dep_kinds};
+use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+
+pub(crate) fn explain_reexecution(tcx: TyCtxt<'_>) {
+    // The HIR of the crate changes with any of its items, whose changes are more precise.
+    let is_input = |node: DepNode| {
+        node.kind == dep_kinds::opt_hir_owner_nodes || node.kind == dep_kinds::hir_attr_map
+    };
+    let Some(explanation) = tcx.dep_graph.explain_reexecution(is_input) else { return };
+
+    // Codegen units are identified by their names, which are only known from the partitioning.
+    // It has been computed already when codegen units were re-executed.
+    let reexecuted_nodes = || {
+        explanation
+            .causes
+            .iter()
+            .flat_map(|cause| &cause.reexecuted)
+            .chain(&explanation.unexplained)
+    };
+    let cgu_names: FxHashMap<DepNode, String> =
+        if reexecuted_nodes().any(|node| node.kind == dep_kinds::CompileCodegenUnit) {
+            tcx.collect_and_partition_mono_items(())
+                .codegen_units
+                .iter()
+                .map(|cgu| (cgu.codegen_dep_node(tcx), cgu.name().to_string()))
+                .collect()
+        } else {
+            Default::default()
+        };
+
+    let mut changes: Vec<_> = explanation
+        .causes
+        .iter()
+        .map(|cause| {
+            let (file, change) = describe_change(tcx, cause.changed);
+            (file, change, Reexecuted::new(cause.reexecuted.iter().copied(), &cgu_names))
+        })
+        .collect();
+    changes.sort_by(|(file_a, change_a, _), (file_b, change_b, _)| {
+        (file_a, change_a).cmp(&(file_b, change_b))
+    });
+
+    // A node can be re-executed because of several changes.
+    let total = Reexecuted::new(reexecuted_nodes().copied().collect::<FxIndexSet<_>>(), &cgu_names);
+    eprintln!(
+        "[incremental] {} caused {} to be re-executed",
+        plural(changes.len(), "change", "changes"),
+        total.describe(),
+    );
+    for (file, change, reexecuted) in changes {
+        match file {
+            Some(file) => eprintln!(
+                "[incremental] in `{file}`, {change} changed, re-executing {}",
+                reexecuted.describe()
+            ),
+            None => {
+                eprintln!("[incremental] {change} changed, re-executing {}", reexecuted.describe())
+            }
+        }
+        reexecuted.print_details();
+    }
+
+    if !explanation.unexplained.is_empty() {
+        let unexplained = Reexecuted::new(explanation.unexplained.iter().copied(), &cgu_names);
+        eprintln!(
+            "[incremental] {} re-executed without a change of their dependencies",
+            unexplained.describe()
+        );
+        unexplained.print_details();
+    }
+}
+
+/// Describes a change, with the source file that it comes from, if any.
+fn describe_change(tcx: TyCtxt<'_>, node: DepNode) -> (Option<String>, String) {
+    let kind = DepsType::name(node.kind);
+    let Some(def_id) = node.extract_def_id(tcx) else {
+        return (None, format!("the result of `{kind}`"));
+    };
+
+    if node.kind == dep_kinds::crate_hash {
+        return (None, format!("the hash of crate `{}`", tcx.crate_name(def_id.krate)));
+    }
+
+    let file = def_id.as_local().map(|def_id| {
+        let source_map = tcx.sess.source_map();
+        let file = source_map.span_to_filename(tcx.source_span(def_id));
+        source_map.filename_for_diagnostics(&file).to_string()
+    });
+    let path = with_no_trimmed_paths!(tcx.def_path_str(def_id));
+    let change =
+        if node.kind == dep_kinds::opt_hir_owner_nodes || node.kind == dep_kinds::hir_attr_map {
+            format!("the HIR of `{path}`")
+        } else if node.kind == dep_kinds::source_span {
+            format!("the span of `{path}`")
+        } else {
+            format!("the result of `{kind}({path})`")
+        };
+    (file, change)
+}
+
+/// The queries and the codegen units that were re-executed.
+struct Reexecuted {
+    queries: usize,
+    /// The number of queries of each kind, with the most re-executed kinds first.
+    query_kinds: Vec<(&'static str, usize)>,
+    cgus: Vec<String>,
+}
+
+impl Reexecuted {
+    fn new(
+        nodes: impl IntoIterator<Item = DepNode>,
+        cgu_names: &FxHashMap<DepNode, String>,
+    ) -> Self {
+        let mut queries = 0;
+        let mut query_kinds: FxIndexMap<_, usize> = FxIndexMap::default();
+        let mut cgus = Vec::new();
+        for node in nodes {
+            if node.kind == dep_kinds::CompileCodegenUnit {
+                cgus.push(cgu_names.get(&node).cloned().unwrap_or_else(|| format!("{node:?}")));
+            } else {
+                queries += 1;
+                *query_kinds.entry(DepsType::name(node.kind)).or_default() += 1;
+            }
+        }
+
+        let mut query_kinds: Vec<_> = query_kinds.into_iter().collect();
+        query_kinds.sort_by(|(kind_a, count_a), (kind_b, count_b)| {
+            count_b.cmp(count_a).then(kind_a.cmp(kind_b))
+        });
+        cgus.sort();
+        Reexecuted { queries, query_kinds, cgus }
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} and {}",
+            plural(self.queries, "query", "queries"),
+            plural(self.cgus.len(), "codegen unit", "codegen units")
+        )
+    }
+
+    fn print_details(&self) {
+        if !self.query_kinds.is_empty() {
+            let kinds: Vec<_> =
+                self.query_kinds.iter().map(|(kind, count)| format!("{kind} ({count})")).collect();
+            eprintln!("[incremental]     queries: {}", kinds.join(", "));
+        }
+        if !self.cgus.is_empty() {
+            let cgus: Vec<_> = self.cgus.iter().map(|cgu| format!("`{cgu}`")).collect();
+            eprintln!("[incremental]     codegen units: {}", cgus.join(", "));
+        }
+    }
+}
+
+fn plural(count: usize, singular: &str, plural: &str) -> String {
+    format!("{count} {}", if count == 1 { singular } else { plural })
+}
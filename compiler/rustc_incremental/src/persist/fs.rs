@@ -89,6 +89,23 @@
 //! so, it will also place a read lock on that the respective session directory
 //! so that it won't be deleted while the metadata hashes are loaded.
 //!
+//! ## Shared Caches
+//!
+//! With `-Z incremental-shared-cache`, a compilation session that finds no
+//! finalized session directory for its crate can start from the one of
+//! another incremental compilation directory instead, typically populated by
+//! CI and shared with the developers of a project. That directory is only read
+//! from: its session directories are copied like local ones, but without
+//! locking them, and it is never garbage collected.
+//!
+//! Session directories are found by crate name and `StableCrateId`, and their
+//! contents only refer to source files through their names, which do not
+//! depend on the location of a checkout when it is remapped with
+//! `--remap-path-prefix`. The prefixes that are remapped are not part of the
+//! hash of the command-line arguments either (see
+//! `Options::incremental_dep_tracking_hash`), so the session directories of a
+//! checkout can be reused by another one in a different directory.
+//!
 //! ## Preconditions
 //!
 //! This system relies on two features being available in the file system in
@@ -256,11 +273,21 @@ pub(crate) fn prepare_session_directory(
         // directory
         create_dir(sess, &session_dir, "session");
 
-        // Find a suitable source directory to copy from. Ignore those that we
-        // have already tried before.
-        let source_directory = find_source_directory(&crate_dir, &source_directories_already_tried);
+        // Find a suitable source directory to copy from, in the shared cache if
+        // there is none of our own. Ignore those that we have already tried before.
+        let source_directory = find_source_directory(&crate_dir, &source_directories_already_tried)
+            .map(|source_directory| (source_directory, false))
+            .or_else(|| {
+                find_shared_source_directory(
+                    sess,
+                    crate_name,
+                    stable_crate_id,
+                    &source_directories_already_tried,
+                )
+                .map(|source_directory| (source_directory, true))
+            });
 
-        let Some(source_directory) = source_directory else {
+        let Some((source_directory, is_shared)) = source_directory else {
             // There's nowhere to copy from, we're done
             debug!(
                 "no source directory found. Continuing with empty session \
@@ -274,10 +301,19 @@ pub(crate) fn prepare_session_directory(
         debug!("attempting to copy data from source: {}", source_directory.display());
 
         // Try copying over all files from the source directory
-        if let Ok(allows_links) = copy_files(sess, &session_dir, &source_directory) {
+        if let Ok(allows_links) = copy_files(sess, &session_dir, &source_directory, is_shared) {
             debug!("successfully copied data from: {}", source_directory.display());
 
-            if !allows_links {
+            if is_shared && sess.opts.unstable_opts.incremental_info {
+                eprintln!(
+                    "[incremental] starting from the shared cache: {}",
+                    source_directory.display()
+                );
+            }
+
+            // The shared cache is usually on another file system, where
+            // nothing can be hard-linked.
+            if !allows_links && !is_shared {
                 sess.dcx().emit_warn(errors::HardLinkFailed { path: &session_dir });
             }
 
@@ -386,20 +422,32 @@ pub(crate) fn delete_all_session_dir_contents(sess: &Session) -> io::Result<()>
     Ok(())
 }
 
-fn copy_files(sess: &Session, target_dir: &Path, source_dir: &Path) -> Result<bool, ()> {
+fn copy_files(
+    sess: &Session,
+    target_dir: &Path,
+    source_dir: &Path,
+    is_shared: bool,
+) -> Result<bool, ()> {
     // We acquire a shared lock on the lock file of the directory, so that
     // nobody deletes it out from under us while we are reading from it.
-    let lock_file_path = lock_file_path(source_dir);
-
-    // not exclusive
-    let Ok(_lock) = flock::Lock::new(
-        &lock_file_path,
-        false, // don't wait,
-        false, // don't create
-        false,
-    ) else {
-        // Could not acquire the lock, don't try to copy from here
-        return Err(());
+    // Directories of the shared cache are never deleted, and it may be
+    // read-only, so they are not locked.
+    let _lock = if is_shared {
+        None
+    } else {
+        let lock_file_path = lock_file_path(source_dir);
+
+        // not exclusive
+        let Ok(lock) = flock::Lock::new(
+            &lock_file_path,
+            false, // don't wait,
+            false, // don't create
+            false,
+        ) else {
+            // Could not acquire the lock, don't try to copy from here
+            return Err(());
+        };
+        Some(lock)
     };
 
     let Ok(source_dir_iterator) = source_dir.read_dir() else {
@@ -515,6 +563,26 @@ fn find_source_directory(
     find_source_directory_in_iter(iter, source_directories_already_tried)
 }
 
+/// Finds the most recent published session directory of the crate in the
+/// shared cache of `-Z incremental-shared-cache`, if there is one that is not
+/// in the ignore-list.
+fn find_shared_source_directory(
+    sess: &Session,
+    crate_name: Symbol,
+    stable_crate_id: StableCrateId,
+    source_directories_already_tried: &FxHashSet<PathBuf>,
+) -> Option<PathBuf> {
+    let shared_cache = sess.opts.unstable_opts.incremental_shared_cache.as_ref()?;
+    let crate_dir = shared_cache.join(crate_dir_name(crate_name, stable_crate_id));
+    let Ok(iter) = crate_dir.read_dir() else {
+        debug!("no crate directory in the shared cache: {}", crate_dir.display());
+        return None;
+    };
+    let iter = iter.filter_map(|e| e.ok().map(|e| e.path()));
+
+    find_source_directory_in_iter(iter, source_directories_already_tried)
+}
+
 fn find_source_directory_in_iter<I>(
     iter: I,
     source_directories_already_tried: &FxHashSet<PathBuf>,
@@ -600,9 +668,11 @@ fn string_to_timestamp(s: &str) -> Result<SystemTime, &'static str> {
 fn crate_path(sess: &Session, crate_name: Symbol, stable_crate_id: StableCrateId) -> PathBuf {
     let incr_dir = sess.opts.incremental.as_ref().unwrap().clone();
 
-    let crate_name =
-        format!("{crate_name}-{}", stable_crate_id.as_u64().to_base_fixed_len(CASE_INSENSITIVE));
-    incr_dir.join(crate_name)
+    incr_dir.join(crate_dir_name(crate_name, stable_crate_id))
+}
+
+fn crate_dir_name(crate_name: Symbol, stable_crate_id: StableCrateId) -> String {
+    format!("{crate_name}-{}", stable_crate_id.as_u64().to_base_fixed_len(CASE_INSENSITIVE))
 }
 
 fn is_old_enough_to_be_collected(timestamp: SystemTime) -> bool {
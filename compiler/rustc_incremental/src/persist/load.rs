@@ -1,5 +1,6 @@
 //! Code to load the dep-graph from files.
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -104,7 +105,7 @@ fn load_dep_graph(sess: &Session) -> LoadResult<(Arc<SerializedDepGraph>, WorkPr
     // Calling `sess.incr_comp_session_dir()` will panic if `sess.opts.incremental.is_none()`.
     // Fortunately, we just checked that this isn't the case.
     let path = dep_graph_path(sess);
-    let expected_hash = sess.opts.dep_tracking_hash(false);
+    let expected_hash = sess.opts.incremental_dep_tracking_hash();
 
     let mut prev_work_products = UnordMap::default();
 
@@ -148,7 +149,15 @@ fn load_dep_graph(sess: &Session) -> LoadResult<(Arc<SerializedDepGraph>, WorkPr
     let _prof_timer = prof.generic_activity("incr_comp_load_dep_graph");
 
     match load_data(&path, sess) {
-        LoadResult::DataOutOfDate => LoadResult::DataOutOfDate,
+        LoadResult::DataOutOfDate => {
+            if sess.opts.unstable_opts.incremental_explain {
+                eprintln!(
+                    "[incremental] all queries are executed because there is no previous session \
+                     compiled by this compiler"
+                );
+            }
+            LoadResult::DataOutOfDate
+        }
         LoadResult::LoadDepGraph(path, err) => LoadResult::LoadDepGraph(path, err),
         LoadResult::Ok { data: (bytes, start_pos) } => {
             let Ok(mut decoder) = MemDecoder::new(&bytes, start_pos) else {
@@ -156,6 +165,7 @@ fn load_dep_graph(sess: &Session) -> LoadResult<(Arc<SerializedDepGraph>, WorkPr
                 return LoadResult::DataOutOfDate;
             };
             let prev_commandline_args_hash = Hash64::decode(&mut decoder);
+            let prev_option_hashes = Option::<BTreeMap<String, Hash64>>::decode(&mut decoder);
 
             if prev_commandline_args_hash != expected_hash {
                 if sess.opts.unstable_opts.incremental_info {
@@ -164,6 +174,27 @@ fn load_dep_graph(sess: &Session) -> LoadResult<(Arc<SerializedDepGraph>, WorkPr
                                     differing commandline arguments"
                     );
                 }
+                if sess.opts.unstable_opts.incremental_explain {
+                    if let Some(prev_option_hashes) = prev_option_hashes {
+                        let option_hashes = sess.opts.incremental_dep_tracking_hashes();
+                        let changed: Vec<String> = option_hashes
+                            .iter()
+                            .filter(|(name, hash)| prev_option_hashes.get(*name) != Some(hash))
+                            .map(|(name, _)| format!("`{name}`"))
+                            .collect();
+                        eprintln!(
+                            "[incremental] all queries are executed because these command-line \
+                             options changed: {}",
+                            changed.join(", ")
+                        );
+                    } else {
+                        eprintln!(
+                            "[incremental] all queries are executed because the command-line \
+                             options changed, which are only named if the previous session also \
+                             used `-Zincremental-explain`"
+                        );
+                    }
+                }
                 // We can't reuse the cache, purge it.
                 debug!("load_dep_graph_new: differing commandline arg hashes");
 
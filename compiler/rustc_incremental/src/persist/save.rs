@@ -13,6 +13,7 @@
 use tracing::debug;
 
 use super::data::*;
+use super::explain::explain_reexecution;
 use super::fs::*;
 use super::{dirty_clean, file_format, work_product};
 use crate::assert_dep_graph::assert_dep_graph;
@@ -32,6 +33,9 @@ pub(crate) fn save_dep_graph(tcx: TyCtxt<'_>) {
         if sess.opts.incremental.is_none() {
             return;
         }
+        if sess.opts.unstable_opts.incremental_explain {
+            sess.time("incr_comp_explain_reexecution", || explain_reexecution(tcx));
+        }
         // This is going to be deleted in finalize_session_directory, so let's not create it.
         if sess.dcx().has_errors_or_delayed_bugs().is_some() {
             return;
@@ -165,8 +169,16 @@ pub(crate) fn build_dep_graph(
 
     file_format::write_file_header(&mut encoder, sess);
 
-    // First encode the commandline arguments hash
-    sess.opts.dep_tracking_hash(false).encode(&mut encoder);
+    // First encode the commandline arguments hash. With `-Zincremental-explain`, follow it with
+    // the hash of each option, which tells the next session which options changed if the first
+    // hash differs.
+    sess.opts.incremental_dep_tracking_hash().encode(&mut encoder);
+    let option_hashes = sess
+        .opts
+        .unstable_opts
+        .incremental_explain
+        .then(|| sess.opts.incremental_dep_tracking_hashes());
+    option_hashes.encode(&mut encoder);
 
     Some(DepGraph::new(sess, prev_graph, prev_work_products, encoder))
 }
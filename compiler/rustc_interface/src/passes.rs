@@ -52,6 +52,7 @@
 pub fn parse<'a>(sess: &'a Session) -> ast::Crate {
     let mut krate = sess
         .time("parse_crate", || {
+            let _timing = sess.timings.section_guard(sess.dcx(), TimingSection::Parse);
             let mut parser = unwrap_or_emit_fatal(match &sess.io.input {
                 Input::File(file) => new_parser_from_file(
                     &sess.psess,
@@ -163,6 +164,7 @@ fn configure_and_expand(
 
     // Expand all macros
     krate = sess.time("macro_expand_crate", || {
+        let _timing = sess.timings.section_guard(sess.dcx(), TimingSection::MacroExpansion);
         // Windows dlls do not have rpaths, so they don't know how to find their
         // dependencies. It's up to us to tell the system where to find all the
         // dependent dlls. Note that this uses cfg!(windows) as opposed to
@@ -300,7 +302,10 @@ fn configure_and_expand(
 
     // Done with macro expansion!
 
-    resolver.resolve_crate(&krate);
+    {
+        let _timing = sess.timings.section_guard(sess.dcx(), TimingSection::NameResolution);
+        resolver.resolve_crate(&krate);
+    }
 
     CStore::from_tcx(tcx).report_incompatible_target_modifiers(tcx, &krate);
     CStore::from_tcx(tcx).report_incompatible_async_drop_feature(tcx, &krate);
@@ -1083,7 +1088,10 @@ fn run_required_analyses(tcx: TyCtxt<'_>) {
         );
     });
 
-    rustc_hir_analysis::check_crate(tcx);
+    {
+        let _timing = sess.timings.section_guard(sess.dcx(), TimingSection::TypeCheck);
+        rustc_hir_analysis::check_crate(tcx);
+    }
     // Freeze definitions as we don't add new ones at this point.
     // We need to wait until now since we synthesize a by-move body
     // for all coroutine-closures.
@@ -1092,6 +1100,7 @@ fn run_required_analyses(tcx: TyCtxt<'_>) {
     tcx.untracked().definitions.freeze();
 
     sess.time("MIR_borrow_checking", || {
+        let _timing = sess.timings.section_guard(sess.dcx(), TimingSection::Borrowck);
         tcx.par_hir_body_owners(|def_id| {
             let not_typeck_child = !tcx.is_typeck_child(def_id.to_def_id());
             if not_typeck_child {
@@ -1261,7 +1270,10 @@ pub(crate) fn start_codegen<'tcx>(
                 crate_info: CrateInfo::new(tcx, "<dummy cpu>".to_owned()),
             })
         } else {
-            codegen_backend.codegen_crate(tcx)
+            let codegen = codegen_backend.codegen_crate(tcx);
+            // This must run after codegen so that the size of the IR of the mono items is known.
+            rustc_monomorphize::dump_mono_stats(tcx);
+            codegen
         }
     });
 
@@ -15,9 +15,9 @@
     FmtDebug, FunctionReturn, InliningThreshold, Input, InstrumentCoverage, InstrumentXRay,
     LinkSelfContained, LinkerPluginLto, LocationDetail, LtoCli, MirIncludeSpans, NextSolverConfig,
     Offload, Options, OutFileName, OutputType, OutputTypes, PAuthKey, PacRet, Passes,
-    PatchableFunctionEntry, Polonius, ProcMacroExecutionStrategy, Strip, SwitchWithOptPath,
-    SymbolManglingVersion, WasiExecModel, build_configuration, build_session_options,
-    rustc_optgroups,
+    PatchableFunctionEntry, Polonius, ProcMacroExecutionStrategy, PublicMirFormat, Strip,
+    SwitchWithOptPath, SymbolManglingVersion, WasiExecModel, build_configuration,
+    build_session_options, rustc_optgroups,
 };
 use rustc_session::lint::Level;
 use rustc_session::search_paths::SearchPath;
@@ -25,7 +25,7 @@
 use rustc_session::{CompilerIO, EarlyDiagCtxt, Session, build_session, getopts};
 use rustc_span::edition::{DEFAULT_EDITION, Edition};
 use rustc_span::source_map::{RealFileLoader, SourceMapInputs};
-use rustc_span::{FileName, SourceFileHashAlgorithm, sym};
+use rustc_span::{FileName, RemapPathScopeComponents, SourceFileHashAlgorithm, sym};
 use rustc_target::spec::{
     CodeModel, FramePointer, LinkerFlavorCli, MergeFunctions, OnBrokenPipe, PanicStrategy,
     RelocModel, RelroLevel, SanitizerSet, SplitDebuginfo, StackProtector, TlsModel,
@@ -675,6 +675,41 @@ macro_rules! tracked {
     // tidy-alphabetical-end
 }
 
+#[test]
+fn test_incremental_hash_of_remapped_paths() {
+    let checkout = |dir: &str, remapped_dir: &str| {
+        let mut opts = Options::default();
+        opts.remap_path_prefix = vec![
+            (format!("/{dir}/project").into(), "/project".into()),
+            (format!("/{dir}/.rustup").into(), remapped_dir.into()),
+        ];
+        opts.real_rust_source_base_dir =
+            Some(format!("/{dir}/.rustup/toolchains/nightly/lib/rustlib/src/rust").into());
+        opts
+    };
+
+    // Checkouts in different directories share their incremental caches when they are remapped to
+    // the same paths...
+    let ci = checkout("ci", "/rustup");
+    let local = checkout("home/bors", "/rustup");
+    assert_ne!(ci.dep_tracking_hash(false), local.dep_tracking_hash(false));
+    assert_eq!(ci.incremental_dep_tracking_hash(), local.incremental_dep_tracking_hash());
+
+    // ...but not when they are remapped to different ones.
+    let elsewhere = checkout("home/bors", "/elsewhere");
+    assert_ne!(ci.incremental_dep_tracking_hash(), elsewhere.incremental_dep_tracking_hash());
+
+    // Paths that are not remapped in every scope are hashed as they are.
+    let mut ci_debuginfo = ci.clone();
+    ci_debuginfo.remap_path_scope = RemapPathScopeComponents::DEBUGINFO;
+    let mut local_debuginfo = local.clone();
+    local_debuginfo.remap_path_scope = RemapPathScopeComponents::DEBUGINFO;
+    assert_ne!(
+        ci_debuginfo.incremental_dep_tracking_hash(),
+        local_debuginfo.incremental_dep_tracking_hash()
+    );
+}
+
 #[test]
 fn test_unstable_options_tracking_hash() {
     let reference = Options::default();
@@ -702,14 +737,18 @@ macro_rules! untracked {
     untracked!(dump_mir_graphviz, true);
     untracked!(dump_mono_stats, SwitchWithOptPath::Enabled(Some("mono-items-dir/".into())));
     untracked!(dump_mono_stats_format, DumpMonoStatsFormat::Json);
+    untracked!(dump_public_mir, Some(PublicMirFormat::Json));
     untracked!(dylib_lto, true);
     untracked!(emit_stack_sizes, true);
     untracked!(future_incompat_test, true);
     untracked!(identify_regions, true);
+    untracked!(incremental_explain, true);
     untracked!(incremental_info, true);
+    untracked!(incremental_shared_cache, Some(PathBuf::from("shared-incr")));
     untracked!(incremental_verify_ich, true);
     untracked!(input_stats, true);
     untracked!(link_native_libraries, false);
+    untracked!(lint_baseline, Some(PathBuf::from("lint-baseline.json")));
     untracked!(llvm_time_trace, true);
     untracked!(ls, vec!["all".to_owned()]);
     untracked!(macro_backtrace, true);
@@ -749,6 +788,7 @@ macro_rules! untracked {
     untracked!(unpretty, Some("expanded".to_string()));
     untracked!(unstable_options, true);
     untracked!(validate_mir, true);
+    untracked!(write_lint_baseline, true);
     untracked!(write_long_types_to_disk, false);
     // tidy-alphabetical-end
 
@@ -5,10 +5,11 @@
 
 use std::cell::Cell;
 use std::slice;
+use std::sync::OnceLock;
 
 use rustc_ast::BindingMode;
 use rustc_ast::util::parser::ExprPrecedence;
-use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
 use rustc_data_structures::sync;
 use rustc_data_structures::unord::UnordMap;
 use rustc_errors::{Diag, LintBuffer, LintDiagnostic, MultiSpan};
@@ -60,6 +61,10 @@ pub struct LintStore {
 
     /// Map of registered lint groups to what lints they expand to.
     lint_groups: FxIndexMap<&'static str, LintGroup>,
+
+    /// The non-deprecated groups that contain each lint. Built the first time that it is needed,
+    /// once all lints and groups have been registered.
+    groups_by_lint: OnceLock<FxHashMap<LintId, Vec<&'static str>>>,
 }
 
 impl DynLintStore for LintStore {
@@ -68,6 +73,21 @@ fn lint_groups_iter(&self) -> Box<dyn Iterator<Item = rustc_session::LintGroup>
             rustc_session::LintGroup { name, lints, is_externally_loaded }
         }))
     }
+
+    fn lint_groups_of(&self, lint: LintId) -> &[&'static str] {
+        let groups_by_lint = self.groups_by_lint.get_or_init(|| {
+            let mut groups_by_lint: FxHashMap<_, Vec<_>> = FxHashMap::default();
+            for (&name, group) in &self.lint_groups {
+                if group.depr.is_none() {
+                    for &lint in &group.lint_ids {
+                        groups_by_lint.entry(lint).or_default().push(name);
+                    }
+                }
+            }
+            groups_by_lint
+        });
+        groups_by_lint.get(&lint).map_or(&[], Vec::as_slice)
+    }
 }
 
 /// The target of the `by_name` map, which accounts for renaming/deprecation.
@@ -135,6 +155,7 @@ pub fn new() -> LintStore {
             late_module_passes: vec![],
             by_name: Default::default(),
             lint_groups: Default::default(),
+            groups_by_lint: Default::default(),
         }
     }
 
@@ -581,6 +581,31 @@ pub fn default_level(&self, edition: Edition) -> Level {
             .map(|(_, l)| l)
             .unwrap_or(self.default_level)
     }
+
+    /// Returns the URL of the documentation of the lint, if it is a lint of rustc, rustdoc or
+    /// Clippy.
+    pub fn docs_url(&self) -> Option<String> {
+        let name = self.name_lower();
+        match name.split_once("::") {
+            None => {
+                let page = match self.default_level {
+                    Level::Allow => "allowed-by-default",
+                    Level::Warn => "warn-by-default",
+                    Level::Deny | Level::Forbid => "deny-by-default",
+                    Level::Expect | Level::ForceWarn => return None,
+                };
+                let anchor = name.replace('_', "-");
+                Some(format!("https://doc.rust-lang.org/rustc/lints/listing/{page}.html#{anchor}"))
+            }
+            Some(("rustdoc", name)) => {
+                Some(format!("https://doc.rust-lang.org/rustdoc/lints.html#{name}"))
+            }
+            Some(("clippy", name)) => {
+                Some(format!("https://rust-lang.github.io/rust-clippy/master/index.html#{name}"))
+            }
+            Some(_) => None,
+        }
+    }
 }
 
 /// Identifies a lint known to the compiler.
@@ -2,7 +2,7 @@
 
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_data_structures::sorted_map::SortedMap;
-use rustc_errors::{Diag, MultiSpan};
+use rustc_errors::{Diag, LintMetadata, MultiSpan};
 use rustc_hir::{HirId, ItemLocalId};
 use rustc_lint_defs::EditionFcw;
 use rustc_macros::{Decodable, Encodable, HashStable};
@@ -390,6 +390,9 @@ fn lint_level_impl(
         }
 
         err.is_lint(lint.name_lower(), has_future_breakage);
+        let groups =
+            sess.lint_groups_of(LintId::of(lint)).iter().map(|&group| group.to_owned()).collect();
+        err.lint_metadata(LintMetadata::new(lint, groups));
 
         // Lint diagnostics that are covered by the expect level will not be emitted outside
         // the compiler. It is therefore not necessary to add any information for the user.
@@ -16,6 +16,7 @@
 use rustc_const_eval::util;
 use rustc_data_structures::fx::FxIndexSet;
 use rustc_data_structures::steal::Steal;
+use rustc_errors::timings::TimingSection;
 use rustc_hir as hir;
 use rustc_hir::def::{CtorKind, DefKind};
 use rustc_hir::def_id::LocalDefId;
@@ -790,6 +791,9 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
 
 /// Optimize the MIR and prepare it for codegen.
 fn optimized_mir(tcx: TyCtxt<'_>, did: LocalDefId) -> &Body<'_> {
+    // Optimizing a body can optimize others, for example to inline them.
+    let _timing =
+        tcx.sess.timings.reentrant_section_guard(tcx.dcx(), TimingSection::MirOptimization);
     tcx.arena.alloc(inner_optimized_mir(tcx, did))
 }
 
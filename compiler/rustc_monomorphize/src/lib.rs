@@ -20,6 +20,8 @@
 mod partitioning;
 mod util;
 
+pub use partitioning::dump_mono_stats;
+
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
 
 fn custom_coerce_unsize_info<'tcx>(
@@ -101,6 +101,7 @@
 use rustc_data_structures::fx::{FxIndexMap, FxIndexSet};
 use rustc_data_structures::sync;
 use rustc_data_structures::unord::{UnordMap, UnordSet};
+use rustc_errors::timings::TimingSection;
 use rustc_hir::LangItem;
 use rustc_hir::attrs::{InlineAttr, Linkage};
 use rustc_hir::def::DefKind;
@@ -1129,6 +1130,7 @@ fn assert_symbols_are_distinct<'a, 'tcx, I>(tcx: TyCtxt<'tcx>, mono_items: I)
 }
 
 fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> MonoItemPartitions<'_> {
+    let _timing = tcx.sess.timings.section_guard(tcx.dcx(), TimingSection::MonoItemCollection);
     let collection_strategy = if tcx.sess.link_dead_code() {
         MonoItemCollectionStrategy::Eager
     } else {
@@ -1175,14 +1177,6 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> MonoItemPartitio
         })
         .collect();
 
-    // Output monomorphization stats per def_id
-    if let SwitchWithOptPath::Enabled(ref path) = tcx.sess.opts.unstable_opts.dump_mono_stats
-        && let Err(err) =
-            dump_mono_items_stats(tcx, codegen_units, path, tcx.crate_name(LOCAL_CRATE))
-    {
-        tcx.dcx().emit_fatal(CouldntDumpMonoStats { error: err.to_string() });
-    }
-
     if tcx.sess.opts.unstable_opts.print_mono_items {
         let mut item_to_cgus: UnordMap<_, Vec<_>> = Default::default();
 
@@ -1235,6 +1229,24 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> MonoItemPartitio
     MonoItemPartitions { all_mono_items: tcx.arena.alloc(mono_items), codegen_units }
 }
 
+/// Outputs stats about the monomorphized items of the crate to a file in the directory of
+/// `-Z dump-mono-stats`, if it is enabled.
+///
+/// This runs after codegen, so that the size of the LLVM IR of the items is known when the LLVM
+/// backend is used.
+pub fn dump_mono_stats(tcx: TyCtxt<'_>) {
+    if let SwitchWithOptPath::Enabled(ref path) = tcx.sess.opts.unstable_opts.dump_mono_stats
+        && let Err(err) = dump_mono_items_stats(
+            tcx,
+            tcx.collect_and_partition_mono_items(()).codegen_units,
+            path,
+            tcx.crate_name(LOCAL_CRATE),
+        )
+    {
+        tcx.dcx().emit_fatal(CouldntDumpMonoStats { error: err.to_string() });
+    }
+}
+
 /// Outputs stats about instantiation counts and estimated size, per `MonoItem`'s
 /// def, to a file in the given output directory.
 fn dump_mono_items_stats<'tcx>(
@@ -1256,7 +1268,8 @@ fn dump_mono_items_stats<'tcx>(
     let output_path = output_directory.join(&filename);
     let mut file = File::create_buffered(&output_path)?;
 
-    // Gather instantiated mono items grouped by def_id
+    // Gather instantiated mono items grouped by def_id. Closures and coroutines are instantiated
+    // along with the function that defines them, and are attributed to it.
     let mut items_per_def_id: FxIndexMap<_, Vec<_>> = Default::default();
     for cgu in codegen_units {
         cgu.items()
@@ -1264,16 +1277,19 @@ fn dump_mono_items_stats<'tcx>(
             // Avoid variable-sized compiler-generated shims
             .filter(|mono_item| mono_item.is_user_defined())
             .for_each(|mono_item| {
-                items_per_def_id.entry(mono_item.def_id()).or_default().push(mono_item);
+                let def_id = tcx.typeck_root_def_id(mono_item.def_id());
+                items_per_def_id.entry(def_id).or_default().push(mono_item);
             });
     }
 
     #[derive(serde::Serialize)]
     struct MonoItem {
         name: String,
+        instantiating_crate: String,
         instantiation_count: usize,
         size_estimate: usize,
         total_estimate: usize,
+        llvm_ir_size: Option<usize>,
     }
 
     // Output stats sorted by total instantiated size, from heaviest to lightest
@@ -1282,9 +1298,24 @@ struct MonoItem {
         .map(|(def_id, items)| {
             let name = with_no_trimmed_paths!(tcx.def_path_str(def_id));
             let instantiation_count = items.len();
-            let size_estimate = items[0].size_estimate(tcx);
-            let total_estimate = instantiation_count * size_estimate;
-            MonoItem { name, instantiation_count, size_estimate, total_estimate }
+            let total_estimate = items.iter().map(|item| item.size_estimate(tcx)).sum();
+            let size_estimate = total_estimate / instantiation_count;
+            // The backend records the size of the IR of all the instances of a def together.
+            let llvm_ir_size = items
+                .iter()
+                .map(|item| item.def_id())
+                .collect::<FxIndexSet<_>>()
+                .into_iter()
+                .filter_map(|def_id| tcx.sess.code_stats.llvm_ir_size(def_id))
+                .reduce(|a, b| a + b);
+            MonoItem {
+                name,
+                instantiating_crate: crate_name.to_string(),
+                instantiation_count,
+                size_estimate,
+                total_estimate,
+                llvm_ir_size,
+            }
         })
         .collect();
     stats.sort_unstable_by_key(|item| cmp::Reverse(item.total_estimate));
@@ -1295,14 +1326,26 @@ struct MonoItem {
             DumpMonoStatsFormat::Markdown => {
                 writeln!(
                     file,
-                    "| Item | Instantiation count | Estimated Cost Per Instantiation | Total Estimated Cost |"
+                    "| Item | Instantiation count | Estimated Cost Per Instantiation | Total Estimated Cost | LLVM IR Instructions |"
                 )?;
-                writeln!(file, "| --- | ---: | ---: | ---: |")?;
-
-                for MonoItem { name, instantiation_count, size_estimate, total_estimate } in stats {
+                writeln!(file, "| --- | ---: | ---: | ---: | ---: |")?;
+
+                for MonoItem {
+                    name,
+                    instantiation_count,
+                    size_estimate,
+                    total_estimate,
+                    llvm_ir_size,
+                    ..
+                } in stats
+                {
+                    let llvm_ir_size = match llvm_ir_size {
+                        Some(size) => size.to_string(),
+                        None => "-".to_string(),
+                    };
                     writeln!(
                         file,
-                        "| `{name}` | {instantiation_count} | {size_estimate} | {total_estimate} |"
+                        "| `{name}` | {instantiation_count} | {size_estimate} | {total_estimate} | {llvm_ir_size} |"
                     )?;
                 }
             }
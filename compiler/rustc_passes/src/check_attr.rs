@@ -1084,6 +1084,8 @@ fn check_doc_attrs(&self, attr: &DocAttribute, hir_id: HirId, target: Target) {
             auto_cfg: _,
             // already checked in attr_parsing
             auto_cfg_change: _,
+            // already checked in attr_parsing
+            scrape_examples: _,
             fake_variadic,
             keyword,
             masked,
@@ -12,6 +12,7 @@
 
 use crate::abi::{FnAbi, Layout, LayoutShape, ReprOptions};
 use crate::crate_def::Attribute;
+use crate::diagnostic::Diagnostic;
 use crate::mir::alloc::{AllocId, GlobalAlloc};
 use crate::mir::mono::{Instance, InstanceDef, StaticDef};
 use crate::mir::{BinOp, Body, Place, UnOp};
@@ -19,8 +20,9 @@
 use crate::ty::{
     AdtDef, AdtKind, Allocation, ClosureDef, ClosureKind, CoroutineDef, Discr, FieldDef, FnDef,
     ForeignDef, ForeignItemKind, ForeignModule, ForeignModuleDef, GenericArgs, GenericPredicates,
-    Generics, ImplDef, ImplTrait, IntrinsicDef, LineInfo, MirConst, PolyFnSig, RigidTy, Span,
-    TraitDecl, TraitDef, Ty, TyConst, TyConstId, TyKind, UintTy, VariantDef, VariantIdx,
+    Generics, ImplDef, ImplTrait, IntrinsicDef, LineInfo, MirConst, ParamEnv, PolyFnSig, RigidTy,
+    Span, TraitDecl, TraitDef, TraitRef, Ty, TyConst, TyConstId, TyKind, UintTy, VariantDef,
+    VariantIdx,
 };
 use crate::unstable::{RustcInternal, Stable, new_item_kind};
 use crate::{
@@ -293,6 +295,29 @@ pub(crate) fn span_to_string(&self, span: Span) -> String {
         cx.span_to_string(sp)
     }
 
+    /// Report a diagnostic to the user.
+    pub(crate) fn emit_diagnostic(&self, diagnostic: Diagnostic) {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let children = diagnostic
+            .children
+            .iter()
+            .map(|child| {
+                (
+                    child.level.internal(&mut *tables, cx.tcx),
+                    child.message.clone(),
+                    child.span.internal(&mut *tables, cx.tcx),
+                )
+            })
+            .collect();
+        cx.emit_diagnostic(
+            diagnostic.level.internal(&mut *tables, cx.tcx),
+            diagnostic.message,
+            diagnostic.span.internal(&mut *tables, cx.tcx),
+            children,
+        );
+    }
+
     /// Return filename from given `Span`, for diagnostic purposes.
     pub(crate) fn get_filename(&self, span: &Span) -> Filename {
         let tables = self.tables.borrow_mut();
@@ -672,6 +697,58 @@ pub(crate) fn resolve_instance(&self, def: FnDef, args: &GenericArgs) -> Option<
         cx.resolve_instance(def_id, args_ref).map(|inst| inst.stable(&mut *tables, cx))
     }
 
+    /// Resolve an instance from the given function definition and generic arguments in the given
+    /// environment.
+    pub(crate) fn resolve_instance_in(
+        &self,
+        def: FnDef,
+        args: &GenericArgs,
+        param_env: ParamEnv,
+    ) -> Option<Instance> {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let def_id = def.0.internal(&mut *tables, cx.tcx);
+        let args_ref = args.internal(&mut *tables, cx.tcx);
+        let item = param_env.internal(&mut *tables, cx.tcx);
+        cx.resolve_instance_in(def_id, args_ref, item).map(|inst| inst.stable(&mut *tables, cx))
+    }
+
+    /// Check whether the given trait reference holds in the given environment.
+    pub(crate) fn trait_ref_holds(&self, trait_ref: &TraitRef, param_env: ParamEnv) -> bool {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let trait_ref = trait_ref.internal(&mut *tables, cx.tcx);
+        let item = param_env.internal(&mut *tables, cx.tcx);
+        cx.trait_ref_holds(trait_ref, item)
+    }
+
+    /// Check whether the given type is `Send` in the given environment.
+    pub(crate) fn is_send(&self, ty: Ty, param_env: ParamEnv) -> bool {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let ty = ty.internal(&mut *tables, cx.tcx);
+        let item = param_env.internal(&mut *tables, cx.tcx);
+        cx.is_send(ty, item)
+    }
+
+    /// Check whether the given type is `Freeze` in the given environment.
+    pub(crate) fn is_freeze(&self, ty: Ty, param_env: ParamEnv) -> bool {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let ty = ty.internal(&mut *tables, cx.tcx);
+        let item = param_env.internal(&mut *tables, cx.tcx);
+        cx.is_freeze(ty, item)
+    }
+
+    /// Normalize the given type in the given environment.
+    pub(crate) fn normalize(&self, ty: Ty, param_env: ParamEnv) -> Result<Ty, Error> {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let ty = ty.internal(&mut *tables, cx.tcx);
+        let item = param_env.internal(&mut *tables, cx.tcx);
+        cx.normalize(ty, item).map(|ty| ty.stable(&mut *tables, cx))
+    }
+
     /// Resolve an instance for drop_in_place for the given type.
     pub(crate) fn resolve_drop_in_place(&self, ty: Ty) -> Instance {
         let mut tables = self.tables.borrow_mut();
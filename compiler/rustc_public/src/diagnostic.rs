@@ -0,0 +1,96 @@
+//! Report diagnostics through the compiler.
+//!
+//! Diagnostics emitted this way are rendered exactly like the compiler's own diagnostics, and they
+//! respect flags such as `--error-format=json`.
+
+use serde::Serialize;
+
+use crate::compiler_interface::with;
+use crate::ty::Span;
+
+/// The severity of a diagnostic.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum Level {
+    /// An error makes the compilation fail once the analysis callback returns.
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+/// A message attached to a [Diagnostic], such as a note or a help message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SubDiagnostic {
+    pub level: Level,
+    pub message: String,
+    pub span: Option<Span>,
+}
+
+/// A diagnostic that can be reported to the user.
+///
+/// ```ignore(needs-extern-crate)
+/// # use rustc_public::diagnostic::Diagnostic;
+/// # let span = rustc_public::entry_fn().unwrap().span();
+/// Diagnostic::warning("this function is never called")
+///     .with_span(span)
+///     .with_help("remove it")
+///     .emit();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub message: String,
+    /// The primary location of the diagnostic, if any.
+    pub span: Option<Span>,
+    pub children: Vec<SubDiagnostic>,
+}
+
+impl Diagnostic {
+    pub fn new(level: Level, message: impl Into<String>) -> Diagnostic {
+        Diagnostic { level, message: message.into(), span: None, children: vec![] }
+    }
+
+    pub fn error(message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Level::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Level::Warning, message)
+    }
+
+    /// Set the primary location of this diagnostic.
+    pub fn with_span(mut self, span: Span) -> Diagnostic {
+        self.span = Some(span);
+        self
+    }
+
+    /// Attach a note that has no location of its own.
+    pub fn with_note(self, message: impl Into<String>) -> Diagnostic {
+        self.with_child(Level::Note, message, None)
+    }
+
+    /// Attach a note that points to `span`.
+    pub fn with_span_note(self, span: Span, message: impl Into<String>) -> Diagnostic {
+        self.with_child(Level::Note, message, Some(span))
+    }
+
+    /// Attach a help message that has no location of its own.
+    pub fn with_help(self, message: impl Into<String>) -> Diagnostic {
+        self.with_child(Level::Help, message, None)
+    }
+
+    /// Attach a help message that points to `span`.
+    pub fn with_span_help(self, span: Span, message: impl Into<String>) -> Diagnostic {
+        self.with_child(Level::Help, message, Some(span))
+    }
+
+    fn with_child(mut self, level: Level, message: impl Into<String>, span: Option<Span>) -> Self {
+        self.children.push(SubDiagnostic { level, message: message.into(), span });
+        self
+    }
+
+    /// Report this diagnostic to the user.
+    pub fn emit(self) {
+        with(|cx| cx.emit_diagnostic(self))
+    }
+}
@@ -18,6 +18,7 @@
 
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::ops::ControlFlow;
 use std::{fmt, io};
 
 pub(crate) use rustc_public_bridge::IndexedVal;
@@ -46,6 +47,7 @@
 #[macro_use]
 pub mod crate_def;
 pub mod compiler_interface;
+pub mod diagnostic;
 #[macro_use]
 pub mod error;
 pub mod mir;
@@ -209,6 +211,21 @@ pub fn all_local_items() -> CrateItems {
     with(|cx| cx.all_local_items())
 }
 
+/// Invoke `callback` with the body of every item in the local crate that has one, until the
+/// callback returns `ControlFlow::Break`.
+///
+/// When this is called from an analysis callback, every body has already been borrow-checked.
+pub fn for_each_local_body<B>(
+    mut callback: impl FnMut(CrateItem, &Body) -> ControlFlow<B>,
+) -> ControlFlow<B> {
+    for item in all_local_items() {
+        if let Some(body) = item.body() {
+            callback(item, &body)?;
+        }
+    }
+    ControlFlow::Continue(())
+}
+
 pub fn all_trait_decls() -> TraitDecls {
     with(|cx| cx.all_trait_decls())
 }
@@ -0,0 +1,250 @@
+//! A self-contained representation of the MIR of a crate that can be exchanged with tools that
+//! do not link against the compiler.
+//!
+//! The bodies refer to types, spans, allocations and definitions by their id. Since those ids are
+//! meaningless outside of the compiler session that created them, [CrateMir] also contains tables
+//! that describe every id the bodies refer to, directly or indirectly.
+
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::io;
+use std::ops::ControlFlow;
+
+use serde::Serialize;
+
+use crate::mir::alloc::{AllocId, GlobalAlloc};
+use crate::mir::visit::Location;
+use crate::mir::{Body, MirVisitor};
+use crate::ty::{
+    Allocation, ConstantKind, LineInfo, MirConst, RigidTy, Span, Ty, TyConst, TyConstKind, TyKind,
+};
+use crate::visitor::{Visitable, Visitor};
+use crate::{CrateDef, CrateItem, DefId, Filename, ItemKind, Symbol};
+
+/// The version of the format produced by [CrateMir::write_json].
+///
+/// This is incremented whenever a change to the format could break an existing consumer.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The MIR of all items of the local crate that have a body, together with everything the bodies
+/// refer to.
+#[derive(Clone, Debug, Serialize)]
+pub struct CrateMir {
+    pub schema_version: u32,
+    pub crate_name: Symbol,
+    pub items: Vec<ItemMir>,
+    pub types: Vec<TyEntry>,
+    pub spans: Vec<SpanEntry>,
+    pub allocations: Vec<AllocEntry>,
+    /// The definitions that types and allocations refer to.
+    pub defs: Vec<DefEntry>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ItemMir {
+    pub id: DefId,
+    pub name: Symbol,
+    pub kind: ItemKind,
+    pub body: Body,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TyEntry {
+    pub id: Ty,
+    pub kind: TyKind,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SpanEntry {
+    pub id: Span,
+    pub file: Filename,
+    pub lines: LineInfo,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AllocEntry {
+    pub id: AllocId,
+    pub alloc: GlobalAlloc,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DefEntry {
+    pub id: DefId,
+    pub name: Symbol,
+}
+
+impl CrateMir {
+    /// Collect the MIR of the local crate.
+    pub fn collect() -> CrateMir {
+        let mut collector = Collector::default();
+        let items: Vec<ItemMir> = crate::all_local_items()
+            .into_iter()
+            .filter_map(|item: CrateItem| {
+                let body = item.body()?;
+                collector.visit_body(&body);
+                Some(ItemMir { id: item.def_id(), name: item.name(), kind: item.kind(), body })
+            })
+            .collect();
+        let allocations = collector.collect_allocations();
+        CrateMir {
+            schema_version: SCHEMA_VERSION,
+            crate_name: crate::local_crate().name,
+            items,
+            types: collector
+                .tys
+                .order
+                .into_iter()
+                .map(|id| TyEntry { id, kind: id.kind() })
+                .collect(),
+            spans: collector
+                .spans
+                .order
+                .into_iter()
+                .map(|id| SpanEntry { id, file: id.get_filename(), lines: id.get_lines() })
+                .collect(),
+            allocations,
+            defs: collector
+                .defs
+                .order
+                .into_iter()
+                .map(|id| DefEntry { id, name: id.name() })
+                .collect(),
+        }
+    }
+
+    /// Write this MIR as a single JSON object.
+    pub fn write_json<W: io::Write>(&self, w: W) -> io::Result<()> {
+        serde_json::to_writer(w, self).map_err(io::Error::from)
+    }
+}
+
+/// A set that remembers the order in which values were inserted.
+struct Table<T> {
+    order: Vec<T>,
+    seen: HashSet<T>,
+}
+
+impl<T> Default for Table<T> {
+    fn default() -> Self {
+        Table { order: Vec::new(), seen: HashSet::new() }
+    }
+}
+
+impl<T: Copy + Eq + Hash> Table<T> {
+    /// Returns whether the value was not in the table yet.
+    fn insert(&mut self, value: T) -> bool {
+        let new = self.seen.insert(value);
+        if new {
+            self.order.push(value);
+        }
+        new
+    }
+}
+
+/// Finds every type, span, allocation and definition that the visited bodies refer to.
+#[derive(Default)]
+struct Collector {
+    tys: Table<Ty>,
+    spans: Table<Span>,
+    allocs: Table<AllocId>,
+    defs: Table<DefId>,
+}
+
+impl Collector {
+    fn add_provenance(&mut self, alloc: &Allocation) {
+        for (_, prov) in &alloc.provenance.ptrs {
+            self.allocs.insert(prov.0);
+        }
+    }
+
+    /// Describe all allocations found so far, including the ones that they point to.
+    fn collect_allocations(&mut self) -> Vec<AllocEntry> {
+        let mut entries = Vec::new();
+        // Allocations can point to further allocations, which get appended to the table.
+        while let Some(&id) = self.allocs.order.get(entries.len()) {
+            let alloc = GlobalAlloc::from(id);
+            match &alloc {
+                GlobalAlloc::Function(instance) => {
+                    self.defs.insert(instance.def.def_id());
+                    let _ = instance.ty().visit(self);
+                }
+                GlobalAlloc::VTable(ty, _) | GlobalAlloc::TypeId { ty } => {
+                    let _ = ty.visit(self);
+                }
+                GlobalAlloc::Static(def) => {
+                    self.defs.insert(def.def_id());
+                }
+                GlobalAlloc::Memory(alloc) => self.add_provenance(alloc),
+            }
+            entries.push(AllocEntry { id, alloc });
+        }
+        entries
+    }
+}
+
+impl Visitor for Collector {
+    type Break = ();
+
+    fn visit_ty(&mut self, ty: &Ty) -> ControlFlow<Self::Break> {
+        if !self.tys.insert(*ty) {
+            return ControlFlow::Continue(());
+        }
+        match ty.kind() {
+            TyKind::RigidTy(RigidTy::Adt(def, _)) => {
+                self.defs.insert(def.def_id());
+            }
+            TyKind::RigidTy(RigidTy::FnDef(def, _)) => {
+                self.defs.insert(def.def_id());
+            }
+            TyKind::RigidTy(RigidTy::Closure(def, _)) => {
+                self.defs.insert(def.def_id());
+            }
+            TyKind::RigidTy(RigidTy::Coroutine(def, _)) => {
+                self.defs.insert(def.def_id());
+            }
+            TyKind::RigidTy(RigidTy::CoroutineClosure(def, _)) => {
+                self.defs.insert(def.def_id());
+            }
+            TyKind::RigidTy(RigidTy::Foreign(def)) => {
+                self.defs.insert(def.def_id());
+            }
+            TyKind::Alias(_, alias) => {
+                self.defs.insert(alias.def_id.def_id());
+            }
+            TyKind::RigidTy(_) | TyKind::Param(_) | TyKind::Bound(..) => {}
+        }
+        ty.super_visit(self)
+    }
+
+    fn visit_const(&mut self, c: &TyConst) -> ControlFlow<Self::Break> {
+        if let TyConstKind::Value(_, alloc) = c.kind() {
+            self.add_provenance(alloc);
+        }
+        c.super_visit(self)
+    }
+}
+
+impl MirVisitor for Collector {
+    fn visit_ty(&mut self, ty: &Ty, _location: Location) {
+        let _ = ty.visit(self);
+    }
+
+    fn visit_span(&mut self, span: &Span) {
+        self.spans.insert(*span);
+    }
+
+    fn visit_mir_const(&mut self, constant: &MirConst, location: Location) {
+        match constant.kind() {
+            ConstantKind::Allocated(alloc) => self.add_provenance(alloc),
+            ConstantKind::Ty(c) => {
+                let _ = c.visit(self);
+            }
+            ConstantKind::Unevaluated(_) | ConstantKind::Param(_) | ConstantKind::ZeroSized => {}
+        }
+        self.super_mir_const(constant, location)
+    }
+
+    fn visit_ty_const(&mut self, constant: &TyConst, _location: Location) {
+        let _ = constant.visit(self);
+    }
+}
@@ -7,7 +7,9 @@
 use crate::abi::FnAbi;
 use crate::crate_def::CrateDef;
 use crate::mir::Body;
-use crate::ty::{Allocation, ClosureDef, ClosureKind, FnDef, GenericArgs, Ty, index_impl};
+use crate::ty::{
+    Allocation, ClosureDef, ClosureKind, FnDef, GenericArgs, ParamEnv, Ty, index_impl,
+};
 use crate::{CrateItem, DefId, Error, ItemKind, Opaque, Symbol, ThreadLocalIndex, with};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
@@ -126,6 +128,26 @@ pub fn resolve(def: FnDef, args: &GenericArgs) -> Result<Instance, Error> {
         })
     }
 
+    /// Resolve an instance like [Instance::resolve], but for generic arguments that may refer to
+    /// the generic parameters in scope in `param_env`.
+    ///
+    /// This is useful to find out which method a call to a trait method in a generic function
+    /// dispatches to. If the method cannot be resolved yet because it depends on a generic
+    /// parameter, this returns an error.
+    pub fn resolve_in(
+        def: FnDef,
+        args: &GenericArgs,
+        param_env: ParamEnv,
+    ) -> Result<Instance, Error> {
+        with(|context| {
+            context.resolve_instance_in(def, args, param_env).ok_or_else(|| {
+                bridge::Error::new(format!(
+                    "Failed to resolve `{def:?}` with `{args:?}` in `{param_env:?}`"
+                ))
+            })
+        })
+    }
+
     /// Resolve the drop in place for a given type.
     pub fn resolve_drop_in_place(ty: Ty) -> Instance {
         with(|cx| cx.resolve_drop_in_place(ty))
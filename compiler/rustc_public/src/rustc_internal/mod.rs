@@ -149,6 +149,44 @@ macro_rules! run_with_tcx {
     };
 }
 
+/// Instantiate and run the compiler with the provided arguments, and invoke the callback with the
+/// body of every item in the local crate that has one.
+///
+/// The callback is invoked with a [`CrateItem`](crate::CrateItem) and its [`Body`](crate::mir::Body)
+/// once all bodies have been borrow-checked, and it can report problems through
+/// [`Diagnostic`](crate::diagnostic::Diagnostic). Unless the callback returns `ControlFlow::Break`,
+/// the compilation continues as usual afterwards, so a tool that runs a pass this way can be used
+/// as a `RUSTC_WORKSPACE_WRAPPER` in an ordinary `cargo build`. Compilation fails if the callback
+/// emitted any errors.
+/// ```ignore(needs-extern-crate)
+/// # extern crate rustc_driver;
+/// # extern crate rustc_interface;
+/// # extern crate rustc_middle;
+/// # #[macro_use]
+/// # extern crate rustc_public;
+/// #
+/// # fn main() {
+/// #   use std::ops::ControlFlow;
+/// #   use rustc_public::diagnostic::Diagnostic;
+///     // Cargo passes the path to `rustc` first, which takes the place of the binary name.
+///     let args: Vec<String> = std::env::args().skip(1).collect();
+///     let result = run_mir_pass!(&args, |item, body| {
+///         if body.blocks.len() > 100 {
+///             Diagnostic::warning(format!("`{}` is too complex", item.name()))
+///                 .with_span(body.span)
+///                 .emit();
+///         }
+///         ControlFlow::<()>::Continue(())
+///     });
+/// # }
+/// ```
+#[macro_export]
+macro_rules! run_mir_pass {
+    ($args:expr, $callback:expr) => {
+        $crate::run_driver!($args, || $crate::for_each_local_body($callback))
+    };
+}
+
 /// Optionally include an ident. This is needed due to macro hygiene.
 #[macro_export]
 #[doc(hidden)]
@@ -3,6 +3,7 @@
 use rustc_middle::ty::TyCtxt;
 
 use super::run;
+use crate::mir::interchange::CrateMir;
 
 pub fn write_smir_pretty<'tcx, W: io::Write>(tcx: TyCtxt<'tcx>, w: &mut W) -> io::Result<()> {
     writeln!(
@@ -19,3 +20,10 @@ pub fn write_smir_pretty<'tcx, W: io::Write>(tcx: TyCtxt<'tcx>, w: &mut W) -> io
     });
     Ok(())
 }
+
+/// Write the MIR of the local crate in the versioned JSON format described by
+/// [`CrateMir`](crate::mir::interchange::CrateMir).
+pub fn write_public_mir_json<'tcx, W: io::Write>(tcx: TyCtxt<'tcx>, w: &mut W) -> io::Result<()> {
+    run(tcx, || CrateMir::collect().write_json(w))
+        .map_err(|err| io::Error::other(err.to_string()))?
+}
@@ -98,6 +98,46 @@ pub fn unsigned_ty(inner: UintTy) -> Ty {
     pub fn layout(self) -> Result<Layout, Error> {
         with(|cx| cx.ty_layout(self))
     }
+
+    /// Check whether this type implements the trait `def` with the given generic arguments
+    /// (excluding `Self`) in `param_env`.
+    pub fn implements_trait(self, def: TraitDef, args: &GenericArgs, param_env: ParamEnv) -> bool {
+        TraitRef::new(def, self, args).holds(param_env)
+    }
+
+    /// Check whether this type implements `Send` in `param_env`.
+    pub fn is_send(self, param_env: ParamEnv) -> bool {
+        with(|cx| cx.is_send(self, param_env))
+    }
+
+    /// Check whether this type implements `Freeze` in `param_env`, i.e., whether it has no
+    /// interior mutability that is not behind a pointer.
+    pub fn is_freeze(self, param_env: ParamEnv) -> bool {
+        with(|cx| cx.is_freeze(self, param_env))
+    }
+
+    /// Normalize all associated type projections in this type in `param_env`.
+    ///
+    /// Regions are erased in the resulting type.
+    pub fn normalize(self, param_env: ParamEnv) -> Result<Ty, Error> {
+        with(|cx| cx.normalize(self, param_env))
+    }
+}
+
+/// The where-clauses that are assumed to hold when asking the trait solver questions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum ParamEnv {
+    /// Nothing is assumed, so the types involved must not contain any generic parameters.
+    Empty,
+    /// The where-clauses of the given item and of its parents are assumed.
+    Item(DefId),
+}
+
+impl ParamEnv {
+    /// The environment of `item`, in which its generic parameters are in scope.
+    pub fn of<D: CrateDef>(item: D) -> ParamEnv {
+        ParamEnv::Item(item.def_id())
+    }
 }
 
 impl Ty {
@@ -1449,6 +1489,12 @@ pub fn self_ty(&self) -> Ty {
         };
         self_ty
     }
+
+    /// Check whether this trait reference holds in `param_env`, i.e., whether the self type
+    /// implements the trait.
+    pub fn holds(&self, param_env: ParamEnv) -> bool {
+        with(|cx| cx.trait_ref_holds(self, param_env))
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
@@ -10,14 +10,15 @@
 
 use crate::abi::Layout;
 use crate::compiler_interface::BridgeTys;
+use crate::diagnostic::Level;
 use crate::mir::alloc::AllocId;
 use crate::mir::mono::{Instance, MonoItem, StaticDef};
 use crate::mir::{BinOp, Mutability, Place, ProjectionElem, RawPtrKind, Safety, UnOp};
 use crate::ty::{
     Abi, AdtDef, Binder, BoundRegionKind, BoundTyKind, BoundVariableKind, ClosureKind,
     ExistentialPredicate, ExistentialProjection, ExistentialTraitRef, FloatTy, FnSig,
-    GenericArgKind, GenericArgs, IntTy, MirConst, Movability, Pattern, Region, RigidTy, Span,
-    TermKind, TraitRef, Ty, TyConst, UintTy, VariantDef, VariantIdx,
+    GenericArgKind, GenericArgs, IntTy, MirConst, Movability, ParamEnv, Pattern, Region, RigidTy,
+    Span, TermKind, TraitRef, Ty, TyConst, UintTy, VariantDef, VariantIdx,
 };
 use crate::unstable::{InternalCx, RustcInternal};
 use crate::{CrateItem, CrateNum, DefId, IndexedVal};
@@ -647,6 +648,38 @@ fn internal<'tcx>(
     }
 }
 
+impl RustcInternal for ParamEnv {
+    type T<'tcx> = Option<rustc_span::def_id::DefId>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            ParamEnv::Empty => None,
+            ParamEnv::Item(def_id) => Some(tables[*def_id]),
+        }
+    }
+}
+
+impl RustcInternal for Level {
+    type T<'tcx> = rustc_errors::Level;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            Level::Error => rustc_errors::Level::Error,
+            Level::Warning => rustc_errors::Level::Warning,
+            Level::Note => rustc_errors::Level::Note,
+            Level::Help => rustc_errors::Level::Help,
+        }
+    }
+}
+
 impl RustcInternal for Layout {
     type T<'tcx> = rustc_abi::Layout<'tcx>;
 
@@ -5,6 +5,7 @@
 use std::iter;
 
 use rustc_abi::{Endian, Layout, ReprOptions};
+use rustc_errors::{Diag, Level, MultiSpan};
 use rustc_hir::def::DefKind;
 use rustc_hir::{Attribute, LangItem};
 use rustc_middle::mir::interpret::{AllocId, ConstAllocation, ErrorHandled, GlobalAlloc, Scalar};
@@ -23,8 +24,9 @@
 use rustc_middle::{mir, ty};
 use rustc_session::cstore::ForeignModule;
 use rustc_span::def_id::{CrateNum, DefId, LOCAL_CRATE};
-use rustc_span::{Span, Symbol};
+use rustc_span::{Span, Symbol, sym};
 use rustc_target::callconv::FnAbi;
+use rustc_trait_selection::infer::{InferCtxtExt, TyCtxtInferExt};
 
 use super::{AllocRangeHelpers, CompilerCtxt, TyHelpers, TypingEnvHelpers};
 use crate::builder::BodyBuilder;
@@ -322,6 +324,24 @@ pub fn span_to_string(&self, span: Span) -> String {
         self.tcx.sess.source_map().span_to_diagnostic_string(span)
     }
 
+    /// Emit a diagnostic with the given notes and help messages attached.
+    pub fn emit_diagnostic(
+        &self,
+        level: Level,
+        message: String,
+        span: Option<Span>,
+        children: Vec<(Level, String, Option<Span>)>,
+    ) {
+        let mut diag = Diag::<()>::new(self.tcx.dcx(), level, message);
+        if let Some(span) = span {
+            diag.span(span);
+        }
+        for (level, message, span) in children {
+            diag.sub(level, message, span.map_or_else(MultiSpan::new, MultiSpan::from_span));
+        }
+        diag.emit();
+    }
+
     /// Return filename from given `Span`, for diagnostic purposes.
     pub fn get_filename(&self, span: Span) -> String {
         self.tcx.sess.source_map().span_to_filename(span).prefer_local_unconditionally().to_string()
@@ -647,12 +667,61 @@ pub fn resolve_instance(
         def_id: DefId,
         args_ref: GenericArgsRef<'tcx>,
     ) -> Option<Instance<'tcx>> {
-        match Instance::try_resolve(self.tcx, self.fully_monomorphized(), def_id, args_ref) {
+        self.resolve_instance_in(def_id, args_ref, None)
+    }
+
+    /// Resolve an instance from the given function definition and generic arguments, assuming
+    /// the where-clauses of `item` hold.
+    pub fn resolve_instance_in(
+        &self,
+        def_id: DefId,
+        args_ref: GenericArgsRef<'tcx>,
+        item: Option<DefId>,
+    ) -> Option<Instance<'tcx>> {
+        match Instance::try_resolve(self.tcx, self.typing_env_of(item), def_id, args_ref) {
             Ok(Some(instance)) => Some(instance),
             Ok(None) | Err(_) => None,
         }
     }
 
+    /// The typing environment in which the where-clauses of `item` hold, or the fully
+    /// monomorphized one if there is no item.
+    fn typing_env_of(&self, item: Option<DefId>) -> ty::TypingEnv<'tcx> {
+        match item {
+            Some(def_id) => ty::TypingEnv::post_analysis(self.tcx, def_id),
+            None => self.fully_monomorphized(),
+        }
+    }
+
+    /// Check whether `trait_ref` holds, assuming the where-clauses of `item` hold.
+    pub fn trait_ref_holds(&self, trait_ref: TraitRef<'tcx>, item: Option<DefId>) -> bool {
+        let (infcx, param_env) =
+            self.tcx.infer_ctxt().build_with_typing_env(self.typing_env_of(item));
+        infcx
+            .type_implements_trait(trait_ref.def_id, trait_ref.args, param_env)
+            .must_apply_modulo_regions()
+    }
+
+    /// Check whether `ty` is `Send`, assuming the where-clauses of `item` hold.
+    pub fn is_send(&self, ty: Ty<'tcx>, item: Option<DefId>) -> bool {
+        self.tcx
+            .get_diagnostic_item(sym::Send)
+            .is_some_and(|send| self.trait_ref_holds(TraitRef::new(self.tcx, send, [ty]), item))
+    }
+
+    /// Check whether `ty` is `Freeze`, assuming the where-clauses of `item` hold.
+    pub fn is_freeze(&self, ty: Ty<'tcx>, item: Option<DefId>) -> bool {
+        ty.is_freeze(self.tcx, self.typing_env_of(item))
+    }
+
+    /// Normalize all associated type projections in `ty`, assuming the where-clauses of `item`
+    /// hold.
+    pub fn normalize(&self, ty: Ty<'tcx>, item: Option<DefId>) -> Result<Ty<'tcx>, B::Error> {
+        self.tcx
+            .try_normalize_erasing_regions(self.typing_env_of(item), ty)
+            .map_err(|err| B::Error::new(format!("Failed to normalize `{ty}`: {err:?}")))
+    }
+
     /// Resolve an instance for drop_in_place for the given type.
     pub fn resolve_drop_in_place(&self, internal_ty: Ty<'tcx>) -> Instance<'tcx> {
         let instance = Instance::resolve_drop_in_place(self.tcx, internal_ty);
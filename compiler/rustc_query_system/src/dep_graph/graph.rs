@@ -107,6 +107,10 @@ pub(crate) struct DepGraphData<D: Deps> {
     /// a particular query result was decoded from disk
     /// (not just marked green)
     debug_loaded_from_disk: Lock<FxHashSet<DepNode>>,
+
+    /// The nodes of the previous dep-graph whose queries were re-executed in this session,
+    /// recorded with `-Z incremental-explain` to explain why they were re-executed.
+    reexecuted_nodes: Option<Lock<Vec<SerializedDepNodeIndex>>>,
 }
 
 pub fn hash_result<R>(hcx: &mut StableHashingContext<'_>, result: &R) -> Fingerprint
@@ -161,6 +165,11 @@ pub fn new(
                 previous: prev_graph,
                 colors,
                 debug_loaded_from_disk: Default::default(),
+                reexecuted_nodes: session
+                    .opts
+                    .unstable_opts
+                    .incremental_explain
+                    .then(Default::default),
             })),
             virtual_dep_node_index: Arc::new(AtomicU32::new(0)),
         }
@@ -358,6 +367,9 @@ pub(crate) fn with_task<Ctxt: HasDepContext<Deps = D>, A: Debug, R>(
 
         let dcx = cx.dep_context();
         let dep_node_index = self.hash_result_and_alloc_node(dcx, key, edges, &result, hash_result);
+        if self.reexecuted_nodes.is_some() {
+            self.record_reexecuted(&key, dcx.is_eval_always(key.kind));
+        }
 
         (result, dep_node_index)
     }
@@ -608,7 +620,12 @@ pub fn with_feed_task<Ctxt: DepContext<Deps = D>, R: Debug>(
                 }
             });
 
-            data.hash_result_and_alloc_node(&cx, node, edges, result, hash_result)
+            let dep_node_index =
+                data.hash_result_and_alloc_node(&cx, node, edges, result, hash_result);
+            if data.reexecuted_nodes.is_some() {
+                data.record_reexecuted(&node, true);
+            }
+            dep_node_index
         } else {
             // Incremental compilation is turned off. We just execute the task
             // without tracking. We still provide a dep-node index that uniquely
@@ -771,6 +788,20 @@ fn alloc_and_color_node(
         }
     }
 
+    /// Records that the node of `key` from the previous session was re-executed, or fed, for
+    /// `-Z incremental-explain`. The nodes that are re-executed or fed in every session are only
+    /// recorded when they changed, as they only explain re-executions then.
+    #[cold]
+    fn record_reexecuted(&self, key: &DepNode, in_every_session: bool) {
+        if let Some(reexecuted_nodes) = &self.reexecuted_nodes
+            && let Some(prev_index) = self.previous.node_to_index_opt(key)
+        {
+            if !in_every_session || matches!(self.colors.get(prev_index), DepNodeColor::Red) {
+                reexecuted_nodes.lock().push(prev_index);
+            }
+        }
+    }
+
     fn promote_node_and_deps_to_current(&self, prev_index: SerializedDepNodeIndex) -> DepNodeIndex {
         self.current.debug_assert_not_in_new_nodes(&self.previous, prev_index);
 
@@ -1061,6 +1092,78 @@ pub fn exec_cache_promotions<Tcx: DepContext>(&self, tcx: Tcx) {
         }
     }
 
+    /// Explains why the queries of the previous session that were re-executed in this session
+    /// had to be re-executed, by finding the changes that they depend on. Returns `None` unless
+    /// the re-executed queries were recorded, with `-Z incremental-explain`.
+    ///
+    /// A query is re-executed when one of its dependencies changed, that is, when the dependency
+    /// was itself re-executed and its result differs from the previous session. The changes that
+    /// do not come from changed dependencies are the changes of the inputs of the compilation,
+    /// which are re-executed in every session. `is_input` tells which other nodes are reported as
+    /// changes, like the HIR of an item, which is projected from the HIR of the whole crate. Every
+    /// change is given with the queries that were re-executed because of it, through the changed
+    /// queries in between that are not changes themselves.
+    pub fn explain_reexecution(
+        &self,
+        is_input: impl Fn(DepNode) -> bool,
+    ) -> Option<ReexecutionExplanation> {
+        let data = self.data.as_deref()?;
+        let reexecuted_nodes = data.reexecuted_nodes.as_ref()?.lock();
+
+        // Side effects depend on the forever red node, which is not a change.
+        let forever_red = SerializedDepNodeIndex::from_u32(DepNodeIndex::FOREVER_RED_NODE.as_u32());
+        let is_red =
+            |index| index != forever_red && matches!(data.colors.get(index), DepNodeColor::Red);
+
+        // Only re-executed nodes can be red, so the red dependencies of the re-executed nodes are
+        // all the edges that can have caused a re-execution.
+        let mut dependents: FxHashMap<_, Vec<_>> = FxHashMap::default();
+        let mut changes = Vec::new();
+        let mut unexplained = Vec::new();
+        for &index in reexecuted_nodes.iter() {
+            let mut has_red_dependency = false;
+            for dependency in data.previous.edge_targets_from(index) {
+                if is_red(dependency) {
+                    has_red_dependency = true;
+                    dependents.entry(dependency).or_default().push(index);
+                }
+            }
+            if is_red(index)
+                && (!has_red_dependency || is_input(data.previous.index_to_node(index)))
+            {
+                changes.push(index);
+            } else if !has_red_dependency {
+                unexplained.push(data.previous.index_to_node(index));
+            }
+        }
+
+        let is_change: FxHashSet<_> = changes.iter().copied().collect();
+        let causes = changes
+            .iter()
+            .map(|&change| {
+                let mut seen = FxHashSet::default();
+                let mut stack = vec![change];
+                let mut reexecuted = Vec::new();
+                while let Some(index) = stack.pop() {
+                    for &dependent in dependents.get(&index).into_iter().flatten() {
+                        if seen.insert(dependent) {
+                            reexecuted.push(data.previous.index_to_node(dependent));
+                            // Dependents whose result did not change were not re-executed
+                            // because of the change in turn, and the dependents of other changes
+                            // are given with them.
+                            if is_red(dependent) && !is_change.contains(&dependent) {
+                                stack.push(dependent);
+                            }
+                        }
+                    }
+                }
+                ReexecutionCause { changed: data.previous.index_to_node(change), reexecuted }
+            })
+            .collect();
+
+        Some(ReexecutionExplanation { causes, unexplained })
+    }
+
     pub fn finish_encoding(&self) -> FileEncodeResult {
         if let Some(data) = &self.data { data.current.encoder.finish(&data.current) } else { Ok(0) }
     }
@@ -1072,6 +1175,25 @@ pub(crate) fn next_virtual_depnode_index(&self) -> DepNodeIndex {
     }
 }
 
+/// Why the queries of the previous session were re-executed, as found by
+/// [`DepGraph::explain_reexecution`].
+#[derive(Debug)]
+pub struct ReexecutionExplanation {
+    /// The changes that caused queries to be re-executed.
+    pub causes: Vec<ReexecutionCause>,
+    /// The re-executed nodes that depend on no change, like the nodes that depend on nodes that
+    /// no longer exist.
+    pub unexplained: Vec<DepNode>,
+}
+
+/// A change, that is a node whose result changed without any of its dependencies having changed,
+/// or an input whose result changed, with the nodes that were re-executed because of it.
+#[derive(Debug)]
+pub struct ReexecutionCause {
+    pub changed: DepNode,
+    pub reexecuted: Vec<DepNode>,
+}
+
 /// A "work product" is an intermediate result that we save into the
 /// incremental directory for later re-use. The primary example are
 /// the object files that we save for each partition at code
@@ -81,6 +81,10 @@ session_invalid_num_literal_suffix = invalid suffix `{$suffix}` for number liter
 
 session_linker_plugin_lto_windows_not_supported = linker plugin based LTO is not supported together with `-C prefer-dynamic` when targeting Windows-like targets
 
+session_lint_baseline_read_failed = failed to read the lint baseline `{$path}`: {$err}
+
+session_lint_baseline_write_failed = failed to write the lint baseline `{$path}`: {$err}
+
 session_must_be_name_of_associated_function = must be a name of an associated function
 
 session_not_circumvent_feature = `-Zunleash-the-miri-inside-of-you` may not be used to circumvent feature gates, except when testing error paths in the CTFE engine
@@ -147,3 +151,5 @@ session_unsupported_dwarf_version_help = supported DWARF versions are 2, 3, 4 an
 session_unsupported_reg_struct_return_arch = `-Zreg-struct-return` is only supported on x86
 session_unsupported_regparm = `-Zregparm={$regparm}` is unsupported (valid values 0-3)
 session_unsupported_regparm_arch = `-Zregparm=N` is only supported on x86
+
+session_write_lint_baseline_requires_lint_baseline = `-Zwrite-lint-baseline` requires `-Zlint-baseline`
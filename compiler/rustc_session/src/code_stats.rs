@@ -1,9 +1,10 @@
 use std::cmp;
 
 use rustc_abi::{Align, Size};
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lock;
 use rustc_span::Symbol;
+use rustc_span::def_id::DefId;
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct VariantInfo {
@@ -75,6 +76,9 @@ pub struct CodeStats {
     /// The hash set that actually holds all the type size information.
     /// The field is public for use in external tools. See #139876.
     pub type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
+    /// The number of LLVM instructions of the monomorphized functions of each definition, before
+    /// they are optimized, for `-Z dump-mono-stats`.
+    llvm_ir_sizes: Lock<FxHashMap<DefId, usize>>,
 }
 
 impl CodeStats {
@@ -108,6 +112,16 @@ pub fn record_type_size<S: ToString>(
         self.type_sizes.borrow_mut().insert(info);
     }
 
+    pub fn record_llvm_ir_size(&self, def_id: DefId, instructions: usize) {
+        *self.llvm_ir_sizes.borrow_mut().entry(def_id).or_default() += instructions;
+    }
+
+    /// Returns the number of LLVM instructions recorded for the functions of `def_id`, if the
+    /// codegen backend recorded any.
+    pub fn llvm_ir_size(&self, def_id: DefId) -> Option<usize> {
+        self.llvm_ir_sizes.borrow().get(&def_id).copied()
+    }
+
     pub fn print_type_sizes(&self) {
         let type_sizes = self.type_sizes.borrow();
         // We will soon sort, so the initial order does not matter.
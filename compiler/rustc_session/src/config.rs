@@ -1,6 +1,7 @@
 //! Contains infrastructure for configuring the compiler, including parsing
 //! command-line options.
 
+use std::borrow::Cow;
 use std::collections::btree_map::{
     Iter as BTreeMapIter, Keys as BTreeMapKeysIter, Values as BTreeMapValuesIter,
 };
@@ -822,6 +823,8 @@ pub enum ErrorOutputType {
         json_rendered: HumanReadableErrorType,
         color_config: ColorConfig,
     },
+    /// A single SARIF 2.1.0 log, consumed by code-scanning tools.
+    Sarif,
 }
 
 #[derive(Clone, Hash, Debug)]
@@ -1465,6 +1468,46 @@ pub fn file_path_mapping(&self) -> FilePathMapping {
         file_path_mapping(self.remap_path_prefix.clone(), self.remap_path_scope)
     }
 
+    /// The hash of the options that an incremental compilation session must share with the
+    /// previous one to reuse its results, like `dep_tracking_hash(false)`.
+    ///
+    /// When paths are remapped in every scope, the paths of the options are hashed in their
+    /// remapped form, like the names of source files and the working directory are. Checkouts of
+    /// a project in different directories, remapped to the same path, then get the same hash and
+    /// can share their incremental compilation caches.
+    pub fn incremental_dep_tracking_hash(&self) -> Hash64 {
+        self.incremental_options().dep_tracking_hash(false)
+    }
+
+    /// The hash of each option that `incremental_dep_tracking_hash` covers, by name.
+    pub fn incremental_dep_tracking_hashes(&self) -> BTreeMap<String, Hash64> {
+        self.incremental_options().dep_tracking_hashes(false)
+    }
+
+    /// The options hashed by `incremental_dep_tracking_hash`.
+    fn incremental_options(&self) -> Cow<'_, Options> {
+        if self.remap_path_scope != RemapPathScopeComponents::all() {
+            return Cow::Borrowed(self);
+        }
+        let file_mapping = self.file_path_mapping();
+        let remap = |path: &PathBuf| {
+            file_mapping
+                .to_real_filename(&RealFileName::empty(), path)
+                .path(RemapPathScopeComponents::DIAGNOSTICS)
+                .to_path_buf()
+        };
+
+        let mut relocated = self.clone();
+        // Which paths are remapped shows in the names of source files, so only the paths that
+        // they are remapped to are hashed.
+        relocated.remap_path_prefix =
+            self.remap_path_prefix.iter().map(|(_, to)| (to.clone(), to.clone())).collect();
+        relocated.real_rust_source_base_dir = self.real_rust_source_base_dir.as_ref().map(remap);
+        relocated.real_rustc_dev_source_base_dir =
+            self.real_rustc_dev_source_base_dir.as_ref().map(remap);
+        Cow::Owned(relocated)
+    }
+
     /// Returns `true` if there will be an output file generated.
     pub fn will_create_output_file(&self) -> bool {
         !self.unstable_opts.parse_crate_root_only && // The file is just being parsed
@@ -2051,6 +2094,7 @@ pub fn parse_error_format(
             Some("pretty-json") => {
                 ErrorOutputType::Json { pretty: true, json_rendered, color_config: json_color }
             }
+            Some("sarif") => ErrorOutputType::Sarif,
             Some("short") => ErrorOutputType::HumanReadable {
                 kind: HumanReadableErrorType { short: true, unicode: false },
                 color_config,
@@ -2066,7 +2110,7 @@ pub fn parse_error_format(
                 });
                 early_dcx.early_fatal(format!(
                     "argument for `--error-format` must be `human`, `human-unicode`, \
-                    `json`, `pretty-json` or `short` (instead was `{arg}`)"
+                    `json`, `pretty-json`, `sarif` or `short` (instead was `{arg}`)"
                 ))
             }
         }
@@ -2127,6 +2171,7 @@ fn check_error_format_stability(
     }
     let format = match format {
         ErrorOutputType::Json { pretty: true, .. } => "pretty-json",
+        ErrorOutputType::Sarif => "sarif",
         ErrorOutputType::HumanReadable { kind, .. } => match kind {
             HumanReadableErrorType { unicode: true, .. } => "human-unicode",
             _ => return,
@@ -3284,6 +3329,21 @@ pub(crate) fn stable_hash(
             sub_hash.hash(hasher, error_format, for_crate_hash);
         }
     }
+
+    /// Hashes each of `sub_hashes` on its own, under its key preceded by `prefix`.
+    pub(crate) fn individual_hashes(
+        prefix: &str,
+        sub_hashes: BTreeMap<&'static str, &dyn DepTrackingHash>,
+        hashes: &mut BTreeMap<String, Hash64>,
+        error_format: ErrorOutputType,
+        for_crate_hash: bool,
+    ) {
+        for (key, sub_hash) in sub_hashes {
+            let mut hasher = StableHasher::new();
+            sub_hash.hash(&mut hasher, error_format, for_crate_hash);
+            hashes.insert(format!("{prefix}{key}"), hasher.finish());
+        }
+    }
 }
 
 /// How to run proc-macro code when building this crate
@@ -3314,6 +3374,21 @@ pub fn extension(self) -> &'static str {
     }
 }
 
+/// Which format to use for `-Z dump-public-mir`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum PublicMirFormat {
+    /// Emit the versioned JSON format of `rustc_public::mir::interchange`
+    Json,
+}
+
+impl PublicMirFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+        }
+    }
+}
+
 /// `-Z patchable-function-entry` representation - how many nops to put before and after function
 /// entry.
 #[derive(Clone, Copy, PartialEq, Hash, Debug, Default)]
@@ -496,6 +496,24 @@ pub(crate) struct FailedToCreateProfiler {
     pub(crate) err: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(session_lint_baseline_read_failed)]
+pub(crate) struct LintBaselineReadFailed<'a> {
+    pub(crate) path: &'a std::path::Path,
+    pub(crate) err: std::io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_lint_baseline_write_failed)]
+pub(crate) struct LintBaselineWriteFailed<'a> {
+    pub(crate) path: &'a std::path::Path,
+    pub(crate) err: std::io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_write_lint_baseline_requires_lint_baseline)]
+pub(crate) struct WriteLintBaselineRequiresLintBaseline;
+
 #[derive(Diagnostic)]
 #[diag(session_soft_float_ignored)]
 #[note]
@@ -61,6 +61,21 @@ macro_rules! hash_substruct {
     };
 }
 
+macro_rules! hash_substruct_individually {
+    ($opt_name:ident, $opt_expr:expr, $error_format:expr, $for_crate_hash:expr, $hashes:expr, [UNTRACKED]) => {{}};
+    ($opt_name:ident, $opt_expr:expr, $error_format:expr, $for_crate_hash:expr, $hashes:expr, [TRACKED]) => {{}};
+    ($opt_name:ident, $opt_expr:expr, $error_format:expr, $for_crate_hash:expr, $hashes:expr, [TRACKED_NO_CRATE_HASH]) => {{}};
+    ($opt_name:ident, $opt_expr:expr, $error_format:expr, $for_crate_hash:expr, $hashes:expr, [SUBSTRUCT]) => {
+        dep_tracking::individual_hashes(
+            concat!(stringify!($opt_name), "."),
+            $opt_expr.tracked_options($for_crate_hash),
+            $hashes,
+            $error_format,
+            $for_crate_hash,
+        );
+    };
+}
+
 /// Extended target modifier info.
 /// For example, when external target modifier is '-Zregparm=2':
 /// Target modifier enum value + user value ('2') from external crate
@@ -323,7 +338,10 @@ pub struct Options {
         }
 
         impl Options {
-            pub fn dep_tracking_hash(&self, for_crate_hash: bool) -> Hash64 {
+            fn tracked_options(
+                &self,
+                for_crate_hash: bool,
+            ) -> BTreeMap<&'static str, &dyn dep_tracking::DepTrackingHash> {
                 let mut sub_hashes = BTreeMap::new();
                 $({
                     hash_opt!($opt,
@@ -332,8 +350,12 @@ pub fn dep_tracking_hash(&self, for_crate_hash: bool) -> Hash64 {
                                 for_crate_hash,
                                 [$dep_tracking_marker]);
                 })*
+                sub_hashes
+            }
+
+            pub fn dep_tracking_hash(&self, for_crate_hash: bool) -> Hash64 {
                 let mut hasher = StableHasher::new();
-                dep_tracking::stable_hash(sub_hashes,
+                dep_tracking::stable_hash(self.tracked_options(for_crate_hash),
                                           &mut hasher,
                                           self.error_format,
                                           for_crate_hash);
@@ -348,6 +370,26 @@ pub fn dep_tracking_hash(&self, for_crate_hash: bool) -> Hash64 {
                 hasher.finish()
             }
 
+            /// The hash of each option that `dep_tracking_hash` covers, by name. The options of
+            /// `-C` and `-Z` are named after their field, like `cg.opt_level`.
+            pub fn dep_tracking_hashes(&self, for_crate_hash: bool) -> BTreeMap<String, Hash64> {
+                let mut hashes = BTreeMap::new();
+                dep_tracking::individual_hashes("",
+                                                self.tracked_options(for_crate_hash),
+                                                &mut hashes,
+                                                self.error_format,
+                                                for_crate_hash);
+                $({
+                    hash_substruct_individually!($opt,
+                        &self.$opt,
+                        self.error_format,
+                        for_crate_hash,
+                        &mut hashes,
+                        [$dep_tracking_marker]);
+                })*
+                hashes
+            }
+
             pub fn gather_target_modifiers(&self) -> Vec<TargetModifier> {
                 let mut mods = Vec::<TargetModifier>::new();
                 $({
@@ -635,17 +677,24 @@ pub fn build(
             build_options(early_dcx, matches, target_modifiers, $stat, $prefix, $outputname)
         }
 
-        fn dep_tracking_hash(&self, for_crate_hash: bool, error_format: ErrorOutputType) -> Hash64 {
+        fn tracked_options(
+            &self,
+            _for_crate_hash: bool,
+        ) -> BTreeMap<&'static str, &dyn dep_tracking::DepTrackingHash> {
             let mut sub_hashes = BTreeMap::new();
             $({
                 hash_opt!($opt,
                             &self.$opt,
                             &mut sub_hashes,
-                            for_crate_hash,
+                            _for_crate_hash,
                             [$dep_tracking_marker]);
             })*
+            sub_hashes
+        }
+
+        fn dep_tracking_hash(&self, for_crate_hash: bool, error_format: ErrorOutputType) -> Hash64 {
             let mut hasher = StableHasher::new();
-            dep_tracking::stable_hash(sub_hashes,
+            dep_tracking::stable_hash(self.tracked_options(for_crate_hash),
                                         &mut hasher,
                                         error_format,
                                         for_crate_hash
@@ -824,6 +873,7 @@ mod desc {
     pub(crate) const parse_strip: &str = "either `none`, `debuginfo`, or `symbols`";
     pub(crate) const parse_linker_flavor: &str = ::rustc_target::spec::LinkerFlavorCli::one_of();
     pub(crate) const parse_dump_mono_stats: &str = "`markdown` (default) or `json`";
+    pub(crate) const parse_public_mir_format: &str = "`json`";
     pub(crate) const parse_instrument_coverage: &str = parse_bool;
     pub(crate) const parse_coverage_options: &str = "`block` | `branch` | `condition`";
     pub(crate) const parse_instrument_xray: &str = "either a boolean (`yes`, `no`, `on`, `off`, etc), or a comma separated list of settings: `always` or `never` (mutually exclusive), `ignore-loops`, `instruction-threshold=N`, `skip-entry`, `skip-exit`";
@@ -1429,6 +1479,19 @@ pub(crate) fn parse_time_passes_format(slot: &mut TimePassesFormat, v: Option<&s
         }
     }
 
+    pub(crate) fn parse_public_mir_format(
+        slot: &mut Option<PublicMirFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            Some("json") => {
+                *slot = Some(PublicMirFormat::Json);
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub(crate) fn parse_dump_mono_stats(slot: &mut DumpMonoStatsFormat, v: Option<&str>) -> bool {
         match v {
             None => true,
@@ -2319,6 +2382,9 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         "output statistics about monomorphization collection"),
     dump_mono_stats_format: DumpMonoStatsFormat = (DumpMonoStatsFormat::Markdown, parse_dump_mono_stats, [UNTRACKED],
         "the format to use for -Z dump-mono-stats (`markdown` (default) or `json`)"),
+    dump_public_mir: Option<PublicMirFormat> = (None, parse_public_mir_format, [UNTRACKED],
+        "write the MIR of the local crate, together with the types and allocations it refers to, \
+        to `<crate>.public_mir.<format>` in the output directory (only `json` is supported)"),
     #[rustc_lint_opt_deny_field_access("use `Session::dwarf_version` instead of this field")]
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
@@ -2387,11 +2453,17 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         "display unnamed regions as `'<id>`, using a non-ident unique id (default: no)"),
     ignore_directory_in_diagnostics_source_blocks: Vec<String> = (Vec::new(), parse_string_push, [UNTRACKED],
         "do not display the source code block in diagnostics for files in the directory"),
+    incremental_explain: bool = (false, parse_bool, [UNTRACKED],
+        "print which changes caused queries and codegen units to be re-executed by incremental \
+        compilation (default: no)"),
     incremental_ignore_spans: bool = (false, parse_bool, [TRACKED],
         "ignore spans during ICH computation -- used for testing (default: no)"),
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
         "print high-level information about incremental reuse (or the lack thereof) \
         (default: no)"),
+    incremental_shared_cache: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "a read-only incremental compilation directory to start from when the incremental \
+        directory has no previous session for the crate"),
     incremental_verify_ich: bool = (false, parse_bool, [UNTRACKED],
         "verify extended properties for incr. comp. (default: no):
         - hashes of green query instances
@@ -2439,6 +2511,8 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         "link the `.rlink` file generated by `-Z no-link` (default: no)"),
     lint_llvm_ir: bool = (false, parse_bool, [TRACKED],
         "lint LLVM IR (default: no)"),
+    lint_baseline: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "do not report the lint diagnostics listed in this baseline file"),
     lint_mir: bool = (false, parse_bool, [UNTRACKED],
         "lint MIR before and after each transformation"),
     llvm_module_flag: Vec<(String, u32, String)> = (Vec::new(), parse_llvm_module_flag, [TRACKED],
@@ -2792,6 +2866,9 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
     // FIXME remove this after a couple releases
     wasm_c_abi: () = ((), parse_wasm_c_abi, [TRACKED],
         "use spec-compliant C ABI for `wasm32-unknown-unknown` (deprecated, always enabled)"),
+    write_lint_baseline: bool = (false, parse_bool, [UNTRACKED],
+        "write the lint diagnostics of the crate to the file of `-Z lint-baseline` instead of \
+        reporting them (default: no)"),
     write_long_types_to_disk: bool = (true, parse_bool, [UNTRACKED],
         "whether long type names should be written to files instead of being printed in errors"),
     // tidy-alphabetical-end
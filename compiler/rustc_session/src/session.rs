@@ -15,11 +15,12 @@
 use rustc_errors::codes::*;
 use rustc_errors::emitter::{DynEmitter, HumanReadableErrorType, OutputTheme, stderr_destination};
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::timings::TimingSectionHandler;
 use rustc_errors::translation::Translator;
 use rustc_errors::{
     Diag, DiagCtxt, DiagCtxtHandle, DiagMessage, Diagnostic, ErrorGuaranteed, FatalAbort,
-    TerminalUrl, fallback_fluent_bundle,
+    LintBaseline, TerminalUrl, fallback_fluent_bundle,
 };
 use rustc_hir::limit::Limit;
 use rustc_macros::HashStable_Generic;
@@ -82,6 +83,9 @@ pub struct CompilerIO {
 pub trait DynLintStore: Any + DynSync + DynSend {
     /// Provides a way to access lint groups without depending on `rustc_lint`
     fn lint_groups_iter(&self) -> Box<dyn Iterator<Item = LintGroup> + '_>;
+
+    /// The names of the lint groups that contain `lint`, without the deprecated ones.
+    fn lint_groups_of(&self, lint: LintId) -> &[&'static str];
 }
 
 /// Represents the data associated with a compilation
@@ -229,6 +233,15 @@ pub fn finish_diagnostics(&self) -> Option<ErrorGuaranteed> {
         let mut guar = None;
         guar = guar.or(self.check_miri_unleashed_features());
         guar = guar.or(self.dcx().emit_stashed_diagnostics());
+        // A compilation that stopped early did not emit all of its lints, so its baseline would
+        // drop entries that are still needed.
+        if self.dcx().has_errors_excluding_lint_errors().is_none()
+            && let Err(err) = self.dcx().write_lint_baseline()
+        {
+            let path = self.opts.unstable_opts.lint_baseline.as_deref().unwrap();
+            guar =
+                guar.or(Some(self.dcx().emit_err(errors::LintBaselineWriteFailed { path, err })));
+        }
         self.dcx().print_error_count();
         if self.opts.json_future_incompat {
             self.dcx().emit_future_breakage_report();
@@ -549,6 +562,13 @@ pub fn lint_groups_iter(&self) -> Box<dyn Iterator<Item = LintGroup> + '_> {
             None => Box::new(std::iter::empty()),
         }
     }
+
+    pub fn lint_groups_of(&self, lint: LintId) -> &[&'static str] {
+        match self.lint_store {
+            Some(ref lint_store) => lint_store.lint_groups_of(lint),
+            None => &[],
+        }
+    }
 }
 
 // JUSTIFICATION: defn of the suggested wrapper fns
@@ -896,6 +916,7 @@ fn default_emitter(
     sopts: &config::Options,
     source_map: Arc<SourceMap>,
     translator: Translator,
+    cfg_version: &'static str,
 ) -> Box<DynEmitter> {
     let macro_backtrace = sopts.unstable_opts.macro_backtrace;
     let track_diagnostics = sopts.unstable_opts.track_diagnostics;
@@ -954,6 +975,13 @@ fn default_emitter(
             .track_diagnostics(track_diagnostics)
             .terminal_url(terminal_url),
         ),
+        config::ErrorOutputType::Sarif => Box::new(
+            SarifEmitter::new(Box::new(io::BufWriter::new(io::stderr())), source_map, translator)
+                .tool_version(cfg_version.to_owned())
+                .ignored_directories_in_source_blocks(
+                    sopts.unstable_opts.ignore_directory_in_diagnostics_source_blocks.clone(),
+                ),
+        ),
     }
 }
 
@@ -990,7 +1018,7 @@ pub fn build_session(
         ),
     };
     let source_map = rustc_span::source_map::get_source_map().unwrap();
-    let emitter = default_emitter(&sopts, Arc::clone(&source_map), translator);
+    let emitter = default_emitter(&sopts, Arc::clone(&source_map), translator, cfg_version);
 
     let mut dcx = DiagCtxt::new(emitter)
         .with_flags(sopts.unstable_opts.dcx_flags(can_emit_warnings))
@@ -998,6 +1026,14 @@ pub fn build_session(
     if let Some(ice_file) = ice_file {
         dcx = dcx.with_ice_file(ice_file);
     }
+    if let Some(path) = &sopts.unstable_opts.lint_baseline {
+        match LintBaseline::load(path.clone(), sopts.unstable_opts.write_lint_baseline) {
+            Ok(lint_baseline) => dcx = dcx.with_lint_baseline(lint_baseline),
+            Err(err) => {
+                dcx.handle().emit_fatal(errors::LintBaselineReadFailed { path, err });
+            }
+        }
+    }
 
     let host_triple = TargetTuple::from_tuple(config::host_tuple());
     let (host, target_warnings) =
@@ -1128,6 +1164,12 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
         }
     }
 
+    if sess.opts.unstable_opts.write_lint_baseline
+        && sess.opts.unstable_opts.lint_baseline.is_none()
+    {
+        sess.dcx().emit_err(errors::WriteLintBaselineRequiresLintBaseline);
+    }
+
     // Do the same for sample profile data.
     if let Some(ref path) = sess.opts.unstable_opts.profile_sample_use {
         if !path.exists() {
@@ -1447,6 +1489,11 @@ fn mk_emitter(output: ErrorOutputType) -> Box<DynEmitter> {
                 color_config,
             ))
         }
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::new(
+            Box::new(io::BufWriter::new(io::stderr())),
+            Some(Arc::new(SourceMap::new(FilePathMapping::empty()))),
+            translator,
+        )),
     };
     emitter
 }
@@ -919,6 +919,7 @@
         doc_masked,
         doc_notable_trait,
         doc_primitive,
+        doc_scrape_examples,
         doc_spotlight,
         doctest,
         dotdot: "..",
@@ -983,6 +984,7 @@
         except,
         exception_handling: "exception-handling",
         exchange_malloc,
+        exclude,
         exclusive_range_pattern,
         exhaustive_integer_patterns,
         exhaustive_patterns,
@@ -2053,6 +2055,7 @@
         saturating_add,
         saturating_div,
         saturating_sub,
+        scrape_examples,
         sdylib,
         search_unbox,
         select_unpredictable,
@@ -3,8 +3,9 @@
 use std::env;
 use std::io::{self, IsTerminal, Write};
 use std::path::PathBuf;
+use std::time::Duration;
 
-use super::options::{ColorConfig, Options, OutputFormat, RunIgnored};
+use super::options::{ColorConfig, Options, OutputFormat, RunIgnored, TestShard};
 use super::time::TestTimeOptions;
 
 #[derive(Debug)]
@@ -23,9 +24,13 @@ pub struct TestOpts {
     pub format: OutputFormat,
     pub shuffle: bool,
     pub shuffle_seed: Option<u64>,
+    pub shard: Option<TestShard>,
     pub test_threads: Option<usize>,
     pub skip: Vec<String>,
     pub time_options: Option<TestTimeOptions>,
+    /// Run every test in a subprocess, and kill and fail the tests that are still running
+    /// after this long.
+    pub test_timeout: Option<Duration>,
     /// Stop at first failing test.
     /// May run a few more tests due to threading, but will
     /// abort as soon as possible.
@@ -103,8 +108,9 @@ fn optgroups() -> getopts::Options {
             pretty = Print verbose output;
             terse  = Display one character per test;
             json   = Output a json document;
-            junit  = Output a JUnit document",
-            "pretty|terse|json|junit",
+            junit  = Output a JUnit document;
+            tap    = Output a TAP 14 stream",
+            "pretty|terse|json|junit|tap",
         )
         .optflag("", "show-output", "Show captured stdout of successful tests")
         .optopt(
@@ -143,13 +149,29 @@ fn optgroups() -> getopts::Options {
             `CRITICAL_TIME` here means the limit that should not be exceeded by test.
             ",
         )
+        .optopt(
+            "",
+            "test-timeout",
+            "Run each test in a separate process, and kill and fail
+            the tests that take longer than SECONDS to finish",
+            "SECONDS",
+        )
         .optflag("", "shuffle", "Run tests in random order")
         .optopt(
             "",
             "shuffle-seed",
             "Run tests in random order; seed the random number generator with SEED",
             "SEED",
-        );
+        )
+        .optopt(
+            "",
+            "shard-index",
+            "Only run the tests of shard INDEX (counting from 0) out of
+            --shard-count shards. Tests are assigned to shards by a
+            hash of their name.",
+            "INDEX",
+        )
+        .optopt("", "shard-count", "Split the tests into COUNT shards", "COUNT");
     opts
 }
 
@@ -171,6 +193,11 @@ fn usage(binary: &str, options: &getopts::Options) {
 --shuffle and --shuffle-seed do not affect whether the tests are run in
 parallel.
 
+The tests can be split across several invocations of the test binary with
+--shard-count and --shard-index. Each test is assigned to a shard by a hash
+of its name, so every shard runs a disjoint set of tests and the same test
+always lands in the same shard.
+
 All tests have their standard output and standard error captured by default.
 This can be overridden with the --no-capture flag to a value other than "0".
 Logging is not captured by default.
@@ -263,6 +290,9 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
     let exclude_should_panic = unstable_optflag!(matches, allow_unstable, "exclude-should-panic");
     let fail_fast = unstable_optflag!(matches, allow_unstable, "fail-fast");
     let time_options = get_time_options(&matches, allow_unstable)?;
+    let test_timeout = get_test_timeout(&matches, allow_unstable)?;
+    let shard = get_shard(&matches, allow_unstable)?;
+
     let shuffle = get_shuffle(&matches, allow_unstable)?;
     let shuffle_seed = get_shuffle_seed(&matches, allow_unstable)?;
 
@@ -285,6 +315,10 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
 
     let options = Options::new().display_output(matches.opt_present("show-output"));
 
+    if test_timeout.is_some() && force_run_in_process {
+        return Err("--test-timeout cannot be used with --force-run-in-process".to_string());
+    }
+
     if logfile.is_some() {
         let _ = write!(io::stderr(), "warning: `--logfile` is deprecated");
     }
@@ -304,9 +338,11 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
         format,
         shuffle,
         shuffle_seed,
+        shard,
         test_threads,
         skip,
         time_options,
+        test_timeout,
         options,
         fail_fast,
     };
@@ -343,8 +379,24 @@ fn get_time_options(
     Ok(options)
 }
 
+fn get_test_timeout(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<Option<Duration>> {
+    let Some(secs) = unstable_optopt!(matches, allow_unstable, "test-timeout") else {
+        return Ok(None);
+    };
+    match secs.parse::<f64>().map(Duration::try_from_secs_f64) {
+        Ok(Ok(timeout)) if !timeout.is_zero() => Ok(Some(timeout)),
+        _ => Err(format!(
+            "argument for --test-timeout must be a positive number of seconds, got `{secs}`"
+        )),
+    }
+}
+
 fn get_shuffle(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<bool> {
-    let mut shuffle = unstable_optflag!(matches, allow_unstable, "shuffle");
+    let mut shuffle = matches.opt_present("shuffle");
+    // The environment variable is deprecated, so it was not stabilized along with the flag.
     if !shuffle && allow_unstable {
         shuffle = match env::var("RUST_TEST_SHUFFLE") {
             Ok(val) => &val != "0",
@@ -356,7 +408,7 @@ fn get_shuffle(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<b
 }
 
 fn get_shuffle_seed(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<Option<u64>> {
-    let mut shuffle_seed = match unstable_optopt!(matches, allow_unstable, "shuffle-seed") {
+    let mut shuffle_seed = match matches.opt_str("shuffle-seed") {
         Some(n_str) => match n_str.parse::<u64>() {
             Ok(n) => Some(n),
             Err(e) => {
@@ -382,6 +434,43 @@ fn get_shuffle_seed(matches: &getopts::Matches, allow_unstable: bool) -> OptPart
     Ok(shuffle_seed)
 }
 
+fn get_shard(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<Option<TestShard>> {
+    let index = unstable_optopt!(matches, allow_unstable, "shard-index");
+    let count = unstable_optopt!(matches, allow_unstable, "shard-count");
+    let (index, count) = match (index, count) {
+        (Some(index), Some(count)) => (index, count),
+        (None, None) => return Ok(None),
+        _ => return Err("--shard-index and --shard-count must be used together".to_string()),
+    };
+
+    let count = match count.parse::<usize>() {
+        Ok(0) => return Err("argument for --shard-count must not be 0".to_string()),
+        Ok(n) => n,
+        Err(e) => {
+            return Err(format!(
+                "argument for --shard-count must be a number > 0 \
+                 (error: {e})"
+            ));
+        }
+    };
+    let index = match index.parse::<usize>() {
+        Ok(n) if n < count => n,
+        Ok(n) => {
+            return Err(format!(
+                "argument for --shard-index must be less than --shard-count ({n} >= {count})"
+            ));
+        }
+        Err(e) => {
+            return Err(format!(
+                "argument for --shard-index must be a number \
+                 (error: {e})"
+            ));
+        }
+    };
+
+    Ok(Some(TestShard { index, count }))
+}
+
 fn get_test_threads(matches: &getopts::Matches) -> OptPartRes<Option<usize>> {
     let test_threads = match matches.opt_str("test-threads") {
         Some(n_str) => match n_str.parse::<usize>() {
@@ -421,9 +510,10 @@ fn get_format(
             }
             OutputFormat::Junit
         }
+        Some("tap") => OutputFormat::Tap,
         Some(v) => {
             return Err(format!(
-                "argument for --format must be pretty, terse, json or junit (was \
+                "argument for --format must be pretty, terse, json, junit or tap (was \
                  {v})"
             ));
         }
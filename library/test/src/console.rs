@@ -9,7 +9,7 @@
 use super::cli::TestOpts;
 use super::event::{CompletedTest, TestEvent};
 use super::formatters::{
-    JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TerseFormatter,
+    JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TapFormatter, TerseFormatter,
 };
 use super::helpers::concurrency::get_concurrency;
 use super::helpers::metrics::MetricMap;
@@ -83,6 +83,8 @@ pub(crate) struct ConsoleTestState {
     pub filtered_out: usize,
     pub measured: usize,
     pub exec_time: Option<TestSuiteExecTime>,
+    /// The seed the tests were shuffled with, if they were shuffled.
+    pub shuffle_seed: Option<u64>,
     pub metrics: MetricMap,
     pub failures: Vec<(TestDesc, Vec<u8>)>,
     pub not_failures: Vec<(TestDesc, Vec<u8>)>,
@@ -107,6 +109,7 @@ pub(crate) fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             filtered_out: 0,
             measured: 0,
             exec_time: None,
+            shuffle_seed: None,
             metrics: MetricMap::new(),
             failures: Vec::new(),
             not_failures: Vec::new(),
@@ -177,7 +180,7 @@ pub(crate) fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) ->
     };
 
     let mut out: Box<dyn OutputFormatter> = match opts.format {
-        OutputFormat::Pretty | OutputFormat::Junit => {
+        OutputFormat::Pretty | OutputFormat::Junit | OutputFormat::Tap => {
             Box::new(PrettyFormatter::new(output, false, 0, false, None))
         }
         OutputFormat::Terse => Box::new(TerseFormatter::new(output, false, 0, false)),
@@ -259,6 +262,7 @@ fn on_test_event(
     match (*event).clone() {
         TestEvent::TeFiltered(filtered_tests, shuffle_seed) => {
             st.total = filtered_tests;
+            st.shuffle_seed = shuffle_seed;
             out.write_run_start(filtered_tests, shuffle_seed)?;
         }
         TestEvent::TeFilteredOut(filtered_out) => {
@@ -302,6 +306,7 @@ pub(crate) fn get_formatter(opts: &TestOpts, max_name_len: usize) -> Box<dyn Out
         }
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
         OutputFormat::Junit => Box::new(JunitFormatter::new(output)),
+        OutputFormat::Tap => Box::new(TapFormatter::new(output)),
     }
 }
 
@@ -276,6 +276,13 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             if !state.time_failures.is_empty() {
                 self.write_time_failures(state)?;
             }
+
+            if let Some(shuffle_seed) = state.shuffle_seed {
+                self.write_plain(format!(
+                    "\nnote: the tests were run in random order; \
+                     use `--shuffle-seed {shuffle_seed}` to run them in the same order again\n"
+                ))?;
+            }
         }
 
         self.write_plain("\ntest result: ")?;
@@ -0,0 +1,215 @@
+use std::fmt::Write as _;
+use std::io;
+use std::io::prelude::Write;
+
+use super::OutputFormatter;
+use crate::console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation};
+use crate::test_result::TestResult;
+use crate::time;
+use crate::types::TestDesc;
+
+/// Formats test results as a [TAP 14] stream.
+///
+/// Every result is a test point numbered in completion order. Failures and captured output are
+/// reported in a YAML diagnostic block following the test point. The plan is written once the
+/// run finishes, so that it is correct even when `--fail-fast` stops the run early.
+///
+/// [TAP 14]: https://testanything.org/tap-version-14-specification.html
+pub(crate) struct TapFormatter<T> {
+    out: OutputLocation<T>,
+    /// Number of the last test point written.
+    test_point: usize,
+}
+
+impl<T: Write> TapFormatter<T> {
+    pub(crate) fn new(out: OutputLocation<T>) -> Self {
+        Self { out, test_point: 0 }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
+    fn write_plain<S: AsRef<str>>(&mut self, s: S) -> io::Result<()> {
+        let s = s.as_ref();
+        self.out.write_all(s.as_bytes())?;
+        self.out.flush()
+    }
+
+    fn write_test_point(
+        &mut self,
+        ok: bool,
+        desc: &TestDesc,
+        directive: Option<&str>,
+        diagnostics: &[(&str, String)],
+    ) -> io::Result<()> {
+        self.test_point += 1;
+        let mut s = String::new();
+        if !ok {
+            s.push_str("not ");
+        }
+        write!(s, "ok {} - {}", self.test_point, escape_description(desc.name.as_slice())).unwrap();
+        if let Some(directive) = directive {
+            write!(s, " # {directive}").unwrap();
+        }
+        s.push('\n');
+        if !diagnostics.is_empty() {
+            s.push_str("  ---\n");
+            for (key, value) in diagnostics {
+                writeln!(s, "  {key}: {value}").unwrap();
+            }
+            s.push_str("  ...\n");
+        }
+        self.write_plain(s)
+    }
+}
+
+impl<T: Write> OutputFormatter for TapFormatter<T> {
+    fn write_discovery_start(&mut self) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_test_discovered(&mut self, _desc: &TestDesc, _test_type: &str) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_discovery_finish(&mut self, _state: &ConsoleTestDiscoveryState) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        self.write_plain("TAP version 14\n")?;
+        let noun = if test_count != 1 { "tests" } else { "test" };
+        let shuffle_seed_msg = if let Some(shuffle_seed) = shuffle_seed {
+            format!(" (shuffle seed: {shuffle_seed})")
+        } else {
+            String::new()
+        };
+        self.write_plain(format!("# running {test_count} {noun}{shuffle_seed_msg}\n"))
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // We do not output anything on test start.
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.write_plain(format!(
+            "# {} has been running for over {} seconds\n",
+            escape_description(desc.name.as_slice()),
+            time::TEST_WARN_TIMEOUT_S
+        ))
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let mut diagnostics = vec![];
+        let message = match *result {
+            TestResult::TrFailedMsg(ref m) => Some(&**m),
+            TestResult::TrTimedFail => Some("time limit exceeded"),
+            _ => None,
+        };
+        if let Some(message) = message {
+            diagnostics.push(("message", yaml_string(message)));
+        }
+        if let Some(exec_time) = exec_time {
+            diagnostics.push(("duration_ms", format!("{}", exec_time.0.as_secs_f64() * 1000.0)));
+        }
+        let display_stdout = state.options.display_output || *result != TestResult::TrOk;
+        if display_stdout && !stdout.is_empty() {
+            diagnostics.push(("stdout", yaml_string(&String::from_utf8_lossy(stdout))));
+        }
+
+        match *result {
+            TestResult::TrOk => self.write_test_point(true, desc, None, &diagnostics),
+
+            TestResult::TrFailed | TestResult::TrFailedMsg(_) | TestResult::TrTimedFail => {
+                self.write_test_point(false, desc, None, &diagnostics)
+            }
+
+            TestResult::TrIgnored => {
+                let directive = match desc.ignore_message {
+                    Some(msg) => format!("SKIP {}", escape_description(msg)),
+                    None => String::from("SKIP"),
+                };
+                self.write_test_point(true, desc, Some(&directive), &diagnostics)
+            }
+
+            TestResult::TrBench(ref bs) => {
+                diagnostics.push(("median_ns", format!("{}", bs.ns_iter_summ.median)));
+                diagnostics.push((
+                    "deviation_ns",
+                    format!("{}", bs.ns_iter_summ.max - bs.ns_iter_summ.min),
+                ));
+                if bs.mb_s != 0 {
+                    diagnostics.push(("mib_per_second", format!("{}", bs.mb_s)));
+                }
+                self.write_test_point(true, desc, None, &diagnostics)
+            }
+        }
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let success = state.failed == 0;
+        self.write_plain(format!("1..{}\n", self.test_point))?;
+
+        let mut s = format!(
+            "# test result: {}. {} passed; {} failed; {} ignored; {} measured; {} filtered out",
+            if success { "ok" } else { "FAILED" },
+            state.passed,
+            state.failed,
+            state.ignored,
+            state.measured,
+            state.filtered_out
+        );
+        if let Some(ref exec_time) = state.exec_time {
+            write!(s, "; finished in {exec_time}").unwrap();
+        }
+        s.push('\n');
+        self.write_plain(s)?;
+
+        Ok(success)
+    }
+
+    fn write_merged_doctests_times(
+        &mut self,
+        total_time: f64,
+        compilation_time: f64,
+    ) -> io::Result<()> {
+        self.write_plain(format!(
+            "# all doctests ran in {total_time:.2}s; merged doctests compilation took {compilation_time:.2}s\n",
+        ))
+    }
+}
+
+/// Escapes the characters that would otherwise end a test point description, as described by
+/// the "Escaping" section of the TAP 14 specification.
+fn escape_description(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('#', "\\#").replace('\n', " ")
+}
+
+/// Renders `s` as a double-quoted YAML scalar, which can hold any string on a single line.
+fn yaml_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -259,6 +259,13 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         let success = state.failed == 0;
         if !success {
             self.write_failures(state)?;
+
+            if let Some(shuffle_seed) = state.shuffle_seed {
+                self.write_plain(format!(
+                    "\nnote: the tests were run in random order; \
+                     use `--shuffle-seed {shuffle_seed}` to run them in the same order again\n"
+                ))?;
+            }
         }
 
         self.write_plain("\ntest result: ")?;
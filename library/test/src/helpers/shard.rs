@@ -0,0 +1,19 @@
+use crate::options::TestShard;
+
+/// Returns whether the test named `name` belongs to `shard`.
+///
+/// Tests are assigned by hashing their name, which keeps the assignment of a test independent
+/// of which other tests exist. The hash has to give the same result on every platform and with
+/// every version of the standard library, so that separate invocations of the test binary, for
+/// example on different CI machines, agree on the partition. `DefaultHasher` guarantees neither.
+pub(crate) fn is_in_shard(name: &str, shard: TestShard) -> bool {
+    (fnv1a(name.as_bytes()) % shard.count as u64) as usize == shard.index
+}
+
+/// The 64-bit FNV-1a hash function.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
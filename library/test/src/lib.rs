@@ -35,7 +35,7 @@
 pub use self::ColorConfig::*;
 pub use self::bench::{Bencher, black_box};
 pub use self::console::run_tests_console;
-pub use self::options::{ColorConfig, Options, OutputFormat, RunIgnored, ShouldPanic};
+pub use self::options::{ColorConfig, Options, OutputFormat, RunIgnored, ShouldPanic, TestShard};
 pub use self::types::TestName::*;
 pub use self::types::*;
 
@@ -44,7 +44,7 @@ pub mod test {
     pub use crate::bench::Bencher;
     pub use crate::cli::{TestOpts, parse_opts};
     pub use crate::helpers::metrics::{Metric, MetricMap};
-    pub use crate::options::{Options, RunIgnored, RunStrategy, ShouldPanic};
+    pub use crate::options::{Options, RunIgnored, RunStrategy, ShouldPanic, TestShard};
     pub use crate::test_result::{TestResult, TrFailed, TrFailedMsg, TrIgnored, TrOk};
     pub use crate::time::{TestExecTime, TestTimeOptions};
     pub use crate::types::{
@@ -56,10 +56,10 @@ pub mod test {
 
 use std::collections::VecDeque;
 use std::io::prelude::Write;
-use std::mem::ManuallyDrop;
+use std::mem::{self, ManuallyDrop};
 use std::panic::{self, AssertUnwindSafe, PanicHookInfo, catch_unwind};
 use std::process::{self, Command, Termination};
-use std::sync::mpsc::{Sender, channel};
+use std::sync::mpsc::{Receiver, Sender, channel};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use std::{env, io, thread};
@@ -84,6 +84,7 @@ pub mod test {
 
 use event::{CompletedTest, TestEvent};
 use helpers::concurrency::get_concurrency;
+use helpers::shard::is_in_shard;
 use helpers::shuffle::{get_shuffle_seed, shuffle_tests};
 use options::RunStrategy;
 use test_result::*;
@@ -107,6 +108,13 @@ pub fn test_main_with_exit_callback<F: FnOnce()>(
     options: Option<Options>,
     exit_callback: F,
 ) {
+    // If we're being run in SpawnedSecondary mode, run the test here. run_test
+    // will then exit the process.
+    if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
+        let panic_abort = options.is_some_and(|options| options.panic_abort);
+        run_test_as_spawned_secondary(name, tests, panic_abort);
+    }
+
     let mut opts = match cli::parse_opts(args) {
         Some(Ok(o)) => o,
         Some(Err(msg)) => {
@@ -192,44 +200,44 @@ pub fn test_main_static(tests: &[&TestDescAndFn]) {
 /// This is the entry point for the main function generated by `rustc --test`
 /// when panic=abort.
 pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
-    // If we're being run in SpawnedSecondary mode, run the test here. run_test
-    // will then exit the process.
-    if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
+    let args = env::args().collect::<Vec<_>>();
+    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
+    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
+}
+
+/// Runs the test named `name` in a process spawned by `spawn_test_subprocess`, then exits
+/// the process.
+fn run_test_as_spawned_secondary(name: String, tests: Vec<TestDescAndFn>, panic_abort: bool) -> ! {
+    unsafe {
+        env::remove_var(SECONDARY_TEST_INVOKER_VAR);
+    }
+
+    // Convert benchmarks to tests if we're not benchmarking.
+    let mut tests = tests;
+    if env::var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR).is_ok() {
         unsafe {
-            env::remove_var(SECONDARY_TEST_INVOKER_VAR);
+            env::remove_var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR);
         }
+    } else {
+        tests = convert_benchmarks_to_tests(tests);
+    };
 
-        // Convert benchmarks to tests if we're not benchmarking.
-        let mut tests = tests.iter().map(make_owned_test).collect::<Vec<_>>();
-        if env::var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR).is_ok() {
-            unsafe {
-                env::remove_var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR);
-            }
-        } else {
-            tests = convert_benchmarks_to_tests(tests);
-        };
-
-        let test = tests
-            .into_iter()
-            .find(|test| test.desc.name.as_slice() == name)
-            .unwrap_or_else(|| panic!("couldn't find a test with the provided name '{name}'"));
-        let TestDescAndFn { desc, testfn } = test;
-        match testfn.into_runnable() {
-            Runnable::Test(runnable_test) => {
-                if runnable_test.is_dynamic() {
-                    panic!("only static tests are supported");
-                }
-                run_test_in_spawned_subprocess(desc, runnable_test);
-            }
-            Runnable::Bench(_) => {
-                panic!("benchmarks should not be executed into child processes")
+    let test = tests
+        .into_iter()
+        .find(|test| test.desc.name.as_slice() == name)
+        .unwrap_or_else(|| panic!("couldn't find a test with the provided name '{name}'"));
+    let TestDescAndFn { desc, testfn } = test;
+    match testfn.into_runnable() {
+        Runnable::Test(runnable_test) => {
+            if runnable_test.is_dynamic() {
+                panic!("only static tests are supported");
             }
+            run_test_in_spawned_subprocess(desc, runnable_test, panic_abort);
+        }
+        Runnable::Bench(_) => {
+            panic!("benchmarks should not be executed into child processes")
         }
     }
-
-    let args = env::args().collect::<Vec<_>>();
-    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
-    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
 }
 
 /// Clones static values for putting into a dynamic vector, which test_main()
@@ -378,11 +386,25 @@ struct TimeoutEntry {
     let mut pending = 0;
 
     let (tx, rx) = channel::<CompletedTest>();
-    let run_strategy = if opts.options.panic_abort && !opts.force_run_in_process {
+    // Tests that may be killed have to run in a process of their own.
+    let spawn = opts.options.panic_abort || opts.test_timeout.is_some();
+    let run_strategy = if spawn && !opts.force_run_in_process {
         RunStrategy::SpawnPrimary
     } else {
         RunStrategy::InProcess
     };
+    if opts.test_timeout.is_some()
+        && !opts.options.panic_abort
+        && remaining
+            .iter()
+            .any(|(_, test)| matches!(test.testfn, DynTestFn(..) | DynBenchAsTestFn(..)))
+    {
+        // See `run_test`.
+        let _ = writeln!(
+            io::stderr(),
+            "warning: `--test-timeout` is not enforced for tests created at runtime, like doctests"
+        );
+    }
 
     let mut running_tests: TestMap = HashMap::default();
     let mut timeout_queue: VecDeque<TimeoutEntry> = VecDeque::new();
@@ -550,6 +572,11 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
         RunIgnored::No => {}
     }
 
+    // Only keep the tests assigned to this shard
+    if let Some(shard) = opts.shard {
+        filtered.retain(|test| is_in_shard(test.desc.name.as_slice(), shard));
+    }
+
     filtered
 }
 
@@ -591,9 +618,15 @@ pub fn run_test(
 
     match testfn.into_runnable() {
         Runnable::Test(runnable_test) => {
+            let mut strategy = strategy;
             if runnable_test.is_dynamic() {
                 match strategy {
                     RunStrategy::InProcess => (),
+                    // A spawned process can't look up a dynamic test, so `--test-timeout`
+                    // is not enforced for them, which `run_tests` warns about.
+                    RunStrategy::SpawnPrimary if !opts.options.panic_abort => {
+                        strategy = RunStrategy::InProcess
+                    }
                     _ => panic!("Cannot run dynamic test fn out-of-process"),
                 };
             }
@@ -602,6 +635,7 @@ pub fn run_test(
             let nocapture = opts.nocapture;
             let time_options = opts.time_options;
             let bench_benchmarks = opts.bench_benchmarks;
+            let test_timeout = opts.test_timeout;
 
             let runtest = move || match strategy {
                 RunStrategy::InProcess => run_test_in_process(
@@ -617,10 +651,10 @@ pub fn run_test(
                     id,
                     desc,
                     nocapture,
-                    time_options.is_some(),
                     monitor_ch,
                     time_options,
                     bench_benchmarks,
+                    test_timeout,
                 ),
             };
 
@@ -718,10 +752,10 @@ fn spawn_test_subprocess(
     id: TestId,
     desc: TestDesc,
     nocapture: bool,
-    report_time: bool,
     monitor_ch: Sender<CompletedTest>,
     time_opts: Option<time::TestTimeOptions>,
     bench_benchmarks: bool,
+    test_timeout: Option<Duration>,
 ) {
     let (result, test_output, exec_time) = (|| {
         let args = env::args().collect::<Vec<_>>();
@@ -737,8 +771,14 @@ fn spawn_test_subprocess(
             command.stderr(process::Stdio::inherit());
         }
 
-        let start = report_time.then(Instant::now);
-        let output = match command.output() {
+        let start = time_opts.is_some().then(Instant::now);
+        let output = match test_timeout {
+            None => {
+                command.output().map(|output| (Some(output.status), output.stdout, output.stderr))
+            }
+            Some(timeout) => output_with_timeout(&mut command, nocapture, timeout),
+        };
+        let (status, stdout, stderr) = match output {
             Ok(out) => out,
             Err(e) => {
                 let err = format!("Failed to spawn {} as child for test: {:?}", args[0], e);
@@ -750,13 +790,19 @@ fn spawn_test_subprocess(
             TestExecTime(duration)
         });
 
-        let std::process::Output { stdout, stderr, status } = output;
         let mut test_output = stdout;
         formatters::write_stderr_delimiter(&mut test_output, &desc.name);
         test_output.extend_from_slice(&stderr);
 
-        let result =
-            get_result_from_exit_code(&desc, status, time_opts.as_ref(), exec_time.as_ref());
+        let result = match status {
+            Some(status) => {
+                get_result_from_exit_code(&desc, status, time_opts.as_ref(), exec_time.as_ref())
+            }
+            None => TrFailedMsg(format!(
+                "test did not finish within {:.3}s and was killed",
+                test_timeout.unwrap().as_secs_f64()
+            )),
+        };
         (result, test_output, exec_time)
     })();
 
@@ -764,7 +810,100 @@ fn spawn_test_subprocess(
     monitor_ch.send(message).unwrap();
 }
 
-fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -> ! {
+/// Runs `command` like `Command::output` does, but kills the process if it hasn't exited
+/// after `timeout`.
+///
+/// Returns the exit status, or `None` if the process was killed, along with whatever the
+/// process wrote to stdout and stderr. The output is only captured if `nocapture` is false.
+fn output_with_timeout(
+    command: &mut Command,
+    nocapture: bool,
+    timeout: Duration,
+) -> io::Result<(Option<process::ExitStatus>, Vec<u8>, Vec<u8>)> {
+    // There is no way to wait for a child process with a timeout in `std`, so poll it.
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    // Processes spawned by the test may keep the pipes open after it exits or is killed, so
+    // the pipes are only read until shortly after the timeout.
+    const PIPE_GRACE_PERIOD: Duration = Duration::from_millis(500);
+
+    /// Reads a pipe until it is closed, keeping what was read so far available.
+    struct BackgroundReader {
+        output: Arc<Mutex<Vec<u8>>>,
+        closed: Receiver<()>,
+    }
+
+    impl BackgroundReader {
+        fn new(mut pipe: impl io::Read + Send + 'static) -> Self {
+            let output = Arc::new(Mutex::new(Vec::new()));
+            let (tx, closed) = channel();
+            let output2 = output.clone();
+            thread::spawn(move || {
+                let mut buf = [0; 4096];
+                while let Ok(n @ 1..) = pipe.read(&mut buf) {
+                    output2.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(&buf[..n]);
+                }
+                let _ = tx.send(());
+            });
+            BackgroundReader { output, closed }
+        }
+
+        fn finish(self, deadline: Instant) -> Vec<u8> {
+            let _ = self.closed.recv_timeout(deadline.saturating_duration_since(Instant::now()));
+            mem::take(&mut *self.output.lock().unwrap_or_else(|e| e.into_inner()))
+        }
+    }
+
+    // `Command::output` would set these up itself, but `Command::spawn` doesn't.
+    command.stdin(process::Stdio::null());
+    if !nocapture {
+        command.stdout(process::Stdio::piped());
+        command.stderr(process::Stdio::piped());
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut child = command.spawn()?;
+    // Drain the pipes while waiting, so that a test writing a lot of output doesn't block on
+    // a full pipe until it is killed.
+    let stdout = child.stdout.take().map(BackgroundReader::new);
+    let stderr = child.stderr.take().map(BackgroundReader::new);
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            child.kill()?;
+            child.wait()?;
+            break None;
+        }
+        thread::sleep(POLL_INTERVAL.min(deadline - now));
+    };
+
+    let pipe_deadline = deadline.max(Instant::now()) + PIPE_GRACE_PERIOD;
+    let finish = |reader: Option<BackgroundReader>| {
+        reader.map(|reader| reader.finish(pipe_deadline)).unwrap_or_default()
+    };
+    Ok((status, finish(stdout), finish(stderr)))
+}
+
+fn run_test_in_spawned_subprocess(
+    desc: TestDesc,
+    runnable_test: RunnableTest,
+    panic_abort: bool,
+) -> ! {
+    if !panic_abort {
+        // The test may catch its own panics, so its result is taken from what it returns
+        // rather than from the panic hook.
+        let result = fold_err(catch_unwind(AssertUnwindSafe(|| runnable_test.run())));
+        let test_result = calc_result(&desc, result.err().as_deref(), None, None);
+        if let TrFailedMsg(msg) = &test_result {
+            eprintln!("{msg}");
+        }
+        exit_with_result(&test_result)
+    }
+
     let builtin_panic_hook = panic::take_hook();
     let record_result = Arc::new(move |panic_info: Option<&'_ PanicHookInfo<'_>>| {
         let test_result = calc_result(&desc, panic_info.map(|info| info.payload()), None, None);
@@ -779,11 +918,7 @@ fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -
             builtin_panic_hook(info);
         }
 
-        if let TrOk = test_result {
-            process::exit(test_result::TR_OK);
-        } else {
-            process::abort();
-        }
+        exit_with_result(&test_result)
     });
     let record_result2 = record_result.clone();
     panic::set_hook(Box::new(move |info| record_result2(Some(info))));
@@ -793,3 +928,8 @@ fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -
     record_result(None);
     unreachable!("panic=abort callback should have exited the process")
 }
+
+/// Exits a spawned test process with a status that `get_result_from_exit_code` understands.
+fn exit_with_result(test_result: &TestResult) -> ! {
+    if let TrOk = test_result { process::exit(test_result::TR_OK) } else { process::abort() }
+}
@@ -36,6 +36,18 @@ pub enum OutputFormat {
     Json,
     /// JUnit output
     Junit,
+    /// TAP 14 output
+    Tap,
+}
+
+/// The subset of the tests to run, when the tests are split across several invocations
+/// with `--shard-index` and `--shard-count`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TestShard {
+    /// Zero-based index of this shard, less than `count`
+    pub index: usize,
+    /// Total number of shards
+    pub count: usize,
 }
 
 /// Whether ignored test should be run or not
@@ -1,7 +1,7 @@
 use super::*;
 use crate::{
     console::OutputLocation,
-    formatters::PrettyFormatter,
+    formatters::{OutputFormatter, PrettyFormatter, TapFormatter},
     test::{
         MetricMap,
         // FIXME (introduced by #65251)
@@ -29,9 +29,11 @@ fn new() -> TestOpts {
             format: OutputFormat::Pretty,
             shuffle: false,
             shuffle_seed: None,
+            shard: None,
             test_threads: None,
             skip: vec![],
             time_options: None,
+            test_timeout: None,
             options: Options::new(),
             fail_fast: false,
         }
@@ -468,6 +470,87 @@ fn parse_include_ignored_flag() {
     assert_eq!(opts.run_ignored, RunIgnored::Yes);
 }
 
+#[test]
+fn parse_shuffle_seed_flag() {
+    let args = vec!["progname".to_string(), "--shuffle-seed".to_string(), "42".to_string()];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.shuffle_seed, Some(42));
+}
+
+#[test]
+fn parse_shard_options() {
+    let parse = |index: Option<&str>, count: Option<&str>| {
+        let mut args = vec!["progname".to_string(), "-Zunstable-options".to_string()];
+        if let Some(index) = index {
+            args.extend(["--shard-index".to_string(), index.to_string()]);
+        }
+        if let Some(count) = count {
+            args.extend(["--shard-count".to_string(), count.to_string()]);
+        }
+        parse_opts(&args).unwrap().map(|opts| opts.shard)
+    };
+
+    assert_eq!(parse(None, None), Ok(None));
+    assert_eq!(parse(Some("1"), Some("3")), Ok(Some(TestShard { index: 1, count: 3 })));
+    assert!(parse(Some("3"), Some("3")).is_err());
+    assert!(parse(Some("0"), Some("0")).is_err());
+    assert!(parse(Some("0"), None).is_err());
+    assert!(parse(None, Some("2")).is_err());
+}
+
+#[test]
+fn parse_test_timeout_option() {
+    let parse = |timeout: &str| {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--test-timeout".to_string(),
+            timeout.to_string(),
+        ];
+        parse_opts(&args).unwrap().map(|opts| opts.test_timeout)
+    };
+
+    assert_eq!(parse("10"), Ok(Some(Duration::from_secs(10))));
+    assert_eq!(parse("0.25"), Ok(Some(Duration::from_millis(250))));
+    assert!(parse("0").is_err());
+    assert!(parse("-1").is_err());
+    assert!(parse("soon").is_err());
+
+    let args = vec!["progname".to_string(), "--test-timeout".to_string(), "10".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+#[cfg(unix)]
+fn output_with_timeout_kills_process() {
+    let mut command = Command::new("sh");
+    command.args(["-c", "echo started; exec sleep 60"]);
+    let start = Instant::now();
+    let (status, stdout, _) =
+        output_with_timeout(&mut command, false, Duration::from_millis(200)).unwrap();
+    assert!(start.elapsed() < Duration::from_secs(60));
+    assert_eq!(status, None);
+    assert_eq!(stdout, b"started\n");
+
+    // A process left behind by the killed one keeps the pipes open. It exits on its own a few
+    // seconds later, so that it doesn't outlive the test for long.
+    let mut command = Command::new("sh");
+    command.args(["-c", "sleep 5 & echo started; exec sleep 60"]);
+    let start = Instant::now();
+    let (status, stdout, _) =
+        output_with_timeout(&mut command, false, Duration::from_millis(200)).unwrap();
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(status, None);
+    assert_eq!(stdout, b"started\n");
+
+    let mut command = Command::new("sh");
+    command.args(["-c", "echo done"]);
+    let (status, stdout, _) =
+        output_with_timeout(&mut command, false, Duration::from_secs(60)).unwrap();
+    assert!(status.unwrap().success());
+    assert_eq!(stdout, b"done\n");
+}
+
 #[test]
 fn filter_for_ignored_option() {
     // When we run ignored tests the test filter should filter out all the
@@ -656,6 +739,26 @@ fn testfn() -> Result<(), String> {
     tests
 }
 
+#[test]
+fn shards_partition_tests() {
+    let mut all_names =
+        sample_tests().into_iter().map(|t| t.desc.name.to_string()).collect::<Vec<_>>();
+    all_names.sort();
+
+    for count in 1..=4 {
+        let mut names = (0..count)
+            .flat_map(|index| {
+                let shard = Some(TestShard { index, count });
+                filter_tests(&TestOpts { shard, ..TestOpts::new() }, sample_tests())
+            })
+            .map(|t| t.desc.name.to_string())
+            .collect::<Vec<_>>();
+        // Every test is in exactly one shard.
+        names.sort();
+        assert_eq!(names, all_names);
+    }
+}
+
 #[test]
 fn shuffle_tests() {
     let mut opts = TestOpts::new();
@@ -859,6 +962,7 @@ fn should_sort_failures_before_printing_them() {
         filtered_out: 0,
         measured: 0,
         exec_time: None,
+        shuffle_seed: None,
         metrics: MetricMap::new(),
         failures: vec![(test_b, Vec::new()), (test_a, Vec::new())],
         options: Options::new(),
@@ -878,6 +982,68 @@ fn should_sort_failures_before_printing_them() {
     assert!(apos < bpos);
 }
 
+#[test]
+fn tap_output() {
+    fn desc(name: &'static str, ignore_message: Option<&'static str>) -> TestDesc {
+        TestDesc {
+            name: StaticTestName(name),
+            ignore: ignore_message.is_some(),
+            ignore_message,
+            source_file: "",
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+            should_panic: ShouldPanic::No,
+            compile_fail: false,
+            no_run: false,
+            test_type: TestType::Unknown,
+        }
+    }
+
+    let mut out = TapFormatter::new(OutputLocation::Raw(Vec::new()));
+    let mut st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+    st.total = 3;
+
+    out.write_run_start(3, None).unwrap();
+    out.write_result(&desc("a", None), &TrOk, Some(&test_exec_time(5)), b"hidden", &st).unwrap();
+    st.passed += 1;
+    out.write_result(
+        &desc("b # c", None),
+        &TrFailedMsg("test did not panic as expected".to_owned()),
+        None,
+        b"\"quoted\"\n",
+        &st,
+    )
+    .unwrap();
+    st.failed += 1;
+    out.write_result(&desc("d", Some("slow")), &TrIgnored, None, b"", &st).unwrap();
+    st.ignored += 1;
+    assert!(!out.write_run_finish(&st).unwrap());
+
+    let s = match out.output_location() {
+        &OutputLocation::Raw(ref m) => String::from_utf8_lossy(&m[..]),
+        &OutputLocation::Pretty(_) => unreachable!(),
+    };
+    assert_eq!(
+        s,
+        "TAP version 14\n\
+         # running 3 tests\n\
+         ok 1 - a\n  \
+         ---\n  \
+         duration_ms: 5\n  \
+         ...\n\
+         not ok 2 - b \\# c\n  \
+         ---\n  \
+         message: \"test did not panic as expected\"\n  \
+         stdout: \"\\\"quoted\\\"\\n\"\n  \
+         ...\n\
+         ok 3 - d # SKIP slow\n\
+         1..3\n\
+         # test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out\n"
+    );
+}
+
 #[test]
 #[cfg(not(target_os = "emscripten"))]
 fn test_dyn_bench_returning_err_fails_when_run_as_test() {
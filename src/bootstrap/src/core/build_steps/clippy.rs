@@ -510,6 +510,8 @@ fn metadata(&self) -> Option<StepMetadata> {
     RemoteTestServer, "src/tools/remote-test-server", "remote-test-server", Mode::ToolTarget;
     RustAnalyzer, "src/tools/rust-analyzer", "rust-analyzer", Mode::ToolRustcPrivate;
     Rustdoc, "src/librustdoc", "clippy", Mode::ToolRustcPrivate;
+    RustdocSearch, "src/tools/rustdoc-search", "rustdoc-search", Mode::ToolTarget;
+    RustdocSemverDiff, "src/tools/rustdoc-semver-diff", "rustdoc-semver-diff", Mode::ToolTarget;
     Rustfmt, "src/tools/rustfmt", "rustfmt", Mode::ToolRustcPrivate;
     RustInstaller, "src/tools/rust-installer", "rust-installer", Mode::ToolTarget;
     Tidy, "src/tools/tidy", "tidy", Mode::ToolTarget;
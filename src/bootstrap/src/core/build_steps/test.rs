@@ -64,6 +64,8 @@ fn should_run(run: ShouldRun<'_>) -> ShouldRun<'_> {
         run.path("src/tools/jsondoclint")
             .path("src/tools/replace-version-placeholder")
             .path("src/tools/coverage-dump")
+            .path("src/tools/rustdoc-search")
+            .path("src/tools/rustdoc-semver-diff")
             // We want `./x test tidy` to _run_ the tidy tool, not its tests.
             // So we need a separate alias to test the tidy tool itself.
             .alias("tidyselftest")
@@ -497,6 +497,8 @@ fn metadata(&self) -> Option<StepMetadata> {
     GenerateCopyright, "src/tools/generate-copyright", "generate-copyright";
     GenerateWindowsSys, "src/tools/generate-windows-sys", "generate-windows-sys";
     RustdocGUITest, "src/tools/rustdoc-gui-test", "rustdoc-gui-test";
+    RustdocSearch, "src/tools/rustdoc-search", "rustdoc-search";
+    RustdocSemverDiff, "src/tools/rustdoc-semver-diff", "rustdoc-semver-diff";
     CoverageDump, "src/tools/coverage-dump", "coverage-dump";
     UnicodeTableGenerator, "src/tools/unicode-table-generator", "unicode-table-generator";
     FeaturesStatusDump, "src/tools/features-status-dump", "features-status-dump";
@@ -722,7 +724,12 @@ fn run(self, builder: &Builder<'_>) -> Self::Output {
         // If CI rustc is enabled and we haven't modified the rustdoc sources,
         // use the precompiled rustdoc from CI rustc's sysroot to speed up bootstrapping.
         if builder.download_rustc() && builder.rust_info().is_managed_git_subrepository() {
-            let files_to_track = &["src/librustdoc", "src/tools/rustdoc", "src/rustdoc-json-types"];
+            let files_to_track = &[
+                "src/librustdoc",
+                "src/tools/rustdoc",
+                "src/rustdoc-json-types",
+                "src/rustdoc-search-index-types",
+            ];
 
             // Check if unchanged
             if !builder.config.has_changes_from_upstream(files_to_track) {
@@ -830,6 +830,8 @@ macro_rules! describe {
                 clippy::RemoteTestServer,
                 clippy::RustAnalyzer,
                 clippy::Rustdoc,
+                clippy::RustdocSearch,
+                clippy::RustdocSemverDiff,
                 clippy::Rustfmt,
                 clippy::RustInstaller,
                 clippy::TestFloatParse,
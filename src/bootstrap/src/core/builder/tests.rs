@@ -2109,6 +2109,8 @@ fn test_all_stage_1() {
         [test] crate-bootstrap <host> src/tools/coverage-dump
         [test] crate-bootstrap <host> src/tools/jsondoclint
         [test] crate-bootstrap <host> src/tools/replace-version-placeholder
+        [test] crate-bootstrap <host> src/tools/rustdoc-search
+        [test] crate-bootstrap <host> src/tools/rustdoc-semver-diff
         [test] crate-bootstrap <host> tidyselftest
         [build] rustc 0 <host> -> UnstableBookGen 1 <host>
         [build] rustc 0 <host> -> Rustbook 1 <host>
@@ -2290,6 +2292,8 @@ fn test_all_stage_2() {
         [test] crate-bootstrap <host> src/tools/coverage-dump
         [test] crate-bootstrap <host> src/tools/jsondoclint
         [test] crate-bootstrap <host> src/tools/replace-version-placeholder
+        [test] crate-bootstrap <host> src/tools/rustdoc-search
+        [test] crate-bootstrap <host> src/tools/rustdoc-semver-diff
         [test] crate-bootstrap <host> tidyselftest
         [build] rustc 0 <host> -> UnstableBookGen 1 <host>
         [build] rustc 0 <host> -> Rustbook 1 <host>
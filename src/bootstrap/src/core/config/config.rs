@@ -76,6 +76,7 @@
     ":!src/tools",
     ":!src/librustdoc",
     ":!src/rustdoc-json-types",
+    ":!src/rustdoc-search-index-types",
     ":!tests",
     ":!triagebot.toml",
 ];
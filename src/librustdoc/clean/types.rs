@@ -165,12 +165,14 @@ pub(crate) fn src_root(&self, tcx: TyCtxt<'_>) -> PathBuf {
     }
 
     /// Attempts to find where an external crate is located, given that we're
-    /// rendering into the specified source destination.
+    /// rendering into the specified source destination. `included_crates` are the crates whose
+    /// info is passed with `--include-parts-dir`, which end up in the same destination.
     pub(crate) fn location(
         &self,
         extern_url: Option<&str>,
         extern_url_takes_precedence: bool,
         dst: &std::path::Path,
+        included_crates: &FxHashSet<Symbol>,
         tcx: TyCtxt<'_>,
     ) -> ExternalLocation {
         use ExternalLocation::*;
@@ -187,7 +189,7 @@ fn to_remote(url: impl ToString) -> ExternalLocation {
         // WARNING: since rustdoc creates these directories as it generates documentation, this check is only accurate before rendering starts.
         // Make sure to call `location()` by that time.
         let local_location = dst.join(self.name(tcx).as_str());
-        if local_location.is_dir() {
+        if local_location.is_dir() || included_crates.contains(&self.name(tcx)) {
             return Local;
         }
 
@@ -0,0 +1,186 @@
+//! Rewriting of documentation comments for the Markdown backend.
+//!
+//! Documentation is already Markdown, so instead of going through an HTML renderer, the source is
+//! kept as written and only a few ranges of it are replaced:
+//!
+//! - intra-doc links are pointed at the generated files,
+//! - hidden lines are removed from Rust code blocks, which are tagged as `rust`,
+//! - headings are shifted so that they nest under the heading of the page section they appear in.
+
+use std::ops::Range;
+
+use rustc_resolve::rustdoc::pulldown_cmark::{CodeBlockKind, Event, LinkType, Parser, Tag};
+
+use crate::clean::RenderedLink;
+use crate::html::markdown::{
+    ErrorCodes, LangString, Line, MarkdownLink, MarkdownLinkRange, main_body_opts, map_line,
+    markdown_links,
+};
+
+/// A replacement of `range` in the original documentation with `text`.
+struct Edit {
+    range: Range<usize>,
+    text: String,
+}
+
+/// Rewrites `md` so that it can be embedded in a generated file.
+///
+/// `links` are the resolved intra-doc links of the item the documentation belongs to, and every
+/// heading is moved `heading_offset` levels down.
+pub(super) fn rewrite(md: &str, links: &[RenderedLink], heading_offset: usize) -> String {
+    let mut edits = link_edits(md, links);
+    edits.extend(block_edits(md, heading_offset));
+    apply(md, edits)
+}
+
+fn apply(md: &str, mut edits: Vec<Edit>) -> String {
+    edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+    let mut out = String::with_capacity(md.len());
+    let mut last = 0;
+    for edit in edits {
+        // Edits never overlap in well-formed Markdown, but if they do, the first one wins.
+        if edit.range.start < last {
+            continue;
+        }
+        out.push_str(&md[last..edit.range.start]);
+        out.push_str(&edit.text);
+        last = edit.range.end;
+    }
+    out.push_str(&md[last..]);
+    out
+}
+
+fn link_edits(md: &str, links: &[RenderedLink]) -> Vec<Edit> {
+    if links.is_empty() {
+        return Vec::new();
+    }
+    let mut edits: Vec<Edit> = Vec::new();
+    for MarkdownLink { kind, link, range } in markdown_links(md, Some) {
+        // Links with escapes in them cannot be located precisely, so they are left alone.
+        let MarkdownLinkRange::Destination(range) = range else { continue };
+        let Some(resolved) = links.iter().find(|l| *l.original_text == *link) else { continue };
+        let href = &resolved.href;
+        let edit = match kind {
+            // `[Foo]`
+            LinkType::ShortcutUnknown if range.start > 0 => Edit {
+                range: range.start - 1..range.end + 1,
+                text: format!("[{}]({href})", link_label(&md[range], resolved)),
+            },
+            // `[Foo][]`
+            LinkType::CollapsedUnknown if md[range.end..].starts_with("][]") => Edit {
+                range: range.start - 1..range.end + 3,
+                text: format!("[{}]({href})", link_label(&md[range], resolved)),
+            },
+            // `[text][Foo]`
+            LinkType::ReferenceUnknown if range.start > 0 => {
+                Edit { range: range.start - 1..range.end + 1, text: format!("({href})") }
+            }
+            // `[text](Foo)` and the definition of `[Foo]: Foo`, which can be used several times.
+            LinkType::Inline | LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut => {
+                if edits.iter().any(|edit| edit.range == range) {
+                    continue;
+                }
+                Edit { range, text: href.clone() }
+            }
+            _ => continue,
+        };
+        edits.push(edit);
+    }
+    edits
+}
+
+/// The text of a link whose label is the path it links to. Like in the HTML backend, the
+/// disambiguator is removed from the label, but its code formatting is kept.
+fn link_label(label: &str, link: &RenderedLink) -> String {
+    if label.replace('`', "") == *link.new_text {
+        label.to_owned()
+    } else if label.starts_with('`') {
+        format!("`{}`", link.new_text)
+    } else {
+        link.new_text.to_string()
+    }
+}
+
+fn block_edits(md: &str, heading_offset: usize) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let mut in_rust_block = false;
+    for (event, range) in Parser::new_ext(md, main_body_opts()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) if heading_offset > 0 => {
+                edits.extend(heading_edits(md, range, level as usize + heading_offset));
+            }
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                in_rust_block = LangString::parse_without_check(&info, ErrorCodes::No).rust;
+                if in_rust_block && let Some(info_range) = fence_info_range(md, range.start) {
+                    edits.push(Edit { range: info_range, text: "rust".to_owned() });
+                }
+            }
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Indented)) => in_rust_block = true,
+            Event::End(_) => in_rust_block = false,
+            Event::Text(_) if in_rust_block => edits.extend(hidden_line_edits(md, range)),
+            _ => {}
+        }
+    }
+    edits
+}
+
+/// Moves the heading in `range` to `level`. Setext headings are turned into ATX ones, since they
+/// cannot go deeper than level 2.
+fn heading_edits(md: &str, range: Range<usize>, level: usize) -> Vec<Edit> {
+    let hashes = "#".repeat(level.min(6));
+    let source = &md[range.clone()];
+    let start = range.start + (source.len() - source.trim_start().len());
+    if md[start..].starts_with('#') {
+        let end = start + md[start..].bytes().take_while(|&b| b == b'#').count();
+        return vec![Edit { range: start..end, text: hashes }];
+    }
+    // The underline is the last line of the heading.
+    let Some(underline) = source.trim_end().rfind('\n') else { return Vec::new() };
+    vec![
+        Edit { range: start..start, text: format!("{hashes} ") },
+        Edit {
+            range: range.start + underline..range.start + source.trim_end().len(),
+            text: String::new(),
+        },
+    ]
+}
+
+/// Returns the range of the info string of the fenced code block starting at `start`, or an
+/// empty range right after the fence if it has none.
+fn fence_info_range(md: &str, start: usize) -> Option<Range<usize>> {
+    let line = md[start..].split('\n').next()?;
+    let fence = line.trim_start();
+    let fence_char = fence.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let fence_start = start + (line.len() - fence.len());
+    let info_start = fence_start + fence.len() - fence.trim_start_matches(fence_char).len();
+    let info_end = start + line.trim_end().len();
+    Some(info_start..info_end.max(info_start))
+}
+
+/// Removes the hidden lines of a Rust code block, and unescapes the lines starting with `##`.
+fn hidden_line_edits(md: &str, range: Range<usize>) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let mut line_start = range.start;
+    for line in md[range].split_inclusive('\n') {
+        let line_range = line_start..line_start + line.len();
+        line_start = line_range.end;
+        match map_line(line.trim_end_matches('\n')) {
+            Line::Hidden(_) => {
+                // Take the indentation that precedes the text along, so that the next line keeps
+                // its own.
+                let indent =
+                    md[..line_range.start].bytes().rev().take_while(|&b| b == b' ').count();
+                edits.push(Edit {
+                    range: line_range.start - indent..line_range.end,
+                    text: String::new(),
+                });
+            }
+            Line::Shown(text) if text.len() != line.trim_end_matches('\n').len() => {
+                let newline = if line.ends_with('\n') { "\n" } else { "" };
+                edits.push(Edit { range: line_range, text: format!("{text}{newline}") });
+            }
+            Line::Shown(_) => {}
+        }
+    }
+    edits
+}
@@ -0,0 +1,416 @@
+//! Plain-text rendering of signatures for the Markdown backend.
+//!
+//! Signatures are written into fenced code blocks, which cannot contain links, so types, paths,
+//! generics and function declarations are printed with the alternate (`{:#}`) format of the
+//! printers in [`crate::html::format`], which produces plain text. This module only lays out the
+//! declarations around them.
+
+use std::fmt::{self, Display, Write};
+
+use rustc_hir as hir;
+use rustc_middle::ty;
+
+use crate::clean;
+use crate::clean::utils::find_nearest_parent_module;
+use crate::html::format::{
+    PrintWithSpace, full_print_fn_decl, print_abi_with_space, print_constness_with_space,
+    print_default_space, print_fn_decl, print_generic_bounds, print_generics, print_path,
+    print_type, print_where_predicate,
+};
+use crate::html::render::Context;
+
+/// How a where clause is laid out.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum Layout {
+    /// On the same line as the rest of the declaration, for use in headings and lists.
+    SingleLine,
+    /// On its own lines, for use in code blocks.
+    MultiLine,
+}
+
+/// Prints the where clause of `generics`, if it has one, including the leading space or newline.
+pub(super) fn print_where_clause(
+    generics: &clean::Generics,
+    layout: Layout,
+    cx: &Context<'_>,
+) -> impl Display {
+    fmt::from_fn(move |f| {
+        let mut predicates = generics.where_predicates.iter().map(|p| print_where_predicate(p, cx));
+        match layout {
+            Layout::SingleLine => {
+                let Some(first) = predicates.next() else { return Ok(()) };
+                write!(f, " where {first:#}")?;
+                for predicate in predicates {
+                    write!(f, ", {predicate:#}")?;
+                }
+                Ok(())
+            }
+            Layout::MultiLine => {
+                if generics.where_predicates.is_empty() {
+                    return Ok(());
+                }
+                f.write_str("\nwhere")?;
+                for predicate in predicates {
+                    write!(f, "\n    {predicate:#},")?;
+                }
+                Ok(())
+            }
+        }
+    })
+}
+
+pub(super) fn print_visibility(item: &clean::Item, cx: &Context<'_>) -> impl Display {
+    fmt::from_fn(move |f| {
+        let tcx = cx.tcx();
+        let Some(vis) = item.visibility(tcx) else {
+            return Ok(());
+        };
+        match vis {
+            ty::Visibility::Public => f.write_str("pub "),
+            ty::Visibility::Restricted(vis_did) => {
+                let parent_module = find_nearest_parent_module(tcx, item.item_id.expect_def_id());
+                if vis_did.is_crate_root() {
+                    f.write_str("pub(crate) ")
+                } else if parent_module == Some(vis_did) {
+                    // `pub(in foo)` where `foo` is the parent module is the same as no visibility
+                    // modifier.
+                    Ok(())
+                } else if parent_module.and_then(|parent| find_nearest_parent_module(tcx, parent))
+                    == Some(vis_did)
+                {
+                    f.write_str("pub(super) ")
+                } else {
+                    write!(f, "pub(in {}) ", tcx.def_path_str(vis_did))
+                }
+            }
+        }
+    })
+}
+
+/// Prints the header of an impl block, such as `impl<T: Clone> Clone for Vec<T>`.
+pub(super) fn print_impl(impl_: &clean::Impl, layout: Layout, cx: &Context<'_>) -> impl Display {
+    fmt::from_fn(move |f| {
+        write!(
+            f,
+            "{}impl{:#} ",
+            impl_.safety.print_with_space(),
+            print_generics(&impl_.generics, cx)
+        )?;
+        if let Some(trait_) = &impl_.trait_ {
+            if impl_.is_negative_trait_impl() {
+                f.write_char('!')?;
+            }
+            write!(f, "{:#} for ", print_path(trait_, cx))?;
+        }
+        let for_ = impl_.kind.as_blanket_ty().unwrap_or(&impl_.for_);
+        write!(f, "{:#}", print_type(for_, cx))?;
+        print_where_clause(&impl_.generics, layout, cx).fmt(f)
+    })
+}
+
+/// Prints the signature of a function or method, without its body.
+pub(super) fn print_fn_signature(
+    item: &clean::Item,
+    function: &clean::Function,
+    layout: Layout,
+    cx: &Context<'_>,
+) -> impl Display {
+    fmt::from_fn(move |f| {
+        let tcx = cx.tcx();
+        let header = item.fn_header(tcx).expect("printing a function which isn't a function");
+        let head = format!(
+            "{vis}{default}{constness}{asyncness}{safety}{abi:#}fn {name}{generics:#}",
+            vis = print_visibility(item, cx),
+            default = print_default_space(item.is_default()),
+            constness = print_constness_with_space(
+                &header.constness,
+                item.stable_since(tcx),
+                item.const_stability(tcx),
+            ),
+            asyncness = header.asyncness.print_with_space(),
+            safety = header.safety.print_with_space(),
+            abi = print_abi_with_space(header.abi),
+            name = item.name.unwrap(),
+            generics = print_generics(&function.generics, cx),
+        );
+        f.write_str(&head)?;
+        // Parameters are only put on their own lines in code blocks.
+        match layout {
+            Layout::SingleLine => write!(f, "{:#}", print_fn_decl(&function.decl, cx))?,
+            Layout::MultiLine => {
+                write!(f, "{:#}", full_print_fn_decl(&function.decl, head.len(), 0, cx))?
+            }
+        }
+        print_where_clause(&function.generics, layout, cx).fmt(f)
+    })
+}
+
+/// Prints the declaration of an associated item, as it appears in a trait or impl block.
+pub(super) fn print_assoc_item(
+    item: &clean::Item,
+    layout: Layout,
+    cx: &Context<'_>,
+) -> impl Display {
+    fmt::from_fn(move |f| {
+        let tcx = cx.tcx();
+        let name = item.name.unwrap();
+        let vis = print_visibility(item, cx);
+        match &item.kind {
+            clean::StrippedItem(..) => Ok(()),
+            clean::RequiredMethodItem(function) | clean::MethodItem(function, _) => {
+                print_fn_signature(item, function, layout, cx).fmt(f)
+            }
+            clean::RequiredAssocConstItem(generics, ty) => {
+                write!(
+                    f,
+                    "{vis}const {name}{:#}: {:#}",
+                    print_generics(generics, cx),
+                    print_type(ty, cx)
+                )?;
+                print_where_clause(generics, layout, cx).fmt(f)
+            }
+            clean::ProvidedAssocConstItem(ct) | clean::ImplAssocConstItem(ct) => {
+                write!(
+                    f,
+                    "{vis}const {name}{:#}: {:#} = {}",
+                    print_generics(&ct.generics, cx),
+                    print_type(&ct.type_, cx),
+                    ct.kind.expr(tcx),
+                )?;
+                print_where_clause(&ct.generics, layout, cx).fmt(f)
+            }
+            clean::RequiredAssocTypeItem(generics, bounds) => {
+                write!(f, "{vis}type {name}{:#}", print_generics(generics, cx))?;
+                if !bounds.is_empty() {
+                    write!(f, ": {:#}", print_generic_bounds(bounds, cx))?;
+                }
+                print_where_clause(generics, layout, cx).fmt(f)
+            }
+            clean::AssocTypeItem(alias, bounds) => {
+                write!(f, "{vis}type {name}{:#}", print_generics(&alias.generics, cx))?;
+                if !bounds.is_empty() {
+                    write!(f, ": {:#}", print_generic_bounds(bounds, cx))?;
+                }
+                print_where_clause(&alias.generics, layout, cx).fmt(f)?;
+                write!(f, " = {:#}", print_type(&alias.type_, cx))
+            }
+            _ => unreachable!("not an associated item: {:?}", item.type_()),
+        }
+    })
+}
+
+/// Prints the fields of a struct, union or variant inside braces or parentheses.
+fn print_fields(
+    fields: &[clean::Item],
+    ctor_kind: Option<hir::def::CtorKind>,
+    indent: &'static str,
+    cx: &Context<'_>,
+) -> impl Display {
+    fmt::from_fn(move |f| {
+        let has_stripped = fields.iter().any(|field| field.is_stripped());
+        let visible = fields.iter().filter_map(|field| match &field.kind {
+            clean::StructFieldItem(ty) => Some((field, ty)),
+            _ => None,
+        });
+        match ctor_kind {
+            Some(hir::def::CtorKind::Fn) => {
+                let mut parts: Vec<String> = fields
+                    .iter()
+                    .map(|field| match &field.kind {
+                        clean::StructFieldItem(ty) => {
+                            format!("{}{:#}", print_visibility(field, cx), print_type(ty, cx))
+                        }
+                        _ => "_".to_owned(),
+                    })
+                    .collect();
+                if parts.iter().all(|part| part == "_") && has_stripped {
+                    parts = vec!["/* private fields */".to_owned()];
+                }
+                write!(f, "({})", parts.join(", "))
+            }
+            Some(hir::def::CtorKind::Const) => Ok(()),
+            None => {
+                f.write_str(" {\n")?;
+                for (field, ty) in visible {
+                    writeln!(
+                        f,
+                        "{indent}    {}{}: {:#},",
+                        print_visibility(field, cx),
+                        field.name.unwrap(),
+                        print_type(ty, cx),
+                    )?;
+                }
+                if has_stripped {
+                    writeln!(f, "{indent}    /* private fields */")?;
+                }
+                write!(f, "{indent}}}")
+            }
+        }
+    })
+}
+
+/// Prints a variant on a single line, such as `Point { x: i32, y: i32 }`.
+pub(super) fn print_variant(
+    item: &clean::Item,
+    variant: &clean::Variant,
+    cx: &Context<'_>,
+) -> String {
+    let name = item.name.unwrap();
+    let field_type = |field: &clean::Item| match &field.kind {
+        clean::StructFieldItem(ty) => format!("{:#}", print_type(ty, cx)),
+        _ => "_".to_owned(),
+    };
+    match &variant.kind {
+        clean::VariantKind::CLike => {
+            match variant.discriminant.as_ref().and_then(|d| d.expr(cx.tcx())) {
+                Some(discriminant) => format!("{name} = {discriminant}"),
+                None => name.to_string(),
+            }
+        }
+        clean::VariantKind::Tuple(fields) => {
+            format!("{name}({})", fields.iter().map(field_type).collect::<Vec<_>>().join(", "))
+        }
+        clean::VariantKind::Struct(s) => {
+            let fields = s
+                .fields
+                .iter()
+                .filter(|field| !field.is_stripped())
+                .map(|field| format!("{}: {}", field.name.unwrap(), field_type(field)))
+                .collect::<Vec<_>>();
+            format!("{name} {{ {} }}", fields.join(", "))
+        }
+    }
+}
+
+/// Prints the declaration of an item, as it is shown at the top of its page.
+pub(super) fn print_declaration(item: &clean::Item, cx: &Context<'_>) -> Option<String> {
+    let tcx = cx.tcx();
+    let name = item.name?;
+    let vis = print_visibility(item, cx);
+    let decl = match &item.kind {
+        clean::FunctionItem(function) | clean::ForeignFunctionItem(function, _) => {
+            print_fn_signature(item, function, Layout::MultiLine, cx).to_string()
+        }
+        clean::StructItem(s) => {
+            let generics = print_generics(&s.generics, cx);
+            let where_clause = print_where_clause(&s.generics, Layout::MultiLine, cx);
+            let fields = print_fields(&s.fields, s.ctor_kind, "", cx);
+            match s.ctor_kind {
+                None => format!("{vis}struct {name}{generics:#}{where_clause}{fields}"),
+                Some(_) => format!("{vis}struct {name}{generics:#}{fields}{where_clause};"),
+            }
+        }
+        clean::UnionItem(u) => format!(
+            "{vis}union {name}{:#}{}{}",
+            print_generics(&u.generics, cx),
+            print_where_clause(&u.generics, Layout::MultiLine, cx),
+            print_fields(&u.fields, None, "", cx),
+        ),
+        clean::EnumItem(e) => {
+            let mut decl = format!(
+                "{vis}enum {name}{:#}{} {{\n",
+                print_generics(&e.generics, cx),
+                print_where_clause(&e.generics, Layout::MultiLine, cx),
+            );
+            for variant in e.non_stripped_variants() {
+                let clean::VariantItem(v) = &variant.kind else { continue };
+                let variant_name = variant.name.unwrap();
+                let fields = match &v.kind {
+                    clean::VariantKind::CLike => String::new(),
+                    clean::VariantKind::Tuple(fields) => {
+                        print_fields(fields, Some(hir::def::CtorKind::Fn), "    ", cx).to_string()
+                    }
+                    clean::VariantKind::Struct(s) => {
+                        print_fields(&s.fields, None, "    ", cx).to_string()
+                    }
+                };
+                writeln!(decl, "    {variant_name}{fields},").unwrap();
+            }
+            if e.has_stripped_entries() {
+                decl.push_str("    // some variants omitted\n");
+            }
+            decl.push('}');
+            decl
+        }
+        clean::TraitItem(t) => {
+            let mut decl = format!(
+                "{vis}{}{}trait {name}{:#}",
+                t.safety(tcx).print_with_space(),
+                if t.is_auto(tcx) { "auto " } else { "" },
+                print_generics(&t.generics, cx),
+            );
+            if !t.bounds.is_empty() {
+                write!(decl, ": {:#}", print_generic_bounds(&t.bounds, cx)).unwrap();
+            }
+            write!(decl, "{}", print_where_clause(&t.generics, Layout::MultiLine, cx)).unwrap();
+            let items: Vec<_> = t.items.iter().filter(|item| !item.is_stripped()).collect();
+            if items.is_empty() {
+                decl.push_str(" {}");
+            } else {
+                decl.push_str(" {\n");
+                for item in items {
+                    let assoc = print_assoc_item(item, Layout::SingleLine, cx);
+                    let body =
+                        if matches!(item.kind, clean::MethodItem(..)) { " { ... }" } else { ";" };
+                    writeln!(decl, "    {assoc}{body}").unwrap();
+                }
+                decl.push('}');
+            }
+            decl
+        }
+        clean::TraitAliasItem(ta) => format!(
+            "trait {name}{:#}{} = {:#};",
+            print_generics(&ta.generics, cx),
+            print_where_clause(&ta.generics, Layout::MultiLine, cx),
+            print_generic_bounds(&ta.bounds, cx),
+        ),
+        clean::TypeAliasItem(alias) => format!(
+            "{vis}type {name}{:#}{} = {:#};",
+            print_generics(&alias.generics, cx),
+            print_where_clause(&alias.generics, Layout::MultiLine, cx),
+            print_type(&alias.type_, cx),
+        ),
+        clean::ConstantItem(ct) => format!(
+            "{vis}const {name}{:#}: {:#} = {};",
+            print_generics(&ct.generics, cx),
+            print_type(&ct.type_, cx),
+            ct.kind.expr(tcx),
+        ),
+        clean::StaticItem(s) | clean::ForeignStaticItem(s, _) => format!(
+            "{vis}static {}{name}: {:#};",
+            s.mutability.print_with_space(),
+            print_type(&s.type_, cx),
+        ),
+        clean::ForeignTypeItem => format!("{vis}type {name};"),
+        clean::MacroItem(m) => m.source.clone(),
+        clean::ProcMacroItem(m) => match m.kind {
+            rustc_span::hygiene::MacroKind::Bang => format!("{name}!() {{ /* proc-macro */ }}"),
+            rustc_span::hygiene::MacroKind::Attr => format!("#[{name}]"),
+            rustc_span::hygiene::MacroKind::Derive => {
+                let mut decl = format!("#[derive({name})]");
+                if !m.helpers.is_empty() {
+                    decl.push_str("\n// Helper attributes:");
+                    for helper in &m.helpers {
+                        write!(decl, "\n#[{helper}]").unwrap();
+                    }
+                }
+                decl
+            }
+        },
+        _ => return None,
+    };
+    Some(decl)
+}
+
+/// Makes `code` usable inside an inline code span, by picking a backtick fence that does not
+/// appear in it.
+pub(super) fn inline_code(code: &str) -> impl Display {
+    fmt::from_fn(move |f| {
+        let mut fence = "`".to_owned();
+        while code.contains(&fence) {
+            fence.push('`');
+        }
+        let pad = if code.starts_with('`') || code.ends_with('`') { " " } else { "" };
+        write!(f, "{fence}{pad}{code}{pad}{fence}")
+    })
+}
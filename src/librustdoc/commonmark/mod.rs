@@ -0,0 +1,576 @@
+//! Rustdoc's Markdown backend, enabled with `--output-format markdown`.
+//!
+//! Like the HTML backend, this writes one file per module (`index.md`) and one file per item
+//! (`struct.Foo.md`), in a directory hierarchy that mirrors the module tree. Each file has the
+//! signature of the item, its stability, its documentation with intra-doc links pointing to the
+//! other generated files, and the list of its fields, variants, associated items and impls.
+
+mod docs;
+mod format;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+use rustc_ast::join_path_syms;
+use rustc_hir::attrs::{AttributeKind, DeprecatedSince, Deprecation};
+use rustc_hir::def_id::DefId;
+use rustc_hir::{Attribute, StabilityLevel, StableSince};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::hygiene::MacroKind;
+use rustc_span::symbol::{Symbol, sym};
+use tracing::debug;
+
+use self::format::{Layout, inline_code};
+use crate::clean::types::ExternalLocation;
+use crate::clean::{self, RenderedLink};
+use crate::config::{ModuleSorting, RenderOptions};
+use crate::docfs::PathError;
+use crate::error::Error;
+use crate::formats::cache::Cache;
+use crate::formats::item_type::ItemType;
+use crate::formats::{FormatRenderer, Impl};
+use crate::html::format::{fragment, href_relative_parts, print_import, print_type};
+use crate::html::markdown::plain_text_summary;
+use crate::html::render::{Context, ItemSection, compare_names, item_ty_to_section};
+use crate::html::url_parts_builder::UrlPartsBuilder;
+use crate::passes::collect_intra_doc_links::UrlFragment;
+use crate::try_err;
+
+pub(crate) struct CommonMarkRenderer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The HTML context, which holds the cache. It is only used to print signatures as plain text
+    /// and never writes any files.
+    cx: Context<'tcx>,
+    /// The path of the module being rendered, starting with the crate name.
+    current: Vec<Symbol>,
+    /// The directory where the files of the current module are written.
+    dst: PathBuf,
+    /// Whether the current module is stripped. Such modules only survive the passes because they
+    /// contain impls or re-exported items, which are documented elsewhere.
+    in_stripped_module: bool,
+    module_sorting: ModuleSorting,
+    issue_tracker_base_url: Option<String>,
+}
+
+impl<'tcx> CommonMarkRenderer<'tcx> {
+    pub(crate) fn init(
+        krate: clean::Crate,
+        options: RenderOptions,
+        cache: Cache,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<(Self, clean::Crate), Error> {
+        debug!("Initializing markdown renderer");
+
+        let mut issue_tracker_base_url = None;
+        for attr in &krate.module.attrs.other_attrs {
+            let Attribute::Parsed(AttributeKind::Doc(d)) = attr else { continue };
+            if let Some((s, _)) = d.issue_tracker_base_url {
+                issue_tracker_base_url = Some(s.to_string());
+            }
+        }
+
+        let dst = options.output.clone();
+        let module_sorting = options.module_sorting;
+        let cx = Context::new(&krate, options, cache, tcx, Default::default())?;
+        Ok((
+            CommonMarkRenderer {
+                tcx,
+                cx,
+                current: Vec::new(),
+                dst,
+                in_stripped_module: false,
+                module_sorting,
+                issue_tracker_base_url,
+            },
+            krate,
+        ))
+    }
+
+    fn write_file(&self, file_name: &str, content: String) -> Result<(), Error> {
+        try_err!(fs::create_dir_all(&self.dst), &self.dst);
+        let path = self.dst.join(file_name);
+        try_err!(fs::write(&path, content), &path);
+        Ok(())
+    }
+
+    /// Returns the path of the file documenting `did`, relative to the current module, or a URL if
+    /// it is documented in a crate whose documentation was built elsewhere.
+    ///
+    /// `did` must be an item with its own page, like the `page_id` of intra-doc links.
+    fn href(&self, did: DefId) -> Option<String> {
+        let cache = self.cx.cache();
+        if !did.is_local()
+            && !cache.effective_visibilities.is_directly_public(self.tcx, did)
+            && !cache.document_private
+            && !cache.primitive_locations.values().any(|&id| id == did)
+        {
+            return None;
+        }
+        let (fqp, shortty, mut url_parts, extension) =
+            if let Some((fqp, shortty)) = cache.paths.get(&did) {
+                let url_parts = href_relative_parts(module_fqp(*shortty, fqp), &self.current);
+                (fqp, *shortty, url_parts, "md")
+            } else if let Some((fqp, shortty)) = cache.external_paths.get(&did) {
+                let module_fqp = module_fqp(*shortty, fqp);
+                match cache.extern_locations[&did.krate] {
+                    ExternalLocation::Remote(ref url) => {
+                        let mut url_parts = UrlPartsBuilder::singleton(url.trim_end_matches('/'));
+                        url_parts.extend(module_fqp.iter().copied());
+                        (fqp, *shortty, url_parts, "html")
+                    }
+                    ExternalLocation::Local => {
+                        (fqp, *shortty, href_relative_parts(module_fqp, &self.current), "md")
+                    }
+                    ExternalLocation::Unknown => return None,
+                }
+            } else {
+                return None;
+            };
+        match shortty {
+            ItemType::Module => url_parts.push_fmt(format_args!("index.{extension}")),
+            _ => url_parts.push_fmt(format_args!("{shortty}.{}.{extension}", fqp.last().unwrap())),
+        }
+        Some(url_parts.finish())
+    }
+
+    /// The intra-doc links of `item` that point to an item with documentation.
+    fn links(&self, item: &clean::Item) -> Vec<RenderedLink> {
+        let Some(links) = self.cx.cache().intra_doc_links.get(&item.item_or_reexport_id()) else {
+            return Vec::new();
+        };
+        links
+            .iter()
+            .filter_map(|link| {
+                let mut href = self.href(link.page_id)?;
+                match &link.fragment {
+                    Some(UrlFragment::Item(def_id)) => {
+                        write!(href, "{}", fragment(*def_id, self.tcx)).unwrap()
+                    }
+                    Some(UrlFragment::UserWritten(raw)) => {
+                        href.push('#');
+                        href.push_str(raw);
+                    }
+                    None => {}
+                }
+                Some(RenderedLink {
+                    original_text: link.link.clone(),
+                    new_text: link.link_text.clone(),
+                    href,
+                    tooltip: String::new(),
+                })
+            })
+            .collect()
+    }
+
+    fn render_page(&self, item: &clean::Item, title: &str) -> String {
+        let tcx = self.tcx;
+        let path = match item.kind {
+            // Primitives and keywords are documented in a crate, but not actually part of it.
+            clean::PrimitiveItem(_) | clean::KeywordItem | clean::AttributeItem => {
+                item.name.unwrap().to_string()
+            }
+            clean::ModuleItem(_) => join_path_syms(&self.current),
+            _ => join_path_syms(self.current.iter().chain(&item.name)),
+        };
+        let mut out = format!("# {title} {}\n\n", inline_code(&path));
+        if let Some(declaration) = format::print_declaration(item, &self.cx) {
+            write!(out, "```rust\n{declaration}\n```\n\n").unwrap();
+        }
+        self.write_item_info(&mut out, item, None);
+        self.write_docs(&mut out, item, 1);
+
+        match &item.kind {
+            clean::ModuleItem(module) => self.write_module_items(&mut out, &module.items),
+            clean::StructItem(s) => self.write_fields(&mut out, item, &s.fields),
+            clean::UnionItem(u) => self.write_fields(&mut out, item, &u.fields),
+            clean::EnumItem(e) => self.write_variants(&mut out, item, e),
+            clean::TraitItem(t) => self.write_trait_items(&mut out, item, t),
+            _ => {}
+        }
+        if let Some(did) = item.item_id.as_def_id() {
+            self.write_impls(&mut out, did);
+        }
+        out
+    }
+
+    /// Writes the deprecation, stability and portability notes of `item` as a block quote.
+    fn write_item_info(&self, out: &mut String, item: &clean::Item, parent: Option<&clean::Item>) {
+        let tcx = self.tcx;
+        let mut notes = Vec::new();
+
+        if let Some(depr @ Deprecation { note, since, suggestion: _ }) = item.deprecation(tcx) {
+            let mut message = match since {
+                DeprecatedSince::RustcVersion(version) => {
+                    if depr.is_in_effect() {
+                        format!("Deprecated since {version}")
+                    } else {
+                        format!("Deprecating in {version}")
+                    }
+                }
+                DeprecatedSince::Future => String::from("Deprecating in a future version"),
+                DeprecatedSince::NonStandard(since) => format!("Deprecated since {since}"),
+                DeprecatedSince::Unspecified | DeprecatedSince::Err => String::from("Deprecated"),
+            };
+            if let Some(note) = note {
+                message.push_str(": ");
+                message.push_str(&docs::rewrite(note.as_str(), &self.links(item), 0));
+            }
+            notes.push(message);
+        }
+
+        // Like in the HTML output, the permanently unstable `rustc_private` crates are not marked.
+        match item.stability(tcx) {
+            Some(stab) if stab.feature == sym::rustc_private => {}
+            Some(stab) => match stab.level {
+                StabilityLevel::Unstable { issue, .. } => {
+                    let mut message = format!(
+                        "This is a nightly-only experimental API. ({}",
+                        inline_code(stab.feature.as_str()),
+                    );
+                    if let (Some(url), Some(issue)) = (&self.issue_tracker_base_url, issue) {
+                        write!(message, " [#{issue}]({url}{issue})").unwrap();
+                    }
+                    message.push(')');
+                    notes.push(message);
+                }
+                StabilityLevel::Stable { since: StableSince::Version(version), .. }
+                    if parent.is_none() =>
+                {
+                    notes.push(format!("Stable since Rust {version}."));
+                }
+                StabilityLevel::Stable { .. } => {}
+            },
+            None => {}
+        }
+
+        let cfg = match (&item.cfg, parent.and_then(|p| p.cfg.as_ref())) {
+            (Some(cfg), Some(parent_cfg)) => cfg.simplify_with(parent_cfg),
+            (cfg, _) => cfg.as_deref().cloned(),
+        };
+        if let Some(cfg) = cfg {
+            notes.push(format!("{}.", cfg.render_long_plain()));
+        }
+
+        if !notes.is_empty() {
+            let notes: Vec<String> = notes.iter().map(|note| format!("> {note}\n")).collect();
+            out.push_str(&notes.join(">\n"));
+            out.push('\n');
+        }
+    }
+
+    fn write_docs(&self, out: &mut String, item: &clean::Item, heading_offset: usize) {
+        let Some(doc) = item.opt_doc_value() else { return };
+        let doc = docs::rewrite(&doc, &self.links(item), heading_offset);
+        let doc = doc.trim();
+        if !doc.is_empty() {
+            out.push_str(doc);
+            out.push_str("\n\n");
+        }
+    }
+
+    /// Writes a heading for a field, variant or associated item, followed by its documentation.
+    fn write_member(
+        &self,
+        out: &mut String,
+        item: &clean::Item,
+        parent: &clean::Item,
+        anchor: Option<String>,
+        signature: &str,
+        level: usize,
+    ) {
+        if let Some(anchor) = anchor {
+            writeln!(out, "<a id=\"{anchor}\"></a>\n").unwrap();
+        }
+        write!(out, "{} {}\n\n", "#".repeat(level), inline_code(signature)).unwrap();
+        self.write_item_info(out, item, Some(parent));
+        self.write_docs(out, item, level);
+    }
+
+    fn write_module_items(&self, out: &mut String, items: &[clean::Item]) {
+        let tcx = self.tcx;
+        let mut items: Vec<&clean::Item> = items.iter().filter(|i| !i.is_stripped()).collect();
+        if self.module_sorting == ModuleSorting::Alphabetical {
+            items.sort_by(|i1, i2| {
+                let is_stable = |i: &clean::Item| {
+                    i.stability(tcx).as_ref().map(|s| s.level.is_stable()).unwrap_or(true)
+                };
+                // Stable items come first.
+                is_stable(i2).cmp(&is_stable(i1)).then_with(|| match (i1.name, i2.name) {
+                    (Some(name1), Some(name2)) => compare_names(name1.as_str(), name2.as_str()),
+                    (name1, name2) => name1.is_some().cmp(&name2.is_some()),
+                })
+            });
+        }
+
+        for &section in ItemSection::ALL {
+            let mut entries = String::new();
+            for item in items.iter().filter(|i| item_ty_to_section(i.type_()) == section) {
+                match &item.kind {
+                    clean::ImportItem(import) if import.should_be_displayed => {
+                        writeln!(
+                            entries,
+                            "- {}",
+                            inline_code(&format!("{:#}", print_import(import, &self.cx)))
+                        )
+                        .unwrap();
+                    }
+                    clean::ImportItem(_) => {}
+                    clean::ExternCrateItem { src } => {
+                        let name = item.name.unwrap();
+                        let decl = match src {
+                            Some(src) => format!("extern crate {src} as {name};"),
+                            None => format!("extern crate {name};"),
+                        };
+                        writeln!(entries, "- {}", inline_code(&decl)).unwrap();
+                    }
+                    _ => {
+                        let Some(name) = item.name else { continue };
+                        let file = match item.type_() {
+                            ItemType::Module => format!("{name}/index.md"),
+                            item_type => format!("{item_type}.{name}.md"),
+                        };
+                        write!(entries, "- [{}]({file})", inline_code(name.as_str())).unwrap();
+                        let summary = plain_text_summary(&item.doc_value(), &[]);
+                        if !summary.is_empty() {
+                            write!(entries, ": {summary}").unwrap();
+                        }
+                        entries.push('\n');
+                    }
+                }
+            }
+            if !entries.is_empty() {
+                write!(out, "## {}\n\n{entries}\n", section.name()).unwrap();
+            }
+        }
+    }
+
+    fn write_fields(&self, out: &mut String, item: &clean::Item, fields: &[clean::Item]) {
+        let fields: Vec<_> = fields
+            .iter()
+            .filter_map(|field| match &field.kind {
+                clean::StructFieldItem(ty) => Some((field, ty)),
+                _ => None,
+            })
+            .collect();
+        if fields.is_empty() {
+            return;
+        }
+        out.push_str("## Fields\n\n");
+        for (field, ty) in fields {
+            let name = field.name.unwrap();
+            let signature = format!("{name}: {:#}", print_type(ty, &self.cx));
+            let anchor = format!("structfield.{name}");
+            self.write_member(out, field, item, Some(anchor), &signature, 3);
+        }
+    }
+
+    fn write_variants(&self, out: &mut String, item: &clean::Item, e: &clean::Enum) {
+        let mut variants = e.non_stripped_variants().peekable();
+        if variants.peek().is_none() {
+            return;
+        }
+        out.push_str("## Variants\n\n");
+        for variant in variants {
+            let clean::VariantItem(v) = &variant.kind else { continue };
+            let name = variant.name.unwrap();
+            let signature = format::print_variant(variant, v, &self.cx);
+            let anchor = format!("variant.{name}");
+            self.write_member(out, variant, item, Some(anchor), &signature, 3);
+            if let clean::VariantKind::Struct(s) = &v.kind {
+                for field in &s.fields {
+                    let clean::StructFieldItem(ty) = &field.kind else { continue };
+                    let field_name = field.name.unwrap();
+                    let signature = format!("{field_name}: {:#}", print_type(ty, &self.cx));
+                    let anchor = format!("variant.{name}.field.{field_name}");
+                    self.write_member(out, field, variant, Some(anchor), &signature, 4);
+                }
+            }
+        }
+    }
+
+    fn write_trait_items(&self, out: &mut String, item: &clean::Item, t: &clean::Trait) {
+        let sections: [(&str, fn(&clean::ItemKind) -> bool); 6] = [
+            ("Required Associated Types", |kind| matches!(kind, clean::RequiredAssocTypeItem(..))),
+            ("Provided Associated Types", |kind| matches!(kind, clean::AssocTypeItem(..))),
+            ("Required Associated Constants", |kind| {
+                matches!(kind, clean::RequiredAssocConstItem(..))
+            }),
+            ("Provided Associated Constants", |kind| {
+                matches!(kind, clean::ProvidedAssocConstItem(..))
+            }),
+            ("Required Methods", |kind| matches!(kind, clean::RequiredMethodItem(..))),
+            ("Provided Methods", |kind| matches!(kind, clean::MethodItem(..))),
+        ];
+        for (title, filter) in sections {
+            let mut members = t.items.iter().filter(|i| !i.is_stripped() && filter(&i.kind));
+            let Some(first) = members.next() else { continue };
+            write!(out, "## {title}\n\n").unwrap();
+            for member in std::iter::once(first).chain(members) {
+                self.write_assoc_item(out, member, item, true, 3);
+            }
+        }
+
+        if let Some(implementors) = self.cx.cache().implementors.get(&t.def_id) {
+            out.push_str("## Implementors\n\n");
+            let mut lines: Vec<String> =
+                implementors.iter().map(|i| self.impl_line(i, ImplLink::ForType)).collect();
+            lines.sort();
+            lines.dedup();
+            out.push_str(&lines.concat());
+            out.push('\n');
+        }
+    }
+
+    fn write_assoc_item(
+        &self,
+        out: &mut String,
+        item: &clean::Item,
+        parent: &clean::Item,
+        anchor: bool,
+        level: usize,
+    ) {
+        let signature = format::print_assoc_item(item, Layout::SingleLine, &self.cx).to_string();
+        let anchor = anchor.then(|| format!("{}.{}", item.type_(), item.name.unwrap()));
+        self.write_member(out, item, parent, anchor, &signature, level);
+    }
+
+    /// Writes the impls of the type, trait or primitive `did`. Inherent impls are written in full,
+    /// trait impls are listed.
+    fn write_impls(&self, out: &mut String, did: DefId) {
+        let Some(impls) = self.cx.cache().impls.get(&did) else { return };
+        let (inherent, traits): (Vec<&Impl>, Vec<&Impl>) =
+            impls.iter().partition(|i| i.inner_impl().trait_.is_none());
+
+        if !inherent.is_empty() {
+            out.push_str("## Implementations\n\n");
+            for i in inherent {
+                let header = format::print_impl(i.inner_impl(), Layout::SingleLine, &self.cx);
+                write!(out, "### {}\n\n", inline_code(&header.to_string())).unwrap();
+                self.write_docs(out, &i.impl_item, 3);
+                for member in i.inner_impl().items.iter().filter(|i| !i.is_stripped()) {
+                    self.write_assoc_item(out, member, &i.impl_item, true, 4);
+                }
+            }
+        }
+
+        let (synthetic, concrete): (Vec<&Impl>, Vec<&Impl>) =
+            traits.into_iter().partition(|t| t.inner_impl().kind.is_auto());
+        let (blanket, concrete): (Vec<&Impl>, Vec<&Impl>) =
+            concrete.into_iter().partition(|t| t.inner_impl().kind.is_blanket());
+        for (title, impls) in [
+            ("Trait Implementations", concrete),
+            ("Auto Trait Implementations", synthetic),
+            ("Blanket Implementations", blanket),
+        ] {
+            if impls.is_empty() {
+                continue;
+            }
+            let mut lines: Vec<String> =
+                impls.into_iter().map(|i| self.impl_line(i, ImplLink::Trait)).collect();
+            lines.sort();
+            write!(out, "## {title}\n\n{}\n", lines.concat()).unwrap();
+        }
+    }
+
+    /// A list entry with the header of an impl, linking to its trait or to the implementing type.
+    fn impl_line(&self, i: &Impl, link: ImplLink) -> String {
+        let header = format::print_impl(i.inner_impl(), Layout::SingleLine, &self.cx).to_string();
+        let did = match link {
+            ImplLink::Trait => i.trait_did(),
+            ImplLink::ForType => i.inner_impl().for_.def_id(self.cx.cache()),
+        };
+        match did.and_then(|did| self.href(did)) {
+            Some(href) => format!("- [{}]({href})\n", inline_code(&header)),
+            None => format!("- {}\n", inline_code(&header)),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ImplLink {
+    Trait,
+    ForType,
+}
+
+fn module_fqp(shortty: ItemType, fqp: &[Symbol]) -> &[Symbol] {
+    if shortty == ItemType::Module { fqp } else { &fqp[..fqp.len() - 1] }
+}
+
+/// The title of the page of `item`, or `None` if it does not get one. These are the same as in the
+/// HTML output.
+fn page_title(item: &clean::Item) -> Option<&'static str> {
+    let title = match &item.kind {
+        clean::ModuleItem(_) if item.is_crate() => "Crate",
+        clean::ModuleItem(_) => "Module",
+        clean::FunctionItem(..) | clean::ForeignFunctionItem(..) => "Function",
+        clean::TraitItem(..) => "Trait",
+        clean::StructItem(..) => "Struct",
+        clean::UnionItem(..) => "Union",
+        clean::EnumItem(..) => "Enum",
+        clean::TypeAliasItem(..) => "Type Alias",
+        clean::MacroItem(..) => "Macro",
+        clean::ProcMacroItem(mac) => match mac.kind {
+            MacroKind::Bang => "Macro",
+            MacroKind::Attr => "Attribute Macro",
+            MacroKind::Derive => "Derive Macro",
+        },
+        clean::PrimitiveItem(..) => "Primitive Type",
+        clean::StaticItem(..) | clean::ForeignStaticItem(..) => "Static",
+        clean::ConstantItem(..) => "Constant",
+        clean::ForeignTypeItem => "Foreign Type",
+        clean::KeywordItem => "Keyword",
+        clean::AttributeItem => "Attribute",
+        clean::TraitAliasItem(..) => "Trait Alias",
+        _ => return None,
+    };
+    Some(title)
+}
+
+impl<'tcx> FormatRenderer<'tcx> for CommonMarkRenderer<'tcx> {
+    fn descr() -> &'static str {
+        "markdown"
+    }
+
+    const RUN_ON_MODULE: bool = true;
+    type ModuleData = bool;
+
+    fn save_module_data(&mut self) -> Self::ModuleData {
+        self.in_stripped_module
+    }
+
+    fn restore_module_data(&mut self, in_stripped_module: Self::ModuleData) {
+        self.in_stripped_module = in_stripped_module;
+    }
+
+    fn mod_item_in(&mut self, item: &clean::Item) -> Result<(), Error> {
+        self.in_stripped_module |= item.is_stripped();
+        let name = item.name.unwrap();
+        self.dst.push(name.as_str());
+        self.current.push(name);
+        if !self.in_stripped_module {
+            let page = self.render_page(item, page_title(item).unwrap());
+            self.write_file("index.md", page)?;
+        }
+        Ok(())
+    }
+
+    fn mod_item_out(&mut self) -> Result<(), Error> {
+        self.dst.pop();
+        self.current.pop();
+        Ok(())
+    }
+
+    fn item(&mut self, item: &clean::Item) -> Result<(), Error> {
+        if self.in_stripped_module || item.is_stripped() {
+            return Ok(());
+        }
+        let Some(title) = page_title(item) else { return Ok(()) };
+        let page = self.render_page(item, title);
+        self.write_file(&format!("{}.{}.md", item.type_(), item.name.unwrap()), page)
+    }
+
+    fn after_krate(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
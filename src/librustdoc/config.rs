@@ -34,6 +34,7 @@ pub(crate) enum OutputFormat {
     #[default]
     Html,
     Doctest,
+    Markdown,
 }
 
 impl OutputFormat {
@@ -50,6 +51,7 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
             "json" => Ok(OutputFormat::Json),
             "html" => Ok(OutputFormat::Html),
             "doctest" => Ok(OutputFormat::Doctest),
+            "markdown" => Ok(OutputFormat::Markdown),
             _ => Err(format!("unknown output format `{value}`")),
         }
     }
@@ -130,6 +132,9 @@ pub(crate) struct Options {
     /// Optional path to persist the doctest executables to, defaults to a
     /// temporary directory if not set.
     pub(crate) persist_doctests: Option<PathBuf>,
+    /// Optional path to a directory where the results of doctests are cached, so that unchanged
+    /// doctests are not compiled and run again.
+    pub(crate) doctest_cache_dir: Option<PathBuf>,
     /// Whether to merge
     pub(crate) merge_doctests: MergeDoctests,
     /// Runtool to run doctests with
@@ -152,6 +157,8 @@ pub(crate) struct Options {
     /// Whether to run the `calculate-doc-coverage` pass, which counts the number of public items
     /// with and without documentation.
     pub(crate) show_coverage: bool,
+    /// Options for the report printed with `--show-coverage`.
+    pub(crate) coverage_options: CoverageOptions,
 
     // Options that alter generated documentation pages
     /// Crate version to note on the sidebar of generated docs.
@@ -214,7 +221,9 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("test_args", &self.test_args)
             .field("test_run_directory", &self.test_run_directory)
             .field("persist_doctests", &self.persist_doctests)
+            .field("doctest_cache_dir", &self.doctest_cache_dir)
             .field("show_coverage", &self.show_coverage)
+            .field("coverage_options", &self.coverage_options)
             .field("crate_version", &self.crate_version)
             .field("test_runtool", &self.test_runtool)
             .field("test_runtool_args", &self.test_runtool_args)
@@ -325,6 +334,8 @@ pub(crate) enum EmitType {
     Toolchain,
     InvocationSpecific,
     DepInfo(Option<OutFileName>),
+    /// The export of the search index, see `rustdoc_search_index_types`.
+    SearchIndex,
 }
 
 impl FromStr for EmitType {
@@ -335,6 +346,7 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             "toolchain-shared-resources" => Ok(Self::Toolchain),
             "invocation-specific" => Ok(Self::InvocationSpecific),
             "dep-info" => Ok(Self::DepInfo(None)),
+            "search-index" => Ok(Self::SearchIndex),
             option => match option.strip_prefix("dep-info=") {
                 Some("-") => Ok(Self::DepInfo(Some(OutFileName::Stdout))),
                 Some(f) => Ok(Self::DepInfo(Some(OutFileName::Real(f.into())))),
@@ -511,8 +523,15 @@ fn println_condition(condition: Condition) {
                     "the -Z unstable-options flag must be passed to enable --output-format for documentation generation (see https://github.com/rust-lang/rust/issues/134529)",
                 );
             }
+            (Some(OutputFormat::Markdown), false, false) => {
+                dcx.fatal(
+                    "the -Z unstable-options flag must be passed to enable --output-format=markdown",
+                );
+            }
         }
 
+        let coverage_options = parse_coverage_options(matches, show_coverage, dcx);
+
         let to_check = matches.opt_strs("check-theme");
         if !to_check.is_empty() {
             let mut content =
@@ -784,6 +803,7 @@ fn println_condition(condition: Condition) {
         let static_root_path = matches.opt_str("static-root-path");
         let test_run_directory = matches.opt_str("test-run-directory").map(PathBuf::from);
         let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
+        let doctest_cache_dir = matches.opt_str("doctest-cache-dir").map(PathBuf::from);
         let test_builder = matches.opt_str("test-builder").map(PathBuf::from);
         let codegen_options_strs = matches.opt_strs("C");
         let unstable_opts_strs = matches.opt_strs("Z");
@@ -856,9 +876,11 @@ fn println_condition(condition: Condition) {
             should_test,
             test_args,
             show_coverage,
+            coverage_options,
             crate_version,
             test_run_directory,
             persist_doctests,
+            doctest_cache_dir,
             merge_doctests,
             test_runtool,
             test_runtool_args,
@@ -1007,6 +1029,22 @@ fn from_flag(path: String) -> Result<PathToParts, String> {
             Ok(PathToParts(path))
         }
     }
+
+    /// Names of the crates that have their info stored in this directory.
+    ///
+    /// Unreadable entries are skipped here, they are reported when the info is merged.
+    pub(crate) fn crate_names(&self) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(&self.0) else { return Vec::new() };
+        entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_owned())
+            })
+            .collect()
+    }
 }
 
 /// Reports error if --include-parts-dir is not a directory
@@ -1041,9 +1079,6 @@ fn parse_merge(m: &getopts::Matches) -> Result<ShouldMerge, &'static str> {
     match m.opt_str("merge").as_deref() {
         // default = read-write
         None => Ok(ShouldMerge { read_rendered_cci: true, write_rendered_cci: true }),
-        Some("none") if m.opt_present("include-parts-dir") => {
-            Err("--include-parts-dir not allowed if --merge=none")
-        }
         Some("none") => Ok(ShouldMerge { read_rendered_cci: false, write_rendered_cci: false }),
         Some("shared") if m.opt_present("parts-out-dir") || m.opt_present("include-parts-dir") => {
             Err("--parts-out-dir and --include-parts-dir not allowed if --merge=shared")
@@ -1073,3 +1108,47 @@ fn parse_merge_doctests(
         }
     }
 }
+
+/// Options for the report printed with `--show-coverage`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CoverageOptions {
+    /// Whether to list the items that lack documentation or one of its usual sections.
+    pub(crate) details: bool,
+    /// The minimum percentage of documented items of the whole crate (for `None`), or of a module
+    /// and its submodules.
+    pub(crate) thresholds: Vec<(Option<String>, f64)>,
+}
+
+fn parse_coverage_options(
+    m: &getopts::Matches,
+    show_coverage: bool,
+    dcx: DiagCtxtHandle<'_>,
+) -> CoverageOptions {
+    let details = m.opt_present("coverage-details");
+    let thresholds = m.opt_strs("coverage-threshold");
+    if !show_coverage {
+        if details {
+            dcx.fatal("--coverage-details requires --show-coverage");
+        }
+        if !thresholds.is_empty() {
+            dcx.fatal("--coverage-threshold requires --show-coverage");
+        }
+    }
+    let thresholds = thresholds
+        .iter()
+        .map(|threshold| {
+            let (module, percentage) = match threshold.rsplit_once('=') {
+                Some((module, percentage)) => (Some(module.to_owned()), percentage),
+                None => (None, threshold.as_str()),
+            };
+            match percentage.trim_end_matches('%').parse::<f64>() {
+                Ok(percentage) if (0.0..=100.0).contains(&percentage) => (module, percentage),
+                _ => dcx.fatal(format!(
+                    "argument to --coverage-threshold must be `[MODULE=]PERCENTAGE`, with a \
+                     percentage between 0 and 100, found `{threshold}`"
+                )),
+            }
+        })
+        .collect();
+    CoverageOptions { details, thresholds }
+}
@@ -1,9 +1,12 @@
+mod cache;
 mod extracted;
 mod make;
 mod markdown;
+mod report;
 mod runner;
 mod rust;
 
+use std::fmt::Write as _;
 use std::fs::File;
 use std::hash::{Hash, Hasher};
 use std::io::{self, Write};
@@ -34,6 +37,8 @@
 use tempfile::{Builder as TempFileBuilder, TempDir};
 use tracing::debug;
 
+use self::cache::{CachedResult, DoctestCache};
+use self::report::DocTestReport;
 use self::rust::HirCollector;
 use crate::config::{MergeDoctests, Options as RustdocOptions, OutputFormat};
 use crate::html::markdown::{ErrorCodes, Ignore, LangString, MdRelLine};
@@ -86,6 +91,8 @@ pub(crate) struct GlobalTestOptions {
     pub(crate) insert_indent_space: bool,
     /// Path to file containing arguments for the invocation of rustc.
     pub(crate) args_file: PathBuf,
+    /// Where the results of previous runs are stored, if `--doctest-cache-dir` was passed.
+    pub(crate) cache: Option<Arc<DoctestCache>>,
 }
 
 pub(crate) fn generate_args_file(file_path: &Path, options: &RustdocOptions) -> Result<(), String> {
@@ -125,6 +132,19 @@ pub(crate) fn generate_args_file(file_path: &Path, options: &RustdocOptions) ->
     Ok(())
 }
 
+/// Opens the cache of doctest results if `--doctest-cache-dir` was passed.
+fn open_doctest_cache(options: &RustdocOptions) -> Result<Option<Arc<DoctestCache>>, String> {
+    let Some(dir) = &options.doctest_cache_dir else { return Ok(None) };
+    // Unused externs are only reported by compiling doctests, so cached doctests would make the
+    // report incomplete.
+    if options.json_unused_externs.is_enabled() {
+        return Ok(None);
+    }
+    DoctestCache::new(dir.clone(), options)
+        .map(|cache| Some(Arc::new(cache)))
+        .map_err(|error| format!("failed to open doctest cache `{}`: {error}", dir.display()))
+}
+
 fn get_doctest_dir(opts: &RustdocOptions) -> io::Result<TempDir> {
     let mut builder = TempFileBuilder::new();
     builder.prefix("rustdoctest");
@@ -212,6 +232,10 @@ pub(crate) fn run(dcx: DiagCtxtHandle<'_>, input: Input, options: RustdocOptions
     };
     let args_path = temp_dir.path().join("rustdoc-cfgs");
     crate::wrap_return(dcx, generate_args_file(&args_path, &options));
+    let cache = match open_doctest_cache(&options) {
+        Ok(cache) => cache,
+        Err(error) => return crate::wrap_return(dcx, Err(error)),
+    };
 
     let extract_doctests = options.output_format == OutputFormat::Doctest;
     let save_temps = options.codegen_options.save_temps;
@@ -220,7 +244,7 @@ pub(crate) fn run(dcx: DiagCtxtHandle<'_>, input: Input, options: RustdocOptions
 
         let collector = rustc_interface::create_and_enter_global_ctxt(compiler, krate, |tcx| {
             let crate_name = tcx.crate_name(LOCAL_CRATE).to_string();
-            let opts = scrape_test_config(tcx, crate_name, args_path);
+            let opts = scrape_test_config(tcx, crate_name, args_path, cache);
 
             let hir_collector = HirCollector::new(
                 ErrorCodes::from(compiler.sess.opts.unstable_features.is_nightly_build()),
@@ -374,8 +398,19 @@ pub(crate) fn run_tests(
         times.add_compilation_time(duration);
         if let Ok(success) = ret {
             ran_edition_tests += 1;
+            report::merged_doctests_finished(&doctests, rustdoc_options, &test_args, duration);
             if !success {
                 nb_errors += 1;
+            } else if let Some(cache) = &opts.cache
+                // Test arguments can filter out or skip tests, which then did not actually pass.
+                && rustdoc_options.test_args.is_empty()
+            {
+                for (_, scraped_test) in &doctests {
+                    if !scraped_test.is_ignored(&target_str) {
+                        let key = cache.key(scraped_test, &opts, rustdoc_options);
+                        cache.insert(key, &CachedResult::Passed);
+                    }
+                }
             }
             continue;
         }
@@ -438,12 +473,14 @@ fn scrape_test_config(
     tcx: TyCtxt<'_>,
     crate_name: String,
     args_file: PathBuf,
+    cache: Option<Arc<DoctestCache>>,
 ) -> GlobalTestOptions {
     let mut opts = GlobalTestOptions {
         crate_name,
         no_crate_inject: false,
         insert_indent_space: false,
         args_file,
+        cache,
     };
 
     let source_map = tcx.sess.source_map();
@@ -938,6 +975,8 @@ pub(crate) struct ScrapedDocTest {
     langstr: LangString,
     text: String,
     name: String,
+    /// The path of the item that the doctest documents, like `foo::Bar::new`.
+    item_path: String,
     span: Span,
     global_crate_attrs: Vec<String>,
 }
@@ -954,15 +993,13 @@ fn new(
     ) -> Self {
         let mut item_path = logical_path.join("::");
         item_path.retain(|c| c != ' ');
-        if !item_path.is_empty() {
-            item_path.push(' ');
-        }
         let name = format!(
-            "{} - {item_path}(line {line})",
-            filename.display(RemapPathScopeComponents::DOCUMENTATION)
+            "{} - {item_path}{space}(line {line})",
+            filename.display(RemapPathScopeComponents::DOCUMENTATION),
+            space = if item_path.is_empty() { "" } else { " " },
         );
 
-        Self { filename, line, langstr, text, name, span, global_crate_attrs }
+        Self { filename, line, langstr, text, name, item_path, span, global_crate_attrs }
     }
     fn edition(&self, opts: &RustdocOptions) -> Edition {
         self.langstr.edition.unwrap_or(opts.edition)
@@ -972,6 +1009,14 @@ fn no_run(&self, opts: &RustdocOptions) -> bool {
         self.langstr.no_run || opts.no_run
     }
 
+    fn is_ignored(&self, target_str: &str) -> bool {
+        match self.langstr.ignore {
+            Ignore::All => true,
+            Ignore::None => false,
+            Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
+        }
+    }
+
     fn path(&self) -> PathBuf {
         match &self.filename {
             FileName::Real(name) => {
@@ -1055,6 +1100,15 @@ fn add_test(&mut self, scraped_test: ScrapedDocTest, dcx: Option<DiagCtxtHandle<
             .lang_str(&scraped_test.langstr)
             .span(scraped_test.span)
             .build(dcx);
+        if let Some(cache) = &self.opts.cache {
+            let key = cache.key(&scraped_test, &self.opts, &self.rustdoc_options);
+            if let Some(result) = cache.get(key) {
+                let test_desc =
+                    cached_test_desc_and_fn(scraped_test, result, &self.rustdoc_options);
+                self.standalone_tests.push(test_desc);
+                return;
+            }
+        }
         let is_standalone = !doctest.can_be_merged
             || self.rustdoc_options.no_capture
             || self.rustdoc_options.test_args.iter().any(|arg| arg == "--show-output");
@@ -1102,31 +1156,12 @@ fn generate_test_desc_and_fn(
     rustdoc_options: Arc<RustdocOptions>,
     unused_externs: Arc<Mutex<Vec<UnusedExterns>>>,
 ) -> test::TestDescAndFn {
-    let target_str = rustdoc_options.target.to_string();
     let rustdoc_test_options =
         IndividualTestOptions::new(&rustdoc_options, &test.test_id, scraped_test.path());
 
     debug!("creating test {}: {}", scraped_test.name, scraped_test.text);
     test::TestDescAndFn {
-        desc: test::TestDesc {
-            name: test::DynTestName(scraped_test.name.clone()),
-            ignore: match scraped_test.langstr.ignore {
-                Ignore::All => true,
-                Ignore::None => false,
-                Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
-            },
-            ignore_message: None,
-            source_file: "",
-            start_line: 0,
-            start_col: 0,
-            end_line: 0,
-            end_col: 0,
-            // compiler failures are test failures
-            should_panic: test::ShouldPanic::No,
-            compile_fail: scraped_test.langstr.compile_fail,
-            no_run: scraped_test.no_run(&rustdoc_options),
-            test_type: test::TestType::DocTest,
-        },
+        desc: test_desc(&scraped_test, &rustdoc_options),
         testfn: test::DynTestFn(Box::new(move || {
             doctest_run_fn(
                 rustdoc_test_options,
@@ -1140,6 +1175,52 @@ fn generate_test_desc_and_fn(
     }
 }
 
+/// Creates a test that reports the `result` of a previous run instead of running `scraped_test`.
+fn cached_test_desc_and_fn(
+    scraped_test: ScrapedDocTest,
+    result: CachedResult,
+    rustdoc_options: &RustdocOptions,
+) -> test::TestDescAndFn {
+    debug!("using cached result for test {}: {result:?}", scraped_test.name);
+    let json_report = DocTestReport::new(&scraped_test, rustdoc_options, false);
+    test::TestDescAndFn {
+        desc: test_desc(&scraped_test, rustdoc_options),
+        testfn: test::DynTestFn(Box::new(move || {
+            if let Some(json_report) = &json_report {
+                json_report.finished(true, None, None);
+            }
+            match result {
+                CachedResult::Passed => Ok(()),
+                CachedResult::Failed(report) => {
+                    eprint!(
+                        "{report}\n\nThis result was cached by a previous run: neither the test \
+                         nor the crates it uses have changed since."
+                    );
+                    panic::resume_unwind(Box::new(()));
+                }
+            }
+        })),
+    }
+}
+
+fn test_desc(scraped_test: &ScrapedDocTest, rustdoc_options: &RustdocOptions) -> test::TestDesc {
+    test::TestDesc {
+        name: test::DynTestName(scraped_test.name.clone()),
+        ignore: scraped_test.is_ignored(&rustdoc_options.target.to_string()),
+        ignore_message: None,
+        source_file: "",
+        start_line: 0,
+        start_col: 0,
+        end_line: 0,
+        end_col: 0,
+        // compiler failures are test failures
+        should_panic: test::ShouldPanic::No,
+        compile_fail: scraped_test.langstr.compile_fail,
+        no_run: scraped_test.no_run(rustdoc_options),
+        test_type: test::TestType::DocTest,
+    }
+}
+
 fn doctest_run_fn(
     test_opts: IndividualTestOptions,
     global_opts: GlobalTestOptions,
@@ -1151,12 +1232,17 @@ fn doctest_run_fn(
     let report_unused_externs = |uext| {
         unused_externs.lock().unwrap().push(uext);
     };
+    let cache = global_opts.cache.clone().map(|cache| {
+        let key = cache.key(&scraped_test, &global_opts, &rustdoc_options);
+        (cache, key)
+    });
     let (wrapped, full_test_line_offset) = doctest.generate_unique_doctest(
         &scraped_test.text,
         scraped_test.langstr.test_harness,
         &global_opts,
         Some(&global_opts.crate_name),
     );
+    let no_run = scraped_test.no_run(&rustdoc_options);
     let runnable_test = RunnableDocTest {
         full_test_code: wrapped.to_string(),
         full_test_line_offset,
@@ -1165,64 +1251,92 @@ fn doctest_run_fn(
         langstr: scraped_test.langstr.clone(),
         line: scraped_test.line,
         edition: scraped_test.edition(&rustdoc_options),
-        no_run: scraped_test.no_run(&rustdoc_options),
+        no_run,
         merged_test_code: None,
     };
-    let (_, res) =
+    let json_report = DocTestReport::new(&scraped_test, &rustdoc_options, false);
+    let instant = Instant::now();
+    let (compile_time, res) =
         run_test(runnable_test, &rustdoc_options, doctest.supports_color, report_unused_externs);
+    if let Some(json_report) = json_report {
+        // `run_test` only returns how long the compilation took, the rest of the time was spent
+        // running the test, if it got that far.
+        let ran = !no_run
+            && !scraped_test.langstr.compile_fail
+            && !matches!(res, Err(TestFailure::CompileError));
+        let run_time = ran.then(|| instant.elapsed().saturating_sub(compile_time));
+        json_report.finished(false, Some(compile_time), run_time);
+    }
 
     if let Err(err) = res {
-        match err {
-            TestFailure::CompileError => {
-                eprint!("Couldn't compile the test.");
-            }
-            TestFailure::UnexpectedCompilePass => {
-                eprint!("Test compiled successfully, but it's marked `compile_fail`.");
-            }
-            TestFailure::UnexpectedRunPass => {
-                eprint!("Test executable succeeded, but it's marked `should_panic`.");
-            }
-            TestFailure::MissingErrorCodes(codes) => {
-                eprint!("Some expected error codes were not found: {codes:?}");
+        // Failing to start the test executable says nothing about the test itself.
+        let cacheable = !matches!(err, TestFailure::ExecutionError(_));
+        let report = failure_report(err);
+        eprint!("{report}");
+        if let Some((cache, key)) = cache
+            && cacheable
+        {
+            cache.insert(key, &CachedResult::Failed(report));
+        }
+        panic::resume_unwind(Box::new(()));
+    }
+    if let Some((cache, key)) = cache {
+        cache.insert(key, &CachedResult::Passed);
+    }
+    Ok(())
+}
+
+/// Explains to the user why a doctest failed.
+fn failure_report(failure: TestFailure) -> String {
+    let mut report = String::new();
+    match failure {
+        TestFailure::CompileError => {
+            report.push_str("Couldn't compile the test.");
+        }
+        TestFailure::UnexpectedCompilePass => {
+            report.push_str("Test compiled successfully, but it's marked `compile_fail`.");
+        }
+        TestFailure::UnexpectedRunPass => {
+            report.push_str("Test executable succeeded, but it's marked `should_panic`.");
+        }
+        TestFailure::MissingErrorCodes(codes) => {
+            write!(report, "Some expected error codes were not found: {codes:?}").unwrap();
+        }
+        TestFailure::ExecutionError(err) => {
+            write!(report, "Couldn't run the test: {err}").unwrap();
+            if err.kind() == io::ErrorKind::PermissionDenied {
+                report.push_str(" - maybe your tempdir is mounted with noexec?");
             }
-            TestFailure::ExecutionError(err) => {
-                eprint!("Couldn't run the test: {err}");
-                if err.kind() == io::ErrorKind::PermissionDenied {
-                    eprint!(" - maybe your tempdir is mounted with noexec?");
+        }
+        TestFailure::ExecutionFailure(out) => {
+            writeln!(report, "Test executable failed ({reason}).", reason = out.status).unwrap();
+
+            // FIXME(#12309): An unfortunate side-effect of capturing the test
+            // executable's output is that the relative ordering between the test's
+            // stdout and stderr is lost. However, this is better than the
+            // alternative: if the test executable inherited the parent's I/O
+            // handles the output wouldn't be captured at all, even on success.
+            //
+            // The ordering could be preserved if the test process' stderr was
+            // redirected to stdout, but that functionality does not exist in the
+            // standard library, so it may not be portable enough.
+            let stdout = str::from_utf8(&out.stdout).unwrap_or_default();
+            let stderr = str::from_utf8(&out.stderr).unwrap_or_default();
+
+            if !stdout.is_empty() || !stderr.is_empty() {
+                report.push('\n');
+
+                if !stdout.is_empty() {
+                    writeln!(report, "stdout:\n{stdout}").unwrap();
                 }
-            }
-            TestFailure::ExecutionFailure(out) => {
-                eprintln!("Test executable failed ({reason}).", reason = out.status);
-
-                // FIXME(#12309): An unfortunate side-effect of capturing the test
-                // executable's output is that the relative ordering between the test's
-                // stdout and stderr is lost. However, this is better than the
-                // alternative: if the test executable inherited the parent's I/O
-                // handles the output wouldn't be captured at all, even on success.
-                //
-                // The ordering could be preserved if the test process' stderr was
-                // redirected to stdout, but that functionality does not exist in the
-                // standard library, so it may not be portable enough.
-                let stdout = str::from_utf8(&out.stdout).unwrap_or_default();
-                let stderr = str::from_utf8(&out.stderr).unwrap_or_default();
-
-                if !stdout.is_empty() || !stderr.is_empty() {
-                    eprintln!();
-
-                    if !stdout.is_empty() {
-                        eprintln!("stdout:\n{stdout}");
-                    }
 
-                    if !stderr.is_empty() {
-                        eprintln!("stderr:\n{stderr}");
-                    }
+                if !stderr.is_empty() {
+                    writeln!(report, "stderr:\n{stderr}").unwrap();
                 }
             }
         }
-
-        panic::resume_unwind(Box::new(()));
     }
-    Ok(())
+    report
 }
 
 #[cfg(test)] // used in tests
@@ -0,0 +1,172 @@
+//! A persistent cache of doctest results, enabled with `--doctest-cache-dir`.
+//!
+//! Every result is stored in a file named after a hash of everything that can affect it: the code
+//! and attributes of the doctest, the flags rustdoc was invoked with, the compiler that builds the
+//! doctests and the contents of the crates passed with `--extern`. A doctest whose hash is found in
+//! the cache is neither compiled nor run again, and its previous result is reported instead.
+//!
+//! The hash does not cover the environment the doctests run in, such as environment variables or
+//! files that they read.
+
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::stable_hasher::StableHasher;
+use tempfile::NamedTempFile;
+use tracing::debug;
+
+use crate::config::Options as RustdocOptions;
+use crate::doctest::{GlobalTestOptions, ScrapedDocTest};
+
+/// The first line of a cache entry, followed by the failure report for failed tests.
+const PASSED: &str = "passed";
+const FAILED: &str = "failed";
+
+/// Identifies the result of one doctest in the cache.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct CacheKey(Fingerprint);
+
+/// The outcome of a doctest, as stored in the cache.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum CachedResult {
+    Passed,
+    /// The test failed, and this is the explanation that was printed for it.
+    Failed(String),
+}
+
+pub(crate) struct DoctestCache {
+    dir: PathBuf,
+    /// Hash of the inputs that are shared by all doctests of this rustdoc invocation.
+    environment: Fingerprint,
+}
+
+impl DoctestCache {
+    /// Opens the cache in `dir`, creating the directory if needed.
+    pub(crate) fn new(dir: PathBuf, options: &RustdocOptions) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let environment = hash_environment(options)?;
+        debug!(?dir, ?environment, "opened doctest cache");
+        Ok(Self { dir, environment })
+    }
+
+    /// Computes the key under which the result of `test` is stored.
+    pub(crate) fn key(
+        &self,
+        test: &ScrapedDocTest,
+        opts: &GlobalTestOptions,
+        options: &RustdocOptions,
+    ) -> CacheKey {
+        let mut hasher = StableHasher::new();
+        self.environment.hash(&mut hasher);
+        test.name.hash(&mut hasher);
+        test.text.hash(&mut hasher);
+        test.langstr.original.hash(&mut hasher);
+        test.edition(options).hash(&mut hasher);
+        test.no_run(options).hash(&mut hasher);
+        test.global_crate_attrs.hash(&mut hasher);
+        opts.crate_name.hash(&mut hasher);
+        opts.no_crate_inject.hash(&mut hasher);
+        CacheKey(hasher.finish())
+    }
+
+    pub(crate) fn get(&self, key: CacheKey) -> Option<CachedResult> {
+        let content = fs::read_to_string(self.path(key)).ok()?;
+        match content.split_once('\n').unwrap_or((&content, "")) {
+            (PASSED, _) => Some(CachedResult::Passed),
+            (FAILED, report) => Some(CachedResult::Failed(report.to_owned())),
+            _ => None,
+        }
+    }
+
+    /// Stores `result` for `key`. Failing to do so only means that the test runs again next time,
+    /// so errors are ignored.
+    pub(crate) fn insert(&self, key: CacheKey, result: &CachedResult) {
+        let content = match result {
+            CachedResult::Passed => PASSED.to_owned(),
+            CachedResult::Failed(report) => format!("{FAILED}\n{report}"),
+        };
+        // Tests run in parallel, so the entry is written to a temporary file first to make sure
+        // that nobody reads a partially written entry.
+        let result = NamedTempFile::new_in(&self.dir).and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.persist(self.path(key)).map_err(|error| error.error)?;
+            Ok(())
+        });
+        if let Err(error) = result {
+            debug!(?key, ?error, "failed to write doctest cache entry");
+        }
+    }
+
+    fn path(&self, key: CacheKey) -> PathBuf {
+        self.dir.join(key.0.to_hex())
+    }
+}
+
+/// Hashes the flags and files that influence the result of every doctest.
+fn hash_environment(options: &RustdocOptions) -> io::Result<Fingerprint> {
+    let mut hasher = StableHasher::new();
+    rustc_interface::util::rustc_version_str().hash(&mut hasher);
+    // Development builds of rustdoc all share the same version, so the binary itself is included.
+    if let Ok(rustdoc) = std::env::current_exe() {
+        hash_file_stamp(&rustdoc, &mut hasher);
+    }
+    let rustc = options
+        .test_builder
+        .as_deref()
+        .or_else(|| rustc_interface::util::rustc_path(&options.sysroot));
+    if let Some(rustc) = rustc {
+        hash_file_stamp(rustc, &mut hasher);
+    }
+    options.test_builder_wrappers.hash(&mut hasher);
+    options.target.to_string().hash(&mut hasher);
+    options.cfgs.hash(&mut hasher);
+    options.check_cfgs.hash(&mut hasher);
+    options.lib_strs.hash(&mut hasher);
+    options.codegen_options_strs.hash(&mut hasher);
+    options.unstable_opts_strs.hash(&mut hasher);
+    options.doctest_build_args.hash(&mut hasher);
+    options.persist_doctests.is_some().hash(&mut hasher);
+    options.test_runtool.hash(&mut hasher);
+    options.test_runtool_args.hash(&mut hasher);
+    options.test_run_directory.hash(&mut hasher);
+    // The crates that doctests can use are hashed by content, so that rebuilding a crate without
+    // changing it does not invalidate the results.
+    options.extern_strs.hash(&mut hasher);
+    for extern_str in &options.extern_strs {
+        if let Some((_, path)) = extern_str.split_once('=') {
+            hash_file_contents(Path::new(path), &mut hasher)?;
+        }
+    }
+    Ok(hasher.finish())
+}
+
+/// Hashes the size and modification time of the file at `path`, if it exists.
+fn hash_file_stamp(path: &Path, hasher: &mut StableHasher) {
+    path.hash(hasher);
+    if let Ok(metadata) = fs::metadata(path) {
+        metadata.len().hash(hasher);
+        metadata.modified().ok().hash(hasher);
+    }
+}
+
+fn hash_file_contents(path: &Path, hasher: &mut StableHasher) -> io::Result<()> {
+    path.hash(hasher);
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        // A missing crate makes every doctest that uses it fail, which is cached like any other
+        // result.
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error),
+    };
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(());
+        }
+        hasher.write(&buffer[..read]);
+    }
+}
@@ -11,6 +11,7 @@
 
 use super::{
     CreateRunnableDocTests, DocTestVisitor, GlobalTestOptions, ScrapedDocTest, generate_args_file,
+    open_doctest_cache,
 };
 use crate::config::Options;
 use crate::html::markdown::{ErrorCodes, LangString, MdRelLine, find_testable_code};
@@ -100,6 +101,7 @@ pub(crate) fn test(input: &Input, options: Options, dcx: DiagCtxtHandle<'_>) ->
         no_crate_inject: true,
         insert_indent_space: false,
         args_file,
+        cache: open_doctest_cache(&options)?,
     };
 
     let mut md_collector = MdCollector {
@@ -0,0 +1,145 @@
+//! Structured results of doctests, for tools that read libtest's JSON output.
+//!
+//! When the test arguments ask libtest for JSON output (`--format json`), every doctest that
+//! runs is also described by an event of type `doctest`, printed on the same stream as the events
+//! of libtest. It can be matched with the events of libtest by its `name`, and gives what libtest
+//! does not know about the doctest: the item it documents, where it is, its edition, and how much
+//! of its time was spent compiling it.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_span::RemapPathScopeComponents;
+use serde::Serialize;
+
+use crate::config::Options as RustdocOptions;
+use crate::doctest::{DocTestBuilder, ScrapedDocTest, test_desc};
+
+/// What is known about a doctest before it runs, to be printed once it finished.
+pub(crate) struct DocTestReport {
+    name: String,
+    item: String,
+    file: String,
+    line: usize,
+    edition: String,
+    merged: bool,
+}
+
+#[derive(Serialize)]
+struct Event<'a> {
+    #[serde(rename = "type")]
+    ty: &'static str,
+    event: &'static str,
+    name: &'a str,
+    item: &'a str,
+    file: &'a str,
+    line: usize,
+    edition: &'a str,
+    merged: bool,
+    cached: bool,
+    compile_time: Option<f64>,
+    run_time: Option<f64>,
+}
+
+impl DocTestReport {
+    /// Returns `None` if the results of doctests are not reported, because libtest does not print
+    /// JSON.
+    pub(crate) fn new(
+        test: &ScrapedDocTest,
+        options: &RustdocOptions,
+        merged: bool,
+    ) -> Option<Self> {
+        if !is_enabled(options) {
+            return None;
+        }
+        Some(Self {
+            name: test.name.clone(),
+            item: test.item_path.clone(),
+            file: test.filename.display(RemapPathScopeComponents::DOCUMENTATION).to_string(),
+            line: test.line,
+            edition: test.edition(options).to_string(),
+            merged,
+        })
+    }
+
+    /// Prints the event of a doctest that finished. `compile_time` is `None` for results taken
+    /// from the doctest cache, and `run_time` is `None` for doctests that did not run on their
+    /// own, because they only had to compile, failed to, or were merged with other doctests.
+    pub(crate) fn finished(
+        &self,
+        cached: bool,
+        compile_time: Option<Duration>,
+        run_time: Option<Duration>,
+    ) {
+        let event = Event {
+            ty: "doctest",
+            event: "finished",
+            name: &self.name,
+            item: &self.item,
+            file: &self.file,
+            line: self.line,
+            edition: &self.edition,
+            merged: self.merged,
+            cached,
+            compile_time: compile_time.map(|time| time.as_secs_f64()),
+            run_time: run_time.map(|time| time.as_secs_f64()),
+        };
+        let event = serde_json::to_string(&event).unwrap();
+        // Libtest captures the output of `print!` while a test runs, so the event is written to
+        // the standard output directly, in one go so that it does not mix with libtest's own.
+        let _ = writeln!(io::stdout().lock(), "{event}");
+    }
+}
+
+/// Prints the events of the merged `doctests` that ran, once their binary ran with `test_args`.
+/// They were compiled together in `compile_time`, and libtest reports how long each of them ran.
+pub(crate) fn merged_doctests_finished(
+    doctests: &[(DocTestBuilder, ScrapedDocTest)],
+    options: &RustdocOptions,
+    test_args: &[String],
+    compile_time: Duration,
+) {
+    if !is_enabled(options) {
+        return;
+    }
+    let Some(Ok(test_opts)) = test::test::parse_opts(test_args) else {
+        return;
+    };
+    // The merged binary runs the doctests that libtest keeps with the same test arguments.
+    let tests = doctests
+        .iter()
+        .map(|(_, scraped_test)| {
+            let mut desc = test_desc(scraped_test, options);
+            if !scraped_test.langstr.no_run && scraped_test.langstr.should_panic {
+                desc.should_panic = test::ShouldPanic::Yes;
+            }
+            test::TestDescAndFn { desc, testfn: test::DynTestFn(Box::new(|| Ok(()))) }
+        })
+        .collect();
+    let ran: FxHashSet<String> = test::filter_tests(&test_opts, tests)
+        .into_iter()
+        .filter(|test| !test.desc.ignore)
+        .map(|test| test.desc.name.as_slice().to_owned())
+        .collect();
+    for (_, scraped_test) in doctests {
+        if ran.contains(&scraped_test.name)
+            && let Some(json_report) = DocTestReport::new(scraped_test, options, true)
+        {
+            json_report.finished(false, Some(compile_time), None);
+        }
+    }
+}
+
+/// Whether the test arguments ask libtest for JSON output.
+fn is_enabled(options: &RustdocOptions) -> bool {
+    let mut args = options.test_args.iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--format") {
+            Some("=json") => return true,
+            Some("") if args.next().is_some_and(|format| format == "json") => return true,
+            _ => {}
+        }
+    }
+    false
+}
@@ -8,7 +8,7 @@
     DocTestBuilder, GlobalTestOptions, IndividualTestOptions, RunnableDocTest, RustdocOptions,
     ScrapedDocTest, TestFailure, UnusedExterns, run_test,
 };
-use crate::html::markdown::{Ignore, LangString};
+use crate::html::markdown::LangString;
 
 /// Convenient type to merge compatible doctests into one.
 pub(crate) struct DocTestRunner {
@@ -40,11 +40,7 @@ pub(crate) fn add_test(
         scraped_test: &ScrapedDocTest,
         target_str: &str,
     ) {
-        let ignore = match scraped_test.langstr.ignore {
-            Ignore::All => true,
-            Ignore::None => false,
-            Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
-        };
+        let ignore = scraped_test.is_ignored(target_str);
         if !ignore {
             for line in doctest.crate_attrs.split('\n') {
                 self.crate_attrs.insert(line.to_string());
@@ -36,6 +36,7 @@ fn default_global_opts(crate_name: impl Into<String>) -> GlobalTestOptions {
         no_crate_inject: false,
         insert_indent_space: false,
         args_file: PathBuf::new(),
+        cache: None,
     }
 }
 
@@ -171,13 +171,27 @@ pub(crate) fn populate(
 
         let extern_url_takes_precedence = render_options.extern_html_root_takes_precedence;
         let dst = &render_options.output;
+        // Crates documented separately, like in another job of a CI, that are merged into the same
+        // output directory later on.
+        let included_crates: FxHashSet<Symbol> = render_options
+            .include_parts_dir
+            .iter()
+            .flat_map(|parts| parts.crate_names())
+            .map(|name| Symbol::intern(&name))
+            .collect();
 
         // Make `--extern-html-root-url` support the same names as `--extern` whenever possible
         let cstore = CStore::from_tcx(tcx);
         for (name, extern_url) in &render_options.extern_html_root_urls {
             if let Some(crate_num) = cstore.resolved_extern_crate(Symbol::intern(name)) {
                 let e = ExternalCrate { crate_num };
-                let location = e.location(Some(extern_url), extern_url_takes_precedence, dst, tcx);
+                let location = e.location(
+                    Some(extern_url),
+                    extern_url_takes_precedence,
+                    dst,
+                    &included_crates,
+                    tcx,
+                );
                 cx.cache.extern_locations.insert(e.crate_num, location);
             }
         }
@@ -193,7 +207,7 @@ pub(crate) fn populate(
                 // transitive dependencies and injected crates may be loaded without `--extern`
                 let extern_url =
                     render_options.extern_html_root_urls.get(name.as_str()).map(|u| &**u);
-                e.location(extern_url, extern_url_takes_precedence, dst, tcx)
+                e.location(extern_url, extern_url_takes_precedence, dst, &included_crates, tcx)
             });
             cx.cache.external_paths.insert(e.def_id(), (vec![name], ItemType::Module));
         }
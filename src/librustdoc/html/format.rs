@@ -118,7 +118,10 @@ pub(crate) enum Ending {
     NoNewline,
 }
 
-fn print_where_predicate(predicate: &clean::WherePredicate, cx: &Context<'_>) -> impl Display {
+pub(crate) fn print_where_predicate(
+    predicate: &clean::WherePredicate,
+    cx: &Context<'_>,
+) -> impl Display {
     fmt::from_fn(move |f| {
         match predicate {
             clean::WherePredicate::BoundPredicate { ty, bounds, bound_params } => {
@@ -1288,7 +1291,7 @@ fn print_parameter(parameter: &clean::Parameter, cx: &Context<'_>) -> impl fmt::
     })
 }
 
-fn print_fn_decl(fn_decl: &clean::FnDecl, cx: &Context<'_>) -> impl Display {
+pub(crate) fn print_fn_decl(fn_decl: &clean::FnDecl, cx: &Context<'_>) -> impl Display {
     fmt::from_fn(move |f| {
         let ellipsis = if fn_decl.c_variadic { ", ..." } else { "" };
         Wrapped::with_parens()
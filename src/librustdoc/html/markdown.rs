@@ -304,6 +304,7 @@ fn next(&mut self) -> Option<Self::Item> {
                 no_crate_inject: false,
                 insert_indent_space: true,
                 args_file: PathBuf::new(),
+                cache: None,
             };
             let mut builder = doctest::BuildDocTestBuilder::new(&test).edition(edition);
             if let Some(krate) = krate {
@@ -1167,7 +1168,7 @@ fn default() -> Self {
 }
 
 impl LangString {
-    fn parse_without_check(string: &str, allow_error_code_check: ErrorCodes) -> Self {
+    pub(crate) fn parse_without_check(string: &str, allow_error_code_check: ErrorCodes) -> Self {
         Self::parse(string, allow_error_code_check, None)
     }
 
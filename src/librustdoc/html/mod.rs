@@ -10,7 +10,7 @@
 pub(crate) mod sources;
 pub(crate) mod static_files;
 pub(crate) mod toc;
-mod url_parts_builder;
+pub(crate) mod url_parts_builder;
 
 #[cfg(test)]
 mod tests;
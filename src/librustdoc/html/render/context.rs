@@ -481,6 +481,30 @@ pub(crate) fn init(
         // need to save a copy of the options for rendering the index page
         let md_opts = options.clone();
         let emit_crate = options.should_emit_crate();
+        let no_emit_shared = options.no_emit_shared;
+        let mut cx = Context::new(&krate, options, cache, tcx, expanded_codes)?;
+
+        if emit_crate {
+            sources::render(&mut cx, &krate)?;
+        }
+
+        if !no_emit_shared {
+            write_shared(&mut cx, &krate, &md_opts, tcx)?;
+        }
+
+        Ok((cx, krate))
+    }
+
+    /// Creates the context without rendering the source files or the shared files. Other backends
+    /// use such a context to print items as plain text, with the alternate (`{:#}`) format of the
+    /// printers in [`crate::html::format`].
+    pub(crate) fn new(
+        krate: &clean::Crate,
+        options: RenderOptions,
+        cache: Cache,
+        tcx: TyCtxt<'tcx>,
+        expanded_codes: FxHashMap<BytePos, Vec<ExpandedCode>>,
+    ) -> Result<Self, Error> {
         let RenderOptions {
             output,
             external_html,
@@ -496,7 +520,6 @@ pub(crate) fn init(
             show_type_layout,
             generate_link_to_definition,
             call_locations,
-            no_emit_shared,
             html_no_source,
             ..
         } = options;
@@ -559,7 +582,7 @@ pub(crate) fn init(
 
         let (local_sources, matches) = collect_spans_and_sources(
             tcx,
-            &krate,
+            krate,
             &src_root,
             include_sources,
             generate_link_to_definition,
@@ -594,7 +617,7 @@ pub(crate) fn init(
         let dst = output;
         scx.ensure_dir(&dst)?;
 
-        let mut cx = Context {
+        Ok(Context {
             current: Vec::new(),
             dst,
             id_map: RefCell::new(id_map),
@@ -602,17 +625,7 @@ pub(crate) fn init(
             shared: scx,
             types_with_notable_traits: RefCell::new(FxIndexSet::default()),
             info: ContextInfo::new(include_sources),
-        };
-
-        if emit_crate {
-            sources::render(&mut cx, &krate)?;
-        }
-
-        if !no_emit_shared {
-            write_shared(&mut cx, &krate, &md_opts, tcx)?;
-        }
-
-        Ok((cx, krate))
+        })
     }
 }
 
@@ -64,6 +64,7 @@
 use tracing::{debug, info};
 
 pub(crate) use self::context::*;
+pub(crate) use self::print_item::compare_names;
 pub(crate) use self::span_map::{LinkFromSrc, collect_spans_and_sources};
 pub(crate) use self::write_shared::*;
 use crate::clean::{self, ItemId, RenderedLink};
@@ -83,7 +84,7 @@
 };
 use crate::html::static_files::SCRAPE_EXAMPLES_HELP_MD;
 use crate::html::{highlight, sources};
-use crate::scrape_examples::{CallData, CallLocation};
+use crate::scrape_examples::{CallData, CallLocation, ExampleKind};
 use crate::{DOC_RUST_LANG_ORG_VERSION, try_none};
 
 pub(crate) fn ensure_trailing_slash(v: &str) -> impl fmt::Display {
@@ -2508,7 +2509,7 @@ pub(crate) enum ItemSection {
 }
 
 impl ItemSection {
-    const ALL: &'static [Self] = {
+    pub(crate) const ALL: &'static [Self] = {
         use ItemSection::*;
         // NOTE: The order here affects the order in the UI.
         // Keep this synchronized with addSidebarItems in main.js
@@ -2571,7 +2572,7 @@ fn id(self) -> &'static str {
         }
     }
 
-    fn name(self) -> &'static str {
+    pub(crate) fn name(self) -> &'static str {
         match self {
             Self::Reexports => "Re-exports",
             Self::Modules => "Modules",
@@ -2602,7 +2603,7 @@ fn name(self) -> &'static str {
     }
 }
 
-fn item_ty_to_section(ty: ItemType) -> ItemSection {
+pub(crate) fn item_ty_to_section(ty: ItemType) -> ItemSection {
     match ty {
         ItemType::ExternCrate | ItemType::Import => ItemSection::Reexports,
         ItemType::Module => ItemSection::Modules,
@@ -2847,26 +2848,31 @@ fn render_call_locations<W: fmt::Write>(
     // The call locations are output in sequence, so that sequence needs to be determined.
     // Ideally the most "relevant" examples would be shown first, but there's no general algorithm
     // for determining relevance. We instead proxy relevance with the following heuristics:
-    //   1. Code written to be an example is better than code not written to be an example, e.g.
-    //      a snippet from examples/foo.rs is better than src/lib.rs. We don't know the Cargo
-    //      directory structure in Rustdoc, so we proxy this by prioritizing code that comes from
-    //      a --crate-type bin.
-    //   2. Smaller examples are better than large examples. So we prioritize snippets that have
+    //   1. Examples that the author pinned with `#[doc(scrape_examples = "pin")]` come first.
+    //   2. Code written to be an example is better than test code, which is better than code not
+    //      written to be an example, e.g. a snippet from examples/foo.rs is better than one from
+    //      tests/foo.rs, which is better than src/lib.rs. We don't know the Cargo directory
+    //      structure in Rustdoc, so we proxy this by prioritizing code that comes from a
+    //      --crate-type bin, then code that was scraped with --scrape-tests.
+    //   3. Smaller examples are better than large examples. So we prioritize snippets that have
     //      the smallest number of lines in their enclosing item.
-    //   3. Finally we sort by the displayed file name, which is arbitrary but prevents the
+    //   4. Finally we sort by the displayed file name, which is arbitrary but prevents the
     //      ordering of examples from randomly changing between Rustdoc invocations.
+    // Examples that are equally relevant by the first two heuristics are then interleaved by
+    // directory, so that the visible examples don't all come from the tests of a single crate.
     let ordered_locations = {
         fn sort_criterion<'a>(
             (_, call_data): &(&PathBuf, &'a CallData),
-        ) -> (bool, u32, &'a String) {
+        ) -> (bool, ExampleKind, u32, &'a String) {
             // Use the first location because that's what the user will see initially
-            let (lo, hi) = call_data.locations[0].enclosing_item.byte_span;
-            (!call_data.is_bin, hi - lo, &call_data.display_name)
+            let location = &call_data.locations[0];
+            let (lo, hi) = location.enclosing_item.byte_span;
+            (!location.pinned, call_data.kind, hi - lo, &call_data.display_name)
         }
 
         let mut locs = call_locations.iter().collect::<Vec<_>>();
         locs.sort_by_key(sort_criterion);
-        locs
+        interleave_directories(locs)
     };
 
     let mut it = ordered_locations.into_iter().peekable();
@@ -2926,6 +2932,30 @@ fn sort_criterion<'a>(
     w.write_str("</div>")
 }
 
+/// Reorders examples so that, among those with the same priority, consecutive examples come from
+/// different directories when possible. The examples of each directory stay in the same order.
+fn interleave_directories<'a>(
+    locs: Vec<(&'a PathBuf, &'a CallData)>,
+) -> Vec<(&'a PathBuf, &'a CallData)> {
+    let mut ordered = Vec::with_capacity(locs.len());
+    for examples in locs.chunk_by(|(_, a), (_, b)| {
+        (a.locations[0].pinned, a.kind) == (b.locations[0].pinned, b.kind)
+    }) {
+        let mut rest = examples.to_vec();
+        while !rest.is_empty() {
+            let mut directories = FxHashSet::default();
+            rest.retain(|&(path, call_data)| {
+                let is_new = directories.insert(path.parent());
+                if is_new {
+                    ordered.push((path, call_data));
+                }
+                !is_new
+            });
+        }
+    }
+    ordered
+}
+
 fn render_attributes_in_code(
     w: &mut impl fmt::Write,
     item: &clean::Item,
@@ -1,4 +1,5 @@
 pub(crate) mod encode;
+mod export;
 mod serde;
 
 use std::collections::BTreeSet;
@@ -0,0 +1,191 @@
+//! The export of the search index that is written with `--emit=search-index`.
+//!
+//! The format is described by the `rustdoc_search_index_types` crate. Unlike the files read by
+//! `search.js`, it refers to other entries by their position in plain lists, so that it can be
+//! consumed without reimplementing the decoding of the index.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use rustc_ast::join_path_syms;
+use rustdoc_search_index_types::{self as types, FORMAT_VERSION};
+
+use super::SerializedSearchIndex;
+use crate::error::Error;
+use crate::formats::item_type::ItemType;
+use crate::html::render::{IndexItemFunctionType, RenderType, RenderTypeId};
+use crate::try_err;
+
+impl SerializedSearchIndex {
+    /// Fails if the types of signatures have not been converted to indexes in the index yet.
+    pub(crate) fn export(&self) -> Result<types::SearchIndex, String> {
+        // Paths and items share the rows of the index, so a row can end up in both lists.
+        let mut path_ids = vec![None; self.names.len()];
+        let mut paths = Vec::new();
+        for (row, path_data) in self.path_data.iter().enumerate() {
+            let Some(path_data) = path_data else { continue };
+            path_ids[row] = Some(paths.len());
+            paths.push(types::Path {
+                name: self.names[row].clone(),
+                kind: item_kind(path_data.ty),
+                module: join_path_syms(&path_data.module_path),
+                unbox: self.type_data[row].as_ref().is_some_and(|data| data.search_unbox),
+            });
+        }
+
+        let mut aliases = vec![Vec::new(); self.names.len()];
+        for (row, target) in self.alias_pointers.iter().enumerate() {
+            if let Some(target) = *target {
+                aliases[target].push(self.names[row].clone());
+            }
+        }
+
+        let mut crates = BTreeSet::new();
+        let mut items = Vec::new();
+        for (row, entry) in self.entry_data.iter().enumerate() {
+            let Some(entry) = entry else { continue };
+            let krate = self.names[entry.krate].clone();
+            crates.insert(krate.clone());
+            let signature = match &self.function_data[row] {
+                Some(function) => signature(function, &path_ids)?,
+                None => None,
+            };
+            items.push(types::Item {
+                name: self.names[row].clone(),
+                kind: item_kind(entry.ty),
+                krate,
+                module: entry.module_path.map(|row| self.full_path(row)).unwrap_or_default(),
+                parent: entry.parent.and_then(|row| path_ids[row]),
+                aliases: std::mem::take(&mut aliases[row]),
+                desc: self.descs[row].clone(),
+                deprecated: entry.deprecated,
+                signature,
+            });
+        }
+
+        Ok(types::SearchIndex {
+            format_version: FORMAT_VERSION,
+            crates: crates.into_iter().collect(),
+            items,
+            paths,
+        })
+    }
+
+    pub(crate) fn write_export_to(
+        &self,
+        doc_root: &Path,
+        resource_suffix: &str,
+    ) -> Result<(), Error> {
+        let path = doc_root.join(format!("search-index{resource_suffix}.json"));
+        let export = try_err!(self.export(), &path);
+        let json = serde_json::to_string(&export).expect("failed to serialize search index");
+        try_err!(fs::write(&path, json), &path);
+        Ok(())
+    }
+
+    /// Returns the path of the module or crate stored in `row`.
+    fn full_path(&self, row: usize) -> String {
+        let name = &self.names[row];
+        match &self.path_data[row] {
+            Some(path_data) if !path_data.module_path.is_empty() => {
+                format!("{}::{name}", join_path_syms(&path_data.module_path))
+            }
+            _ => name.clone(),
+        }
+    }
+}
+
+fn signature(
+    function: &IndexItemFunctionType,
+    path_ids: &[Option<usize>],
+) -> Result<Option<types::Signature>, String> {
+    // Like `search.js`, ignore signatures that contain types which could not be simplified.
+    if function
+        .inputs
+        .iter()
+        .chain(&function.output)
+        .any(|ty| ty.id.is_none() && ty.generics.is_none())
+    {
+        return Ok(None);
+    }
+    let convert = |list: &[RenderType]| -> Result<Vec<types::Type>, String> {
+        list.iter().map(|ty| convert_type(ty, path_ids)).collect()
+    };
+    Ok(Some(types::Signature {
+        inputs: convert(&function.inputs)?,
+        output: convert(&function.output)?,
+        generics: function
+            .where_clause
+            .iter()
+            .map(|bounds| convert(bounds))
+            .collect::<Result<_, _>>()?,
+    }))
+}
+
+fn convert_type(ty: &RenderType, path_ids: &[Option<usize>]) -> Result<types::Type, String> {
+    let mut bindings = Vec::new();
+    for (assoc, constraints) in ty.bindings.iter().flatten() {
+        let Some(types::TypeId::Path(assoc)) = type_id(*assoc, path_ids)? else { continue };
+        let constraints =
+            constraints.iter().map(|t| convert_type(t, path_ids)).collect::<Result<_, _>>()?;
+        bindings.push(types::Binding { assoc, constraints });
+    }
+    Ok(types::Type {
+        id: match ty.id {
+            Some(id) => type_id(id, path_ids)?,
+            None => None,
+        },
+        generics: ty
+            .generics
+            .iter()
+            .flatten()
+            .map(|t| convert_type(t, path_ids))
+            .collect::<Result<_, _>>()?,
+        bindings,
+    })
+}
+
+fn type_id(id: RenderTypeId, path_ids: &[Option<usize>]) -> Result<Option<types::TypeId>, String> {
+    match id {
+        RenderTypeId::Index(generic) if generic < 0 => {
+            Ok(Some(types::TypeId::Generic(generic.unsigned_abs() - 1)))
+        }
+        RenderTypeId::Index(row) => Ok(usize::try_from(row)
+            .ok()
+            .and_then(|row| path_ids.get(row).copied().flatten())
+            .map(types::TypeId::Path)),
+        id => Err(format!("type `{id:?}` was not converted to an index before the export")),
+    }
+}
+
+fn item_kind(ty: ItemType) -> types::ItemKind {
+    match ty {
+        ItemType::Module => types::ItemKind::Module,
+        ItemType::ExternCrate => types::ItemKind::ExternCrate,
+        ItemType::Import => types::ItemKind::Import,
+        ItemType::Struct => types::ItemKind::Struct,
+        ItemType::Union => types::ItemKind::Union,
+        ItemType::Enum => types::ItemKind::Enum,
+        ItemType::Function => types::ItemKind::Function,
+        ItemType::TypeAlias => types::ItemKind::TypeAlias,
+        ItemType::Static => types::ItemKind::Static,
+        ItemType::Trait => types::ItemKind::Trait,
+        ItemType::Impl => types::ItemKind::Impl,
+        ItemType::TyMethod => types::ItemKind::TyMethod,
+        ItemType::Method => types::ItemKind::Method,
+        ItemType::StructField => types::ItemKind::StructField,
+        ItemType::Variant => types::ItemKind::Variant,
+        ItemType::Macro => types::ItemKind::Macro,
+        ItemType::Primitive => types::ItemKind::Primitive,
+        ItemType::AssocType => types::ItemKind::AssocType,
+        ItemType::Constant => types::ItemKind::Constant,
+        ItemType::AssocConst => types::ItemKind::AssocConst,
+        ItemType::ForeignType => types::ItemKind::ForeignType,
+        ItemType::Keyword => types::ItemKind::Keyword,
+        ItemType::ProcAttribute => types::ItemKind::ProcAttribute,
+        ItemType::ProcDerive => types::ItemKind::ProcDerive,
+        ItemType::TraitAlias => types::ItemKind::TraitAlias,
+        ItemType::Attribute => types::ItemKind::Attribute,
+    }
+}
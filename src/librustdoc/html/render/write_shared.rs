@@ -33,6 +33,7 @@
 use rustc_middle::ty::fast_reject::DeepRejectCtxt;
 use rustc_span::Symbol;
 use rustc_span::def_id::DefId;
+use rustc_span::edition::Edition;
 use serde::de::DeserializeOwned;
 use serde::ser::SerializeSeq;
 use serde::{Deserialize, Serialize, Serializer};
@@ -81,7 +82,8 @@ pub(crate) fn write_shared(
     let crate_name_json = OrderedJson::serialize(crate_name).unwrap(); // "rand"
     let external_crates = hack_get_external_crate_names(&cx.dst, &cx.shared.resource_suffix)?;
     let info = CrateInfo {
-        version: CrateInfoVersion::V2,
+        version: CrateInfoVersion::V3,
+        crate_name: crate_name.to_owned(),
         src_files_js: SourcesPart::get(cx, &crate_name_json)?,
         search_index,
         all_crates: AllCratesPart::get(crate_name_json.clone(), &cx.shared.resource_suffix)?,
@@ -100,10 +102,11 @@ pub(crate) fn write_shared(
         );
     }
 
-    let mut crates = CrateInfo::read_many(&opt.include_parts_dir)?;
-    crates.push(info);
-
     if opt.should_merge.write_rendered_cci {
+        let mut crates = CrateInfo::read_many(&opt.include_parts_dir)?;
+        crates.push(info);
+        CrateInfo::dedup(&mut crates);
+
         write_not_crate_specific(
             &crates,
             &cx.dst,
@@ -113,26 +116,14 @@ pub(crate) fn write_shared(
             &cx.shared.resource_suffix,
             cx.info.include_sources,
         )?;
-        match &opt.index_page {
-            Some(index_page) if opt.enable_index_page => {
-                let mut md_opts = opt.clone();
-                md_opts.output = cx.dst.clone();
-                md_opts.external_html = cx.shared.layout.external_html.clone();
-                try_err!(
-                    crate::markdown::render_and_write(index_page, md_opts, cx.shared.edition()),
-                    &index_page
-                );
-            }
-            None if opt.enable_index_page => {
-                write_rendered_cci::<CratesIndexPart, _>(
-                    || CratesIndexPart::blank(cx),
-                    &cx.dst,
-                    &crates,
-                    &opt.should_merge,
-                )?;
-            }
-            _ => {} // they don't want an index page
-        }
+        write_index_page(
+            &crates,
+            &cx.dst,
+            opt,
+            &cx.shared.layout,
+            &cx.shared.style_files,
+            cx.shared.edition(),
+        )?;
     }
 
     cx.shared.fs.set_sync_only(false);
@@ -156,6 +147,36 @@ pub(crate) fn write_not_crate_specific(
     Ok(())
 }
 
+/// Writes the index page of the doc root, which is either the `--index-page` given by the user or
+/// the list of all crates.
+pub(crate) fn write_index_page(
+    crates: &[CrateInfo],
+    dst: &Path,
+    opt: &RenderOptions,
+    layout: &layout::Layout,
+    style_files: &[StylePath],
+    edition: Edition,
+) -> Result<(), Error> {
+    match &opt.index_page {
+        Some(index_page) if opt.enable_index_page => {
+            let mut md_opts = opt.clone();
+            md_opts.output = dst.to_path_buf();
+            md_opts.external_html = layout.external_html.clone();
+            try_err!(crate::markdown::render_and_write(index_page, md_opts, edition), &index_page);
+        }
+        None if opt.enable_index_page => {
+            write_rendered_cci::<CratesIndexPart, _>(
+                || CratesIndexPart::blank(layout, style_files, opt),
+                dst,
+                crates,
+                &opt.should_merge,
+            )?;
+        }
+        _ => {} // they don't want an index page
+    }
+    Ok(())
+}
+
 fn write_rendered_cross_crate_info(
     crates: &[CrateInfo],
     dst: &Path,
@@ -164,16 +185,22 @@ fn write_rendered_cross_crate_info(
     resource_suffix: &str,
 ) -> Result<(), Error> {
     let m = &opt.should_merge;
-    if opt.should_emit_crate() {
-        if include_sources {
-            write_rendered_cci::<SourcesPart, _>(SourcesPart::blank, dst, crates, m)?;
-        }
-        crates
+    let export_search_index = opt.emit.contains(&EmitType::SearchIndex);
+    if opt.should_emit_crate() || export_search_index {
+        let search_index = crates
             .iter()
             .fold(SerializedSearchIndex::default(), |a, b| a.union(&b.search_index))
-            .sort()
-            .write_to(dst, resource_suffix)?;
-        write_rendered_cci::<AllCratesPart, _>(AllCratesPart::blank, dst, crates, m)?;
+            .sort();
+        if export_search_index {
+            search_index.write_export_to(dst, resource_suffix)?;
+        }
+        if opt.should_emit_crate() {
+            if include_sources {
+                write_rendered_cci::<SourcesPart, _>(SourcesPart::blank, dst, crates, m)?;
+            }
+            search_index.write_to(dst, resource_suffix)?;
+            write_rendered_cci::<AllCratesPart, _>(AllCratesPart::blank, dst, crates, m)?;
+        }
     }
     write_rendered_cci::<TraitAliasPart, _>(TraitAliasPart::blank, dst, crates, m)?;
     write_rendered_cci::<TypeAliasPart, _>(TypeAliasPart::blank, dst, crates, m)?;
@@ -234,6 +261,7 @@ fn write_static_files(
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub(crate) struct CrateInfo {
     version: CrateInfoVersion,
+    crate_name: String,
     src_files_js: PartsAndLocations<SourcesPart>,
     search_index: SerializedSearchIndex,
     all_crates: PartsAndLocations<AllCratesPart>,
@@ -267,6 +295,15 @@ pub(crate) fn read_many(parts_paths: &[PathToParts]) -> Result<Vec<Self>, Error>
                 Ok(acc)
             })
     }
+
+    /// Keeps only the last info of each crate, so that the parts of a crate that was documented
+    /// again replace the ones that were included from an older run.
+    pub(crate) fn dedup(crates: &mut Vec<Self>) {
+        let mut seen = FxHashSet::default();
+        crates.reverse();
+        crates.retain(|krate| seen.insert(krate.crate_name.clone()));
+        crates.reverse();
+    }
 }
 
 /// Version for the format of the crate-info file.
@@ -278,7 +315,7 @@ pub(crate) fn read_many(parts_paths: &[PathToParts]) -> Result<Vec<Self>, Error>
 /// to provide better diagnostics about including an invalid file.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 enum CrateInfoVersion {
-    V2,
+    V3,
 }
 
 /// Paths (relative to the doc root) and their pre-merge contents
@@ -397,19 +434,21 @@ fn from_crate_info(crate_info: &CrateInfo) -> &PartsAndLocations<Self> {
 }
 
 impl CratesIndexPart {
-    fn blank(cx: &Context<'_>) -> SortedTemplate<<Self as CciPart>::FileFormat> {
+    fn blank(
+        layout: &layout::Layout,
+        style_files: &[StylePath],
+        opt: &RenderOptions,
+    ) -> SortedTemplate<<Self as CciPart>::FileFormat> {
         let page = layout::Page {
             title: "Index of crates",
             short_title: "Crates",
             css_class: "mod sys",
             root_path: "./",
-            static_root_path: cx.shared.static_root_path.as_deref(),
+            static_root_path: opt.static_root_path.as_deref(),
             description: "List of crates",
-            resource_suffix: &cx.shared.resource_suffix,
+            resource_suffix: &opt.resource_suffix,
             rust_logo: true,
         };
-        let layout = &cx.shared.layout;
-        let style_files = &cx.shared.style_files;
         const DELIMITER: &str = "\u{FFFC}"; // users are being naughty if they have this
         let content = format!(
             "<div class=\"main-heading\">\
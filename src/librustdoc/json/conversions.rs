@@ -7,7 +7,9 @@
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::thin_vec::ThinVec;
 use rustc_hir as hir;
-use rustc_hir::attrs::{self, DeprecatedSince, DocAttribute, DocInline, HideOrShow};
+use rustc_hir::attrs::{
+    self, DeprecatedSince, DocAttribute, DocInline, DocScrapeExamples, HideOrShow,
+};
 use rustc_hir::def::CtorKind;
 use rustc_hir::def_id::DefId;
 use rustc_hir::{HeaderSafety, Safety};
@@ -962,6 +964,7 @@ fn name_value_attr(
                 cfg,
                 auto_cfg,
                 auto_cfg_change,
+                scrape_examples,
                 fake_variadic,
                 keyword,
                 attribute,
@@ -1022,6 +1025,13 @@ fn name_value_attr(
             for (change, _) in auto_cfg_change {
                 ret.push(Attribute::Other(format!("#[doc(auto_cfg = {change})]")));
             }
+            if let Some((scrape_examples, _)) = scrape_examples {
+                let value = match scrape_examples {
+                    DocScrapeExamples::Pin => "pin",
+                    DocScrapeExamples::Exclude => "exclude",
+                };
+                ret.push(Attribute::Other(format!("#[doc(scrape_examples = \"{value}\")]")));
+            }
             toggle_attr(&mut ret, "fake_variadic", fake_variadic);
             name_value_attr(&mut ret, "keyword", keyword);
             name_value_attr(&mut ret, "attribute", attribute);
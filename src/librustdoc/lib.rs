@@ -105,6 +105,7 @@ macro_rules! map {
 }
 
 mod clean;
+mod commonmark;
 mod config;
 mod core;
 mod display;
@@ -485,6 +486,14 @@ fn opts() -> Vec<RustcOptGroup> {
             "Directory to persist doctest executables into",
             "PATH",
         ),
+        opt(
+            Unstable,
+            Opt,
+            "",
+            "doctest-cache-dir",
+            "Directory to cache doctest results in",
+            "PATH",
+        ),
         opt(
             Unstable,
             FlagMulti,
@@ -493,6 +502,23 @@ fn opts() -> Vec<RustcOptGroup> {
             "calculate percentage of public items with documentation",
             "",
         ),
+        opt(
+            Unstable,
+            FlagMulti,
+            "",
+            "coverage-details",
+            "list the items counted by --show-coverage that lack documentation or sections of it",
+            "",
+        ),
+        opt(
+            Unstable,
+            Multi,
+            "",
+            "coverage-threshold",
+            "fail if the percentage of documented items of the crate, or of MODULE and its \
+             submodules, is below PERCENTAGE",
+            "[MODULE=]PERCENTAGE",
+        ),
         opt(
             Stable,
             Opt,
@@ -540,7 +566,7 @@ fn opts() -> Vec<RustcOptGroup> {
             "",
             "emit",
             "Comma separated list of types of output for rustdoc to emit",
-            "[toolchain-shared-resources,invocation-specific,dep-info]",
+            "[toolchain-shared-resources,invocation-specific,dep-info,search-index]",
         ),
         opt(Unstable, FlagMulti, "", "no-run", "Compile doctests without running them", ""),
         opt(
@@ -625,7 +651,7 @@ fn opts() -> Vec<RustcOptGroup> {
             Multi,
             "",
             "include-parts-dir",
-            "Includes trait implementations and other crate info from provided path. Only use with --merge=finalize",
+            "Includes trait implementations and other crate info from provided path. Use with --merge=finalize, or with --merge=none to only link to the included crates",
             "path/to/doc.parts/<crate-name>",
         ),
         opt(Unstable, Flag, "", "html-no-source", "Disable HTML source code pages generation", ""),
@@ -751,7 +777,10 @@ fn run_renderer<
 /// Renders and writes cross-crate info files, like the search index. This function exists so that
 /// we can run rustdoc without a crate root in the `--merge=finalize` mode. Cross-crate info files
 /// discovered via `--include-parts-dir` are combined and written to the doc root.
-fn run_merge_finalize(opt: config::RenderOptions) -> Result<(), error::Error> {
+fn run_merge_finalize(
+    opt: config::RenderOptions,
+    edition: rustc_span::edition::Edition,
+) -> Result<(), error::Error> {
     assert!(
         opt.should_merge.write_rendered_cci,
         "config.rs only allows us to return InputMode::NoInputMergeFinalize if --merge=finalize"
@@ -760,7 +789,8 @@ fn run_merge_finalize(opt: config::RenderOptions) -> Result<(), error::Error> {
         !opt.should_merge.read_rendered_cci,
         "config.rs only allows us to return InputMode::NoInputMergeFinalize if --merge=finalize"
     );
-    let crates = html::render::CrateInfo::read_many(&opt.include_parts_dir)?;
+    let mut crates = html::render::CrateInfo::read_many(&opt.include_parts_dir)?;
+    html::render::CrateInfo::dedup(&mut crates);
     let include_sources = !opt.html_no_source;
     html::render::write_not_crate_specific(
         &crates,
@@ -771,6 +801,18 @@ fn run_merge_finalize(opt: config::RenderOptions) -> Result<(), error::Error> {
         &opt.resource_suffix,
         include_sources,
     )?;
+    // Without an input crate, there is no logo, favicon or version to show on the index page.
+    let layout = html::layout::Layout {
+        logo: String::new(),
+        favicon: String::new(),
+        external_html: opt.external_html.clone(),
+        default_settings: opt.default_settings.clone(),
+        krate: String::new(),
+        krate_version: String::new(),
+        css_file_extension: opt.extension_css.clone(),
+        scrape_examples_extension: false,
+    };
+    html::render::write_index_page(&crates, &opt.output, &opt, &layout, &opt.themes, edition)?;
     Ok(())
 }
 
@@ -816,7 +858,7 @@ fn main_args(early_dcx: &mut EarlyDiagCtxt, at_args: &[String]) {
             return wrap_return(
                 dcx,
                 rustc_span::create_session_globals_then(options.edition, &[], None, || {
-                    run_merge_finalize(render_options)
+                    run_merge_finalize(render_options, options.edition)
                         .map_err(|e| format!("could not write merged cross-crate info: {e}"))
                 }),
             );
@@ -852,6 +894,7 @@ fn main_args(early_dcx: &mut EarlyDiagCtxt, at_args: &[String]) {
     // need to move these items separately because we lose them by the time the closure is called,
     // but we can't create the dcx ahead of time because it's not Send
     let show_coverage = options.show_coverage;
+    let coverage_options = options.coverage_options.clone();
     let run_check = options.run_check;
 
     // First, parse the crate and extract all relevant information.
@@ -891,9 +934,15 @@ fn main_args(early_dcx: &mut EarlyDiagCtxt, at_args: &[String]) {
                 sess.dcx().fatal("Compilation failed, aborting rustdoc");
             }
 
-            let (krate, render_opts, mut cache, expanded_macros) = sess
-                .time("run_global_ctxt", || {
-                    core::run_global_ctxt(tcx, show_coverage, render_options, output_format)
+            let (krate, render_opts, mut cache, expanded_macros) =
+                sess.time("run_global_ctxt", || {
+                    core::run_global_ctxt(
+                        tcx,
+                        show_coverage,
+                        coverage_options,
+                        render_options,
+                        output_format,
+                    )
                 });
             info!("finished with rustc");
 
@@ -968,6 +1017,15 @@ fn main_args(early_dcx: &mut EarlyDiagCtxt, at_args: &[String]) {
                 config::OutputFormat::Json => sess.time("render_json", || {
                     run_renderer(krate, render_opts, cache, tcx, json::JsonRenderer::init)
                 }),
+                config::OutputFormat::Markdown => sess.time("render_markdown", || {
+                    run_renderer(
+                        krate,
+                        render_opts,
+                        cache,
+                        tcx,
+                        commonmark::CommonMarkRenderer::init,
+                    )
+                }),
                 // Already handled above with doctest runners.
                 config::OutputFormat::Doctest => unreachable!(),
             }
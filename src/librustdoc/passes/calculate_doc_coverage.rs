@@ -1,13 +1,18 @@
 //! Calculates information used for the --show-coverage flag.
 
 use std::collections::BTreeMap;
-use std::ops;
+use std::ops::{self, ControlFlow};
 
 use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_hir::intravisit::{self, Visitor};
 use rustc_lint::builtin::MISSING_DOCS;
+use rustc_middle::hir::nested_filter;
 use rustc_middle::lint::{LevelAndSource, LintLevelSource};
+use rustc_middle::ty::TyCtxt;
+use rustc_resolve::rustdoc::pulldown_cmark::{Event, Parser, Tag, TagEnd};
 use rustc_session::lint;
-use rustc_span::{FileName, RemapPathScopeComponents};
+use rustc_span::{FileName, RemapPathScopeComponents, sym};
 use serde::Serialize;
 use tracing::debug;
 
@@ -25,10 +30,11 @@
 };
 
 fn calculate_doc_coverage(krate: clean::Crate, ctx: &mut DocContext<'_>) -> clean::Crate {
-    let mut calc = CoverageCalculator { items: Default::default(), ctx };
+    let mut calc = CoverageCalculator { items: Default::default(), counted_items: Vec::new(), ctx };
     calc.visit_crate(&krate);
 
     calc.print_results();
+    calc.check_thresholds();
 
     krate
 }
@@ -103,8 +109,57 @@ fn add_assign(&mut self, rhs: Self) {
     }
 }
 
+/// A documentation section that some items are expected to have.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Section {
+    Examples,
+    Safety,
+    Panics,
+}
+
+impl Section {
+    const ALL: [Section; 3] = [Section::Examples, Section::Safety, Section::Panics];
+
+    fn heading(self) -> &'static str {
+        match self {
+            Section::Examples => "Examples",
+            Section::Safety => "Safety",
+            Section::Panics => "Panics",
+        }
+    }
+}
+
+/// An item that is part of the coverage, kept for `--coverage-details` and `--coverage-threshold`.
+struct CountedItem {
+    /// The path of the item, relative to the crate root.
+    path: String,
+    /// Where the item is defined, as `file:line:column`.
+    location: String,
+    count: ItemCount,
+    /// Whether the item has no documentation even though it should.
+    missing_docs: bool,
+    missing_sections: Vec<Section>,
+}
+
+#[derive(Serialize)]
+struct ItemLocation<'a> {
+    path: &'a str,
+    location: &'a str,
+}
+
+/// The JSON output of `--show-coverage` when `--coverage-details` is passed.
+#[derive(Serialize)]
+struct DetailedReport<'a> {
+    files: BTreeMap<String, &'a ItemCount>,
+    missing_docs: Vec<ItemLocation<'a>>,
+    missing_examples: Vec<ItemLocation<'a>>,
+    missing_safety: Vec<ItemLocation<'a>>,
+    missing_panics: Vec<ItemLocation<'a>>,
+}
+
 struct CoverageCalculator<'a, 'b> {
     items: BTreeMap<FileName, ItemCount>,
+    counted_items: Vec<CountedItem>,
     ctx: &'a mut DocContext<'b>,
 }
 
@@ -118,16 +173,179 @@ fn limit_filename_len(filename: String) -> String {
     }
 }
 
+/// Returns the text of the headings of `doc`.
+fn headings(doc: &str) -> Vec<String> {
+    let mut headings = Vec::new();
+    let mut current = None;
+    for event in Parser::new(doc) {
+        match event {
+            Event::Start(Tag::Heading { .. }) => current = Some(String::new()),
+            Event::End(TagEnd::Heading(_)) => headings.extend(current.take()),
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = &mut current {
+                    heading.push_str(&text);
+                }
+            }
+            _ => {}
+        }
+    }
+    headings
+}
+
+/// Whether the body of the function `def_id` can panic: it calls `unwrap` or `expect`, or uses one
+/// of the panicking macros of the standard library, such as `panic!` or `assert!`.
+fn may_panic(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    struct PanicFinder<'tcx> {
+        tcx: TyCtxt<'tcx>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for PanicFinder<'tcx> {
+        type NestedFilter = nested_filter::OnlyBodies;
+        type Result = ControlFlow<()>;
+
+        fn maybe_tcx(&mut self) -> Self::MaybeTyCtxt {
+            self.tcx
+        }
+
+        fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) -> ControlFlow<()> {
+            let is_panic_macro = |def_id| {
+                matches!(
+                    self.tcx.get_diagnostic_name(def_id),
+                    Some(
+                        sym::core_panic_macro
+                            | sym::std_panic_macro
+                            | sym::assert_macro
+                            | sym::assert_eq_macro
+                            | sym::assert_ne_macro
+                            | sym::unreachable_macro
+                            | sym::unimplemented_macro
+                            | sym::todo_macro
+                    )
+                )
+            };
+            if expr.span.macro_backtrace().any(|expn| expn.macro_def_id.is_some_and(is_panic_macro))
+            {
+                return ControlFlow::Break(());
+            }
+            if let hir::ExprKind::MethodCall(segment, ..) = expr.kind
+                && matches!(segment.ident.name, sym::unwrap | sym::expect)
+            {
+                return ControlFlow::Break(());
+            }
+            intravisit::walk_expr(self, expr)
+        }
+    }
+
+    let Some(body) = def_id.as_local().and_then(|def_id| tcx.hir_maybe_body_owned_by(def_id))
+    else {
+        return false;
+    };
+    PanicFinder { tcx }.visit_body(body).is_break()
+}
+
 impl CoverageCalculator<'_, '_> {
+    fn files(&self) -> BTreeMap<String, &ItemCount> {
+        self.items
+            .iter()
+            .map(|(k, v)| (k.display(RemapPathScopeComponents::COVERAGE).to_string(), v))
+            .collect()
+    }
+
+    fn locations(&self, filter: impl Fn(&CountedItem) -> bool) -> Vec<ItemLocation<'_>> {
+        self.counted_items
+            .iter()
+            .filter(|item| filter(item))
+            .map(|item| ItemLocation { path: &item.path, location: &item.location })
+            .collect()
+    }
+
     fn to_json(&self) -> String {
-        serde_json::to_string(
-            &self
-                .items
-                .iter()
-                .map(|(k, v)| (k.display(RemapPathScopeComponents::COVERAGE).to_string(), v))
-                .collect::<BTreeMap<String, &ItemCount>>(),
-        )
-        .expect("failed to convert JSON data to string")
+        let json = if self.ctx.coverage_options.details {
+            let missing_section =
+                |section| self.locations(|item| item.missing_sections.contains(&section));
+            serde_json::to_string(&DetailedReport {
+                files: self.files(),
+                missing_docs: self.locations(|item| item.missing_docs),
+                missing_examples: missing_section(Section::Examples),
+                missing_safety: missing_section(Section::Safety),
+                missing_panics: missing_section(Section::Panics),
+            })
+        } else {
+            serde_json::to_string(&self.files())
+        };
+        json.expect("failed to convert JSON data to string")
+    }
+
+    fn print_details(&self) {
+        let missing_docs: Vec<_> = self.counted_items.iter().filter(|i| i.missing_docs).collect();
+        let missing_sections: Vec<(Section, Vec<_>)> = Section::ALL
+            .into_iter()
+            .map(|section| {
+                let items = self
+                    .counted_items
+                    .iter()
+                    .filter(|item| item.missing_sections.contains(&section))
+                    .collect();
+                (section, items)
+            })
+            .collect();
+
+        println!();
+        println!("Missing documentation: {}", missing_docs.len());
+        for (section, items) in &missing_sections {
+            println!("Missing `# {}` sections: {}", section.heading(), items.len());
+        }
+
+        let print_items = |title: &str, items: &[&CountedItem]| {
+            if !items.is_empty() {
+                println!();
+                println!("{title}:");
+                for item in items {
+                    println!("    {} ({})", item.path, item.location);
+                }
+            }
+        };
+        print_items("Items without documentation", &missing_docs);
+        for (section, items) in &missing_sections {
+            print_items(&format!("Items without a `# {}` section", section.heading()), items);
+        }
+    }
+
+    /// Emits an error for every `--coverage-threshold` that is not met.
+    fn check_thresholds(&self) {
+        let dcx = self.ctx.tcx.dcx();
+        for (module, threshold) in &self.ctx.coverage_options.thresholds {
+            let mut count = ItemCount::default();
+            for item in &self.counted_items {
+                let in_module = module.as_deref().is_none_or(|module| {
+                    item.path
+                        .strip_prefix(module)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+                });
+                if in_module {
+                    count += item.count;
+                }
+            }
+            let name = match module {
+                Some(module) => format!("module `{module}`"),
+                None => "the crate".to_owned(),
+            };
+            match count.percentage() {
+                Some(percentage) if percentage < *threshold => {
+                    dcx.err(format!(
+                        "{percentage:.1}% of the items of {name} are documented, which is below \
+                         the threshold of {threshold}%"
+                    ));
+                }
+                Some(_) => {}
+                None if module.is_some() => {
+                    dcx.err(format!(
+                        "--coverage-threshold was given for {name}, but it has no items to count"
+                    ));
+                }
+                None => {}
+            }
+        }
     }
 
     fn print_results(&self) {
@@ -187,6 +405,10 @@ fn print_table_record(
             total.examples_percentage().unwrap_or(0.0),
         );
         print_table_line();
+
+        if self.ctx.coverage_options.details {
+            self.print_details();
+        }
     }
 }
 
@@ -259,12 +481,62 @@ fn visit_item(&mut self, i: &clean::Item) {
                 if let Some(span) = i.span(self.ctx.tcx) {
                     let filename = span.filename(self.ctx.sess());
                     debug!("counting {:?} {:?} in {filename:?}", i.type_(), i.name);
-                    self.items.entry(filename).or_default().count_item(
+                    let should_have_doc_example = should_have_doc_example(self.ctx, i);
+                    let mut count = ItemCount::default();
+                    count.count_item(
                         has_docs,
                         has_doc_example,
-                        should_have_doc_example(self.ctx, i),
+                        should_have_doc_example,
                         should_have_docs,
                     );
+                    *self.items.entry(filename.clone()).or_default() += count;
+
+                    if count.total > 0 {
+                        let tcx = self.ctx.tcx;
+                        let def_id = i.item_id.expect_def_id();
+                        let mut missing_sections = Vec::new();
+                        if has_docs {
+                            let headings = headings(&i.doc_value());
+                            let expects_section = |section| match section {
+                                Section::Examples => should_have_doc_example,
+                                Section::Safety => match &i.kind {
+                                    clean::TraitItem(t) => t.safety(tcx).is_unsafe(),
+                                    _ => i.fn_header(tcx).is_some_and(|header| header.is_unsafe()),
+                                },
+                                Section::Panics => {
+                                    matches!(i.kind, clean::FunctionItem(_) | clean::MethodItem(..))
+                                        && may_panic(tcx, def_id)
+                                }
+                            };
+                            missing_sections = Section::ALL
+                                .into_iter()
+                                .filter(|&section| {
+                                    expects_section(section)
+                                        && !headings.iter().any(|heading| {
+                                            heading.trim().eq_ignore_ascii_case(section.heading())
+                                        })
+                                })
+                                .collect();
+                        }
+                        let lo = span.lo(self.ctx.sess());
+                        let path = if def_id.is_crate_root() {
+                            tcx.crate_name(def_id.krate).to_string()
+                        } else {
+                            tcx.def_path_str(def_id)
+                        };
+                        self.counted_items.push(CountedItem {
+                            path,
+                            location: format!(
+                                "{}:{}:{}",
+                                filename.display(RemapPathScopeComponents::COVERAGE),
+                                lo.line,
+                                lo.col.0 + 1,
+                            ),
+                            count,
+                            missing_docs: !has_docs,
+                            missing_sections,
+                        });
+                    }
                 }
             }
         }
@@ -5,15 +5,16 @@
 
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_errors::DiagCtxtHandle;
+use rustc_hir::attrs::{AttributeKind, DocScrapeExamples};
 use rustc_hir::intravisit::{self, Visitor};
-use rustc_hir::{self as hir};
+use rustc_hir::{self as hir, find_attr};
 use rustc_macros::{Decodable, Encodable};
 use rustc_middle::hir::nested_filter;
 use rustc_middle::ty::{self, TyCtxt};
 use rustc_serialize::opaque::{FileEncoder, MemDecoder};
 use rustc_serialize::{Decodable, Encodable};
 use rustc_session::getopts;
-use rustc_span::def_id::{CrateNum, DefPathHash, LOCAL_CRATE};
+use rustc_span::def_id::{CrateNum, DefPathHash, LOCAL_CRATE, LocalDefId};
 use rustc_span::edition::Edition;
 use rustc_span::{BytePos, FileName, SourceFile};
 use tracing::{debug, trace, warn};
@@ -79,6 +80,8 @@ pub(crate) struct CallLocation {
     pub(crate) call_expr: SyntaxRange,
     pub(crate) call_ident: SyntaxRange,
     pub(crate) enclosing_item: SyntaxRange,
+    /// Whether the call is in an item marked with `#[doc(scrape_examples = "pin")]`.
+    pub(crate) pinned: bool,
 }
 
 impl CallLocation {
@@ -87,22 +90,35 @@ fn new(
         ident_span: rustc_span::Span,
         enclosing_item_span: rustc_span::Span,
         source_file: &SourceFile,
+        pinned: bool,
     ) -> Option<Self> {
         Some(CallLocation {
             call_expr: SyntaxRange::new(expr_span, source_file)?,
             call_ident: SyntaxRange::new(ident_span, source_file)?,
             enclosing_item: SyntaxRange::new(enclosing_item_span, source_file)?,
+            pinned,
         })
     }
 }
 
+/// The kind of crate that an example was scraped from. Examples are shown in this order.
+#[derive(Encodable, Decodable, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum ExampleKind {
+    /// A binary, like the crates of the `examples/` directory.
+    Example,
+    /// A crate scraped with `--scrape-tests`, like integration tests and benches.
+    Test,
+    /// A library.
+    Library,
+}
+
 #[derive(Encodable, Decodable, Debug, Clone)]
 pub(crate) struct CallData {
     pub(crate) locations: Vec<CallLocation>,
     pub(crate) url: String,
     pub(crate) display_name: String,
     pub(crate) edition: Edition,
-    pub(crate) is_bin: bool,
+    pub(crate) kind: ExampleKind,
 }
 
 pub(crate) type FnCallLocations = FxIndexMap<PathBuf, CallData>;
@@ -113,7 +129,27 @@ struct FindCalls<'a, 'tcx> {
     cx: Context<'tcx>,
     target_crates: Vec<CrateNum>,
     calls: &'a mut AllCallLocations,
-    bin_crate: bool,
+    kind: ExampleKind,
+}
+
+impl FindCalls<'_, '_> {
+    /// Returns the value of `#[doc(scrape_examples = "...")]` on `def_id`, or on the closest of its
+    /// parents that has it, so that a whole test module can be excluded at once.
+    fn scrape_examples_attr(&self, def_id: LocalDefId) -> Option<DocScrapeExamples> {
+        let tcx = self.cx.tcx();
+        let mut def_id = Some(def_id);
+        while let Some(id) = def_id {
+            let value = find_attr!(
+                tcx.get_all_attrs(id),
+                AttributeKind::Doc(d) if d.scrape_examples.is_some() => d.scrape_examples
+            );
+            if let Some(Some((value, _))) = value {
+                return Some(value);
+            }
+            def_id = tcx.opt_local_parent(id);
+        }
+        None
+    }
 }
 
 impl<'a, 'tcx> Visitor<'tcx> for FindCalls<'a, 'tcx>
@@ -181,6 +217,15 @@ fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
             return;
         }
 
+        let pinned = match self.scrape_examples_attr(tcx.hir_get_parent_item(ex.hir_id).def_id) {
+            Some(DocScrapeExamples::Exclude) => {
+                trace!("Rejecting expr ({call_span:?}) from excluded item");
+                return;
+            }
+            Some(DocScrapeExamples::Pin) => true,
+            None => false,
+        };
+
         // If the enclosing item doesn't actually enclose the call, this means we probably have a
         // weird macro issue even though the spans aren't tagged as being from an expansion.
         if !enclosing_item_span.contains(call_span) {
@@ -239,9 +284,9 @@ fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
                 let mk_call_data = || {
                     let display_name = file_path.display().to_string();
                     let edition = call_span.edition();
-                    let is_bin = self.bin_crate;
+                    let kind = self.kind;
 
-                    CallData { locations: Vec::new(), url, display_name, edition, is_bin }
+                    CallData { locations: Vec::new(), url, display_name, edition, kind }
                 };
 
                 let fn_key = tcx.def_path_hash(*def_id);
@@ -250,14 +295,19 @@ fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
                 trace!("Including expr: {call_span:?}");
                 let enclosing_item_span =
                     source_map.span_extend_to_prev_char(enclosing_item_span, '\n', false);
-                let location =
-                    match CallLocation::new(call_span, ident_span, enclosing_item_span, &file) {
-                        Some(location) => location,
-                        None => {
-                            trace!("Could not get serializable call location for {call_span:?}");
-                            return;
-                        }
-                    };
+                let location = match CallLocation::new(
+                    call_span,
+                    ident_span,
+                    enclosing_item_span,
+                    &file,
+                    pinned,
+                ) {
+                    Some(location) => location,
+                    None => {
+                        trace!("Could not get serializable call location for {call_span:?}");
+                        return;
+                    }
+                };
                 fn_entries.entry(abs_path).or_insert_with(mk_call_data).locations.push(location);
             }
         }
@@ -298,9 +348,17 @@ pub(crate) fn run(
         debug!("All crates in TyCtxt: {all_crates:?}");
         debug!("Scrape examples target_crates: {target_crates:?}");
 
+        let kind = if options.scrape_tests {
+            ExampleKind::Test
+        } else if bin_crate {
+            ExampleKind::Example
+        } else {
+            ExampleKind::Library
+        };
+
         // Run call-finder on all items
         let mut calls = FxIndexMap::default();
-        let mut finder = FindCalls { calls: &mut calls, cx, target_crates, bin_crate };
+        let mut finder = FindCalls { calls: &mut calls, cx, target_crates, kind };
         tcx.hir_visit_all_item_likes_in_crate(&mut finder);
 
         // The visitor might have found a type error, which we need to
@@ -309,10 +367,10 @@ pub(crate) fn run(
             return Err(String::from("Compilation failed, aborting rustdoc"));
         }
 
-        // Sort call locations within a given file in document order
+        // Sort call locations within a given file in document order, after the pinned ones
         for fn_calls in calls.values_mut() {
             for file_calls in fn_calls.values_mut() {
-                file_calls.locations.sort_by_key(|loc| loc.call_expr.byte_span.0);
+                file_calls.locations.sort_by_key(|loc| (!loc.pinned, loc.call_expr.byte_span.0));
             }
         }
 
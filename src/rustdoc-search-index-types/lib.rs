@@ -0,0 +1,149 @@
+//! The export of rustdoc's search index.
+//!
+//! These types describe the `search-index.json` file that rustdoc writes to the root of the output
+//! directory when it is passed `--emit=search-index`. It contains the data that the search of the
+//! HTML documentation runs on, for all the crates documented in that directory: the items that
+//! can be found by name, and the simplified signatures of functions that type-based searches such
+//! as `Vec<T> -> usize` are matched against.
+
+use serde_derive::{Deserialize, Serialize};
+
+/// The version of the export that this crate represents.
+///
+/// This integer is incremented with every breaking change to the format, and is written along
+/// with the index as [`SearchIndex::format_version`]. Consuming code should check that this value
+/// matches the format version(s) that it supports.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The root of the export.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchIndex {
+    /// The version of the format, see [`FORMAT_VERSION`].
+    pub format_version: u32,
+    /// The names of the crates that are part of the index.
+    pub crates: Vec<String>,
+    /// The items that searches can return.
+    pub items: Vec<Item>,
+    /// The modules, types and traits that items and signatures refer to. [`Item::parent`] and
+    /// [`TypeId::Path`] are indices into this list.
+    pub paths: Vec<Path>,
+}
+
+/// An item that searches can return.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
+    pub kind: ItemKind,
+    /// The name of the crate that documents the item.
+    #[serde(rename = "crate")]
+    pub krate: String,
+    /// The path of the module under which the item is documented, like `std::vec`.
+    pub module: String,
+    /// The type or trait that the item is associated with, like a method or a variant is.
+    pub parent: Option<usize>,
+    /// The other names the item can be found by, given with `#[doc(alias)]`.
+    pub aliases: Vec<String>,
+    /// The first paragraph of the documentation of the item, as HTML.
+    pub desc: String,
+    pub deprecated: bool,
+    /// The signature of the item, for functions, methods, constants, statics and fields.
+    pub signature: Option<Signature>,
+}
+
+/// A module, type or trait that other entries of the index refer to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Path {
+    pub name: String,
+    pub kind: ItemKind,
+    /// The path of the module that defines the item, like `alloc::vec`. It is empty for crates,
+    /// primitive types and associated types.
+    pub module: String,
+    /// Whether the type can be left out of a type-based search that mentions its generics, like
+    /// `Option` in a search for `T -> U` matching `fn(T) -> Option<U>`. This is the case for
+    /// types marked `#[doc(search_unbox)]`, references, raw pointers and tuples.
+    pub unbox: bool,
+}
+
+/// The simplified signature of a function.
+///
+/// The inputs of a method include the `Self` type. Constants and statics have their type as their
+/// only output, and fields also have the type that they belong to as their only input.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    pub inputs: Vec<Type>,
+    pub output: Vec<Type>,
+    /// The trait bounds of each generic parameter. [`TypeId::Generic`] is an index into this
+    /// list.
+    pub generics: Vec<Vec<Type>>,
+}
+
+/// A type in a signature.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Type {
+    /// What the type refers to. It is `None` for types that cannot be named in a search, like an
+    /// `impl Trait` in return position, whose generics hold its bounds.
+    pub id: Option<TypeId>,
+    pub generics: Vec<Type>,
+    /// Constraints on associated types, like `Item = u8` in `Iterator<Item = u8>`.
+    pub bindings: Vec<Binding>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeId {
+    /// A type or trait, as an index into [`SearchIndex::paths`].
+    Path(usize),
+    /// A generic parameter of the function, as an index into [`Signature::generics`].
+    Generic(usize),
+}
+
+/// A constraint on an associated type.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Binding {
+    /// The associated type, as an index into [`SearchIndex::paths`].
+    pub assoc: usize,
+    pub constraints: Vec<Type>,
+}
+
+/// The kind of an item or a path.
+///
+/// The serialized names are the ones that the search of the HTML documentation uses for filters,
+/// like `fn:` in `fn:len`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    #[serde(rename = "mod")]
+    Module,
+    ExternCrate,
+    Import,
+    Struct,
+    Union,
+    Enum,
+    #[serde(rename = "fn")]
+    Function,
+    #[serde(rename = "type")]
+    TypeAlias,
+    Static,
+    Trait,
+    Impl,
+    /// A method of a trait without a default implementation.
+    TyMethod,
+    Method,
+    StructField,
+    Variant,
+    Macro,
+    Primitive,
+    #[serde(rename = "associatedtype")]
+    AssocType,
+    Constant,
+    #[serde(rename = "associatedconstant")]
+    AssocConst,
+    ForeignType,
+    Keyword,
+    #[serde(rename = "attr")]
+    ProcAttribute,
+    #[serde(rename = "derive")]
+    ProcDerive,
+    TraitAlias,
+    Attribute,
+}
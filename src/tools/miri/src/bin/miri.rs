@@ -46,7 +46,7 @@
 
 use miri::{
     BacktraceStyle, BorrowTrackerMethod, GenmcConfig, GenmcCtx, MiriConfig, MiriEntryFnType,
-    ProvenanceMode, TreeBorrowsParams, ValidationMode, run_genmc_mode,
+    ProvenanceMode, Schedule, TreeBorrowsParams, ValidationMode, run_genmc_mode,
 };
 use rustc_abi::ExternAbi;
 use rustc_codegen_ssa::traits::CodegenBackend;
@@ -75,6 +75,7 @@
 struct MiriCompilerCalls {
     miri_config: Option<MiriConfig>,
     many_seeds: Option<ManySeedsConfig>,
+    shrink_schedule: bool,
 }
 
 struct ManySeedsConfig {
@@ -83,8 +84,12 @@ struct ManySeedsConfig {
 }
 
 impl MiriCompilerCalls {
-    fn new(miri_config: MiriConfig, many_seeds: Option<ManySeedsConfig>) -> Self {
-        Self { miri_config: Some(miri_config), many_seeds }
+    fn new(
+        miri_config: MiriConfig,
+        many_seeds: Option<ManySeedsConfig>,
+        shrink_schedule: bool,
+    ) -> Self {
+        Self { miri_config: Some(miri_config), many_seeds, shrink_schedule }
     }
 }
 
@@ -255,6 +260,8 @@ fn after_analysis<'tcx>(
                 eprintln!("Trying seed: {seed}");
                 miri::eval_entry(tcx, entry_def_id, entry_type, &config, /* genmc_ctx */ None)
             })
+        } else if self.shrink_schedule {
+            miri::shrink_schedule(tcx, entry_def_id, entry_type, &config)
         } else {
             miri::eval_entry(tcx, entry_def_id, entry_type, &config, None)
         };
@@ -479,6 +486,7 @@ fn main() {
     // Parse our arguments and split them across `rustc` and `miri`.
     let mut many_seeds: Option<Range<u32>> = None;
     let mut many_seeds_keep_going = false;
+    let mut shrink_schedule = false;
     let mut miri_config = MiriConfig::default();
     miri_config.env = env_snapshot;
 
@@ -594,6 +602,18 @@ fn main() {
             many_seeds = Some(0..64);
         } else if arg == "-Zmiri-many-seeds-keep-going" {
             many_seeds_keep_going = true;
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-record-schedule=") {
+            miri_config.record_schedule = Some(param.into());
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-replay-schedule=") {
+            let schedule = std::fs::read_to_string(param).unwrap_or_else(|err| {
+                fatal_error!("-Zmiri-replay-schedule could not read `{param}`: {err}")
+            });
+            let schedule = Schedule::parse(&schedule).unwrap_or_else(|err| {
+                fatal_error!("-Zmiri-replay-schedule got an invalid schedule `{param}`: {err}")
+            });
+            miri_config.replay_schedule = Some(schedule);
+        } else if arg == "-Zmiri-shrink-schedule" {
+            shrink_schedule = true;
         } else if let Some(trimmed_arg) = arg.strip_prefix("-Zmiri-genmc") {
             if let Err(msg) = GenmcConfig::parse_arg(&mut miri_config.genmc_config, trimmed_arg) {
                 fatal_error!("{msg}");
@@ -651,6 +671,10 @@ fn main() {
             miri_config.gc_interval = interval;
         } else if let Some(param) = arg.strip_prefix("-Zmiri-measureme=") {
             miri_config.measureme_out = Some(param.to_string());
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-coverage=") {
+            miri_config.coverage = Some(param.into());
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-report-json=") {
+            miri_config.report_json = Some(param.into());
         } else if let Some(param) = arg.strip_prefix("-Zmiri-backtrace=") {
             miri_config.backtrace_style = match param {
                 "0" => BacktraceStyle::Off,
@@ -719,6 +743,33 @@ fn main() {
     if many_seeds.is_some() && miri_config.seed.is_some() {
         fatal_error!("Only one of `-Zmiri-seed` and `-Zmiri-many-seeds can be set");
     }
+    // Every seed would overwrite the schedule recorded by the previous one.
+    if many_seeds.is_some() && miri_config.record_schedule.is_some() {
+        fatal_error!("`-Zmiri-record-schedule` cannot be used with `-Zmiri-many-seeds`");
+    }
+    // GenMC does its own scheduling.
+    if miri_config.genmc_config.is_some()
+        && (miri_config.record_schedule.is_some() || miri_config.replay_schedule.is_some())
+    {
+        fatal_error!("recording or replaying a schedule is not supported in GenMC mode");
+    }
+    // Shrinking starts from a failing schedule and writes out a smaller one.
+    if shrink_schedule
+        && (miri_config.replay_schedule.is_none() || miri_config.record_schedule.is_none())
+    {
+        fatal_error!(
+            "`-Zmiri-shrink-schedule` requires `-Zmiri-replay-schedule` and `-Zmiri-record-schedule`"
+        );
+    }
+    if shrink_schedule && many_seeds.is_some() {
+        fatal_error!("`-Zmiri-shrink-schedule` cannot be used with `-Zmiri-many-seeds`");
+    }
+    // Coverage is written by every execution, so there must only be one.
+    if miri_config.coverage.is_some() && (many_seeds.is_some() || shrink_schedule) {
+        fatal_error!(
+            "`-Zmiri-coverage` cannot be used with `-Zmiri-many-seeds` or `-Zmiri-shrink-schedule`"
+        );
+    }
     // We cannot emulate weak memory without the data race detector.
     if miri_config.weak_memory_emulation && !miri_config.data_race_detector {
         fatal_error!(
@@ -754,5 +805,8 @@ fn main() {
             );
         }
     }
-    run_compiler_and_exit(&rustc_args, &mut MiriCompilerCalls::new(miri_config, many_seeds))
+    run_compiler_and_exit(
+        &rustc_args,
+        &mut MiriCompilerCalls::new(miri_config, many_seeds, shrink_schedule),
+    )
 }
@@ -11,10 +11,11 @@
 /// Error reporting
 fn err_sb_ub<'tcx>(
     msg: String,
+    alloc_id: AllocId,
     help: Vec<String>,
     history: Option<TagHistory>,
 ) -> InterpErrorKind<'tcx> {
-    err_machine_stop!(TerminationInfo::StackedBorrowsUb { msg, help, history })
+    err_machine_stop!(TerminationInfo::StackedBorrowsUb { msg, alloc_id, help, history })
 }
 
 #[derive(Clone, Debug)]
@@ -395,6 +396,7 @@ pub(super) fn grant_error(&self, stack: &Stack) -> InterpErrorKind<'tcx> {
         }
         err_sb_ub(
             format!("{action}{}", error_cause(stack, op.orig_tag)),
+            self.history.id,
             helps,
             op.orig_tag.and_then(|orig_tag| self.get_logs_relevant_to(orig_tag, None)),
         )
@@ -418,6 +420,7 @@ pub(super) fn access_error(&self, stack: &Stack) -> InterpErrorKind<'tcx> {
         );
         err_sb_ub(
             format!("{action}{}", error_cause(stack, op.tag)),
+            self.history.id,
             vec![operation_summary("an access", self.history.id, op.range)],
             op.tag.and_then(|tag| self.get_logs_relevant_to(tag, None)),
         )
@@ -435,13 +438,19 @@ pub(super) fn protector_error(
         };
         match self.operation {
             Operation::Dealloc(_) =>
-                err_sb_ub(format!("deallocating while item {item:?} is {protected}",), vec![], None),
+                err_sb_ub(
+                    format!("deallocating while item {item:?} is {protected}",),
+                    self.history.id,
+                    vec![],
+                    None,
+                ),
             Operation::Retag(RetagOp { orig_tag: tag, .. })
             | Operation::Access(AccessOp { tag, .. }) =>
                 err_sb_ub(
                     format!(
                         "not granting access to tag {tag:?} because that would remove {item:?} which is {protected}",
                     ),
+                    self.history.id,
                     vec![],
                     tag.and_then(|tag| self.get_logs_relevant_to(tag, Some(item.tag()))),
                 ),
@@ -460,6 +469,7 @@ pub fn dealloc_error(&self, stack: &Stack) -> InterpErrorKind<'tcx> {
                 alloc_id = self.history.id,
                 cause = error_cause(stack, op.tag),
             ),
+            self.history.id,
             vec![],
             op.tag.and_then(|tag| self.get_logs_relevant_to(tag, None)),
         )
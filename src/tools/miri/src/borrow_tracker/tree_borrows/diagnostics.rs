@@ -394,7 +394,8 @@ pub fn build<'tcx>(self) -> InterpErrorKind<'tcx> {
             conflicting_tag_name,
             true,
         );
-        err_machine_stop!(TerminationInfo::TreeBorrowsUb { title, details, history })
+        let alloc_id = self.access_info.alloc_id;
+        err_machine_stop!(TerminationInfo::TreeBorrowsUb { title, alloc_id, details, history })
     }
 }
 
@@ -409,7 +410,8 @@ pub fn no_valid_exposed_references_error<'tcx>(
     );
     let details = vec![format!("there are no exposed tags which may perform this access here")];
     let history = HistoryData::default();
-    err_machine_stop!(TerminationInfo::TreeBorrowsUb { title, details, history })
+    let alloc_id = *alloc_id;
+    err_machine_stop!(TerminationInfo::TreeBorrowsUb { title, alloc_id, details, history })
 }
 
 type S = &'static str;
@@ -923,7 +923,9 @@ fn atomic_compare_exchange_scalar(
         let success_rate = 1.0 - this.machine.cmpxchg_weak_failure_rate;
         let cmpxchg_success = eq.to_scalar().to_bool()?
             && if can_fail_spuriously {
-                this.machine.rng.get_mut().random_bool(success_rate)
+                let fail = !this.machine.rng.get_mut().random_bool(success_rate);
+                let thread = this.active_thread();
+                !this.machine.schedule.get_mut().cmpxchg_weak_fails(thread, fail)
             } else {
                 true
             };
@@ -0,0 +1,459 @@
+//! Recording, replaying, and shrinking the nondeterministic choices that Miri makes when running a
+//! concurrent program.
+//!
+//! A schedule consists of the points where the active thread got preempted, the thread that got
+//! picked whenever the scheduler had to choose, which store each weak memory load read from, and
+//! which `compare_exchange_weak` operations failed spuriously. All of these are keyed by something
+//! that does not depend on the random number generator (basic block counts and per-thread operation
+//! counts), so a schedule recorded with one Miri version can be replayed with another one as long
+//! as the program executes the same way. If the execution diverges from the schedule, Miri falls
+//! back to the deterministic choice: no preemption, round-robin scheduling, the latest store, and no
+//! spurious failures.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::num::NonZeroI32;
+
+use rand::Rng;
+use rand::seq::IteratorRandom;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+
+use crate::*;
+
+/// The first line of every schedule file.
+const SCHEDULE_HEADER: &str = "miri-schedule v1";
+
+/// A single nondeterministic choice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// The active thread got preempted at the end of the `block`-th basic block.
+    Preempt { block: u64 },
+    /// The scheduler picked `thread` when it had to switch threads in the `block`-th basic block.
+    Switch { block: u64, thread: ThreadId },
+    /// The `index`-th weak memory load of `thread` read from the store that is `age` stores older
+    /// than the latest one.
+    Load { thread: ThreadId, index: u64, age: usize },
+    /// The `index`-th successful `compare_exchange_weak` of `thread` failed spuriously.
+    CmpxchgWeakFail { thread: ThreadId, index: u64 },
+}
+
+/// The choices made by one execution of the program.
+#[derive(Clone, Debug, Default)]
+pub struct Schedule {
+    decisions: Vec<Decision>,
+}
+
+impl Schedule {
+    /// Parse a schedule from the format written by its `Display` implementation.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, SCHEDULE_HEADER)) => {}
+            _ => return Err(format!("the first line must be `{SCHEDULE_HEADER}`")),
+        }
+        let mut decisions = Vec::new();
+        for (line_idx, line) in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |msg: &str| format!("line {}: {msg}: `{line}`", line_idx + 1);
+            let mut words = line.split_ascii_whitespace();
+            let kind = words.next().unwrap();
+            let mut nums = words.map(|word| word.parse::<u64>());
+            let mut next = || {
+                nums.next()
+                    .ok_or_else(|| err("missing number"))?
+                    .map_err(|e| err(&format!("invalid number: {e}")))
+            };
+            let thread = |id: u64| {
+                u32::try_from(id).map(ThreadId::new_unchecked).map_err(|_| err("invalid thread id"))
+            };
+            let decision = match kind {
+                "preempt" => Decision::Preempt { block: next()? },
+                "switch" => Decision::Switch { block: next()?, thread: thread(next()?)? },
+                "load" =>
+                    Decision::Load {
+                        thread: thread(next()?)?,
+                        index: next()?,
+                        age: usize::try_from(next()?).map_err(|_| err("invalid age"))?,
+                    },
+                "cmpxchg-weak-fail" =>
+                    Decision::CmpxchgWeakFail { thread: thread(next()?)?, index: next()? },
+                _ => return Err(err("unknown decision")),
+            };
+            if nums.next().is_some() {
+                return Err(err("trailing data"));
+            }
+            decisions.push(decision);
+        }
+        Ok(Schedule { decisions })
+    }
+
+    /// The number of times the active thread got preempted.
+    pub fn num_preemptions(&self) -> usize {
+        self.preemption_points().count()
+    }
+
+    /// The basic block counts at which the active thread got preempted, in execution order.
+    pub fn preemption_points(&self) -> impl Iterator<Item = u64> {
+        self.decisions.iter().filter_map(|decision| {
+            match decision {
+                Decision::Preempt { block } => Some(*block),
+                _ => None,
+            }
+        })
+    }
+
+    /// The same schedule without the given preemption points.
+    pub fn without_preemptions(&self, remove: &FxHashSet<u64>) -> Schedule {
+        let decisions = self
+            .decisions
+            .iter()
+            .filter(|decision| !matches!(decision, Decision::Preempt { block } if remove.contains(block)))
+            .copied()
+            .collect();
+        Schedule { decisions }
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{SCHEDULE_HEADER}")?;
+        for decision in &self.decisions {
+            match decision {
+                Decision::Preempt { block } => writeln!(f, "preempt {block}")?,
+                Decision::Switch { block, thread } =>
+                    writeln!(f, "switch {block} {}", thread.to_u32())?,
+                Decision::Load { thread, index, age } =>
+                    writeln!(f, "load {} {index} {age}", thread.to_u32())?,
+                Decision::CmpxchgWeakFail { thread, index } =>
+                    writeln!(f, "cmpxchg-weak-fail {} {index}", thread.to_u32())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A schedule that is being replayed, indexed for quick lookup.
+#[derive(Debug, Default)]
+struct Replay {
+    preemptions: FxHashSet<u64>,
+    switches: FxHashMap<u64, VecDeque<ThreadId>>,
+    loads: FxHashMap<(ThreadId, u64), usize>,
+    cmpxchg_weak_fails: FxHashSet<(ThreadId, u64)>,
+}
+
+impl Replay {
+    fn new(schedule: &Schedule) -> Self {
+        let mut replay = Replay::default();
+        for decision in &schedule.decisions {
+            match *decision {
+                Decision::Preempt { block } => {
+                    replay.preemptions.insert(block);
+                }
+                Decision::Switch { block, thread } => {
+                    replay.switches.entry(block).or_default().push_back(thread);
+                }
+                Decision::Load { thread, index, age } => {
+                    replay.loads.insert((thread, index), age);
+                }
+                Decision::CmpxchgWeakFail { thread, index } => {
+                    replay.cmpxchg_weak_fails.insert((thread, index));
+                }
+            }
+        }
+        replay
+    }
+}
+
+/// Picks a random index below `len`, the same way `IteratorRandom::choose` picks an element of a
+/// lazily filtered iterator of that length.
+fn random_index(len: usize, rng: &mut (impl Rng + ?Sized)) -> Option<usize> {
+    // The `filter` hides the exact length from `choose`, which makes it use reservoir sampling.
+    (0..len).filter(|_| true).choose(rng)
+}
+
+/// The part of the machine state that records and replays schedules.
+#[derive(Debug, Default)]
+pub struct ScheduleState {
+    /// The decisions made so far, if we are recording them.
+    recording: Option<Vec<Decision>>,
+    /// The schedule we are replaying, if any.
+    replay: Option<Replay>,
+    /// Whether the execution did something that the replayed schedule did not expect.
+    diverged: bool,
+    /// The number of weak memory loads each thread has performed.
+    loads: FxHashMap<ThreadId, u64>,
+    /// The number of `compare_exchange_weak` operations that could have failed spuriously, for each
+    /// thread.
+    cmpxchg_weaks: FxHashMap<ThreadId, u64>,
+}
+
+impl ScheduleState {
+    pub(crate) fn new(config: &MiriConfig) -> Self {
+        ScheduleState {
+            recording: config.record_schedule.is_some().then(Vec::new),
+            replay: config.replay_schedule.as_ref().map(Replay::new),
+            ..Default::default()
+        }
+    }
+
+    fn record(&mut self, decision: Decision) {
+        if let Some(recording) = &mut self.recording {
+            recording.push(decision);
+        }
+    }
+
+    /// Whether the execution diverged from the replayed schedule.
+    pub fn diverged(&self) -> bool {
+        self.diverged
+    }
+
+    /// The schedule the execution took, if it was recorded.
+    pub fn into_recorded(self) -> Option<Schedule> {
+        self.recording.map(|decisions| Schedule { decisions })
+    }
+
+    /// Decides whether to preempt the active thread at the end of the `block`-th basic block.
+    /// `random` is the decision that would be taken without replaying a schedule.
+    pub(crate) fn preempt(&mut self, block: u64, random: bool) -> bool {
+        let preempt = match &self.replay {
+            Some(replay) => replay.preemptions.contains(&block),
+            None => random,
+        };
+        if preempt {
+            self.record(Decision::Preempt { block });
+        }
+        preempt
+    }
+
+    /// Picks the thread to switch to in the `block`-th basic block. `candidates` lists the enabled
+    /// threads in round-robin order.
+    pub(crate) fn choose_thread(
+        &mut self,
+        block: u64,
+        candidates: &[ThreadId],
+        fixed_scheduling: bool,
+        rng: &mut (impl Rng + ?Sized),
+    ) -> Option<ThreadId> {
+        let mut idx = if fixed_scheduling || candidates.is_empty() {
+            0
+        } else {
+            random_index(candidates.len(), rng).unwrap()
+        };
+        if candidates.len() <= 1 {
+            // Not much of a choice.
+            return candidates.first().copied();
+        }
+        if let Some(replay) = &mut self.replay {
+            idx = 0;
+            if let Some(thread) = replay.switches.get_mut(&block).and_then(VecDeque::pop_front) {
+                match candidates.iter().position(|&t| t == thread) {
+                    Some(pos) => idx = pos,
+                    None => self.diverged = true,
+                }
+            }
+        }
+        let thread = candidates[idx];
+        self.record(Decision::Switch { block, thread });
+        Some(thread)
+    }
+
+    /// Picks the store that a weak memory load of `thread` reads from, as the number of stores it
+    /// is older than the latest one (out of `num_candidates` stores it could read from).
+    pub(crate) fn choose_load(
+        &mut self,
+        thread: ThreadId,
+        num_candidates: usize,
+        rng: &mut (impl Rng + ?Sized),
+    ) -> Option<usize> {
+        let mut age = random_index(num_candidates, rng)?;
+        let counter = self.loads.entry(thread).or_default();
+        let index = *counter;
+        *counter += 1;
+        if let Some(replay) = &self.replay {
+            age = match replay.loads.get(&(thread, index)) {
+                Some(&age) if age < num_candidates => age,
+                Some(_) => {
+                    self.diverged = true;
+                    0
+                }
+                None => 0,
+            };
+        }
+        if age > 0 {
+            self.record(Decision::Load { thread, index, age });
+        }
+        Some(age)
+    }
+
+    /// Decides whether a `compare_exchange_weak` of `thread` that would succeed fails spuriously.
+    /// `random` is the decision that would be taken without replaying a schedule.
+    pub(crate) fn cmpxchg_weak_fails(&mut self, thread: ThreadId, random: bool) -> bool {
+        let counter = self.cmpxchg_weaks.entry(thread).or_default();
+        let index = *counter;
+        *counter += 1;
+        let fail = match &self.replay {
+            Some(replay) => replay.cmpxchg_weak_fails.contains(&(thread, index)),
+            None => random,
+        };
+        if fail {
+            self.record(Decision::CmpxchgWeakFail { thread, index });
+        }
+        fail
+    }
+}
+
+/// Tries to find a schedule with fewer preemption points than the one given by
+/// `-Zmiri-replay-schedule` that still makes the program fail with the same exit code. The smallest
+/// schedule that was found is written to the file given by `-Zmiri-record-schedule`.
+pub fn shrink_schedule<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    entry_id: DefId,
+    entry_type: MiriEntryFnType,
+    config: &MiriConfig,
+) -> Result<(), NonZeroI32> {
+    let record_path = config.record_schedule.as_ref().expect("shrinking needs a file to write to");
+    let run = |schedule: Schedule| {
+        let mut config = config.clone();
+        config.replay_schedule = Some(schedule);
+        let (res, state) =
+            crate::eval::eval_entry_with_schedule(tcx, entry_id, entry_type, &config, None);
+        (res, state.into_recorded().expect("we are recording"))
+    };
+
+    let original = config.replay_schedule.clone().expect("shrinking needs a schedule to replay");
+    eprintln!("Trying the original schedule ({} preemption points)", original.num_preemptions());
+    let (res, mut best) = run(original);
+    let Err(expected) = res else {
+        tcx.dcx().warn(
+            "the replayed schedule does not make the program fail, so there is nothing to shrink",
+        );
+        return Ok(());
+    };
+    let original_preemptions = best.num_preemptions();
+
+    // Try to remove chunks of preemption points, and halve the chunk size whenever no chunk could
+    // be removed. This is a simple version of the ddmin algorithm.
+    let mut chunk_size = original_preemptions.div_ceil(2);
+    while chunk_size > 0 {
+        let points = best.preemption_points().collect::<Vec<_>>();
+        let mut shrunk = false;
+        for chunk in points.chunks(chunk_size) {
+            let candidate = best.without_preemptions(&chunk.iter().copied().collect());
+            eprintln!("Trying a schedule with {} preemption points", candidate.num_preemptions());
+            let (res, taken) = run(candidate);
+            // Replaying never adds preemption points, so this always makes progress.
+            if res == Err(expected) && taken.num_preemptions() < best.num_preemptions() {
+                best = taken;
+                shrunk = true;
+                break;
+            }
+        }
+        if !shrunk {
+            chunk_size /= 2;
+        }
+    }
+
+    eprintln!(
+        "Shrunk the schedule from {original_preemptions} to {} preemption points",
+        best.num_preemptions()
+    );
+    if let Err(err) = std::fs::write(record_path, best.to_string()) {
+        tcx.dcx().fatal(format!("failed to write schedule to `{}`: {err}", record_path.display()));
+    }
+    Err(expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thread(id: u32) -> ThreadId {
+        ThreadId::new_unchecked(id)
+    }
+
+    #[test]
+    fn roundtrip() {
+        let schedule = Schedule {
+            decisions: vec![
+                Decision::Preempt { block: 10 },
+                Decision::Switch { block: 10, thread: thread(1) },
+                Decision::Load { thread: thread(1), index: 3, age: 2 },
+                Decision::CmpxchgWeakFail { thread: thread(0), index: 0 },
+            ],
+        };
+        let text = schedule.to_string();
+        assert_eq!(
+            text,
+            "miri-schedule v1\npreempt 10\nswitch 10 1\nload 1 3 2\ncmpxchg-weak-fail 0 0\n"
+        );
+        assert_eq!(Schedule::parse(&text).unwrap().decisions, schedule.decisions);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Schedule::parse("preempt 10\n").is_err());
+        assert!(Schedule::parse("miri-schedule v1\npreempt\n").is_err());
+        assert!(Schedule::parse("miri-schedule v1\npreempt 10 11\n").is_err());
+        assert!(Schedule::parse("miri-schedule v1\nswitch 10 -1\n").is_err());
+        assert!(Schedule::parse("miri-schedule v1\nyield 10\n").is_err());
+        // Comments and blank lines are fine.
+        let schedule = Schedule::parse("miri-schedule v1\n\n# comment\npreempt 10\n").unwrap();
+        assert_eq!(schedule.num_preemptions(), 1);
+    }
+
+    #[test]
+    fn replay() {
+        use rand::SeedableRng;
+
+        let schedule = Schedule::parse(
+            "miri-schedule v1\npreempt 5\nswitch 5 2\nload 1 0 1\nload 2 0 5\ncmpxchg-weak-fail 1 1\n",
+        )
+        .unwrap();
+        let config = MiriConfig {
+            record_schedule: Some("unused".into()),
+            replay_schedule: Some(schedule),
+            ..MiriConfig::default()
+        };
+        let mut state = ScheduleState::new(&config);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+
+        // Preemptions only happen where the schedule says so.
+        assert!(!state.preempt(4, true));
+        assert!(state.preempt(5, false));
+        // Switches pick the recorded thread, and default to round-robin otherwise.
+        let candidates = [thread(1), thread(2)];
+        assert_eq!(state.choose_thread(5, &candidates, false, &mut rng), Some(thread(2)));
+        assert_eq!(state.choose_thread(5, &candidates, false, &mut rng), Some(thread(1)));
+        // Loads are counted per thread, and default to the latest store.
+        assert_eq!(state.choose_load(thread(1), 3, &mut rng), Some(1));
+        assert_eq!(state.choose_load(thread(1), 3, &mut rng), Some(0));
+        assert!(!state.diverged());
+        // A recorded load from a store that does not exist makes the execution diverge.
+        assert_eq!(state.choose_load(thread(2), 3, &mut rng), Some(0));
+        assert!(state.diverged());
+        // Spurious failures only happen where the schedule says so.
+        assert!(!state.cmpxchg_weak_fails(thread(1), true));
+        assert!(state.cmpxchg_weak_fails(thread(1), false));
+
+        let recorded = state.into_recorded().unwrap();
+        assert_eq!(
+            recorded.to_string(),
+            "miri-schedule v1\npreempt 5\nswitch 5 2\nswitch 5 1\nload 1 0 1\ncmpxchg-weak-fail 1 1\n"
+        );
+    }
+
+    #[test]
+    fn without_preemptions() {
+        let schedule =
+            Schedule::parse("miri-schedule v1\npreempt 5\nswitch 5 1\npreempt 9\npreempt 12\n")
+                .unwrap();
+        assert_eq!(schedule.preemption_points().collect::<Vec<_>>(), [5, 9, 12]);
+        let shrunk = schedule.without_preemptions(&[5, 12].into_iter().collect());
+        assert_eq!(shrunk.to_string(), "miri-schedule v1\nswitch 5 1\npreempt 9\n");
+    }
+}
@@ -5,7 +5,6 @@
 use std::task::Poll;
 use std::time::{Duration, SystemTime};
 
-use rand::seq::IteratorRandom;
 use rustc_abi::ExternAbi;
 use rustc_const_eval::CTRL_C_RECEIVED;
 use rustc_data_structures::either::Either;
@@ -16,6 +15,7 @@
 use rustc_middle::ty::layout::TyAndLayout;
 use rustc_span::{DUMMY_SP, Span};
 use rustc_target::spec::Os;
+use smallvec::SmallVec;
 
 use crate::concurrency::GlobalDataRaceHandler;
 use crate::shims::tls;
@@ -111,6 +111,8 @@ pub enum BlockReason {
     Eventfd,
     /// Blocked on unnamed_socket.
     UnnamedSocket,
+    /// Blocked on a socket.
+    Socket,
     /// Blocked for any reason related to GenMC, such as `assume` statements (GenMC mode only).
     /// Will be implicitly unblocked when GenMC schedules this thread again.
     Genmc,
@@ -768,6 +770,7 @@ fn schedule(&mut self) -> InterpResult<'tcx, SchedulingAction> {
         let thread_manager = &mut this.machine.threads;
         let clock = &this.machine.monotonic_clock;
         let rng = this.machine.rng.get_mut();
+        let schedule = this.machine.schedule.get_mut();
         // This thread and the program can keep going.
         if thread_manager.threads[thread_manager.active_thread].state.is_enabled()
             && !thread_manager.yield_active_thread
@@ -791,7 +794,7 @@ fn schedule(&mut self) -> InterpResult<'tcx, SchedulingAction> {
         // the threads before the current one and then the current thread itself (i.e., this iterator acts
         // like `threads.rotate_left(self.active_thread.index() + 1)`. This ensures that if we pick the first
         // eligible thread, we do regular round-robin scheduling, and all threads get a chance to take a step.
-        let mut threads_iter = thread_manager
+        let candidates = thread_manager
             .threads
             .iter_enumerated()
             .skip(thread_manager.active_thread.index() + 1)
@@ -801,15 +804,18 @@ fn schedule(&mut self) -> InterpResult<'tcx, SchedulingAction> {
                     .iter_enumerated()
                     .take(thread_manager.active_thread.index() + 1),
             )
-            .filter(|(_id, thread)| thread.state.is_enabled());
+            .filter(|(_id, thread)| thread.state.is_enabled())
+            .map(|(id, _thread)| id)
+            .collect::<SmallVec<[_; 8]>>();
         // Pick a new thread, and switch to it.
-        let new_thread = if thread_manager.fixed_scheduling {
-            threads_iter.next()
-        } else {
-            threads_iter.choose(rng)
-        };
+        let new_thread = schedule.choose_thread(
+            this.machine.basic_block_count,
+            &candidates,
+            thread_manager.fixed_scheduling,
+            rng,
+        );
 
-        if let Some((id, _thread)) = new_thread {
+        if let Some(id) = new_thread {
             if thread_manager.active_thread != id {
                 info!(
                     "---------- Now executing on thread `{}` (previous: `{}`) ----------------------------------------",
@@ -1273,9 +1279,9 @@ fn maybe_preempt_active_thread(&mut self) {
         use rand::Rng as _;
 
         let this = self.eval_context_mut();
-        if !this.machine.threads.fixed_scheduling
-            && this.machine.rng.get_mut().random_bool(this.machine.preemption_rate)
-        {
+        let preempt = !this.machine.threads.fixed_scheduling
+            && this.machine.rng.get_mut().random_bool(this.machine.preemption_rate);
+        if this.machine.schedule.get_mut().preempt(this.machine.basic_block_count, preempt) {
             this.yield_active_thread();
         }
     }
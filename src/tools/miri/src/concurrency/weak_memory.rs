@@ -90,6 +90,7 @@
 use std::collections::VecDeque;
 
 use rustc_data_structures::fx::FxHashMap;
+use smallvec::SmallVec;
 
 use super::AllocDataRaceHandler;
 use super::data_race::{GlobalState as DataRaceState, ThreadClockSet};
@@ -286,6 +287,7 @@ fn buffered_read(
         thread_mgr: &ThreadManager<'_>,
         is_seqcst: bool,
         rng: &mut (impl rand::Rng + ?Sized),
+        schedule: &mut ScheduleState,
         validate: impl FnOnce(Option<&VClock>) -> InterpResult<'tcx>,
     ) -> InterpResult<'tcx, (Option<Scalar>, LoadRecency)> {
         // Having a live borrow to store_buffer while calling validate_atomic_load is fine
@@ -296,7 +298,7 @@ fn buffered_read(
             // as the race detector will update it
             let (.., clocks) = global.active_thread_state(thread_mgr);
             // Load from a valid entry in the store buffer
-            self.fetch_store(is_seqcst, &clocks, &mut *rng)
+            self.fetch_store(is_seqcst, &clocks, thread_mgr.active_thread(), &mut *rng, schedule)
         };
 
         // Unlike in buffered_atomic_write, thread clock updates have to be done
@@ -329,9 +331,10 @@ fn fetch_store<R: rand::Rng + ?Sized>(
         &self,
         is_seqcst: bool,
         clocks: &ThreadClockSet,
+        thread: ThreadId,
         rng: &mut R,
+        schedule: &mut ScheduleState,
     ) -> (&StoreElement, LoadRecency) {
-        use rand::seq::IteratorRandom;
         let mut found_sc = false;
         // FIXME: we want an inclusive take_while (stops after a false predicate, but
         // includes the element that gave the false), but such function doesn't yet
@@ -399,7 +402,12 @@ fn fetch_store<R: rand::Rng + ?Sized>(
                 }
             });
 
-        let chosen = candidates.choose(rng).expect("store buffer cannot be empty");
+        // The candidates are ordered from the latest to the oldest store.
+        let candidates = candidates.collect::<SmallVec<[_; 8]>>();
+        let age = schedule
+            .choose_load(thread, candidates.len(), rng)
+            .expect("store buffer cannot be empty");
+        let chosen = candidates[age];
         if std::ptr::eq(chosen, self.buffer.back().expect("store buffer cannot be empty")) {
             (chosen, LoadRecency::Latest)
         } else {
@@ -528,6 +536,7 @@ fn buffered_atomic_read(
                         global.sc_read(&this.machine.threads);
                     }
                     let mut rng = this.machine.rng.borrow_mut();
+                    let mut schedule = this.machine.schedule.borrow_mut();
                     let Some(buffer) = alloc_buffers
                         .get_store_buffer(alloc_range(base_offset, place.layout.size))?
                     else {
@@ -539,6 +548,7 @@ fn buffered_atomic_read(
                         &this.machine.threads,
                         atomic == AtomicReadOrd::SeqCst,
                         &mut *rng,
+                        &mut schedule,
                         validate,
                     )?;
                     if global.track_outdated_loads && recency == LoadRecency::Outdated {
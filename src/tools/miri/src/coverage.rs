@@ -0,0 +1,227 @@
+//! Recording which parts of the program were executed, and writing that out as an lcov tracefile.
+//!
+//! Miri counts how often every basic block of a user-relevant function was executed. When the body
+//! carries coverage mappings (i.e., the program was built with `-Cinstrument-coverage`), the lines
+//! and branches are derived from those mappings, just like `llvm-cov` would do it for a compiled
+//! binary. Otherwise, every statement and terminator counts for the line it starts on.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_index::IndexVec;
+use rustc_middle::mir::coverage::{BasicCoverageBlock, CoverageKind, MappingKind};
+use rustc_middle::mir::{self, BasicBlock, StatementKind};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::source_map::original_sp;
+use rustc_span::{FileName, Span};
+
+use crate::*;
+
+/// The basic block counts of all functions that were executed.
+#[derive(Default, Debug)]
+pub struct Coverage {
+    blocks: FxHashMap<DefId, IndexVec<BasicBlock, u64>>,
+}
+
+impl Coverage {
+    /// Records that `block` of `instance` is about to finish executing.
+    pub fn record<'tcx>(
+        &mut self,
+        instance: ty::Instance<'tcx>,
+        body: &mir::Body<'tcx>,
+        block: BasicBlock,
+    ) {
+        // Shims and promoteds do not correspond to any code the user wrote.
+        if !matches!(instance.def, ty::InstanceKind::Item(_)) || body.source.promoted.is_some() {
+            return;
+        }
+        let counts = self
+            .blocks
+            .entry(instance.def_id())
+            .or_insert_with(|| IndexVec::from_elem(0, &body.basic_blocks));
+        counts[block] += 1;
+    }
+
+    /// Writes an lcov tracefile that lists every function of the local crate, as well as the
+    /// functions of other user-relevant crates that were executed.
+    pub fn write_lcov(&self, tcx: TyCtxt<'_>, path: &Path) -> std::io::Result<()> {
+        let mut def_ids: Vec<DefId> = tcx
+            .mir_keys(())
+            .iter()
+            .map(|def_id| def_id.to_def_id())
+            .filter(|&def_id| {
+                matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn | DefKind::Closure)
+                    && tcx.is_mir_available(def_id)
+            })
+            .collect();
+        def_ids.extend(self.blocks.keys().filter(|def_id| def_id.krate != LOCAL_CRATE));
+
+        let mut files = BTreeMap::<String, FileReport>::new();
+        for def_id in def_ids {
+            let body = tcx.optimized_mir(def_id);
+            let sm = tcx.sess.source_map();
+            if body.span.is_dummy() {
+                continue;
+            }
+            let file = sm.lookup_source_file(body.span.lo());
+            if !matches!(file.name, FileName::Real(_)) {
+                continue;
+            }
+            let report =
+                files.entry(sm.filename_for_diagnostics(&file.name).to_string()).or_default();
+            let counts = self.blocks.get(&def_id);
+            let count = |block: BasicBlock| counts.map_or(0, |counts| counts[block]);
+            let line = |span: Span| sm.lookup_char_pos(span.lo()).line;
+
+            report.functions.push(FunctionReport {
+                name: tcx.def_path_str(def_id),
+                line: line(body.span),
+                count: count(mir::START_BLOCK),
+            });
+            if let Some(info) = &body.function_coverage_info {
+                // Each coverage block is counted by the statement in its leader block.
+                let mut bcb_counts = FxHashMap::<BasicCoverageBlock, u64>::default();
+                for (block, data) in body.basic_blocks.iter_enumerated() {
+                    for statement in &data.statements {
+                        if let StatementKind::Coverage(CoverageKind::VirtualCounter { bcb }) =
+                            statement.kind
+                        {
+                            let bcb_count = bcb_counts.entry(bcb).or_default();
+                            *bcb_count = (*bcb_count).max(count(block));
+                        }
+                    }
+                }
+                let bcb_count = |bcb| bcb_counts.get(&bcb).copied().unwrap_or(0);
+                for (index, mapping) in info.mappings.iter().enumerate() {
+                    match mapping.kind {
+                        MappingKind::Code { bcb } => {
+                            let end = sm.lookup_char_pos(mapping.span.hi()).line;
+                            for line in line(mapping.span)..=end {
+                                report.add_line(line, bcb_count(bcb));
+                            }
+                        }
+                        MappingKind::Branch { true_bcb, false_bcb } =>
+                            report.branches.push(BranchReport {
+                                line: line(mapping.span),
+                                index,
+                                counts: [bcb_count(true_bcb), bcb_count(false_bcb)],
+                            }),
+                    }
+                }
+            } else {
+                for (block, data) in body.basic_blocks.iter_enumerated() {
+                    let spans = data
+                        .statements
+                        .iter()
+                        .map(|statement| statement.source_info.span)
+                        .chain(std::iter::once(data.terminator().source_info.span));
+                    for span in spans {
+                        // Code expanded from macros counts for the line of the macro invocation.
+                        let span = original_sp(span, body.span);
+                        if span.is_dummy() || !body.span.contains(span) {
+                            continue;
+                        }
+                        report.add_line(line(span), count(block));
+                    }
+                }
+            }
+        }
+        std::fs::write(path, render_lcov(tcx.crate_name(LOCAL_CRATE).as_str(), &files))
+    }
+}
+
+/// The coverage of a single source file.
+#[derive(Default, Debug)]
+struct FileReport {
+    functions: Vec<FunctionReport>,
+    /// How often each line was executed.
+    lines: BTreeMap<usize, u64>,
+    branches: Vec<BranchReport>,
+}
+
+impl FileReport {
+    /// A line counts as often as the most frequently executed code on it.
+    fn add_line(&mut self, line: usize, count: u64) {
+        let line = self.lines.entry(line).or_default();
+        *line = (*line).max(count);
+    }
+}
+
+#[derive(Debug)]
+struct FunctionReport {
+    name: String,
+    line: usize,
+    /// How often the function was called.
+    count: u64,
+}
+
+#[derive(Debug)]
+struct BranchReport {
+    line: usize,
+    /// Distinguishes branches that start on the same line.
+    index: usize,
+    /// How often the branch was taken, and how often it was not taken.
+    counts: [u64; 2],
+}
+
+/// Renders `files` in the lcov tracefile format, with `test_name` as the name of the test.
+fn render_lcov(test_name: &str, files: &BTreeMap<String, FileReport>) -> String {
+    let mut out = String::new();
+    for (file, report) in files {
+        writeln!(out, "TN:{test_name}").unwrap();
+        writeln!(out, "SF:{file}").unwrap();
+        for function in &report.functions {
+            writeln!(out, "FN:{},{}", function.line, function.name).unwrap();
+        }
+        for function in &report.functions {
+            writeln!(out, "FNDA:{},{}", function.count, function.name).unwrap();
+        }
+        writeln!(out, "FNF:{}", report.functions.len()).unwrap();
+        writeln!(out, "FNH:{}", report.functions.iter().filter(|f| f.count > 0).count()).unwrap();
+        for branch in &report.branches {
+            for (taken, count) in branch.counts.iter().enumerate() {
+                writeln!(out, "BRDA:{},{},{taken},{count}", branch.line, branch.index).unwrap();
+            }
+        }
+        if !report.branches.is_empty() {
+            writeln!(out, "BRF:{}", 2 * report.branches.len()).unwrap();
+            let hit = report.branches.iter().flat_map(|b| b.counts).filter(|&c| c > 0).count();
+            writeln!(out, "BRH:{hit}").unwrap();
+        }
+        for (line, count) in &report.lines {
+            writeln!(out, "DA:{line},{count}").unwrap();
+        }
+        writeln!(out, "LF:{}", report.lines.len()).unwrap();
+        writeln!(out, "LH:{}", report.lines.values().filter(|&&c| c > 0).count()).unwrap();
+        writeln!(out, "end_of_record").unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lcov() {
+        let mut report = FileReport::default();
+        report.functions.push(FunctionReport { name: "main".into(), line: 1, count: 1 });
+        report.functions.push(FunctionReport { name: "unused".into(), line: 7, count: 0 });
+        report.add_line(2, 1);
+        report.add_line(2, 3);
+        report.add_line(3, 0);
+        report.add_line(1, 1);
+        report.branches.push(BranchReport { line: 2, index: 0, counts: [3, 0] });
+        let files = BTreeMap::from([("src/main.rs".to_owned(), report)]);
+        assert_eq!(
+            render_lcov("demo", &files),
+            "TN:demo\nSF:src/main.rs\nFN:1,main\nFN:7,unused\nFNDA:1,main\nFNDA:0,unused\n\
+             FNF:2\nFNH:1\nBRDA:2,0,0,3\nBRDA:2,0,1,0\nBRF:2\nBRH:1\n\
+             DA:1,1\nDA:2,3\nDA:3,0\nLF:3\nLH:2\nend_of_record\n"
+        );
+    }
+}
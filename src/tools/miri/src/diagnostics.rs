@@ -1,11 +1,15 @@
 use std::fmt::{self, Write};
+use std::fs::OpenOptions;
+use std::io::Write as _;
 use std::num::NonZero;
 use std::sync::Mutex;
 
 use rustc_abi::{Align, Size};
 use rustc_data_structures::fx::{FxBuildHasher, FxHashSet};
 use rustc_errors::{Diag, DiagMessage, Level};
+use rustc_hir::def_id::LOCAL_CRATE;
 use rustc_span::{DUMMY_SP, Span, SpanData, Symbol};
+use serde_json::json;
 
 use crate::borrow_tracker::stacked_borrows::diagnostics::TagHistory;
 use crate::borrow_tracker::tree_borrows::diagnostics as tree_diagnostics;
@@ -23,11 +27,13 @@ pub enum TerminationInfo {
     UnsupportedInIsolation(String),
     StackedBorrowsUb {
         msg: String,
+        alloc_id: AllocId,
         help: Vec<String>,
         history: Option<TagHistory>,
     },
     TreeBorrowsUb {
         title: String,
+        alloc_id: AllocId,
         details: Vec<String>,
         history: tree_diagnostics::HistoryData,
     },
@@ -258,6 +264,13 @@ pub fn report_result<'tcx>(
                 let mut any_pruned = false;
                 for (thread, stack) in ecx.machine.threads.all_blocked_stacks() {
                     let stacktrace = Frame::generate_stacktrace_from_stack(stack);
+                    report_json_finding(
+                        &ecx.machine,
+                        JsonFinding::new("deadlock"),
+                        "the evaluated program deadlocked",
+                        &stacktrace,
+                        Some(thread),
+                    );
                     let (stacktrace, was_pruned) = prune_stacktrace(stacktrace, &ecx.machine);
                     any_pruned |= was_pruned;
                     report_msg(
@@ -309,7 +322,7 @@ pub fn report_result<'tcx>(
                 }
                 helps
             },
-            TreeBorrowsUb { title: _, details, history } => {
+            TreeBorrowsUb { title: _, details, history, .. } => {
                 let mut helps = vec![
                     note!("this indicates a potential bug in the program: it performed an invalid operation, but the Tree Borrows rules it violated are still experimental"),
                     note!("see https://github.com/rust-lang/unsafe-code-guidelines/blob/master/wip/tree-borrows.md for further information"),
@@ -436,8 +449,11 @@ pub fn report_result<'tcx>(
         (Some(title), helps)
     };
 
-    let stacktrace = ecx.generate_stacktrace();
-    let (stacktrace, pruned) = prune_stacktrace(stacktrace, &ecx.machine);
+    // Classify the error for the JSON report before `format_interp_error` consumes it.
+    let finding = ecx.machine.report_json.is_some().then(|| JsonFinding::from_error(&res));
+
+    let full_stacktrace = ecx.generate_stacktrace();
+    let (stacktrace, pruned) = prune_stacktrace(full_stacktrace.clone(), &ecx.machine);
 
     // We want to dump the allocation if this is `InvalidUninitBytes`.
     // Since `format_interp_error` consumes `e`, we compute the outut early.
@@ -461,6 +477,16 @@ pub fn report_result<'tcx>(
     }
     write!(primary_msg, "{}", format_interp_error(ecx.tcx.dcx(), res)).unwrap();
 
+    if let Some(finding) = finding {
+        report_json_finding(
+            &ecx.machine,
+            finding,
+            &primary_msg,
+            &full_stacktrace,
+            Some(ecx.active_thread()),
+        );
+    }
+
     if labels.is_empty() {
         labels.push(format!(
             "{} occurred {}",
@@ -514,6 +540,15 @@ pub fn report_leaks<'tcx>(
             alloc.size().bytes(),
             alloc.align.bytes()
         );
+        let mut finding = JsonFinding::new("memory_leak");
+        finding.allocations.push(id);
+        report_json_finding(
+            &ecx.machine,
+            finding,
+            &title,
+            alloc.extra.backtrace.as_deref().unwrap_or_default(),
+            None,
+        );
         let Some(backtrace) = alloc.extra.backtrace else {
             ecx.tcx.dcx().err(title);
             continue;
@@ -539,6 +574,162 @@ pub fn report_leaks<'tcx>(
     }
 }
 
+/// A finding that is recorded in the `-Zmiri-report-json` file, in addition to the
+/// human-readable diagnostic.
+struct JsonFinding {
+    /// A stable, machine-readable name for the kind of error.
+    kind: &'static str,
+    /// The allocations the error is about.
+    allocations: Vec<AllocId>,
+    /// The borrow tracker history of the tag involved in the error.
+    history: Vec<(Option<SpanData>, String)>,
+}
+
+impl JsonFinding {
+    fn new(kind: &'static str) -> Self {
+        JsonFinding { kind, allocations: vec![], history: vec![] }
+    }
+
+    fn from_error(res: &InterpErrorInfo<'_>) -> Self {
+        use InterpErrorKind::*;
+        use UndefinedBehaviorInfo::*;
+
+        let mut allocations = vec![];
+        let mut history = vec![];
+        let kind = match res.kind() {
+            MachineStop(info) => {
+                let info =
+                    info.downcast_ref::<TerminationInfo>().expect("invalid MachineStop payload");
+                use TerminationInfo::*;
+                match info {
+                    Exit { .. } | GlobalDeadlock =>
+                        unreachable!("`report_result` handles exits and global deadlocks itself"),
+                    Abort(_) => "abort",
+                    Interrupted => "interrupted",
+                    UnsupportedInIsolation(_)
+                    | Int2PtrWithStrictProvenance
+                    | UnsupportedForeignItem(_) => "unsupported",
+                    StackedBorrowsUb { alloc_id, help, history: tag_history, .. } => {
+                        allocations.push(*alloc_id);
+                        history.extend(help.iter().map(|help| (None, help.clone())));
+                        if let Some(TagHistory { created, invalidated, protected }) = tag_history {
+                            history.push((Some(created.1), created.0.clone()));
+                            history.extend(
+                                invalidated
+                                    .iter()
+                                    .chain(protected)
+                                    .map(|(msg, span)| (Some(*span), msg.clone())),
+                            );
+                        }
+                        "stacked_borrows"
+                    }
+                    TreeBorrowsUb { alloc_id, details, history: tag_history, .. } => {
+                        allocations.push(*alloc_id);
+                        history.extend(details.iter().map(|detail| (None, detail.clone())));
+                        history.extend(tag_history.events.iter().cloned());
+                        "tree_borrows"
+                    }
+                    LocalDeadlock => "deadlock",
+                    MultipleSymbolDefinitions { .. } | SymbolShimClashing { .. } =>
+                        "symbol_conflict",
+                    DataRace { ptr, .. } => {
+                        allocations.push(ptr.provenance);
+                        "data_race"
+                    }
+                }
+            }
+            UndefinedBehavior(info) =>
+                match info {
+                    InvalidUninitBytes(access) => {
+                        allocations.extend(access.as_ref().map(|(alloc_id, _)| *alloc_id));
+                        "uninit_read"
+                    }
+                    PointerUseAfterFree(alloc_id, _) => {
+                        allocations.push(*alloc_id);
+                        "use_after_free"
+                    }
+                    PointerOutOfBounds { alloc_id, .. } => {
+                        allocations.push(*alloc_id);
+                        "out_of_bounds"
+                    }
+                    AlignmentCheckFailed { .. } => "misaligned_access",
+                    ValidationError(_) => "invalid_value",
+                    AbiMismatchArgument { .. } | AbiMismatchReturn { .. } => "abi_mismatch",
+                    _ => "undefined_behavior",
+                },
+            ResourceExhaustion(_) => "resource_exhaustion",
+            Unsupported(_) => "unsupported",
+            InvalidProgram(_) => "post_monomorphization_error",
+        };
+        JsonFinding { kind, allocations, history }
+    }
+}
+
+/// Append `finding` as a single line of JSON to the `-Zmiri-report-json` file, if that is enabled.
+/// `stacktrace` should not be pruned: the report always contains the full interpreter backtrace.
+fn report_json_finding<'tcx>(
+    machine: &MiriMachine<'tcx>,
+    finding: JsonFinding,
+    message: &str,
+    stacktrace: &[FrameInfo<'tcx>],
+    thread: Option<ThreadId>,
+) {
+    let Some(path) = &machine.report_json else {
+        return;
+    };
+    let tcx = machine.tcx;
+    let sm = tcx.sess.source_map();
+    let span_json = |span: Span| {
+        let (file, line, column, end_line, end_column) = sm.span_to_location_info(span);
+        file.map(|file| {
+            json!({
+                "file": sm.filename_for_diagnostics(&file.name).to_string(),
+                "line": line,
+                "column": column,
+                "end_line": end_line,
+                "end_column": end_column,
+            })
+        })
+    };
+    let report = json!({
+        "kind": finding.kind,
+        "message": message,
+        "crate": tcx.crate_name(LOCAL_CRATE).as_str(),
+        "thread": thread.map(|t| machine.threads.get_thread_display_name(t)),
+        "allocations": finding
+            .allocations
+            .iter()
+            .map(|alloc_id| format!("{alloc_id:?}"))
+            .collect::<Vec<_>>(),
+        "history": finding
+            .history
+            .iter()
+            .map(|(span, msg)| json!({
+                "message": msg,
+                "span": span.and_then(|span| span_json(span.span())),
+            }))
+            .collect::<Vec<_>>(),
+        "backtrace": stacktrace
+            .iter()
+            .map(|frame| json!({
+                "function": frame.instance.to_string(),
+                "span": span_json(frame.span),
+            }))
+            .collect::<Vec<_>>(),
+    });
+    // Each finding is written with a single `write` on a file opened for appending, so reports of
+    // several Miri processes that use the same file do not get interleaved.
+    let line = format!("{report}\n");
+    if let Err(err) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+    {
+        tcx.dcx().warn(format!("failed to write Miri report to `{}`: {err}", path.display()));
+    }
+}
+
 /// Report an error or note (depending on the `error` argument) with the given stacktrace.
 /// Also emits a full stacktrace of the interpreter stack.
 /// We want to present a multi-line span message for some errors. Diagnostics do not support this
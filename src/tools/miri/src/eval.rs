@@ -6,7 +6,7 @@
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::task::Poll;
-use std::{iter, thread};
+use std::{iter, mem, thread};
 
 use rustc_abi::ExternAbi;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
@@ -117,6 +117,14 @@ pub struct MiriConfig {
     pub short_fd_operations: bool,
     /// A list of crates that are considered user-relevant.
     pub user_relevant_crates: Vec<String>,
+    /// If `Some`, append every error Miri reports as a line of JSON to this file.
+    pub report_json: Option<PathBuf>,
+    /// If `Some`, write the schedule that the execution took to this file.
+    pub record_schedule: Option<PathBuf>,
+    /// If `Some`, force the execution to follow this schedule.
+    pub replay_schedule: Option<Schedule>,
+    /// If `Some`, write an lcov tracefile of the code that got executed to this file.
+    pub coverage: Option<PathBuf>,
 }
 
 impl Default for MiriConfig {
@@ -160,6 +168,10 @@ fn default() -> MiriConfig {
             float_rounding_error: FloatRoundingErrorMode::Random,
             short_fd_operations: true,
             user_relevant_crates: vec![],
+            report_json: None,
+            record_schedule: None,
+            replay_schedule: None,
+            coverage: None,
         }
     }
 }
@@ -461,6 +473,29 @@ pub fn eval_entry<'tcx>(
     config: &MiriConfig,
     genmc_ctx: Option<Rc<GenmcCtx>>,
 ) -> Result<(), NonZeroI32> {
+    let (res, schedule) = eval_entry_with_schedule(tcx, entry_id, entry_type, config, genmc_ctx);
+    if schedule.diverged() {
+        tcx.dcx().warn(
+            "the execution diverged from the replayed schedule, so it was not followed exactly",
+        );
+    }
+    if let Some(path) = &config.record_schedule
+        && let Some(schedule) = schedule.into_recorded()
+        && let Err(err) = std::fs::write(path, schedule.to_string())
+    {
+        tcx.dcx().warn(format!("failed to write schedule to `{}`: {err}", path.display()));
+    }
+    res
+}
+
+/// Like `eval_entry`, but also returns the state that recorded and replayed the schedule.
+pub(crate) fn eval_entry_with_schedule<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    entry_id: DefId,
+    entry_type: MiriEntryFnType,
+    config: &MiriConfig,
+    genmc_ctx: Option<Rc<GenmcCtx>>,
+) -> (Result<(), NonZeroI32>, ScheduleState) {
     // Copy setting before we move `config`.
     let ignore_leaks = config.ignore_leaks;
 
@@ -484,10 +519,27 @@ pub fn eval_entry<'tcx>(
     // indicate an error.
     let Err(res) = res.report_err();
 
+    let res = report_termination(&mut ecx, res, ignore_leaks);
+    if let Some(path) = &config.coverage
+        && let Some(coverage) = &ecx.machine.coverage
+        && let Err(err) = coverage.write_lcov(tcx, path)
+    {
+        tcx.dcx().warn(format!("failed to write coverage to `{}`: {err}", path.display()));
+    }
+    (res, mem::take(ecx.machine.schedule.get_mut()))
+}
+
+/// Reports the result of the execution, and checks for leaks if the program terminated normally.
+fn report_termination<'tcx>(
+    ecx: &mut MiriInterpCx<'tcx>,
+    res: InterpErrorInfo<'tcx>,
+    ignore_leaks: bool,
+) -> Result<(), NonZeroI32> {
+    let tcx = ecx.tcx.tcx;
     // Error reporting: if we survive all checks, we return the exit code the program gave us.
     'miri_error: {
         // Show diagnostic, if any.
-        let Some((return_code, leak_check)) = report_result(&ecx, res) else {
+        let Some((return_code, leak_check)) = report_result(ecx, res) else {
             break 'miri_error;
         };
 
@@ -505,7 +557,7 @@ pub fn eval_entry<'tcx>(
             info!("Additional static roots: {:?}", ecx.machine.static_roots);
             let leaks = ecx.take_leaked_allocations(|ecx| &ecx.machine.static_roots);
             if !leaks.is_empty() {
-                report_leaks(&ecx, leaks);
+                report_leaks(ecx, leaks);
                 tcx.dcx().note("set `MIRIFLAGS=-Zmiri-ignore-leaks` to disable this check");
                 // Ignore the provided return code - let the reported error
                 // determine the return code.
@@ -4,7 +4,7 @@
 use std::borrow::Cow;
 use std::cell::{Cell, RefCell};
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::{fmt, process};
 
@@ -530,6 +530,9 @@ pub struct MiriMachine<'tcx> {
     /// The list of all EpollEventInterest.
     pub(crate) epoll_interests: shims::EpollInterestTable,
 
+    /// The loopback network that sockets are attached to.
+    pub(crate) network: shims::LoopbackNetwork,
+
     /// This machine's monotone clock.
     pub(crate) monotonic_clock: MonotonicClock,
 
@@ -561,6 +564,12 @@ pub struct MiriMachine<'tcx> {
     /// Equivalent setting as RUST_BACKTRACE on encountering an error.
     pub(crate) backtrace_style: BacktraceStyle,
 
+    /// The file that errors are additionally reported to as JSON, if any.
+    pub(crate) report_json: Option<PathBuf>,
+
+    /// The basic block counts for `-Zmiri-coverage`, if enabled.
+    pub(crate) coverage: Option<Coverage>,
+
     /// Crates which are considered user-relevant for the purposes of error reporting.
     pub(crate) user_relevant_crates: Vec<CrateNum>,
 
@@ -571,6 +580,10 @@ pub struct MiriMachine<'tcx> {
     /// Needs to be queried by ptr_to_int, hence needs interior mutability.
     pub(crate) rng: RefCell<StdRng>,
 
+    /// Records and replays the scheduling choices made while resolving non-determinism.
+    /// Needs to be queried by weak memory loads, hence needs interior mutability.
+    pub(crate) schedule: RefCell<ScheduleState>,
+
     /// The allocator used for the machine's `AllocBytes` in native-libs mode.
     pub(crate) allocator: Option<Rc<RefCell<crate::alloc::isolated_alloc::IsolatedAlloc>>>,
 
@@ -749,6 +762,7 @@ pub(crate) fn new(
             validation: config.validation,
             fds: shims::FdTable::init(config.mute_stdout_stderr),
             epoll_interests: shims::EpollInterestTable::new(),
+            network: shims::LoopbackNetwork::new(),
             dirs: Default::default(),
             layouts,
             threads,
@@ -758,9 +772,12 @@ pub(crate) fn new(
             string_cache: Default::default(),
             exported_symbols_cache: FxHashMap::default(),
             backtrace_style: config.backtrace_style,
+            report_json: config.report_json.clone(),
+            coverage: config.coverage.is_some().then(Coverage::default),
             user_relevant_crates,
             extern_statics: FxHashMap::default(),
             rng: RefCell::new(rng),
+            schedule: RefCell::new(ScheduleState::new(config)),
             allocator: (!config.native_lib.is_empty())
                 .then(|| Rc::new(RefCell::new(crate::alloc::isolated_alloc::IsolatedAlloc::new()))),
             tracked_alloc_ids: config.tracked_alloc_ids.clone(),
@@ -1010,6 +1027,7 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             alloc_addresses,
             fds,
             epoll_interests:_,
+            network: _,
             tcx: _,
             isolated_op: _,
             validation: _,
@@ -1020,8 +1038,11 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             string_cache: _,
             exported_symbols_cache: _,
             backtrace_style: _,
+            report_json: _,
+            coverage: _,
             user_relevant_crates: _,
             rng: _,
+            schedule: _,
             allocator: _,
             tracked_alloc_ids: _,
             track_alloc_accesses: _,
@@ -1743,6 +1764,16 @@ fn stack_mut<'a>(
     fn before_terminator(ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<'tcx> {
         ecx.machine.basic_block_count += 1u64; // a u64 that is only incremented by 1 will "never" overflow
         ecx.machine.since_gc += 1;
+        if ecx.machine.coverage.is_some() {
+            let frame = ecx.frame();
+            let instance = frame.instance();
+            if let Either::Left(loc) = frame.current_loc()
+                && ecx.machine.is_local(instance)
+            {
+                let body = frame.body();
+                ecx.machine.coverage.as_mut().unwrap().record(instance, body, loc.block);
+            }
+        }
         // Possibly report our progress. This will point at the terminator we are about to execute.
         if let Some(report_progress) = ecx.machine.report_progress {
             if ecx.machine.basic_block_count.is_multiple_of(u64::from(report_progress)) {
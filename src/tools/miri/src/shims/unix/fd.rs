@@ -215,6 +215,42 @@ fn fcntl(
         }
     }
 
+    fn ioctl(
+        &mut self,
+        fd_num: &OpTy<'tcx>,
+        cmd: &OpTy<'tcx>,
+        varargs: &[OpTy<'tcx>],
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(fd_num)?.to_i32()?;
+        // The type of `cmd` differs between targets and libcs.
+        let cmd_size = cmd.layout.size;
+        let cmd = this.read_scalar(cmd)?.to_uint(cmd_size)?;
+        let fioclex = this.eval_libc("FIOCLEX").to_bits(cmd_size)?;
+        let fionbio = this.eval_libc("FIONBIO").to_bits(cmd_size)?;
+
+        let Some(fd) = this.machine.fds.get(fd_num) else {
+            return this.set_last_error_and_return_i32(LibcError("EBADF"));
+        };
+
+        if cmd == fioclex {
+            // Since we don't support `exec`, this is a NOP.
+            interp_ok(Scalar::from_i32(0))
+        } else if cmd == fionbio {
+            let [arg] = check_min_vararg_count("ioctl(fd, FIONBIO, ...)", varargs)?;
+            let nonblocking = this.deref_pointer_as(arg, this.machine.layouts.i32)?;
+            let nonblocking = this.read_scalar(&nonblocking)?.to_i32()? != 0;
+            // Toggle `O_NONBLOCK` in the file status flags, like `fcntl(F_SETFL)` would.
+            let o_nonblock = this.eval_libc_i32("O_NONBLOCK");
+            let flags = fd.get_flags(this)?.to_i32()?;
+            let flags = if nonblocking { flags | o_nonblock } else { flags & !o_nonblock };
+            fd.set_flags(flags, this)
+        } else {
+            throw_unsup_format!("ioctl: unsupported command {cmd:#x}");
+        }
+    }
+
     fn close(&mut self, fd_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let this = self.eval_context_mut();
 
@@ -255,6 +255,12 @@ fn emulate_foreign_item_inner(
                 let result = this.fcntl(fd_num, cmd, varargs)?;
                 this.write_scalar(result, dest)?;
             }
+            "ioctl" => {
+                let ([fd_num, cmd], varargs) =
+                    this.check_shim_sig_variadic_lenient(abi, CanonAbi::C, link_name, args)?;
+                let result = this.ioctl(fd_num, cmd, varargs)?;
+                this.write_scalar(result, dest)?;
+            }
             "dup" => {
                 let [old_fd] = this.check_shim_sig(
                     shim_sig!(extern "C" fn(i32) -> i32),
@@ -539,6 +545,165 @@ fn emulate_foreign_item_inner(
                 this.write_scalar(result, dest)?;
             }
 
+            // Sockets
+            "socket" => {
+                let [domain, type_, protocol] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.socket(domain, type_, protocol)?;
+                this.write_scalar(result, dest)?;
+            }
+            "bind" => {
+                let [socket, address, address_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *const _, libc::socklen_t) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.bind(socket, address, address_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "listen" => {
+                let [socket, backlog] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.listen(socket, backlog)?;
+                this.write_scalar(result, dest)?;
+            }
+            "accept" => {
+                let [socket, address, address_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.accept4(socket, address, address_len, /* flags */ None, dest)?;
+            }
+            "accept4" => {
+                // Currently this function does not exist on all Unixes, e.g. on macOS.
+                this.check_target_os(&[Os::Linux, Os::Android, Os::FreeBsd], link_name)?;
+                let [socket, address, address_len, flags] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.accept4(socket, address, address_len, Some(flags), dest)?;
+            }
+            "connect" => {
+                let [socket, address, address_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *const _, libc::socklen_t) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.connect(socket, address, address_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getsockname" => {
+                let [socket, address, address_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.getsockname(socket, address, address_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getpeername" => {
+                let [socket, address, address_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.getpeername(socket, address, address_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "send" => {
+                let [socket, buf, len, flags] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *const _, usize, i32) -> isize),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.sendto(socket, buf, len, flags, /* dest_addr */ None, dest)?;
+            }
+            "sendto" => {
+                let [socket, buf, len, flags, dest_addr, dest_len] = this.check_shim_sig(
+                    shim_sig!(
+                        extern "C" fn(
+                            i32,
+                            *const _,
+                            usize,
+                            i32,
+                            *const _,
+                            libc::socklen_t,
+                        ) -> isize
+                    ),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.sendto(socket, buf, len, flags, Some((dest_addr, dest_len)), dest)?;
+            }
+            "recv" => {
+                let [socket, buf, len, flags] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, usize, i32) -> isize),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.recvfrom(socket, buf, len, flags, /* src_addr */ None, dest)?;
+            }
+            "recvfrom" => {
+                let [socket, buf, len, flags, src_addr, src_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, *mut _, usize, i32, *mut _, *mut _) -> isize),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                this.recvfrom(socket, buf, len, flags, Some((src_addr, src_len)), dest)?;
+            }
+            "shutdown" => {
+                let [socket, how] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result = this.shutdown(socket, how)?;
+                this.write_scalar(result, dest)?;
+            }
+            "setsockopt" => {
+                let [socket, level, option_name, option_value, option_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32, i32, *const _, libc::socklen_t) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result =
+                    this.setsockopt(socket, level, option_name, option_value, option_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getsockopt" => {
+                let [socket, level, option_name, option_value, option_len] = this.check_shim_sig(
+                    shim_sig!(extern "C" fn(i32, i32, i32, *mut _, *mut _) -> i32),
+                    link_name,
+                    abi,
+                    args,
+                )?;
+                let result =
+                    this.getsockopt(socket, level, option_name, option_value, option_len)?;
+                this.write_scalar(result, dest)?;
+            }
+
             // Time
             "gettimeofday" => {
                 let [tv, tz] = this.check_shim_sig(
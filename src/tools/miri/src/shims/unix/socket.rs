@@ -0,0 +1,1562 @@
+//! This implements `AF_INET` and `AF_INET6` sockets. They do not correspond to anything on the
+//! host system: all sockets are attached to a loopback network that exists only inside Miri,
+//! so the program can talk to itself over `127.0.0.1` and `::1`, but not to anyone else.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::{io, iter};
+
+use rustc_abi::Size;
+use rustc_target::spec::Os;
+
+use crate::concurrency::VClock;
+use crate::shims::files::{
+    EvalContextExt as _, FdId, FileDescription, FileDescriptionExt as _, FileDescriptionRef,
+    WeakFileDescriptionRef,
+};
+use crate::shims::unix::UnixFileDescription;
+use crate::shims::unix::linux_like::epoll::{EpollEvents, EvalContextExt as _};
+use crate::*;
+
+/// The maximum number of bytes that can be waiting to be read from a socket.
+/// Like for socketpairs, this number is arbitrary as the value can always
+/// be configured in the real system.
+const MAX_SOCKET_BUFFER_CAPACITY: usize = 0x34000;
+
+/// The range of ports that sockets are bound to if the program does not choose a port itself.
+/// This is the default range used by Linux.
+const EPHEMERAL_PORT_START: u16 = 32768;
+const EPHEMERAL_PORT_END: u16 = 60999;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AddressFamily {
+    /// `AF_INET`
+    Ipv4,
+    /// `AF_INET6`
+    Ipv6,
+}
+
+impl AddressFamily {
+    fn unspecified(self) -> IpAddr {
+        match self {
+            AddressFamily::Ipv4 => Ipv4Addr::UNSPECIFIED.into(),
+            AddressFamily::Ipv6 => Ipv6Addr::UNSPECIFIED.into(),
+        }
+    }
+
+    fn contains(self, addr: SocketAddr) -> bool {
+        match self {
+            AddressFamily::Ipv4 => addr.is_ipv4(),
+            AddressFamily::Ipv6 => addr.is_ipv6(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SocketKind {
+    /// `SOCK_STREAM`, i.e. TCP.
+    Stream,
+    /// `SOCK_DGRAM`, i.e. UDP.
+    Datagram,
+}
+
+/// An `AF_INET` or `AF_INET6` socket.
+#[derive(Debug)]
+struct Socket {
+    family: AddressFamily,
+    kind: SocketKind,
+    /// The address this socket is bound to, or `None` if it is not bound yet.
+    local_addr: Cell<Option<SocketAddr>>,
+    /// The address of the socket at the other end of the connection, or `None` if this
+    /// socket is not connected.
+    peer_addr: Cell<Option<SocketAddr>>,
+    state: RefCell<SocketState>,
+    /// The value of the `TCP_NODELAY` option. It has no effect, since Miri never delays
+    /// sending data, but the program can read it back.
+    nodelay: Cell<bool>,
+    /// A list of thread ids blocked because there was nothing to read or accept.
+    blocked_read_tid: RefCell<Vec<ThreadId>>,
+    /// A list of thread ids blocked because the peer's buffer was full.
+    blocked_write_tid: RefCell<Vec<ThreadId>>,
+    /// Whether this fd is non-blocking or not.
+    is_nonblock: Cell<bool>,
+}
+
+#[derive(Debug)]
+enum SocketState {
+    /// A stream socket that has neither been connected nor started listening.
+    Unconnected,
+    /// A stream socket that accepts connections.
+    Listening {
+        /// Connections that have been established but not accepted yet, together with the
+        /// clock of the thread that established them.
+        backlog: VecDeque<(FileDescriptionRef<Socket>, VClock)>,
+    },
+    /// A stream socket with an established connection.
+    Connected(Connection),
+    /// A datagram socket. These can always send and receive, whether they are connected or not.
+    Datagram {
+        /// The datagrams that have been received but not read yet.
+        queue: VecDeque<Datagram>,
+    },
+}
+
+#[derive(Debug)]
+struct Connection {
+    /// The socket at the other end of the connection, which holds the buffer we are writing to.
+    /// This is a weak reference because the other side may be closed before us.
+    peer: WeakFileDescriptionRef<Socket>,
+    /// The data that has been sent to us but not read yet.
+    readbuf: VecDeque<u8>,
+    /// The clock of the last thread that wrote to `readbuf`.
+    clock: VClock,
+    /// Whether the reading half of this connection has been shut down.
+    read_shutdown: bool,
+    /// Whether the writing half of this connection has been shut down.
+    write_shutdown: bool,
+}
+
+impl Connection {
+    fn new(peer: WeakFileDescriptionRef<Socket>) -> Self {
+        Connection {
+            peer,
+            readbuf: VecDeque::new(),
+            clock: VClock::default(),
+            read_shutdown: false,
+            write_shutdown: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Datagram {
+    /// The address of the socket that sent this datagram.
+    from: SocketAddr,
+    data: Vec<u8>,
+    /// The clock of the thread that sent this datagram.
+    clock: VClock,
+}
+
+/// Where to store the address of a peer, as passed to `accept` and `recvfrom`.
+#[derive(Debug, Clone, Copy)]
+struct AddrOut {
+    addr: Pointer,
+    addrlen: Pointer,
+}
+
+impl VisitProvenance for AddrOut {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        self.addr.visit_provenance(visit);
+        self.addrlen.visit_provenance(visit);
+    }
+}
+
+impl Socket {
+    fn new(family: AddressFamily, kind: SocketKind, is_nonblock: bool) -> Self {
+        Socket {
+            family,
+            kind,
+            local_addr: Cell::new(None),
+            peer_addr: Cell::new(None),
+            state: RefCell::new(match kind {
+                SocketKind::Stream => SocketState::Unconnected,
+                SocketKind::Datagram => SocketState::Datagram { queue: VecDeque::new() },
+            }),
+            nodelay: Cell::new(false),
+            blocked_read_tid: RefCell::new(Vec::new()),
+            blocked_write_tid: RefCell::new(Vec::new()),
+            is_nonblock: Cell::new(is_nonblock),
+        }
+    }
+
+    /// Whether this socket will not send any more data to its peer.
+    fn is_write_shutdown(&self) -> bool {
+        match &*self.state.borrow() {
+            SocketState::Connected(conn) => conn.write_shutdown,
+            _ => true,
+        }
+    }
+
+    /// The number of bytes that can still be written to this socket's read buffer.
+    fn available_space(&self) -> usize {
+        match &*self.state.borrow() {
+            SocketState::Connected(conn) =>
+                MAX_SOCKET_BUFFER_CAPACITY.strict_sub(conn.readbuf.len()),
+            _ => 0,
+        }
+    }
+}
+
+impl FileDescription for Socket {
+    fn name(&self) -> &'static str {
+        match self.kind {
+            SocketKind::Stream => "TCP socket",
+            SocketKind::Datagram => "UDP socket",
+        }
+    }
+
+    fn destroy<'tcx>(
+        self,
+        self_id: FdId,
+        communicate_allowed: bool,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<()>> {
+        if let Some(addr) = self.local_addr.get() {
+            ecx.machine.network.unbind(self.kind, addr, self_id);
+        }
+        match self.state.into_inner() {
+            SocketState::Listening { backlog } => {
+                // Connections that were never accepted are closed along with the listener.
+                for (socket, _clock) in backlog {
+                    socket.close_ref(communicate_allowed, ecx)?.unwrap();
+                }
+            }
+            SocketState::Connected(conn) =>
+                if let Some(peer) = conn.peer.upgrade() {
+                    // The peer's reads now return end-of-file and its writes fail, so wake up
+                    // everyone who is waiting for one of these.
+                    unblock_threads(ecx, &peer.blocked_read_tid)?;
+                    unblock_threads(ecx, &peer.blocked_write_tid)?;
+                    ecx.update_epoll_active_events(peer, /* force_edge */ false)?;
+                },
+            SocketState::Unconnected | SocketState::Datagram { .. } => {}
+        }
+        interp_ok(Ok(()))
+    }
+
+    fn read<'tcx>(
+        self: FileDescriptionRef<Self>,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        let nonblock = self.is_nonblock.get();
+        match self.kind {
+            SocketKind::Stream => stream_recv(self, ptr, len, false, nonblock, ecx, finish),
+            SocketKind::Datagram =>
+                datagram_recv(self, ptr, len, false, nonblock, None, ecx, finish),
+        }
+    }
+
+    fn write<'tcx>(
+        self: FileDescriptionRef<Self>,
+        _communicate_allowed: bool,
+        ptr: Pointer,
+        len: usize,
+        ecx: &mut MiriInterpCx<'tcx>,
+        finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+    ) -> InterpResult<'tcx> {
+        match self.kind {
+            SocketKind::Stream => {
+                let nonblock = self.is_nonblock.get();
+                stream_send(self, ptr, len, nonblock, ecx, finish)
+            }
+            SocketKind::Datagram => {
+                let result = datagram_send(&self, ptr, len, None, ecx)?;
+                finish.call(ecx, result)
+            }
+        }
+    }
+
+    fn short_fd_operations(&self) -> bool {
+        // A datagram is always read and written as a whole, so splitting up an access would
+        // change its meaning.
+        matches!(self.kind, SocketKind::Stream)
+    }
+
+    fn as_unix<'tcx>(&self, _ecx: &MiriInterpCx<'tcx>) -> &dyn UnixFileDescription {
+        self
+    }
+
+    fn get_flags<'tcx>(&self, ecx: &mut MiriInterpCx<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let mut flags = ecx.eval_libc_i32("O_RDWR");
+        if self.is_nonblock.get() {
+            flags |= ecx.eval_libc_i32("O_NONBLOCK");
+        }
+        interp_ok(Scalar::from_i32(flags))
+    }
+
+    fn set_flags<'tcx>(
+        &self,
+        mut flag: i32,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let o_nonblock = ecx.eval_libc_i32("O_NONBLOCK");
+        let o_rdonly = ecx.eval_libc_i32("O_RDONLY");
+        let o_wronly = ecx.eval_libc_i32("O_WRONLY");
+        let o_rdwr = ecx.eval_libc_i32("O_RDWR");
+
+        // O_NONBLOCK flag can be set / unset by user.
+        self.is_nonblock.set(flag & o_nonblock == o_nonblock);
+        flag &= !o_nonblock;
+
+        // Ignore all file access mode flags.
+        flag &= !(o_rdonly | o_wronly | o_rdwr);
+
+        // Throw error if there is any unsupported flag.
+        if flag != 0 {
+            throw_unsup_format!("fcntl: only O_NONBLOCK is supported for F_SETFL on sockets")
+        }
+
+        interp_ok(Scalar::from_i32(0))
+    }
+}
+
+impl UnixFileDescription for Socket {
+    fn epoll_active_events<'tcx>(&self) -> InterpResult<'tcx, EpollEvents> {
+        let mut events = EpollEvents::new();
+        match &*self.state.borrow() {
+            SocketState::Unconnected => {
+                // Like Linux, report a socket that is not connected to anything as hung up.
+                events.epollhup = true;
+            }
+            SocketState::Listening { backlog } => {
+                // A listening socket is readable when `accept` would not block.
+                events.epollin = !backlog.is_empty();
+            }
+            SocketState::Connected(conn) => {
+                let peer = conn.peer.upgrade();
+                // Once no more data can arrive, reads return end-of-file instead of blocking.
+                let read_closed =
+                    conn.read_shutdown || peer.as_ref().is_none_or(|peer| peer.is_write_shutdown());
+                // Once we cannot send anymore, writes fail instead of blocking.
+                let write_closed = conn.write_shutdown || peer.is_none();
+                events.epollin = read_closed || !conn.readbuf.is_empty();
+                events.epollout =
+                    write_closed || peer.is_some_and(|peer| peer.available_space() != 0);
+                events.epollrdhup = read_closed;
+                // Like on Linux, the peer closing the connection is not enough for a hangup;
+                // we also need to have shut down writing ourselves.
+                events.epollhup = read_closed && conn.write_shutdown;
+            }
+            SocketState::Datagram { queue } => {
+                events.epollin = !queue.is_empty();
+                // Sending a datagram never blocks.
+                events.epollout = true;
+            }
+        }
+        interp_ok(events)
+    }
+}
+
+/// The loopback network that all `AF_INET` and `AF_INET6` sockets are attached to. It keeps
+/// track of which socket is bound to which address.
+#[derive(Debug)]
+pub struct LoopbackNetwork {
+    bound: BTreeMap<(SocketKind, SocketAddr), (FdId, WeakFileDescriptionRef<Socket>)>,
+    /// The ephemeral port to try next when a socket needs to be bound to any port.
+    next_ephemeral_port: u16,
+}
+
+impl LoopbackNetwork {
+    pub fn new() -> Self {
+        LoopbackNetwork { bound: BTreeMap::new(), next_ephemeral_port: EPHEMERAL_PORT_START }
+    }
+
+    /// Whether binding a socket of the given kind to `addr` conflicts with an existing binding.
+    fn is_in_use(&self, kind: SocketKind, addr: SocketAddr) -> bool {
+        self.bound.keys().any(|&(bound_kind, bound_addr)| {
+            bound_kind == kind
+                && bound_addr.port() == addr.port()
+                && bound_addr.is_ipv4() == addr.is_ipv4()
+                && (bound_addr.ip() == addr.ip()
+                    || bound_addr.ip().is_unspecified()
+                    || addr.ip().is_unspecified())
+        })
+    }
+
+    /// Binds `socket` to `addr`, picking an ephemeral port if the port of `addr` is 0.
+    fn bind(
+        &mut self,
+        socket: &FileDescriptionRef<Socket>,
+        mut addr: SocketAddr,
+    ) -> Result<SocketAddr, IoError> {
+        if addr.port() == 0 {
+            let ports = EPHEMERAL_PORT_END - EPHEMERAL_PORT_START + 1;
+            let port = (0..ports).find_map(|_| {
+                let port = self.next_ephemeral_port;
+                self.next_ephemeral_port =
+                    if port == EPHEMERAL_PORT_END { EPHEMERAL_PORT_START } else { port + 1 };
+                let candidate = SocketAddr::new(addr.ip(), port);
+                (!self.is_in_use(socket.kind, candidate)).then_some(port)
+            });
+            let Some(port) = port else {
+                return Err(ErrorKind::AddrInUse.into());
+            };
+            addr.set_port(port);
+        } else if self.is_in_use(socket.kind, addr) {
+            return Err(ErrorKind::AddrInUse.into());
+        }
+        self.bound
+            .insert((socket.kind, addr), (socket.id(), FileDescriptionRef::downgrade(socket)));
+        socket.local_addr.set(Some(addr));
+        Ok(addr)
+    }
+
+    /// Returns the local address of `socket`, binding it to an ephemeral port first if it is
+    /// not bound yet.
+    fn local_addr_or_bind(
+        &mut self,
+        socket: &FileDescriptionRef<Socket>,
+    ) -> Result<SocketAddr, IoError> {
+        match socket.local_addr.get() {
+            Some(addr) => Ok(addr),
+            None => self.bind(socket, SocketAddr::new(socket.family.unspecified(), 0)),
+        }
+    }
+
+    /// Moves the binding of `socket` from the unspecified address to `ip`. This happens when
+    /// the socket gets connected, since the connection determines its local address.
+    fn set_local_ip(&mut self, socket: &FileDescriptionRef<Socket>, ip: IpAddr) {
+        let old_addr = socket.local_addr.get().unwrap();
+        assert!(old_addr.ip().is_unspecified());
+        let new_addr = SocketAddr::new(ip, old_addr.port());
+        // Nothing else can be bound to `new_addr`, since our binding conflicts with it.
+        let binding = self.bound.remove(&(socket.kind, old_addr)).unwrap();
+        self.bound.insert((socket.kind, new_addr), binding);
+        socket.local_addr.set(Some(new_addr));
+    }
+
+    /// Removes the binding of the socket with the given id. Sockets created by `accept` share
+    /// the address of their listener without being bound to it, so we need to check the id.
+    fn unbind(&mut self, kind: SocketKind, addr: SocketAddr, id: FdId) {
+        if self.bound.get(&(kind, addr)).is_some_and(|&(bound_id, _)| bound_id == id) {
+            self.bound.remove(&(kind, addr));
+        }
+    }
+
+    /// Returns the socket that receives what is sent to `addr`.
+    fn lookup(&self, kind: SocketKind, addr: SocketAddr) -> Option<FileDescriptionRef<Socket>> {
+        let unspecified = match addr {
+            SocketAddr::V4(_) => IpAddr::from(Ipv4Addr::UNSPECIFIED),
+            SocketAddr::V6(_) => IpAddr::from(Ipv6Addr::UNSPECIFIED),
+        };
+        self.bound
+            .get(&(kind, addr))
+            .or_else(|| self.bound.get(&(kind, SocketAddr::new(unspecified, addr.port()))))
+            .and_then(|(_, socket)| socket.upgrade())
+    }
+}
+
+/// Checks that `addr` is on the loopback network, which is the only network Miri emulates,
+/// and returns the address that packets sent to `addr` arrive at.
+fn loopback_destination<'tcx>(name: &str, addr: SocketAddr) -> InterpResult<'tcx, SocketAddr> {
+    let ip = addr.ip();
+    if ip.is_unspecified() {
+        // Sending to the unspecified address sends to the local host.
+        let loopback = match ip {
+            IpAddr::V4(_) => IpAddr::from(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(_) => IpAddr::from(Ipv6Addr::LOCALHOST),
+        };
+        return interp_ok(SocketAddr::new(loopback, addr.port()));
+    }
+    if !ip.is_loopback() {
+        throw_unsup_format!(
+            "{name}: cannot reach {addr}, Miri only supports networking on the loopback interface"
+        );
+    }
+    interp_ok(addr)
+}
+
+/// The address that packets sent from a socket bound to `local` to `destination` come from.
+fn source_addr(local: SocketAddr, destination: SocketAddr) -> SocketAddr {
+    if local.ip().is_unspecified() {
+        SocketAddr::new(destination.ip(), local.port())
+    } else {
+        local
+    }
+}
+
+/// Unblocks all threads in `tids`, so they can check again whether their operation can proceed.
+fn unblock_threads<'tcx>(
+    ecx: &mut MiriInterpCx<'tcx>,
+    tids: &RefCell<Vec<ThreadId>>,
+) -> InterpResult<'tcx> {
+    let waiting_threads = std::mem::take(&mut *tids.borrow_mut());
+    // FIXME: We can randomize the order of unblocking.
+    for thread_id in waiting_threads {
+        ecx.unblock_thread(thread_id, BlockReason::Socket)?;
+    }
+    interp_ok(())
+}
+
+/// Returns the callback that stores the result of a `send` or `recv` in `dest`.
+fn size_or_error<'tcx>(dest: &MPlaceTy<'tcx>) -> DynMachineCallback<'tcx, Result<usize, IoError>> {
+    let dest = dest.clone();
+    callback!(
+        @capture<'tcx> {
+            dest: MPlaceTy<'tcx>,
+        }
+        |this, result: Result<usize, IoError>| {
+            match result {
+                Ok(size) => this.write_int(u64::try_from(size).unwrap(), &dest),
+                Err(e) => this.set_last_error_and_return(e, &dest),
+            }
+        }
+    )
+}
+
+/// Accepts a connection on a listening socket and stores the new file descriptor in `dest`.
+fn socket_accept<'tcx>(
+    self_ref: FileDescriptionRef<Socket>,
+    addr_out: Option<AddrOut>,
+    is_nonblock: bool,
+    dest: MPlaceTy<'tcx>,
+    ecx: &mut MiriInterpCx<'tcx>,
+) -> InterpResult<'tcx> {
+    let mut state = self_ref.state.borrow_mut();
+    let SocketState::Listening { backlog } = &mut *state else {
+        drop(state);
+        return ecx.set_last_error_and_return(LibcError("EINVAL"), &dest);
+    };
+    let Some((socket, clock)) = backlog.pop_front() else {
+        drop(state);
+        if self_ref.is_nonblock.get() {
+            return ecx.set_last_error_and_return(ErrorKind::WouldBlock, &dest);
+        }
+        self_ref.blocked_read_tid.borrow_mut().push(ecx.active_thread());
+        // Block the current thread until a connection arrives; only keep a weak ref for this.
+        let weak_self_ref = FileDescriptionRef::downgrade(&self_ref);
+        ecx.block_thread(
+            BlockReason::Socket,
+            None,
+            callback!(
+                @capture<'tcx> {
+                    weak_self_ref: WeakFileDescriptionRef<Socket>,
+                    addr_out: Option<AddrOut>,
+                    is_nonblock: bool,
+                    dest: MPlaceTy<'tcx>,
+                }
+                |this, unblock: UnblockKind| {
+                    assert_eq!(unblock, UnblockKind::Ready);
+                    // If we got unblocked, then a connecting socket successfully upgraded its
+                    // weak ref to us. That means we can also upgrade our weak ref.
+                    let self_ref = weak_self_ref.upgrade().unwrap();
+                    socket_accept(self_ref, addr_out, is_nonblock, dest, this)
+                }
+            ),
+        );
+        return interp_ok(());
+    };
+    drop(state);
+
+    // Synchronize with the thread that established the connection.
+    ecx.acquire_clock(&clock)?;
+    socket.is_nonblock.set(is_nonblock);
+    if let Some(addr_out) = addr_out {
+        ecx.write_socket_addr(socket.peer_addr.get().unwrap(), addr_out)?;
+    }
+    let fd = ecx.machine.fds.insert(socket);
+    // Notify epoll waiters: we might be no longer readable.
+    ecx.update_epoll_active_events(self_ref, /* force_edge */ false)?;
+    ecx.write_int(fd, &dest)
+}
+
+/// Writes to the peer of a connected stream socket and returns the written byte size.
+fn stream_send<'tcx>(
+    self_ref: FileDescriptionRef<Socket>,
+    ptr: Pointer,
+    len: usize,
+    nonblock: bool,
+    ecx: &mut MiriInterpCx<'tcx>,
+    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+) -> InterpResult<'tcx> {
+    let peer = match &*self_ref.state.borrow() {
+        SocketState::Connected(conn) if conn.write_shutdown => None,
+        SocketState::Connected(conn) => conn.peer.upgrade(),
+        _ => return finish.call(ecx, Err(LibcError("ENOTCONN"))),
+    };
+    let Some(peer) = peer else {
+        // Either we shut down writing, or the peer has been closed.
+        return finish.call(ecx, Err(ErrorKind::BrokenPipe.into()));
+    };
+
+    // Always succeed on write size 0.
+    if len == 0 {
+        return finish.call(ecx, Ok(0));
+    }
+
+    let available_space = peer.available_space();
+    if available_space == 0 {
+        if nonblock {
+            return finish.call(ecx, Err(ErrorKind::WouldBlock.into()));
+        }
+        self_ref.blocked_write_tid.borrow_mut().push(ecx.active_thread());
+        // Block the current thread until the peer reads; only keep a weak ref for this.
+        let weak_self_ref = FileDescriptionRef::downgrade(&self_ref);
+        ecx.block_thread(
+            BlockReason::Socket,
+            None,
+            callback!(
+                @capture<'tcx> {
+                    weak_self_ref: WeakFileDescriptionRef<Socket>,
+                    ptr: Pointer,
+                    len: usize,
+                    nonblock: bool,
+                    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+                }
+                |this, unblock: UnblockKind| {
+                    assert_eq!(unblock, UnblockKind::Ready);
+                    // If we got unblocked, then our peer successfully upgraded its weak
+                    // ref to us. That means we can also upgrade our weak ref.
+                    let self_ref = weak_self_ref.upgrade().unwrap();
+                    stream_send(self_ref, ptr, len, nonblock, this, finish)
+                }
+            ),
+        );
+        return interp_ok(());
+    }
+
+    let mut peer_state = peer.state.borrow_mut();
+    let SocketState::Connected(peer_conn) = &mut *peer_state else {
+        unreachable!("the peer of a connected socket is always connected")
+    };
+    // Remember this clock so `recv` can synchronize with us.
+    ecx.release_clock(|clock| {
+        peer_conn.clock.join(clock);
+    })?;
+    // Do full write / partial write based on the space available.
+    let write_size = len.min(available_space);
+    let actual_write_size = ecx.write_to_host(&mut peer_conn.readbuf, write_size, ptr)?.unwrap();
+    assert_eq!(actual_write_size, write_size);
+    drop(peer_state);
+
+    unblock_threads(ecx, &peer.blocked_read_tid)?;
+    // Notify epoll waiters: we might be no longer writable, peer might now be readable.
+    ecx.update_epoll_active_events(self_ref, /* force_edge */ false)?;
+    ecx.update_epoll_active_events(peer, /* force_edge */ true)?;
+
+    finish.call(ecx, Ok(write_size))
+}
+
+/// Reads from a connected stream socket and returns the number of bytes read.
+fn stream_recv<'tcx>(
+    self_ref: FileDescriptionRef<Socket>,
+    ptr: Pointer,
+    len: usize,
+    peek: bool,
+    nonblock: bool,
+    ecx: &mut MiriInterpCx<'tcx>,
+    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+) -> InterpResult<'tcx> {
+    let mut state = self_ref.state.borrow_mut();
+    let SocketState::Connected(conn) = &mut *state else {
+        drop(state);
+        return finish.call(ecx, Err(LibcError("ENOTCONN")));
+    };
+
+    // Always succeed on read size 0.
+    if len == 0 {
+        drop(state);
+        return finish.call(ecx, Ok(0));
+    }
+
+    if conn.readbuf.is_empty() {
+        let read_closed =
+            conn.read_shutdown || conn.peer.upgrade().is_none_or(|peer| peer.is_write_shutdown());
+        drop(state);
+        if read_closed {
+            // No more data will arrive. 0 bytes successfully read indicates end-of-file.
+            return finish.call(ecx, Ok(0));
+        }
+        if nonblock {
+            return finish.call(ecx, Err(ErrorKind::WouldBlock.into()));
+        }
+        self_ref.blocked_read_tid.borrow_mut().push(ecx.active_thread());
+        // Block the current thread until the peer writes; only keep a weak ref for this.
+        let weak_self_ref = FileDescriptionRef::downgrade(&self_ref);
+        ecx.block_thread(
+            BlockReason::Socket,
+            None,
+            callback!(
+                @capture<'tcx> {
+                    weak_self_ref: WeakFileDescriptionRef<Socket>,
+                    ptr: Pointer,
+                    len: usize,
+                    peek: bool,
+                    nonblock: bool,
+                    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+                }
+                |this, unblock: UnblockKind| {
+                    assert_eq!(unblock, UnblockKind::Ready);
+                    // If we got unblocked, then our peer successfully upgraded its weak
+                    // ref to us. That means we can also upgrade our weak ref.
+                    let self_ref = weak_self_ref.upgrade().unwrap();
+                    stream_recv(self_ref, ptr, len, peek, nonblock, this, finish)
+                }
+            ),
+        );
+        return interp_ok(());
+    }
+
+    // Synchronize with all previous writes to this buffer.
+    // FIXME: this over-synchronizes; a more precise approach would be to
+    // only sync with the writes whose data we will read.
+    ecx.acquire_clock(&conn.clock)?;
+    let read_size = if peek {
+        let read_size = len.min(conn.readbuf.len());
+        ecx.write_bytes_ptr(ptr, conn.readbuf.iter().take(read_size).copied())?;
+        read_size
+    } else {
+        // Conveniently, `read` exists on `VecDeque` and has exactly the desired behavior.
+        ecx.read_from_host(&mut conn.readbuf, len, ptr)?.unwrap()
+    };
+    let readbuf_now_empty = conn.readbuf.is_empty();
+    let peer = conn.peer.upgrade();
+    drop(state);
+
+    if !peek && let Some(peer) = peer {
+        // The peer may be able to write again.
+        unblock_threads(ecx, &peer.blocked_write_tid)?;
+        ecx.update_epoll_active_events(peer, /* force_edge */ readbuf_now_empty)?;
+    }
+    // Notify epoll waiters: we might be no longer readable.
+    ecx.update_epoll_active_events(self_ref, /* force_edge */ false)?;
+
+    finish.call(ecx, Ok(read_size))
+}
+
+/// Sends a datagram to `to`, or to the connected peer if that is `None`, and returns the
+/// number of bytes sent.
+fn datagram_send<'tcx>(
+    self_ref: &FileDescriptionRef<Socket>,
+    ptr: Pointer,
+    len: usize,
+    to: Option<SocketAddr>,
+    ecx: &mut MiriInterpCx<'tcx>,
+) -> InterpResult<'tcx, Result<usize, IoError>> {
+    let Some(to) = to.or(self_ref.peer_addr.get()) else {
+        return interp_ok(Err(LibcError("EDESTADDRREQ")));
+    };
+    // The largest payload that fits into a single IP packet.
+    let max_len = match self_ref.family {
+        AddressFamily::Ipv4 => 65507,
+        AddressFamily::Ipv6 => 65527,
+    };
+    if len > max_len {
+        return interp_ok(Err(LibcError("EMSGSIZE")));
+    }
+    let local_addr = match ecx.machine.network.local_addr_or_bind(self_ref) {
+        Ok(addr) => addr,
+        Err(e) => return interp_ok(Err(e)),
+    };
+    let from = source_addr(local_addr, to);
+
+    // UDP does not guarantee delivery, so datagrams that nobody can receive right now are
+    // silently dropped.
+    if let Some(receiver) = ecx.machine.network.lookup(SocketKind::Datagram, to)
+        && receiver.peer_addr.get().is_none_or(|peer| peer == from)
+    {
+        let data = ecx.read_bytes_ptr_strip_provenance(ptr, Size::from_bytes(len))?.to_vec();
+        let mut state = receiver.state.borrow_mut();
+        let SocketState::Datagram { queue } = &mut *state else {
+            unreachable!("only datagram sockets are bound as datagram sockets")
+        };
+        let queued = queue.iter().map(|datagram| datagram.data.len()).sum::<usize>();
+        if queued.strict_add(len) <= MAX_SOCKET_BUFFER_CAPACITY {
+            // Remember this clock so `recv` can synchronize with us.
+            let mut clock = VClock::default();
+            ecx.release_clock(|release_clock| clock.join(release_clock))?;
+            queue.push_back(Datagram { from, data, clock });
+            drop(state);
+
+            unblock_threads(ecx, &receiver.blocked_read_tid)?;
+            ecx.update_epoll_active_events(receiver, /* force_edge */ true)?;
+        }
+    }
+
+    interp_ok(Ok(len))
+}
+
+/// Reads the next datagram from a datagram socket and returns the number of bytes read. If the
+/// datagram is longer than `len`, the rest of it is discarded.
+fn datagram_recv<'tcx>(
+    self_ref: FileDescriptionRef<Socket>,
+    ptr: Pointer,
+    len: usize,
+    peek: bool,
+    nonblock: bool,
+    addr_out: Option<AddrOut>,
+    ecx: &mut MiriInterpCx<'tcx>,
+    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+) -> InterpResult<'tcx> {
+    let mut state = self_ref.state.borrow_mut();
+    let SocketState::Datagram { queue } = &mut *state else {
+        unreachable!("a datagram socket is always in the datagram state")
+    };
+
+    let Some(datagram) = queue.front() else {
+        drop(state);
+        if nonblock {
+            return finish.call(ecx, Err(ErrorKind::WouldBlock.into()));
+        }
+        self_ref.blocked_read_tid.borrow_mut().push(ecx.active_thread());
+        // Block the current thread until a datagram arrives; only keep a weak ref for this.
+        let weak_self_ref = FileDescriptionRef::downgrade(&self_ref);
+        ecx.block_thread(
+            BlockReason::Socket,
+            None,
+            callback!(
+                @capture<'tcx> {
+                    weak_self_ref: WeakFileDescriptionRef<Socket>,
+                    ptr: Pointer,
+                    len: usize,
+                    peek: bool,
+                    nonblock: bool,
+                    addr_out: Option<AddrOut>,
+                    finish: DynMachineCallback<'tcx, Result<usize, IoError>>,
+                }
+                |this, unblock: UnblockKind| {
+                    assert_eq!(unblock, UnblockKind::Ready);
+                    // If we got unblocked, then a sending socket successfully upgraded its
+                    // weak ref to us. That means we can also upgrade our weak ref.
+                    let self_ref = weak_self_ref.upgrade().unwrap();
+                    datagram_recv(self_ref, ptr, len, peek, nonblock, addr_out, this, finish)
+                }
+            ),
+        );
+        return interp_ok(());
+    };
+
+    // Synchronize with the thread that sent this datagram.
+    ecx.acquire_clock(&datagram.clock)?;
+    let read_size = len.min(datagram.data.len());
+    ecx.write_bytes_ptr(ptr, datagram.data[..read_size].iter().copied())?;
+    let from = datagram.from;
+    if !peek {
+        queue.pop_front();
+    }
+    drop(state);
+
+    if let Some(addr_out) = addr_out {
+        ecx.write_socket_addr(from, addr_out)?;
+    }
+    // Notify epoll waiters: we might be no longer readable.
+    ecx.update_epoll_active_events(self_ref, /* force_edge */ false)?;
+
+    finish.call(ecx, Ok(read_size))
+}
+
+impl<'tcx> EvalContextExtPriv<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextExtPriv<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Returns the socket behind the file descriptor `fd_num`, or sets the last error and
+    /// returns `None` if there is none.
+    fn get_socket(
+        &mut self,
+        fd_num: i32,
+    ) -> InterpResult<'tcx, Option<FileDescriptionRef<Socket>>> {
+        let this = self.eval_context_mut();
+
+        let Some(fd) = this.machine.fds.get(fd_num) else {
+            this.set_last_error(LibcError("EBADF"))?;
+            return interp_ok(None);
+        };
+        let Some(socket) = fd.downcast::<Socket>() else {
+            // FIXME: socketpairs are sockets as well, but we do not support this API on them.
+            this.set_last_error(LibcError("ENOTSOCK"))?;
+            return interp_ok(None);
+        };
+        interp_ok(Some(socket))
+    }
+
+    /// Reads a `sockaddr_in` or `sockaddr_in6` of `addrlen` bytes from `addr`.
+    fn read_socket_addr(
+        &self,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Result<SocketAddr, IoError>> {
+        let this = self.eval_context_ref();
+
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_scalar(addrlen)?.to_uint(addrlen.layout.size)?;
+
+        let sockaddr_layout = this.libc_ty_layout("sockaddr");
+        if addrlen < u128::from(sockaddr_layout.size.bytes()) {
+            return interp_ok(Err(LibcError("EINVAL")));
+        }
+        let family =
+            this.project_field_named(&this.ptr_to_mplace(addr, sockaddr_layout), "sa_family")?;
+        let family = this.read_scalar(&family)?.to_uint(family.layout.size)?;
+        let family = i32::try_from(family).unwrap();
+
+        if family == this.eval_libc_i32("AF_INET") {
+            let layout = this.libc_ty_layout("sockaddr_in");
+            if addrlen < u128::from(layout.size.bytes()) {
+                return interp_ok(Err(LibcError("EINVAL")));
+            }
+            let place = this.ptr_to_mplace(addr, layout);
+            // The port and the address are stored in network byte order.
+            let port = this.project_field_named(&place, "sin_port")?;
+            let port = this.read_bytes_ptr_strip_provenance(port.ptr(), Size::from_bytes(2))?;
+            let port = u16::from_be_bytes(port.try_into().unwrap());
+            let ip = this.project_field_named(&place, "sin_addr")?;
+            let ip = this.read_bytes_ptr_strip_provenance(ip.ptr(), Size::from_bytes(4))?;
+            let ip = Ipv4Addr::from(<[u8; 4]>::try_from(ip).unwrap());
+            interp_ok(Ok(SocketAddrV4::new(ip, port).into()))
+        } else if family == this.eval_libc_i32("AF_INET6") {
+            let layout = this.libc_ty_layout("sockaddr_in6");
+            if addrlen < u128::from(layout.size.bytes()) {
+                return interp_ok(Err(LibcError("EINVAL")));
+            }
+            let place = this.ptr_to_mplace(addr, layout);
+            // The port and the address are stored in network byte order.
+            let port = this.project_field_named(&place, "sin6_port")?;
+            let port = this.read_bytes_ptr_strip_provenance(port.ptr(), Size::from_bytes(2))?;
+            let port = u16::from_be_bytes(port.try_into().unwrap());
+            let ip = this.project_field_named(&place, "sin6_addr")?;
+            let ip = this.read_bytes_ptr_strip_provenance(ip.ptr(), Size::from_bytes(16))?;
+            let ip = Ipv6Addr::from(<[u8; 16]>::try_from(ip).unwrap());
+            let flowinfo = this.project_field_named(&place, "sin6_flowinfo")?;
+            let flowinfo = this.read_scalar(&flowinfo)?.to_u32()?;
+            let scope_id = this.project_field_named(&place, "sin6_scope_id")?;
+            let scope_id = this.read_scalar(&scope_id)?.to_u32()?;
+            interp_ok(Ok(SocketAddrV6::new(ip, port, flowinfo, scope_id).into()))
+        } else {
+            interp_ok(Err(LibcError("EAFNOSUPPORT")))
+        }
+    }
+
+    /// Stores `addr` as a `sockaddr_in` or `sockaddr_in6` in `out.addr`, and its size in
+    /// `out.addrlen`.
+    fn write_socket_addr(&mut self, addr: SocketAddr, out: AddrOut) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let addrlen = this.ptr_to_mplace(out.addrlen, this.libc_ty_layout("socklen_t"));
+        let capacity = this.read_scalar(&addrlen)?.to_uint(addrlen.layout.size)?;
+        let layout = this.libc_ty_layout(match addr {
+            SocketAddr::V4(_) => "sockaddr_in",
+            SocketAddr::V6(_) => "sockaddr_in6",
+        });
+        if capacity < u128::from(layout.size.bytes()) {
+            throw_unsup_format!(
+                "storing a socket address in a buffer that is too small for it is not supported"
+            );
+        }
+        let place = this.ptr_to_mplace(out.addr, layout);
+        // Zero the whole struct first, so padding fields like `sin_zero` are initialized.
+        this.write_bytes_ptr(out.addr, iter::repeat_n(0, layout.size.bytes_usize()))?;
+
+        // The port and the address are stored in network byte order.
+        match addr {
+            SocketAddr::V4(addr) => {
+                // Only BSD-like systems have a length field.
+                if let Some(len) = this.try_project_field_named(&place, "sin_len")? {
+                    this.write_int(layout.size.bytes(), &len)?;
+                }
+                let family = this.project_field_named(&place, "sin_family")?;
+                this.write_int(this.eval_libc_i32("AF_INET"), &family)?;
+                let port = this.project_field_named(&place, "sin_port")?;
+                this.write_bytes_ptr(port.ptr(), addr.port().to_be_bytes())?;
+                let ip = this.project_field_named(&place, "sin_addr")?;
+                this.write_bytes_ptr(ip.ptr(), addr.ip().octets())?;
+            }
+            SocketAddr::V6(addr) => {
+                // Only BSD-like systems have a length field.
+                if let Some(len) = this.try_project_field_named(&place, "sin6_len")? {
+                    this.write_int(layout.size.bytes(), &len)?;
+                }
+                let family = this.project_field_named(&place, "sin6_family")?;
+                this.write_int(this.eval_libc_i32("AF_INET6"), &family)?;
+                let port = this.project_field_named(&place, "sin6_port")?;
+                this.write_bytes_ptr(port.ptr(), addr.port().to_be_bytes())?;
+                let ip = this.project_field_named(&place, "sin6_addr")?;
+                this.write_bytes_ptr(ip.ptr(), addr.ip().octets())?;
+                let flowinfo = this.project_field_named(&place, "sin6_flowinfo")?;
+                this.write_int(addr.flowinfo(), &flowinfo)?;
+                let scope_id = this.project_field_named(&place, "sin6_scope_id")?;
+                this.write_int(addr.scope_id(), &scope_id)?;
+            }
+        }
+
+        this.write_int(layout.size.bytes(), &addrlen)
+    }
+
+    /// Interprets the `flags` argument of the `send` and `recv` families of functions and
+    /// returns whether `MSG_PEEK` and `MSG_DONTWAIT` are set.
+    fn read_msg_flags(&self, name: &str, mut flags: i32) -> InterpResult<'tcx, (bool, bool)> {
+        let this = self.eval_context_ref();
+
+        let msg_peek = this.eval_libc_i32("MSG_PEEK");
+        let msg_dontwait = this.eval_libc_i32("MSG_DONTWAIT");
+
+        // Interpret the flag. Every flag we recognize is "subtracted" from `flags`, so
+        // if there is anything left at the end, that's an unsupported flag.
+        let peek = flags & msg_peek == msg_peek;
+        flags &= !msg_peek;
+        let dontwait = flags & msg_dontwait == msg_dontwait;
+        flags &= !msg_dontwait;
+        if matches!(this.tcx.sess.target.os, Os::Linux | Os::Android | Os::FreeBsd) {
+            // Miri never raises `SIGPIPE`, so there is nothing to suppress.
+            let msg_nosignal = this.eval_libc_i32("MSG_NOSIGNAL");
+            flags &= !msg_nosignal;
+        }
+        if flags != 0 {
+            throw_unsup_format!("{name}: flags {flags:#x} are unsupported");
+        }
+        interp_ok((peek, dontwait))
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    fn socket(
+        &mut self,
+        domain: &OpTy<'tcx>,
+        type_: &OpTy<'tcx>,
+        protocol: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let domain = this.read_scalar(domain)?.to_i32()?;
+        let mut flags = this.read_scalar(type_)?.to_i32()?;
+        let protocol = this.read_scalar(protocol)?.to_i32()?;
+
+        let family = if domain == this.eval_libc_i32("AF_INET") {
+            AddressFamily::Ipv4
+        } else if domain == this.eval_libc_i32("AF_INET6") {
+            AddressFamily::Ipv6
+        } else {
+            throw_unsup_format!(
+                "socket: domain {domain:#x} is unsupported, only AF_INET and AF_INET6 are allowed"
+            );
+        };
+
+        let mut is_sock_nonblock = false;
+
+        // Interpret the flag. Every flag we recognize is "subtracted" from `flags`, so
+        // if there is anything left at the end, that's an unsupported flag.
+        if matches!(this.tcx.sess.target.os, Os::Linux | Os::Android | Os::FreeBsd) {
+            let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+            let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+            if flags & sock_nonblock == sock_nonblock {
+                is_sock_nonblock = true;
+                flags &= !sock_nonblock;
+            }
+            if flags & sock_cloexec == sock_cloexec {
+                flags &= !sock_cloexec;
+            }
+        }
+
+        let (kind, kind_protocol) = if flags == this.eval_libc_i32("SOCK_STREAM") {
+            (SocketKind::Stream, this.eval_libc_i32("IPPROTO_TCP"))
+        } else if flags == this.eval_libc_i32("SOCK_DGRAM") {
+            (SocketKind::Datagram, this.eval_libc_i32("IPPROTO_UDP"))
+        } else {
+            throw_unsup_format!(
+                "socket: type {flags:#x} is unsupported, only SOCK_STREAM, SOCK_DGRAM, \
+                 SOCK_CLOEXEC and SOCK_NONBLOCK are allowed"
+            );
+        };
+        if protocol != 0 && protocol != kind_protocol {
+            throw_unsup_format!(
+                "socket: protocol {protocol} is unsupported, only 0, IPPROTO_TCP and \
+                 IPPROTO_UDP are allowed"
+            );
+        }
+
+        let fd = this.machine.fds.insert_new(Socket::new(family, kind, is_sock_nonblock));
+        interp_ok(Scalar::from_i32(fd))
+    }
+
+    fn bind(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(socket)?.to_i32()?;
+        let Some(socket) = this.get_socket(fd_num)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+        let addr = match this.read_socket_addr(address, address_len)? {
+            Ok(addr) => addr,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        if !socket.family.contains(addr) {
+            return this.set_last_error_and_return_i32(LibcError("EAFNOSUPPORT"));
+        }
+        if socket.local_addr.get().is_some() {
+            // The socket is already bound.
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        }
+        if !addr.ip().is_loopback() && !addr.ip().is_unspecified() {
+            // The loopback interface is the only interface there is.
+            return this.set_last_error_and_return_i32(ErrorKind::AddrNotAvailable);
+        }
+
+        match this.machine.network.bind(&socket, addr) {
+            Ok(_) => interp_ok(Scalar::from_i32(0)),
+            Err(e) => this.set_last_error_and_return_i32(e),
+        }
+    }
+
+    fn listen(&mut self, socket: &OpTy<'tcx>, backlog: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(socket)?.to_i32()?;
+        // Connections are never refused because the backlog is full, so we ignore its size.
+        let _backlog = this.read_scalar(backlog)?.to_i32()?;
+        let Some(socket) = this.get_socket(fd_num)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+        if socket.kind != SocketKind::Stream {
+            return this.set_last_error_and_return_i32(LibcError("EOPNOTSUPP"));
+        }
+
+        match &*socket.state.borrow() {
+            SocketState::Unconnected => {}
+            // Listening again only changes the size of the backlog.
+            SocketState::Listening { .. } => return interp_ok(Scalar::from_i32(0)),
+            SocketState::Connected(_) | SocketState::Datagram { .. } =>
+                return this.set_last_error_and_return_i32(LibcError("EINVAL")),
+        }
+        // Like on Linux, a socket that is not bound yet is bound to an ephemeral port.
+        if let Err(e) = this.machine.network.local_addr_or_bind(&socket) {
+            return this.set_last_error_and_return_i32(e);
+        }
+        socket.state.replace(SocketState::Listening { backlog: VecDeque::new() });
+        // Notify epoll waiters: the socket is no longer hung up.
+        this.update_epoll_active_events(socket, /* force_edge */ false)?;
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    /// Implements `accept`, and `accept4` if `flags` is `Some`.
+    fn accept4(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+        flags: Option<&OpTy<'tcx>>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_pointer(address_len)?;
+        let mut flags = match flags {
+            Some(flags) => this.read_scalar(flags)?.to_i32()?,
+            None => 0,
+        };
+
+        let mut is_sock_nonblock = false;
+        if matches!(this.tcx.sess.target.os, Os::Linux | Os::Android | Os::FreeBsd) {
+            let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+            let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+            if flags & sock_nonblock == sock_nonblock {
+                is_sock_nonblock = true;
+                flags &= !sock_nonblock;
+            }
+            if flags & sock_cloexec == sock_cloexec {
+                flags &= !sock_cloexec;
+            }
+        }
+        if flags != 0 {
+            throw_unsup_format!(
+                "accept4: flags {flags:#x} are unsupported, only SOCK_CLOEXEC and SOCK_NONBLOCK \
+                 are allowed"
+            );
+        }
+
+        let Some(socket) = this.get_socket(fd_num)? else {
+            return this.write_int(-1, dest);
+        };
+        let addr_out = if this.ptr_is_null(address)? {
+            None
+        } else {
+            Some(AddrOut { addr: address, addrlen: address_len })
+        };
+        socket_accept(socket, addr_out, is_sock_nonblock, dest.clone(), this)
+    }
+
+    fn connect(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(socket)?.to_i32()?;
+        let Some(socket) = this.get_socket(fd_num)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+        let addr = match this.read_socket_addr(address, address_len)? {
+            Ok(addr) => addr,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        if !socket.family.contains(addr) {
+            return this.set_last_error_and_return_i32(LibcError("EAFNOSUPPORT"));
+        }
+        let addr = loopback_destination("connect", addr)?;
+
+        match &*socket.state.borrow() {
+            SocketState::Unconnected | SocketState::Datagram { .. } => {}
+            SocketState::Connected(_) =>
+                return this.set_last_error_and_return_i32(LibcError("EISCONN")),
+            SocketState::Listening { .. } =>
+                return this.set_last_error_and_return_i32(LibcError("EINVAL")),
+        }
+        let local_addr = match this.machine.network.local_addr_or_bind(&socket) {
+            Ok(addr) => addr,
+            Err(e) => return this.set_last_error_and_return_i32(e),
+        };
+        if local_addr.ip().is_unspecified() {
+            this.machine.network.set_local_ip(&socket, addr.ip());
+        }
+
+        if socket.kind == SocketKind::Datagram {
+            // Connecting a datagram socket only sets the default destination, and makes it
+            // ignore datagrams from everywhere else.
+            socket.peer_addr.set(Some(addr));
+            return interp_ok(Scalar::from_i32(0));
+        }
+        let Some(listener) =
+            this.machine.network.lookup(SocketKind::Stream, addr).filter(|listener| {
+                matches!(*listener.state.borrow(), SocketState::Listening { .. })
+            })
+        else {
+            return this.set_last_error_and_return_i32(ErrorKind::ConnectionRefused);
+        };
+
+        // The connection is established right away, without waiting for the listener to
+        // accept it. This is also what happens on real systems when the backlog is not full,
+        // so even non-blocking sockets never report `EINPROGRESS`.
+        let server =
+            this.machine.fds.new_ref(Socket::new(socket.family, SocketKind::Stream, false));
+        server.local_addr.set(Some(addr));
+        server.peer_addr.set(socket.local_addr.get());
+        server.state.replace(SocketState::Connected(Connection::new(
+            FileDescriptionRef::downgrade(&socket),
+        )));
+        socket.peer_addr.set(Some(addr));
+        socket.state.replace(SocketState::Connected(Connection::new(
+            FileDescriptionRef::downgrade(&server),
+        )));
+
+        // Remember this clock so `accept` can synchronize with us.
+        let mut clock = VClock::default();
+        this.release_clock(|release_clock| clock.join(release_clock))?;
+        match &mut *listener.state.borrow_mut() {
+            SocketState::Listening { backlog } => backlog.push_back((server, clock)),
+            _ => unreachable!(),
+        }
+
+        unblock_threads(this, &listener.blocked_read_tid)?;
+        // Notify epoll waiters: the listener is now readable, and we are now writable.
+        this.update_epoll_active_events(listener, /* force_edge */ true)?;
+        this.update_epoll_active_events(socket, /* force_edge */ false)?;
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn getsockname(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_pointer(address_len)?;
+        let Some(socket) = this.get_socket(fd_num)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+
+        // A socket that is not bound yet reports the unspecified address and port 0.
+        let addr =
+            socket.local_addr.get().unwrap_or(SocketAddr::new(socket.family.unspecified(), 0));
+        this.write_socket_addr(addr, AddrOut { addr: address, addrlen: address_len })?;
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn getpeername(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_pointer(address_len)?;
+        let Some(socket) = this.get_socket(fd_num)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+
+        let Some(addr) = socket.peer_addr.get() else {
+            return this.set_last_error_and_return_i32(ErrorKind::NotConnected);
+        };
+        this.write_socket_addr(addr, AddrOut { addr: address, addrlen: address_len })?;
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    /// Implements `send`, and `sendto` if `dest_addr` is `Some`.
+    fn sendto(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        dest_addr: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(socket)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let flags = this.read_scalar(flags)?.to_i32()?;
+
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(buf, Size::from_bytes(len), CheckInAllocMsg::MemoryAccess)?;
+        // We cap the number of sent bytes to the largest value that we are able to fit in both
+        // the host's and target's `isize`.
+        let len = len
+            .min(u64::try_from(this.target_isize_max()).unwrap())
+            .min(u64::try_from(isize::MAX).unwrap());
+        let len = usize::try_from(len).unwrap();
+
+        let (peek, dontwait) = this.read_msg_flags("send", flags)?;
+        if peek {
+            throw_unsup_format!("send: MSG_PEEK is unsupported");
+        }
+        let Some(socket) = this.get_socket(fd_num)? else {
+            return this.write_int(-1, dest);
+        };
+        let to = match dest_addr {
+            Some((addr, addrlen)) => {
+                let addr = match this.read_socket_addr(addr, addrlen)? {
+                    Ok(addr) => addr,
+                    Err(e) => return this.set_last_error_and_return(e, dest),
+                };
+                if !socket.family.contains(addr) {
+                    return this.set_last_error_and_return(LibcError("EAFNOSUPPORT"), dest);
+                }
+                Some(loopback_destination("sendto", addr)?)
+            }
+            None => None,
+        };
+
+        let nonblock = dontwait || socket.is_nonblock.get();
+        let finish = size_or_error(dest);
+        match socket.kind {
+            // Like on Linux, the destination is ignored for connected stream sockets.
+            SocketKind::Stream => stream_send(socket, buf, len, nonblock, this, finish),
+            SocketKind::Datagram => {
+                let result = datagram_send(&socket, buf, len, to, this)?;
+                finish.call(this, result)
+            }
+        }
+    }
+
+    /// Implements `recv`, and `recvfrom` if `src_addr` is `Some`.
+    fn recvfrom(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        src_addr: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(socket)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let flags = this.read_scalar(flags)?.to_i32()?;
+
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(buf, Size::from_bytes(len), CheckInAllocMsg::MemoryAccess)?;
+        // We cap the number of received bytes to the largest value that we are able to fit in
+        // both the host's and target's `isize`.
+        let len = len
+            .min(u64::try_from(this.target_isize_max()).unwrap())
+            .min(u64::try_from(isize::MAX).unwrap());
+        let len = usize::try_from(len).unwrap();
+
+        let (peek, dontwait) = this.read_msg_flags("recv", flags)?;
+        let Some(socket) = this.get_socket(fd_num)? else {
+            return this.write_int(-1, dest);
+        };
+        let addr_out = match src_addr {
+            Some((addr, addrlen)) => {
+                let addr = this.read_pointer(addr)?;
+                let addrlen = this.read_pointer(addrlen)?;
+                (!this.ptr_is_null(addr)?).then_some(AddrOut { addr, addrlen })
+            }
+            None => None,
+        };
+
+        let nonblock = dontwait || socket.is_nonblock.get();
+        let finish = size_or_error(dest);
+        match socket.kind {
+            // Like on Linux, no address is stored for connected stream sockets.
+            SocketKind::Stream => stream_recv(socket, buf, len, peek, nonblock, this, finish),
+            SocketKind::Datagram =>
+                datagram_recv(socket, buf, len, peek, nonblock, addr_out, this, finish),
+        }
+    }
+
+    fn shutdown(&mut self, socket: &OpTy<'tcx>, how: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(socket)?.to_i32()?;
+        let how = this.read_scalar(how)?.to_i32()?;
+        let Some(socket) = this.get_socket(fd_num)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+
+        let (read, write) = if how == this.eval_libc_i32("SHUT_RD") {
+            (true, false)
+        } else if how == this.eval_libc_i32("SHUT_WR") {
+            (false, true)
+        } else if how == this.eval_libc_i32("SHUT_RDWR") {
+            (true, true)
+        } else {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        };
+        if socket.kind == SocketKind::Datagram {
+            throw_unsup_format!("shutdown: datagram sockets are unsupported");
+        }
+
+        let mut state = socket.state.borrow_mut();
+        let SocketState::Connected(conn) = &mut *state else {
+            drop(state);
+            return this.set_last_error_and_return_i32(ErrorKind::NotConnected);
+        };
+        conn.read_shutdown |= read;
+        conn.write_shutdown |= write;
+        let peer = conn.peer.upgrade();
+        drop(state);
+
+        // Blocked reads may now return end-of-file, and blocked writes may now fail, on both
+        // ends of the connection.
+        unblock_threads(this, &socket.blocked_read_tid)?;
+        unblock_threads(this, &socket.blocked_write_tid)?;
+        if let Some(peer) = peer {
+            unblock_threads(this, &peer.blocked_read_tid)?;
+            unblock_threads(this, &peer.blocked_write_tid)?;
+            this.update_epoll_active_events(peer, /* force_edge */ false)?;
+        }
+        this.update_epoll_active_events(socket, /* force_edge */ false)?;
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn setsockopt(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        level: &OpTy<'tcx>,
+        option_name: &OpTy<'tcx>,
+        option_value: &OpTy<'tcx>,
+        option_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(socket)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let option_name = this.read_scalar(option_name)?.to_i32()?;
+        let option_value = this.read_pointer(option_value)?;
+        let option_len = this.read_scalar(option_len)?.to_uint(option_len.layout.size)?;
+        let Some(socket) = this.get_socket(fd_num)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+
+        // All options we support are `int`s.
+        if option_len < u128::from(this.machine.layouts.i32.size.bytes()) {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        }
+        let value = this.ptr_to_mplace(option_value, this.machine.layouts.i32);
+        let value = this.read_scalar(&value)?.to_i32()?;
+
+        let sol_socket = this.eval_libc_i32("SOL_SOCKET");
+        if level == sol_socket && option_name == this.eval_libc_i32("SO_REUSEADDR") {
+            // Miri does not keep closed connections around, so addresses are always reusable.
+        } else if level == sol_socket
+            && matches!(this.tcx.sess.target.os, Os::MacOs | Os::FreeBsd)
+            && option_name == this.eval_libc_i32("SO_NOSIGPIPE")
+        {
+            // Miri never raises `SIGPIPE`, so there is nothing to suppress.
+        } else if level == this.eval_libc_i32("IPPROTO_TCP")
+            && option_name == this.eval_libc_i32("TCP_NODELAY")
+        {
+            if socket.kind != SocketKind::Stream {
+                return this.set_last_error_and_return_i32(LibcError("EOPNOTSUPP"));
+            }
+            socket.nodelay.set(value != 0);
+        } else {
+            throw_unsup_format!(
+                "setsockopt: option {option_name:#x} at level {level:#x} is unsupported"
+            );
+        }
+
+        interp_ok(Scalar::from_i32(0))
+    }
+
+    fn getsockopt(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        level: &OpTy<'tcx>,
+        option_name: &OpTy<'tcx>,
+        option_value: &OpTy<'tcx>,
+        option_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd_num = this.read_scalar(socket)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let option_name = this.read_scalar(option_name)?.to_i32()?;
+        let option_value = this.read_pointer(option_value)?;
+        let option_len = this.deref_pointer_as(option_len, this.libc_ty_layout("socklen_t"))?;
+        let Some(socket) = this.get_socket(fd_num)? else {
+            return interp_ok(Scalar::from_i32(-1));
+        };
+
+        let sol_socket = this.eval_libc_i32("SOL_SOCKET");
+        let value = if level == sol_socket && option_name == this.eval_libc_i32("SO_ERROR") {
+            // Errors are always reported right away, so there is never a pending error.
+            0
+        } else if level == sol_socket && option_name == this.eval_libc_i32("SO_TYPE") {
+            match socket.kind {
+                SocketKind::Stream => this.eval_libc_i32("SOCK_STREAM"),
+                SocketKind::Datagram => this.eval_libc_i32("SOCK_DGRAM"),
+            }
+        } else if level == this.eval_libc_i32("IPPROTO_TCP")
+            && option_name == this.eval_libc_i32("TCP_NODELAY")
+        {
+            if socket.kind != SocketKind::Stream {
+                return this.set_last_error_and_return_i32(LibcError("EOPNOTSUPP"));
+            }
+            i32::from(socket.nodelay.get())
+        } else {
+            throw_unsup_format!(
+                "getsockopt: option {option_name:#x} at level {level:#x} is unsupported"
+            );
+        };
+
+        // All options we support are `int`s.
+        let int_size = this.machine.layouts.i32.size.bytes();
+        let capacity = this.read_scalar(&option_len)?.to_uint(option_len.layout.size)?;
+        if capacity < u128::from(int_size) {
+            return this.set_last_error_and_return_i32(LibcError("EINVAL"));
+        }
+        this.write_int(value, &this.ptr_to_mplace(option_value, this.machine.layouts.i32))?;
+        this.write_int(int_size, &option_len)?;
+
+        interp_ok(Scalar::from_i32(0))
+    }
+}
@@ -0,0 +1,20 @@
+//@output-file: json -Zmiri-report-json
+// Reporting errors as JSON does not change the human-readable output.
+// Using `miri_start` keeps the frames of the standard library's runtime out of the reported backtrace.
+#![no_main]
+
+#[rustfmt::skip] // rustfmt bug: https://github.com/rust-lang/rustfmt/issues/5391
+#[no_mangle]
+fn miri_start(_argc: isize, _argv: *const *const u8) -> isize {
+    let mut x = 15;
+    let xraw = &mut x as *mut _;
+    let xref = unsafe { &mut *xraw }; // derived from raw, so using raw is still ok...
+    callee(xraw);
+    let _val = *xref; // ...but any use of raw will invalidate our ref.
+    //~^ ERROR: /read access .* tag does not exist in the borrow stack/
+    0
+}
+
+fn callee(xraw: *mut i32) {
+    let _val = unsafe { *xraw };
+}
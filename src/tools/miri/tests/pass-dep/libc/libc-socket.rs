@@ -0,0 +1,176 @@
+//@only-target: linux android
+
+use std::mem;
+
+#[path = "../../utils/libc.rs"]
+mod libc_utils;
+use libc_utils::epoll::*;
+use libc_utils::*;
+
+fn main() {
+    test_tcp_addresses();
+    test_tcp_epoll();
+    test_recv_flags();
+}
+
+fn localhost_v4(port: u16) -> libc::sockaddr_in {
+    let mut addr: libc::sockaddr_in = unsafe { mem::zeroed() };
+    addr.sin_family = libc::AF_INET as libc::sa_family_t;
+    addr.sin_port = port.to_be();
+    addr.sin_addr = libc::in_addr { s_addr: u32::from_be_bytes([127, 0, 0, 1]).to_be() };
+    addr
+}
+
+/// Creates a listening socket on an ephemeral port and returns it with its address.
+fn listener() -> (i32, libc::sockaddr_in) {
+    unsafe {
+        let fd = errno_result(libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0)).unwrap();
+        let addr = localhost_v4(0);
+        errno_check(libc::bind(
+            fd,
+            (&raw const addr).cast(),
+            mem::size_of_val(&addr) as libc::socklen_t,
+        ));
+        errno_check(libc::listen(fd, 16));
+
+        let mut addr: libc::sockaddr_in = mem::zeroed();
+        let mut len = mem::size_of_val(&addr) as libc::socklen_t;
+        errno_check(libc::getsockname(fd, (&raw mut addr).cast(), &mut len));
+        assert_eq!(len as usize, mem::size_of_val(&addr));
+        assert_eq!(addr.sin_family, libc::AF_INET as libc::sa_family_t);
+        assert_ne!(addr.sin_port, 0);
+        (fd, addr)
+    }
+}
+
+fn connect(addr: &libc::sockaddr_in, flags: i32) -> i32 {
+    unsafe {
+        let fd = errno_result(libc::socket(libc::AF_INET, libc::SOCK_STREAM | flags, 0)).unwrap();
+        errno_check(libc::connect(
+            fd,
+            (addr as *const libc::sockaddr_in).cast(),
+            mem::size_of_val(addr) as libc::socklen_t,
+        ));
+        fd
+    }
+}
+
+fn test_tcp_addresses() {
+    let (listener, listener_addr) = listener();
+    let client = connect(&listener_addr, 0);
+    unsafe {
+        let mut peer: libc::sockaddr_in = mem::zeroed();
+        let mut len = mem::size_of_val(&peer) as libc::socklen_t;
+        let server = errno_result(libc::accept4(
+            listener,
+            (&raw mut peer).cast(),
+            &mut len,
+            libc::SOCK_CLOEXEC,
+        ))
+        .unwrap();
+
+        // The address reported by `accept4` is the one of the client.
+        let mut client_addr: libc::sockaddr_in = mem::zeroed();
+        let mut len = mem::size_of_val(&client_addr) as libc::socklen_t;
+        errno_check(libc::getsockname(client, (&raw mut client_addr).cast(), &mut len));
+        assert_eq!(peer.sin_port, client_addr.sin_port);
+        assert_eq!(peer.sin_addr.s_addr, client_addr.sin_addr.s_addr);
+
+        // And the client's peer is the listener's address.
+        let mut client_peer: libc::sockaddr_in = mem::zeroed();
+        let mut len = mem::size_of_val(&client_peer) as libc::socklen_t;
+        errno_check(libc::getpeername(client, (&raw mut client_peer).cast(), &mut len));
+        assert_eq!(client_peer.sin_port, listener_addr.sin_port);
+
+        // A listening socket has no peer.
+        let mut len = mem::size_of_val(&client_peer) as libc::socklen_t;
+        let res = libc::getpeername(listener, (&raw mut client_peer).cast(), &mut len);
+        assert_eq!(res, -1);
+        assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::ENOTCONN));
+
+        errno_check(libc::close(server));
+        errno_check(libc::close(client));
+        errno_check(libc::close(listener));
+    }
+}
+
+fn test_tcp_epoll() {
+    let epfd = unsafe { errno_result(libc::epoll_create1(0)).unwrap() };
+    let (listener, listener_addr) = listener();
+    let flags = EPOLLIN | EPOLLOUT | EPOLLRDHUP | EPOLLET;
+
+    // A listener with an empty backlog is not ready.
+    epoll_ctl_add(epfd, listener, flags).unwrap();
+    check_epoll_wait_noblock::<4>(epfd, &[]);
+
+    // A pending connection makes it readable.
+    let client = connect(&listener_addr, libc::SOCK_NONBLOCK);
+    check_epoll_wait_noblock::<4>(epfd, &[Ev { events: EPOLLIN, data: listener }]);
+    let server = unsafe {
+        errno_result(libc::accept4(
+            listener,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            libc::SOCK_NONBLOCK,
+        ))
+        .unwrap()
+    };
+
+    // A fresh connection can only be written to.
+    epoll_ctl_add(epfd, client, flags).unwrap();
+    check_epoll_wait_noblock::<4>(epfd, &[Ev { events: EPOLLOUT, data: client }]);
+
+    // Data from the server makes the client readable.
+    write_all_from_slice(server, b"abc").unwrap();
+    check_epoll_wait_noblock::<4>(epfd, &[Ev { events: EPOLLIN | EPOLLOUT, data: client }]);
+    assert_eq!(&read_all_into_array::<3>(client).unwrap(), b"abc");
+
+    // Closing the server side signals RDHUP.
+    unsafe { errno_check(libc::close(server)) };
+    check_epoll_wait_noblock::<4>(
+        epfd,
+        &[Ev { events: EPOLLIN | EPOLLOUT | EPOLLRDHUP, data: client }],
+    );
+
+    // Once the client has also shut down its writing half, the connection is fully hung up.
+    unsafe { errno_check(libc::shutdown(client, libc::SHUT_WR)) };
+    check_epoll_wait_noblock::<4>(
+        epfd,
+        &[Ev { events: EPOLLIN | EPOLLOUT | EPOLLRDHUP | EPOLLHUP, data: client }],
+    );
+
+    unsafe {
+        errno_check(libc::close(client));
+        errno_check(libc::close(listener));
+        errno_check(libc::close(epfd));
+    }
+}
+
+fn test_recv_flags() {
+    let (listener, listener_addr) = listener();
+    let client = connect(&listener_addr, 0);
+    unsafe {
+        let server =
+            errno_result(libc::accept4(listener, std::ptr::null_mut(), std::ptr::null_mut(), 0))
+                .unwrap();
+
+        // The socket is blocking, but `MSG_DONTWAIT` makes this call return immediately.
+        let mut buf = [0u8; 8];
+        let res = libc::recv(server, buf.as_mut_ptr().cast(), buf.len(), libc::MSG_DONTWAIT);
+        assert_eq!(res, -1);
+        assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EAGAIN));
+
+        let res = libc::send(client, b"hello".as_ptr().cast(), 5, libc::MSG_NOSIGNAL);
+        assert_eq!(res, 5);
+        // Peeking leaves the data in the socket.
+        let res = libc::recv(server, buf.as_mut_ptr().cast(), buf.len(), libc::MSG_PEEK);
+        assert_eq!(res, 5);
+        let res = libc::recv(server, buf.as_mut_ptr().cast(), buf.len(), 0);
+        assert_eq!(res, 5);
+        assert_eq!(&buf[..5], b"hello");
+
+        errno_check(libc::close(server));
+        errno_check(libc::close(client));
+        errno_check(libc::close(listener));
+    }
+}
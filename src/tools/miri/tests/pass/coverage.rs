@@ -0,0 +1,17 @@
+//@output-file: lcov -Zmiri-coverage
+// Collecting coverage does not change the behavior of the program. The call of `unused` is never
+// reached, so its line is not covered although the lines around it are.
+
+fn unused() {}
+
+fn double(x: u8) -> u8 {
+    x * 2
+}
+
+fn main() {
+    for i in 0..3 {
+        if double(i) > 10 {
+            unused();
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//@ignore-target: windows solaris illumos # no socket support on these targets
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, Shutdown, TcpListener, TcpStream, UdpSocket};
+use std::thread;
+
+fn main() {
+    test_tcp();
+    test_tcp_ipv6();
+    test_tcp_threaded();
+    test_tcp_nonblocking();
+    test_tcp_errors();
+    test_udp();
+    test_udp_connected();
+    test_udp_threaded();
+}
+
+fn test_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert_eq!(addr.ip(), Ipv4Addr::LOCALHOST);
+    assert_ne!(addr.port(), 0);
+
+    let mut client = TcpStream::connect(addr).unwrap();
+    let (mut server, peer_addr) = listener.accept().unwrap();
+    assert_eq!(peer_addr, client.local_addr().unwrap());
+    assert_eq!(server.local_addr().unwrap(), addr);
+    assert_eq!(client.peer_addr().unwrap(), addr);
+
+    client.write_all(b"hello").unwrap();
+    let mut buf = [0; 5];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+    server.write_all(b"world").unwrap();
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"world");
+
+    // Peeking does not consume the data.
+    client.write_all(b"abc").unwrap();
+    let mut buf = [0; 3];
+    assert_eq!(server.peek(&mut buf).unwrap(), 3);
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"abc");
+
+    client.set_nodelay(true).unwrap();
+    assert!(client.nodelay().unwrap());
+    assert!(client.take_error().unwrap().is_none());
+
+    // Once the client is gone, reads on the server return end-of-file.
+    drop(client);
+    let mut rest = Vec::new();
+    assert_eq!(server.read_to_end(&mut rest).unwrap(), 0);
+}
+
+fn test_tcp_ipv6() {
+    let listener = TcpListener::bind("[::1]:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert_eq!(addr.ip(), Ipv6Addr::LOCALHOST);
+
+    let mut client = TcpStream::connect(addr).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    client.write_all(b"hello").unwrap();
+    let mut buf = [0; 5];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+}
+
+fn test_tcp_threaded() {
+    // Send more than fits into the buffer, so that both sides need to block.
+    let data: Vec<u8> = (0..0x40000).map(|i| i as u8).collect();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        // This blocks until the client connects.
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).unwrap();
+        stream.write_all(&buf).unwrap();
+    });
+
+    let mut client = TcpStream::connect(addr).unwrap();
+    let writer = {
+        let mut client = client.try_clone().unwrap();
+        let data = data.clone();
+        thread::spawn(move || {
+            client.write_all(&data).unwrap();
+            client.shutdown(Shutdown::Write).unwrap();
+        })
+    };
+    let mut buf = Vec::new();
+    client.read_to_end(&mut buf).unwrap();
+    assert!(buf == data);
+
+    writer.join().unwrap();
+    server.join().unwrap();
+}
+
+fn test_tcp_nonblocking() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    listener.set_nonblocking(true).unwrap();
+    assert_eq!(listener.accept().unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    let mut client = TcpStream::connect(addr).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    server.set_nonblocking(true).unwrap();
+    let mut buf = [0; 4];
+    assert_eq!(server.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    client.write_all(b"data").unwrap();
+    assert_eq!(server.read(&mut buf).unwrap(), 4);
+    assert_eq!(&buf, b"data");
+}
+
+fn test_tcp_errors() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert_eq!(TcpListener::bind(addr).unwrap_err().kind(), ErrorKind::AddrInUse);
+
+    // Nobody is listening anymore once the listener is closed.
+    drop(listener);
+    assert_eq!(TcpStream::connect(addr).unwrap_err().kind(), ErrorKind::ConnectionRefused);
+    // But the address can be used again.
+    let _listener = TcpListener::bind(addr).unwrap();
+}
+
+fn test_udp() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let a_addr = a.local_addr().unwrap();
+    let b_addr = b.local_addr().unwrap();
+    assert_ne!(a_addr, b_addr);
+
+    a.send_to(b"hello", b_addr).unwrap();
+    let mut buf = [0; 16];
+    assert_eq!(b.peek_from(&mut buf).unwrap(), (5, a_addr));
+    assert_eq!(b.recv_from(&mut buf).unwrap(), (5, a_addr));
+    assert_eq!(&buf[..5], b"hello");
+
+    // Datagrams are never merged, and the part of a datagram that does not fit into the
+    // buffer is discarded.
+    a.send_to(b"12", b_addr).unwrap();
+    a.send_to(b"345", b_addr).unwrap();
+    let mut buf = [0; 2];
+    assert_eq!(b.recv_from(&mut buf).unwrap(), (2, a_addr));
+    assert_eq!(&buf, b"12");
+    assert_eq!(b.recv_from(&mut buf).unwrap(), (2, a_addr));
+    assert_eq!(&buf, b"34");
+    b.set_nonblocking(true).unwrap();
+    assert_eq!(b.recv_from(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+fn test_udp_connected() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let c = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let a_addr = a.local_addr().unwrap();
+
+    b.connect(a_addr).unwrap();
+    assert_eq!(b.peer_addr().unwrap(), a_addr);
+    b.send(b"hi").unwrap();
+    let mut buf = [0; 2];
+    assert_eq!(a.recv_from(&mut buf).unwrap(), (2, b.local_addr().unwrap()));
+
+    // A connected socket only receives datagrams from its peer.
+    c.send_to(b"no", b.local_addr().unwrap()).unwrap();
+    a.send_to(b"ok", b.local_addr().unwrap()).unwrap();
+    assert_eq!(b.recv(&mut buf).unwrap(), 2);
+    assert_eq!(&buf, b"ok");
+    b.set_nonblocking(true).unwrap();
+    assert_eq!(b.recv(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+fn test_udp_threaded() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b_addr = b.local_addr().unwrap();
+
+    let receiver = thread::spawn(move || {
+        // This blocks until the datagram arrives.
+        let mut buf = [0; 4];
+        let (len, _) = b.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"ping");
+    });
+    a.send_to(b"ping", b_addr).unwrap();
+    receiver.join().unwrap();
+}
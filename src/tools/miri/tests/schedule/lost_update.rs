@@ -0,0 +1,27 @@
+//@compile-flags: -Zmiri-preemption-rate=0
+//@schedule-roundtrip
+//! Two threads increment a counter without making the increment atomic, so an update gets lost if
+//! a thread is preempted between reading and writing the counter. Without preemption, it passes.
+
+use std::hint::black_box;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn increment() {
+    let value = COUNTER.load(SeqCst);
+    // Leave plenty of basic blocks in which the thread can get preempted.
+    for i in 0..100 {
+        black_box(i);
+    }
+    COUNTER.store(value + 1, SeqCst);
+}
+
+fn main() {
+    let thread = thread::spawn(increment);
+    increment();
+    thread.join().unwrap();
+    assert_eq!(COUNTER.load(SeqCst), 2);
+}
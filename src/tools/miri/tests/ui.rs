@@ -2,19 +2,20 @@
 use std::ffi::OsString;
 use std::num::NonZero;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output};
 use std::sync::OnceLock;
 
 use colored::*;
 use regex::bytes::Regex;
 use ui_test::build_manager::BuildManager;
 use ui_test::color_eyre::eyre::{Context, Result};
+use ui_test::custom_flags::Flag;
 use ui_test::custom_flags::edition::Edition;
 use ui_test::dependencies::DependencyBuilder;
 use ui_test::per_test_config::TestConfig;
 use ui_test::spanned::Spanned;
 use ui_test::status_emitter::StatusEmitter;
-use ui_test::{CommandBuilder, Config, Match, ignore_output_conflict};
+use ui_test::{CommandBuilder, Config, Errored, Match, ignore_output_conflict};
 
 #[derive(Copy, Clone, Debug)]
 enum Mode {
@@ -123,6 +124,20 @@ fn miri_config(
     // keep in sync with `./miri run`
     config.comment_defaults.base().add_custom("edition", Edition("2021".into()));
 
+    config.custom_comments.insert("output-file", |parser, args, span| {
+        let Some((extension, flag)) = args.split_once(" ") else {
+            parser.error(args.span(), "expected `//@output-file: <extension> <miri flag>`");
+            return;
+        };
+        let output_file =
+            OutputFile { extension: (*extension.trim()).into(), flag: (*flag.trim()).into() };
+        parser.set_custom_once("output-file", output_file, span);
+    });
+
+    config.custom_comments.insert("schedule-roundtrip", |parser, _args, span| {
+        parser.set_custom_once("schedule-roundtrip", ScheduleRoundtrip, span);
+    });
+
     if let Some(WithDependencies { bless }) = with_dependencies {
         config.comment_defaults.base().set_custom(
             "dependencies",
@@ -221,6 +236,215 @@ fn run_tests(
     )
 }
 
+/// `//@output-file: <extension> <miri flag>` passes `<miri flag>=<file>` to Miri, and compares
+/// the contents of that file with `<test>.<extension>.stdout` once Miri is done.
+/// JSON files are pretty-printed line by line so that they are easier to review.
+#[derive(Clone, Debug)]
+struct OutputFile {
+    extension: String,
+    flag: String,
+}
+
+impl OutputFile {
+    fn path(&self, config: &TestConfig) -> PathBuf {
+        let name = config.status.path().file_stem().unwrap();
+        config.config.out_dir.join(name).with_extension(config.extension(&self.extension))
+    }
+}
+
+impl Flag for OutputFile {
+    fn clone_inner(&self) -> Box<dyn Flag> {
+        Box::new(self.clone())
+    }
+
+    fn apply(
+        &self,
+        cmd: &mut Command,
+        config: &TestConfig,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let path = self.path(config);
+        std::fs::create_dir_all(&config.config.out_dir).unwrap();
+        // Miri appends to some of these files, so get rid of the output of previous runs.
+        let _ = std::fs::remove_file(&path);
+        let mut arg = OsString::from(format!("{}=", self.flag));
+        arg.push(path);
+        cmd.arg(arg);
+        Ok(())
+    }
+
+    fn post_test_action(
+        &self,
+        config: &TestConfig,
+        _output: &Output,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let path = self.path(config);
+        // A missing file is reported as a mismatch with the expected output.
+        let mut output = std::fs::read_to_string(&path).unwrap_or_default();
+        if self.extension == "json" {
+            output = output
+                .lines()
+                .map(|line| {
+                    let pretty = serde_json::from_str::<serde_json::Value>(line)
+                        .and_then(|value| serde_json::to_string_pretty(&value));
+                    pretty.unwrap_or_else(|_| line.to_owned()) + "\n"
+                })
+                .collect();
+        }
+        let mut output = output.into_bytes();
+        for (filter, replacement) in stderr_filters() {
+            let Match::Regex(regex) = filter else { unreachable!() };
+            output = regex.replace_all(&output, *replacement).into_owned();
+        }
+
+        let expected = config
+            .status
+            .path()
+            .with_extension(config.extension(&format!("{}.stdout", self.extension)));
+        let mut errors = vec![];
+        (config.config.output_conflict_handling)(&expected, &output, &mut errors, config);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Errored::new(errors, &format!("checking `{}`", path.display())))
+        }
+    }
+
+    fn must_be_unique(&self) -> bool {
+        true
+    }
+}
+
+/// `//@schedule-roundtrip` checks `-Zmiri-record-schedule`, `-Zmiri-replay-schedule`, and
+/// `-Zmiri-shrink-schedule` on a test that fails depending on how its threads get scheduled: a
+/// failing schedule is recorded, replayed to the same failure, and shrunk.
+/// The test itself should be run without preemption, so that it passes.
+#[derive(Clone, Debug)]
+struct ScheduleRoundtrip;
+
+impl ScheduleRoundtrip {
+    /// Runs the test in Miri again, with preemption and the given flags, and checks that it fails.
+    fn run_failing(
+        config: &TestConfig,
+        build_manager: &BuildManager,
+        flags: &[OsString],
+    ) -> Result<Output, Errored> {
+        let mut cmd = config.build_command(build_manager)?;
+        cmd.arg("-Zmiri-preemption-rate=0.01").args(flags);
+        let output = cmd.output().map_err(|err| {
+            Errored::new(vec![ui_test::Error::Bug(err.to_string())], "running Miri")
+        })?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.code() == Some(101)
+            && stderr.contains("assertion `left == right` failed")
+            && !stderr.contains("diverged from the replayed schedule")
+        {
+            Ok(output)
+        } else {
+            Err(Self::error(format!("{cmd:?} did not fail as expected:\n{stderr}")))
+        }
+    }
+
+    fn error(msg: String) -> Errored {
+        Errored::new(vec![ui_test::Error::Bug(msg)], "checking the recorded schedule")
+    }
+
+    fn num_preemptions(schedule: &Path) -> usize {
+        let schedule = std::fs::read_to_string(schedule).unwrap_or_default();
+        schedule.lines().filter(|line| line.starts_with("preempt ")).count()
+    }
+}
+
+fn path_flag(name: &str, path: &Path) -> OsString {
+    let mut flag = OsString::from(format!("{name}="));
+    flag.push(path);
+    flag
+}
+
+impl Flag for ScheduleRoundtrip {
+    fn clone_inner(&self) -> Box<dyn Flag> {
+        Box::new(self.clone())
+    }
+
+    fn apply(
+        &self,
+        _cmd: &mut Command,
+        _config: &TestConfig,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        Ok(())
+    }
+
+    fn post_test_action(
+        &self,
+        config: &TestConfig,
+        _output: &Output,
+        build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let name = config.status.path().file_stem().unwrap();
+        let dir = config.config.out_dir.join(name).with_extension("schedules");
+        std::fs::create_dir_all(&dir).unwrap();
+        let recorded = dir.join("recorded");
+        let replayed = dir.join("replayed");
+        let shrunk = dir.join("shrunk");
+
+        // Find a seed whose random schedule makes the test fail, and record that schedule.
+        (0..64)
+            .find_map(|seed| {
+                let flags = [
+                    format!("-Zmiri-seed={seed}").into(),
+                    path_flag("-Zmiri-record-schedule", &recorded),
+                ];
+                Self::run_failing(config, build_manager, &flags).ok()
+            })
+            .ok_or_else(|| Self::error("no seed made the test fail".into()))?;
+
+        // Replaying the schedule with another seed leads to the same failure, via the same choices.
+        Self::run_failing(
+            config,
+            build_manager,
+            &[
+                "-Zmiri-seed=1000".into(),
+                path_flag("-Zmiri-replay-schedule", &recorded),
+                path_flag("-Zmiri-record-schedule", &replayed),
+            ],
+        )?;
+        let read = |path: &Path| std::fs::read_to_string(path).unwrap_or_default();
+        if read(&replayed) != read(&recorded) {
+            return Err(Self::error(format!(
+                "replaying `{}` made different choices:\n{}",
+                recorded.display(),
+                read(&replayed)
+            )));
+        }
+
+        // Shrinking keeps the failure with fewer preemption points.
+        Self::run_failing(
+            config,
+            build_manager,
+            &[
+                "-Zmiri-shrink-schedule".into(),
+                path_flag("-Zmiri-replay-schedule", &recorded),
+                path_flag("-Zmiri-record-schedule", &shrunk),
+            ],
+        )?;
+        if Self::num_preemptions(&shrunk) >= Self::num_preemptions(&recorded) {
+            return Err(Self::error(format!(
+                "shrinking `{}` did not remove any preemption:\n{}",
+                recorded.display(),
+                read(&shrunk)
+            )));
+        }
+        Self::run_failing(config, build_manager, &[path_flag("-Zmiri-replay-schedule", &shrunk)])?;
+        Ok(())
+    }
+
+    fn must_be_unique(&self) -> bool {
+        true
+    }
+}
+
 macro_rules! regexes {
     ($name:ident: $($regex:expr => $replacement:expr,)*) => {
         fn $name() -> &'static [(Match, &'static [u8])] {
@@ -334,6 +558,7 @@ fn main() -> Result<()> {
     ui(Mode::Panic, "tests/panic", &target, WithDependencies, tmpdir.path())?;
     ui(Mode::Fail, "tests/fail", &target, WithoutDependencies, tmpdir.path())?;
     ui(Mode::Fail, "tests/fail-dep", &target, WithDependencies, tmpdir.path())?;
+    ui(Mode::Pass, "tests/schedule", &target, WithoutDependencies, tmpdir.path())?;
     if cfg!(all(unix, feature = "native-lib")) && target == host {
         ui(Mode::Pass, "tests/native-lib/pass", &target, WithoutDependencies, tmpdir.path())?;
         ui(Mode::Fail, "tests/native-lib/fail", &target, WithoutDependencies, tmpdir.path())?;
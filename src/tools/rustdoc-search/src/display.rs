@@ -0,0 +1,81 @@
+//! Printing of signatures, in the syntax of queries.
+
+use rustdoc_search_index_types::{Signature, Type, TypeId};
+
+use crate::Searcher;
+
+impl Searcher<'_> {
+    /// Prints a signature like a query that would match it exactly, like `&Vec<T> -> usize`.
+    /// Generic parameters are named after their position, since the index does not keep their
+    /// names.
+    pub fn display_signature(&self, signature: &Signature) -> String {
+        let mut s = self.display_list(&signature.inputs);
+        if !signature.output.is_empty() {
+            if !s.is_empty() {
+                s.push(' ');
+            }
+            s.push_str("-> ");
+            s.push_str(&self.display_list(&signature.output));
+        }
+        s
+    }
+
+    fn display_list(&self, types: &[Type]) -> String {
+        types.iter().map(|ty| self.display_type(ty)).collect::<Vec<_>>().join(", ")
+    }
+
+    fn display_type(&self, ty: &Type) -> String {
+        let generics = self.display_list(&ty.generics);
+        let id = match ty.id {
+            // An `impl Trait` in return position, whose generics are its bounds.
+            None => {
+                let bounds: Vec<_> = ty.generics.iter().map(|ty| self.display_type(ty)).collect();
+                return format!("impl {}", bounds.join(" + "));
+            }
+            Some(TypeId::Generic(generic)) => return generic_name(generic),
+            Some(TypeId::Path(id)) => id,
+        };
+        let name = self.index.paths[id].name.as_str();
+        match name {
+            "reference" => format!("&{}", generics.replacen("mut, ", "mut ", 1)),
+            "pointer" if generics.starts_with("mut, ") => format!("*mut {}", &generics[5..]),
+            "pointer" => format!("*const {generics}"),
+            "slice" => format!("[{generics}]"),
+            "array" => format!("[{generics}; N]"),
+            "tuple" | "unit" => format!("({generics})"),
+            "never" => "!".to_owned(),
+            "fn" => {
+                let output =
+                    ty.bindings.first().map(|binding| self.display_list(&binding.constraints));
+                match output {
+                    Some(output) if !output.is_empty() => format!("fn({generics}) -> {output}"),
+                    _ => format!("fn({generics})"),
+                }
+            }
+            _ => {
+                let bindings = ty.bindings.iter().map(|binding| {
+                    let assoc = &self.index.paths[binding.assoc].name;
+                    format!("{assoc} = {}", self.display_list(&binding.constraints))
+                });
+                let args: Vec<_> = (!generics.is_empty())
+                    .then_some(generics)
+                    .into_iter()
+                    .chain(bindings)
+                    .collect();
+                if args.is_empty() {
+                    name.to_owned()
+                } else {
+                    format!("{name}<{}>", args.join(", "))
+                }
+            }
+        }
+    }
+}
+
+fn generic_name(generic: usize) -> String {
+    const NAMES: [&str; 4] = ["T", "U", "V", "W"];
+    match NAMES.get(generic) {
+        Some(name) => (*name).to_owned(),
+        None => format!("T{generic}"),
+    }
+}
@@ -0,0 +1,310 @@
+//! Searches the export of rustdoc's search index, which is written with `--emit=search-index`,
+//! without a browser.
+//!
+//! Queries use the syntax of the search of the HTML documentation: a name like `vec::Vec::len`
+//! finds items by name, and a list of types like `Vec<T> -> usize` finds the functions whose
+//! signature mentions them. Filters like `fn:` restrict the kind of the results.
+//!
+//! Type-based searches follow the same rules as the HTML search, in a simplified form:
+//!
+//! * Every type of the query has to match a different type of the signature, but the signature
+//!   can have more types than the query, and their order does not matter.
+//! * A name that is not the name of any type of the index is a generic parameter. It matches the
+//!   generic parameters of the function, each query parameter standing for a different one.
+//! * Types that are marked as unboxable, like `Option` or references, can be left out of the
+//!   query, which can then match their generics instead. The same goes for the trait bounds of a
+//!   generic parameter, so that `Iterator<Item = u8>` matches `impl Iterator<Item = u8>`.
+
+use std::collections::HashMap;
+
+use rustdoc_search_index_types::{ItemKind, SearchIndex, Signature, Type, TypeId};
+
+pub use crate::query::{ParseError, Query, QueryType, Search, parse};
+
+mod display;
+mod query;
+#[cfg(test)]
+mod tests;
+
+pub struct Searcher<'a> {
+    index: &'a SearchIndex,
+    /// The paths of the index, by their lowercase name.
+    paths_by_name: HashMap<String, Vec<usize>>,
+}
+
+impl<'a> Searcher<'a> {
+    pub fn new(index: &'a SearchIndex) -> Self {
+        let mut paths_by_name: HashMap<String, Vec<usize>> = HashMap::new();
+        for (id, path) in index.paths.iter().enumerate() {
+            paths_by_name.entry(path.name.to_lowercase()).or_default().push(id);
+        }
+        Searcher { index, paths_by_name }
+    }
+
+    /// Returns the items that match `query`, best matches first.
+    pub fn search(&self, query: &Query) -> Vec<&'a rustdoc_search_index_types::Item> {
+        let items = self
+            .index
+            .items
+            .iter()
+            .filter(|item| query.kind.is_none_or(|k| kind_matches(k, item.kind)));
+        let mut results: Vec<_> = match &query.search {
+            Search::Name(path) => {
+                items.filter_map(|item| Some((self.name_rank(path, item)?, item))).collect()
+            }
+            Search::Type { inputs, output } => items
+                .filter_map(|item| {
+                    let signature = item.signature.as_ref()?;
+                    let mut unifier = Unifier { searcher: self, signature, generics: Vec::new() };
+                    let is_match = unifier
+                        .unify_all(inputs, &signature.inputs.iter().collect::<Vec<_>>())
+                        && unifier.unify_all(output, &signature.output.iter().collect::<Vec<_>>());
+                    is_match.then(|| (signature_size(signature), item))
+                })
+                .collect(),
+        };
+        results.sort_by_cached_key(|&(rank, item)| (rank, item.name.len(), self.item_path(item)));
+        results.into_iter().map(|(_, item)| item).collect()
+    }
+
+    /// Returns the names of the query that are not the name of any type of the index, and are
+    /// therefore taken as generic parameters.
+    pub fn generic_names<'q>(&self, query: &'q Query) -> Vec<&'q str> {
+        fn walk<'q>(searcher: &Searcher<'_>, ty: &'q QueryType, names: &mut Vec<&'q str>) {
+            if searcher.is_generic(ty) && !names.contains(&ty.name()) {
+                names.push(ty.name());
+            }
+            let constraints = ty.bindings.iter().flat_map(|(_, constraints)| constraints);
+            for ty in ty.generics.iter().chain(constraints) {
+                walk(searcher, ty, names);
+            }
+        }
+
+        let mut names = Vec::new();
+        if let Search::Type { inputs, output } = &query.search {
+            for ty in inputs.iter().chain(output) {
+                walk(self, ty, &mut names);
+            }
+        }
+        names
+    }
+
+    /// Returns the full path of `item`, like `std::vec::Vec::len`.
+    pub fn item_path(&self, item: &rustdoc_search_index_types::Item) -> String {
+        let mut segments = Vec::new();
+        if !item.module.is_empty() {
+            segments.push(item.module.as_str());
+        }
+        if let Some(parent) = item.parent {
+            segments.push(&self.index.paths[parent].name);
+        }
+        segments.push(&item.name);
+        segments.join("::")
+    }
+
+    /// Ranks a match of a search by name, where lower is better: exact matches come first, then
+    /// names that start with the query, then names that contain it.
+    fn name_rank(
+        &self,
+        query: &[String],
+        item: &rustdoc_search_index_types::Item,
+    ) -> Option<usize> {
+        let (name, parents) = query.split_last().unwrap();
+        let name = normalize(name);
+        let rank = std::iter::once(&item.name)
+            .chain(&item.aliases)
+            .filter_map(|candidate| {
+                let candidate = normalize(candidate);
+                if candidate == name {
+                    Some(0)
+                } else if candidate.starts_with(&name) {
+                    Some(1)
+                } else if candidate.contains(&name) {
+                    Some(2)
+                } else {
+                    None
+                }
+            })
+            .min()?;
+
+        // The other segments of the query have to appear in the path of the item, in order.
+        let path = self.item_path(item).to_lowercase();
+        let segments: Vec<_> = path.split("::").collect();
+        let mut segments = segments[..segments.len() - 1].iter();
+        parents
+            .iter()
+            .all(|parent| segments.any(|&segment| segment == parent.to_lowercase()))
+            .then_some(rank)
+    }
+
+    fn is_generic(&self, ty: &QueryType) -> bool {
+        ty.path.len() == 1
+            && ty.generics.is_empty()
+            && ty.bindings.is_empty()
+            && !matches!(ty.name(), "[]" | "()")
+            && !self.paths_by_name.contains_key(&ty.name().to_lowercase())
+    }
+
+    /// Whether the query type `ty` names the path `id` of the index.
+    fn names_path(&self, ty: &QueryType, id: usize) -> bool {
+        let path = &self.index.paths[id];
+        let name_matches = match ty.name() {
+            "[]" => matches!(path.name.as_str(), "slice" | "array"),
+            "()" => matches!(path.name.as_str(), "tuple" | "unit"),
+            name => path.name.eq_ignore_ascii_case(name),
+        };
+        if !name_matches {
+            return false;
+        }
+        // The other segments of the query have to appear in the module of the type, in order.
+        let module = path.module.to_lowercase();
+        let mut segments = module.split("::");
+        ty.path[..ty.path.len() - 1]
+            .iter()
+            .all(|parent| segments.any(|segment| segment == parent.to_lowercase()))
+    }
+}
+
+/// Matches the types of a query with those of a signature.
+struct Unifier<'s, 'a> {
+    searcher: &'s Searcher<'a>,
+    signature: &'a Signature,
+    /// The generic parameters of the query that have been matched with those of the signature.
+    generics: Vec<(&'s str, usize)>,
+}
+
+impl<'s, 'a> Unifier<'s, 'a> {
+    /// Whether every type of `query` matches a different type of `types`.
+    fn unify_all(&mut self, query: &'s [QueryType], types: &[&'a Type]) -> bool {
+        let mut used = vec![false; types.len()];
+        self.unify_rest(query, types, &mut used)
+    }
+
+    fn unify_rest(
+        &mut self,
+        query: &'s [QueryType],
+        types: &[&'a Type],
+        used: &mut [bool],
+    ) -> bool {
+        let Some((first, rest)) = query.split_first() else { return true };
+        for (i, ty) in types.iter().enumerate() {
+            if used[i] {
+                continue;
+            }
+            let generics = self.generics.len();
+            if self.unify(first, ty) {
+                used[i] = true;
+                if self.unify_rest(rest, types, used) {
+                    return true;
+                }
+                used[i] = false;
+            }
+            self.generics.truncate(generics);
+        }
+        false
+    }
+
+    fn unify(&mut self, query: &'s QueryType, ty: &'a Type) -> bool {
+        let is_generic = self.searcher.is_generic(query);
+        match ty.id {
+            Some(TypeId::Generic(generic)) if is_generic => {
+                return self.unify_generic(query.name(), generic);
+            }
+            Some(TypeId::Path(id)) if !is_generic && self.searcher.names_path(query, id) => {
+                let generics = self.generics.len();
+                if self.unify_generics(query, ty) {
+                    return true;
+                }
+                self.generics.truncate(generics);
+            }
+            _ => {}
+        }
+
+        // Otherwise, the query can match what is inside of the type.
+        let inner: Vec<&'a Type> = match ty.id {
+            None => ty.generics.iter().collect(),
+            Some(TypeId::Path(id)) if self.searcher.index.paths[id].unbox => {
+                ty.generics.iter().chain(constraints(ty)).collect()
+            }
+            Some(TypeId::Generic(generic)) => {
+                self.signature.generics.get(generic).into_iter().flatten().collect()
+            }
+            Some(TypeId::Path(_)) => Vec::new(),
+        };
+        inner.into_iter().any(|inner| {
+            let generics = self.generics.len();
+            let is_match = self.unify(query, inner);
+            if !is_match {
+                self.generics.truncate(generics);
+            }
+            is_match
+        })
+    }
+
+    /// Matches the generics and the associated type constraints of a query type with those of a
+    /// type of the same name. Generics of the query can also match constraints, so that
+    /// `Iterator<u8>` matches `Iterator<Item = u8>`.
+    fn unify_generics(&mut self, query: &'s QueryType, ty: &'a Type) -> bool {
+        let types: Vec<_> = ty.generics.iter().chain(constraints(ty)).collect();
+        if !self.unify_all(&query.generics, &types) {
+            return false;
+        }
+        query.bindings.iter().all(|(name, query_constraints)| {
+            ty.bindings.iter().any(|binding| {
+                let generics = self.generics.len();
+                let is_match =
+                    self.searcher.index.paths[binding.assoc].name.eq_ignore_ascii_case(name)
+                        && self.unify_all(
+                            query_constraints,
+                            &binding.constraints.iter().collect::<Vec<_>>(),
+                        );
+                if !is_match {
+                    self.generics.truncate(generics);
+                }
+                is_match
+            })
+        })
+    }
+
+    /// Matches a generic parameter of the query with one of the signature, which must not have
+    /// been matched with a different one.
+    fn unify_generic(&mut self, name: &'s str, generic: usize) -> bool {
+        match self.generics.iter().find(|&&(n, g)| n == name || g == generic) {
+            Some(&(n, g)) => n == name && g == generic,
+            None => {
+                self.generics.push((name, generic));
+                true
+            }
+        }
+    }
+}
+
+fn constraints(ty: &Type) -> impl Iterator<Item = &Type> {
+    ty.bindings.iter().flat_map(|binding| &binding.constraints)
+}
+
+fn signature_size(signature: &Signature) -> usize {
+    fn size(ty: &Type) -> usize {
+        1 + ty.generics.iter().chain(constraints(ty)).map(size).sum::<usize>()
+    }
+    signature.inputs.iter().chain(&signature.output).map(size).sum()
+}
+
+/// Like the HTML search, names are compared without their case and underscores.
+fn normalize(name: &str) -> String {
+    name.replace('_', "").to_lowercase()
+}
+
+/// Whether a filter on the kind of items accepts `kind`. Like in the HTML search, `fn:` also
+/// accepts methods and `macro:` also accepts procedural macros.
+fn kind_matches(filter: ItemKind, kind: ItemKind) -> bool {
+    match filter {
+        ItemKind::Function => {
+            matches!(kind, ItemKind::Function | ItemKind::Method | ItemKind::TyMethod)
+        }
+        ItemKind::Macro => {
+            matches!(kind, ItemKind::Macro | ItemKind::ProcAttribute | ItemKind::ProcDerive)
+        }
+        _ => filter == kind,
+    }
+}
@@ -0,0 +1,126 @@
+//! Runs searches on the export of rustdoc's search index from a terminal.
+//!
+//! ```text
+//! $ rustdoc -Z unstable-options --emit=invocation-specific,search-index src/lib.rs
+//! $ rustdoc-search doc 'Vec<T> -> usize'
+//! ```
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use clap::Parser;
+use fs_err as fs;
+use rustdoc_search::Searcher;
+use rustdoc_search_index_types::{FORMAT_VERSION, Item, SearchIndex};
+use serde::{Deserialize, Serialize};
+
+#[derive(Parser)]
+struct Cli {
+    /// The `search-index.json` file written by rustdoc, or the documentation directory that
+    /// contains it
+    index: PathBuf,
+
+    /// The query, like `vec::Vec::len`, `fn:len` or `Vec<T> -> usize`
+    query: String,
+
+    /// The maximum number of results to print
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+
+    /// Print the results as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Serialize)]
+struct JsonResult<'a> {
+    path: String,
+    #[serde(flatten)]
+    item: &'a Item,
+}
+
+fn main() -> Result<()> {
+    let Cli { index, query, limit, json } = Cli::parse();
+    let index = load(&index)?;
+    let searcher = Searcher::new(&index);
+    let query = rustdoc_search::parse(&query).context("invalid query")?;
+
+    for name in searcher.generic_names(&query) {
+        if name.chars().count() > 1 {
+            eprintln!(
+                "note: `{name}` is not the name of a type, so it matches any generic parameter"
+            );
+        }
+    }
+
+    let results = searcher.search(&query);
+    let mut stdout = io::stdout().lock();
+    if json {
+        let results: Vec<_> = results
+            .iter()
+            .take(limit)
+            .map(|item| JsonResult { path: searcher.item_path(item), item })
+            .collect();
+        serde_json::to_writer_pretty(&mut stdout, &results)?;
+        writeln!(stdout)?;
+        return Ok(());
+    }
+
+    if results.is_empty() {
+        eprintln!("no results");
+    }
+    for item in results.iter().take(limit) {
+        let kind = serde_json::to_value(item.kind)?;
+        writeln!(stdout, "{} ({})", searcher.item_path(item), kind.as_str().unwrap_or_default())?;
+        if let Some(signature) = &item.signature {
+            writeln!(stdout, "    {}", searcher.display_signature(signature))?;
+        }
+        let desc = plain_text(&item.desc);
+        if !desc.is_empty() {
+            writeln!(stdout, "    {desc}")?;
+        }
+    }
+    if results.len() > limit {
+        writeln!(stdout, "... and {} more results", results.len() - limit)?;
+    }
+    Ok(())
+}
+
+fn load(path: &Path) -> Result<SearchIndex> {
+    #[derive(Deserialize)]
+    struct Version {
+        format_version: u32,
+    }
+
+    let path = if path.is_dir() { path.join("search-index.json") } else { path.to_owned() };
+    let contents = fs::read_to_string(&path)?;
+    // Other versions of the format may not deserialize at all, so the version is checked first.
+    let Version { format_version } = serde_json::from_str(&contents)?;
+    if format_version != FORMAT_VERSION {
+        bail!(
+            "{} uses version {format_version} of the search index format, but this tool only supports version {FORMAT_VERSION}",
+            path.display()
+        );
+    }
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Turns the HTML of a description into text, by removing its tags.
+fn plain_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,243 @@
+//! Parsing of search queries, which use the syntax of the search of the HTML documentation.
+
+use std::fmt;
+
+use rustdoc_search_index_types::ItemKind;
+use serde::Deserialize;
+use serde::de::IntoDeserializer;
+use serde::de::value::Error as DeError;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Query {
+    /// The kind of items to return, given with a prefix like `fn:`.
+    pub kind: Option<ItemKind>,
+    pub search: Search,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Search {
+    /// A search for items by name, like `vec::Vec::len`.
+    Name(Vec<String>),
+    /// A search for items by type, like `Vec<T> -> usize`. The output is empty when the query has
+    /// no arrow, in which case only the inputs are searched.
+    Type { inputs: Vec<QueryType>, output: Vec<QueryType> },
+}
+
+/// A type in a query.
+///
+/// The syntax for primitive types is turned into their names: `&T` is `reference<T>`, `&mut T` is
+/// `reference<mut, T>`, `[T]` is `[]<T>`, which matches both slices and arrays, and `(T, U)` is
+/// `()<T, U>`, which matches both tuples and unit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryType {
+    pub path: Vec<String>,
+    pub generics: Vec<QueryType>,
+    /// Constraints on associated types, like `Item = u8`.
+    pub bindings: Vec<(String, Vec<QueryType>)>,
+}
+
+impl QueryType {
+    fn named(name: &str, generics: Vec<QueryType>) -> QueryType {
+        QueryType { path: vec![name.to_owned()], generics, bindings: Vec::new() }
+    }
+
+    pub fn name(&self) -> &str {
+        self.path.last().unwrap()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError(pub(crate) String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn parse(query: &str) -> Result<Query, ParseError> {
+    let mut parser = Parser { query, pos: 0 };
+    let kind = parser.kind()?;
+    let inputs = parser.list()?;
+    let has_arrow = parser.eat("->");
+    let output = if has_arrow { parser.list()? } else { Vec::new() };
+    parser.skip_whitespace();
+    if let Some(c) = parser.peek() {
+        return Err(ParseError(format!("unexpected `{c}`")));
+    }
+    if inputs.is_empty() && output.is_empty() {
+        return Err(ParseError("empty query".to_owned()));
+    }
+    let search = match <[_; 1]>::try_from(inputs) {
+        // A single name is a search by name, as long as it does not use the syntax of types.
+        Ok([ty])
+            if !has_arrow
+                && ty.generics.is_empty()
+                && ty.bindings.is_empty()
+                && ty.path.iter().all(|segment| is_ident(segment)) =>
+        {
+            Search::Name(ty.path)
+        }
+        Ok(inputs) => Search::Type { inputs: inputs.into(), output },
+        Err(inputs) => Search::Type { inputs, output },
+    };
+    Ok(Query { kind, search })
+}
+
+fn is_ident(s: &str) -> bool {
+    s.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.query[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Skips `token` if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), ParseError> {
+        if self.eat(token) {
+            return Ok(());
+        }
+        match self.peek() {
+            Some(c) => Err(ParseError(format!("expected `{token}`, found `{c}`"))),
+            None => Err(ParseError(format!("expected `{token}`, found the end of the query"))),
+        }
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let len = rest.find(|c: char| !c.is_alphanumeric() && c != '_').unwrap_or(rest.len());
+        if len == 0 {
+            return None;
+        }
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    /// Parses a filter on the kind of items, like `fn:`.
+    fn kind(&mut self) -> Result<Option<ItemKind>, ParseError> {
+        let start = self.pos;
+        if let Some(name) = self.ident()
+            && self.rest().starts_with(':')
+            && !self.rest().starts_with("::")
+        {
+            self.pos += 1;
+            return ItemKind::deserialize(name.into_deserializer())
+                .map(Some)
+                .map_err(|_: DeError| ParseError(format!("unknown item kind `{name}`")));
+        }
+        self.pos = start;
+        Ok(None)
+    }
+
+    /// Parses a list of types separated by commas, which may be empty.
+    fn list(&mut self) -> Result<Vec<QueryType>, ParseError> {
+        let mut types = Vec::new();
+        self.skip_whitespace();
+        while let Some(c) = self.peek()
+            && !self.rest().starts_with("->")
+            && !matches!(c, ')' | ']' | '>')
+        {
+            types.push(self.ty()?);
+            if !self.eat(",") {
+                break;
+            }
+            self.skip_whitespace();
+        }
+        Ok(types)
+    }
+
+    fn ty(&mut self) -> Result<QueryType, ParseError> {
+        if self.eat("&") {
+            let mut generics = Vec::new();
+            let start = self.pos;
+            if self.ident() == Some("mut") {
+                generics.push(QueryType::named("mut", Vec::new()));
+            } else {
+                self.pos = start;
+            }
+            generics.push(self.ty()?);
+            return Ok(QueryType::named("reference", generics));
+        }
+        if self.eat("[") {
+            let generics = self.list()?;
+            self.expect("]")?;
+            return Ok(QueryType::named("[]", generics));
+        }
+        if self.eat("(") {
+            let generics = self.list()?;
+            self.expect(")")?;
+            return Ok(QueryType::named("()", generics));
+        }
+        if self.eat("!") {
+            return Ok(QueryType::named("never", Vec::new()));
+        }
+
+        let mut path = Vec::new();
+        loop {
+            let Some(segment) = self.ident() else {
+                return match self.peek() {
+                    Some(c) => Err(ParseError(format!("expected a type, found `{c}`"))),
+                    None => Err(ParseError("expected a type, found the end of the query".into())),
+                };
+            };
+            path.push(segment.to_owned());
+            if !self.eat("::") {
+                break;
+            }
+        }
+
+        let mut generics = Vec::new();
+        let mut bindings = Vec::new();
+        if self.eat("<") {
+            loop {
+                self.skip_whitespace();
+                if self.peek() == Some('>') {
+                    break;
+                }
+                // An associated type constraint, like `Item = u8`.
+                let start = self.pos;
+                if let Some(name) = self.ident()
+                    && self.eat("=")
+                {
+                    bindings.push((name.to_owned(), vec![self.ty()?]));
+                } else {
+                    self.pos = start;
+                    generics.push(self.ty()?);
+                }
+                if !self.eat(",") {
+                    break;
+                }
+            }
+            self.expect(">")?;
+        }
+        Ok(QueryType { path, generics, bindings })
+    }
+}
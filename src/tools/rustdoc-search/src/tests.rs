@@ -0,0 +1,236 @@
+use rustdoc_search_index_types::{
+    Binding, FORMAT_VERSION, Item, ItemKind, Path, SearchIndex, Signature, Type, TypeId,
+};
+
+use super::*;
+
+/// Builds an index with the types of `std` that the tests use.
+struct Builder {
+    index: SearchIndex,
+}
+
+impl Builder {
+    fn new() -> Self {
+        let mut b = Builder {
+            index: SearchIndex {
+                format_version: FORMAT_VERSION,
+                crates: vec!["std".to_owned()],
+                items: Vec::new(),
+                paths: Vec::new(),
+            },
+        };
+        for (name, kind, module, unbox) in [
+            ("Vec", ItemKind::Struct, "std::vec", false),
+            ("Option", ItemKind::Enum, "std::option", true),
+            ("Iterator", ItemKind::Trait, "std::iter", false),
+            ("Item", ItemKind::AssocType, "", false),
+            ("usize", ItemKind::Primitive, "", false),
+            ("u8", ItemKind::Primitive, "", false),
+            ("bool", ItemKind::Primitive, "", false),
+            ("reference", ItemKind::Primitive, "", true),
+            ("slice", ItemKind::Primitive, "", false),
+            ("mut", ItemKind::Keyword, "", false),
+        ] {
+            b.index.paths.push(Path {
+                name: name.to_owned(),
+                kind,
+                module: module.to_owned(),
+                unbox,
+            });
+        }
+        b
+    }
+
+    fn path(&self, name: &str) -> usize {
+        self.index.paths.iter().position(|path| path.name == name).unwrap()
+    }
+
+    fn ty(&self, name: &str, generics: Vec<Type>) -> Type {
+        Type { id: Some(TypeId::Path(self.path(name))), generics, bindings: Vec::new() }
+    }
+
+    fn add(&mut self, parent: Option<&str>, name: &str, signature: Option<Signature>) {
+        let kind = if parent.is_some() { ItemKind::Method } else { ItemKind::Function };
+        self.index.items.push(Item {
+            name: name.to_owned(),
+            kind,
+            krate: "std".to_owned(),
+            module: "std::vec".to_owned(),
+            parent: parent.map(|parent| self.path(parent)),
+            aliases: Vec::new(),
+            desc: String::new(),
+            deprecated: false,
+            signature,
+        });
+    }
+}
+
+fn generic(generic: usize) -> Type {
+    Type { id: Some(TypeId::Generic(generic)), generics: Vec::new(), bindings: Vec::new() }
+}
+
+fn signature(inputs: Vec<Type>, output: Vec<Type>, generics: usize) -> Option<Signature> {
+    Some(Signature { inputs, output, generics: vec![Vec::new(); generics] })
+}
+
+/// An index with a few methods of `Vec`.
+fn vec_index() -> SearchIndex {
+    let mut b = Builder::new();
+    let vec_t = b.ty("Vec", vec![generic(0)]);
+    let ref_vec_t = b.ty("reference", vec![vec_t.clone()]);
+    let ref_mut_vec_t = b.ty("reference", vec![b.ty("mut", vec![]), vec_t.clone()]);
+    let usize = b.ty("usize", vec![]);
+    let option_t = b.ty("Option", vec![generic(0)]);
+    b.add(Some("Vec"), "len", signature(vec![ref_vec_t.clone()], vec![usize.clone()], 1));
+    b.add(Some("Vec"), "push", signature(vec![ref_mut_vec_t.clone(), generic(0)], vec![], 1));
+    b.add(Some("Vec"), "pop", signature(vec![ref_mut_vec_t], vec![option_t], 1));
+    b.add(Some("Vec"), "is_empty", signature(vec![ref_vec_t], vec![b.ty("bool", vec![])], 1));
+    b.add(None, "from_elem", signature(vec![generic(0), usize], vec![vec_t], 1));
+    b.index
+}
+
+#[track_caller]
+fn check(index: &SearchIndex, query: &str, expected: &[&str]) {
+    let searcher = Searcher::new(index);
+    let query = parse(query).unwrap();
+    let results: Vec<_> =
+        searcher.search(&query).into_iter().map(|item| searcher.item_path(item)).collect();
+    assert_eq!(results, expected);
+}
+
+#[test]
+fn parse_queries() {
+    let named = |name: &str, generics| QueryType {
+        path: vec![name.to_owned()],
+        generics,
+        bindings: Vec::new(),
+    };
+    assert_eq!(
+        parse("fn:vec::Vec::len"),
+        Ok(Query {
+            kind: Some(ItemKind::Function),
+            search: Search::Name(vec!["vec".to_owned(), "Vec".to_owned(), "len".to_owned()]),
+        })
+    );
+    assert_eq!(
+        parse("&mut [T], u8 -> Option<T>"),
+        Ok(Query {
+            kind: None,
+            search: Search::Type {
+                inputs: vec![
+                    named(
+                        "reference",
+                        vec![named("mut", vec![]), named("[]", vec![named("T", vec![])])]
+                    ),
+                    named("u8", vec![]),
+                ],
+                output: vec![named("Option", vec![named("T", vec![])])],
+            },
+        })
+    );
+    assert_eq!(
+        parse("Iterator<Item = u8>"),
+        Ok(Query {
+            kind: None,
+            search: Search::Type {
+                inputs: vec![QueryType {
+                    path: vec!["Iterator".to_owned()],
+                    generics: vec![],
+                    bindings: vec![("Item".to_owned(), vec![named("u8", vec![])])],
+                }],
+                output: vec![],
+            },
+        })
+    );
+    assert_eq!(parse("Vec<T"), Err(ParseError("expected `>`, found the end of the query".into())));
+    assert_eq!(parse("func:len"), Err(ParseError("unknown item kind `func`".into())));
+    assert_eq!(parse("  "), Err(ParseError("empty query".into())));
+}
+
+#[test]
+fn search_by_name() {
+    let index = vec_index();
+    check(&index, "len", &["std::vec::Vec::len"]);
+    // Exact matches come first, and underscores and case are ignored.
+    check(&index, "ISEMPTY", &["std::vec::Vec::is_empty"]);
+    check(&index, "p", &["std::vec::Vec::pop", "std::vec::Vec::push", "std::vec::Vec::is_empty"]);
+    check(&index, "vec::pop", &["std::vec::Vec::pop"]);
+    check(&index, "option::pop", &[]);
+    check(&index, "fn:from", &["std::vec::from_elem"]);
+    check(&index, "method:from", &[]);
+}
+
+#[test]
+fn search_by_type() {
+    let index = vec_index();
+    check(&index, "Vec<T> -> usize", &["std::vec::Vec::len"]);
+    // Inputs can be given in any order, and smaller signatures come first.
+    check(&index, "usize, T -> Vec<T>", &["std::vec::from_elem"]);
+    check(&index, "Vec<T>, T", &["std::vec::Vec::push"]);
+    check(&index, "&mut Vec<T>", &["std::vec::Vec::push", "std::vec::Vec::pop"]);
+    // A shared reference in the query also matches a mutable one.
+    check(
+        &index,
+        "&Vec<T>",
+        &[
+            "std::vec::Vec::len",
+            "std::vec::Vec::is_empty",
+            "std::vec::Vec::push",
+            "std::vec::Vec::pop",
+        ],
+    );
+    // `Option` is unboxable, so it can be left out.
+    check(&index, "Vec<T> -> T", &["std::vec::Vec::pop"]);
+    check(&index, "-> Option<T>", &["std::vec::Vec::pop"]);
+    check(&index, "-> Option<usize>", &[]);
+    // Different generic parameters of the query match different ones of the signature.
+    check(&index, "Vec<T>, U", &[]);
+}
+
+#[test]
+fn search_through_trait_bounds() {
+    let mut b = Builder::new();
+    let iterator = Type {
+        id: Some(TypeId::Path(b.path("Iterator"))),
+        generics: Vec::new(),
+        bindings: vec![Binding { assoc: b.path("Item"), constraints: vec![b.ty("u8", vec![])] }],
+    };
+    let mut sum = signature(vec![generic(0)], vec![b.ty("usize", vec![])], 1).unwrap();
+    sum.generics[0].push(iterator);
+    b.add(None, "sum", Some(sum));
+    let index = b.index;
+
+    check(&index, "Iterator<Item = u8> -> usize", &["std::vec::sum"]);
+    check(&index, "Iterator<u8>", &["std::vec::sum"]);
+    check(&index, "Iterator<Item = bool>", &[]);
+    check(&index, "T -> usize", &["std::vec::sum"]);
+}
+
+#[test]
+fn display_signatures() {
+    let index = vec_index();
+    let searcher = Searcher::new(&index);
+    let signatures: Vec<_> = index
+        .items
+        .iter()
+        .map(|item| searcher.display_signature(item.signature.as_ref().unwrap()))
+        .collect();
+    assert_eq!(
+        signatures,
+        [
+            "&Vec<T> -> usize",
+            "&mut Vec<T>, T",
+            "&mut Vec<T> -> Option<T>",
+            "&Vec<T> -> bool",
+            "T, usize -> Vec<T>",
+        ]
+    );
+}
+
+#[test]
+fn generic_names() {
+    let index = vec_index();
+    let searcher = Searcher::new(&index);
+    let query = parse("Vec<T>, Strng -> Option<T>").unwrap();
+    assert_eq!(searcher.generic_names(&query), ["T", "Strng"]);
+}
@@ -0,0 +1,161 @@
+//! Discovery of the items that make up the public API of a crate.
+
+use std::collections::BTreeMap;
+
+use rustdoc_json_types::{Crate, Id, Item, ItemEnum, ItemKind, ItemSummary, MacroKind, Visibility};
+
+/// An item that can be named from outside of its crate.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Entry<'a> {
+    Local(&'a Item),
+    /// A re-export of an item of another crate, of which only the path is known.
+    External(&'a ItemSummary),
+}
+
+impl Entry<'_> {
+    pub(crate) fn kind(&self) -> ItemKind {
+        match self {
+            Entry::Local(item) => item_kind(item),
+            Entry::External(summary) => summary.kind,
+        }
+    }
+}
+
+pub(crate) struct Api<'a> {
+    /// Every public item, by the path under which it can be named. An item that is re-exported
+    /// appears once for each of its paths.
+    pub(crate) items: BTreeMap<String, Entry<'a>>,
+    krate: &'a Crate,
+    /// The modules that are being visited, to stop at glob imports that form a cycle.
+    stack: Vec<Id>,
+}
+
+impl<'a> Api<'a> {
+    pub(crate) fn new(krate: &'a Crate) -> Self {
+        let mut api = Api { items: BTreeMap::new(), krate, stack: Vec::new() };
+        if let Some(root) = krate.index.get(&krate.root) {
+            api.visit_module(root, root.name.as_deref().unwrap_or_default());
+        }
+        api
+    }
+
+    fn visit_module(&mut self, module: &'a Item, path: &str) {
+        let ItemEnum::Module(m) = &module.inner else { return };
+        if self.stack.contains(&module.id) {
+            return;
+        }
+        self.stack.push(module.id);
+        for id in &m.items {
+            if let Some(item) = self.krate.index.get(id)
+                && item.visibility == Visibility::Public
+            {
+                self.visit_item(item, path);
+            }
+        }
+        self.stack.pop();
+    }
+
+    fn visit_item(&mut self, item: &'a Item, parent: &str) {
+        match &item.inner {
+            ItemEnum::Use(import) => {
+                let Some(id) = import.id else { return };
+                let Some(target) = self.krate.index.get(&id) else {
+                    if let Some(summary) = self.krate.paths.get(&id)
+                        && !import.is_glob
+                    {
+                        self.insert(format!("{parent}::{}", import.name), Entry::External(summary));
+                    }
+                    return;
+                };
+                if !import.is_glob {
+                    self.insert(format!("{parent}::{}", import.name), Entry::Local(target));
+                    return;
+                }
+                match &target.inner {
+                    ItemEnum::Module(_) => self.visit_module(target, parent),
+                    ItemEnum::Enum(e) => {
+                        for variant in e.variants.iter().filter_map(|id| self.krate.index.get(id)) {
+                            self.visit_item(variant, parent);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // Implementations are compared along with the types they are for.
+            ItemEnum::Impl(_) | ItemEnum::ExternCrate { .. } => {}
+            _ => {
+                if let Some(name) = &item.name {
+                    self.insert(format!("{parent}::{name}"), Entry::Local(item));
+                }
+            }
+        }
+    }
+
+    fn insert(&mut self, path: String, entry: Entry<'a>) {
+        if let Entry::Local(item) = entry
+            && let ItemEnum::Module(_) = item.inner
+        {
+            self.visit_module(item, &path);
+        }
+        self.items.entry(path).or_insert(entry);
+    }
+}
+
+pub(crate) fn item_kind(item: &Item) -> ItemKind {
+    match &item.inner {
+        ItemEnum::Module(_) => ItemKind::Module,
+        ItemEnum::ExternCrate { .. } => ItemKind::ExternCrate,
+        ItemEnum::Use(_) => ItemKind::Use,
+        ItemEnum::Union(_) => ItemKind::Union,
+        ItemEnum::Struct(_) => ItemKind::Struct,
+        ItemEnum::StructField(_) => ItemKind::StructField,
+        ItemEnum::Enum(_) => ItemKind::Enum,
+        ItemEnum::Variant(_) => ItemKind::Variant,
+        ItemEnum::Function(_) => ItemKind::Function,
+        ItemEnum::Trait(_) => ItemKind::Trait,
+        ItemEnum::TraitAlias(_) => ItemKind::TraitAlias,
+        ItemEnum::Impl(_) => ItemKind::Impl,
+        ItemEnum::TypeAlias(_) => ItemKind::TypeAlias,
+        ItemEnum::Constant { .. } => ItemKind::Constant,
+        ItemEnum::Static(_) => ItemKind::Static,
+        ItemEnum::ExternType => ItemKind::ExternType,
+        ItemEnum::Macro(_) => ItemKind::Macro,
+        ItemEnum::ProcMacro(proc_macro) => match proc_macro.kind {
+            MacroKind::Bang => ItemKind::Macro,
+            MacroKind::Attr => ItemKind::ProcAttribute,
+            MacroKind::Derive => ItemKind::ProcDerive,
+        },
+        ItemEnum::Primitive(_) => ItemKind::Primitive,
+        ItemEnum::AssocConst { .. } => ItemKind::AssocConst,
+        ItemEnum::AssocType { .. } => ItemKind::AssocType,
+    }
+}
+
+pub(crate) fn kind_name(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Module => "module",
+        ItemKind::ExternCrate => "extern crate",
+        ItemKind::Use => "import",
+        ItemKind::Struct => "struct",
+        ItemKind::StructField => "field",
+        ItemKind::Union => "union",
+        ItemKind::Enum => "enum",
+        ItemKind::Variant => "variant",
+        ItemKind::Function => "function",
+        ItemKind::TypeAlias => "type alias",
+        ItemKind::Constant => "constant",
+        ItemKind::Trait => "trait",
+        ItemKind::TraitAlias => "trait alias",
+        ItemKind::Impl => "impl",
+        ItemKind::Static => "static",
+        ItemKind::ExternType => "extern type",
+        ItemKind::Macro => "macro",
+        ItemKind::ProcAttribute => "attribute macro",
+        ItemKind::ProcDerive => "derive macro",
+        ItemKind::AssocConst => "associated constant",
+        ItemKind::AssocType => "associated type",
+        ItemKind::Primitive => "primitive",
+        ItemKind::Keyword => "keyword",
+        ItemKind::Attribute => "attribute",
+    }
+}
@@ -0,0 +1,787 @@
+//! Comparison of two versions of the public API of a crate.
+//!
+//! Changes are classified following the [SemVer compatibility] guidelines of Cargo. Items are
+//! matched by path, and their signatures are compared as printed by [`crate::render`], so renaming
+//! a generic parameter is reported as a change even though it is not one.
+//!
+//! [SemVer compatibility]: https://doc.rust-lang.org/cargo/reference/semver.html
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use rustdoc_json_types::{
+    Attribute, AttributeRepr, Crate, Enum, Function, GenericBound, GenericParamDefKind, Generics,
+    Id, Impl, Item, ItemEnum, ReprKind, Struct, StructKind, Trait, TraitBoundModifier, Type,
+    VariantKind, Visibility, WherePredicate,
+};
+use serde::Serialize;
+
+use crate::api::{Api, Entry, item_kind, kind_name};
+use crate::render;
+
+#[cfg(test)]
+mod tests;
+
+/// The part of the version of a crate that a change requires to bump.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Level {
+    /// The change does not affect the API.
+    Patch,
+    /// The change extends the API in a backwards compatible way.
+    Minor,
+    /// The change can break code that uses the crate.
+    Major,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Level::Patch => "patch",
+            Level::Minor => "minor",
+            Level::Major => "major",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct Change {
+    pub(crate) level: Level,
+    /// The path of the item that changed.
+    pub(crate) path: String,
+    pub(crate) message: String,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.level, self.path, self.message)
+    }
+}
+
+/// Lists the changes between the public API of `old` and the one of `new`, the most significant
+/// ones first.
+pub(crate) fn diff(old: &Crate, new: &Crate) -> Vec<Change> {
+    let old_api = Api::new(old);
+    let new_api = Api::new(new);
+    let mut differ = Differ { old, new, changes: Vec::new() };
+
+    for (path, old_entry) in &old_api.items {
+        match new_api.items.get(path) {
+            Some(new_entry) => differ.compare_entries(path, *old_entry, *new_entry),
+            None => {
+                differ.push(Level::Major, path, format!("{} removed", kind_name(old_entry.kind())))
+            }
+        }
+    }
+    for (path, new_entry) in &new_api.items {
+        if !old_api.items.contains_key(path) {
+            differ.push(Level::Minor, path, format!("{} added", kind_name(new_entry.kind())));
+        }
+    }
+
+    let mut changes = differ.changes;
+    changes.sort_by(|a, b| b.level.cmp(&a.level).then_with(|| a.path.cmp(&b.path)));
+    changes
+}
+
+struct Differ<'a> {
+    old: &'a Crate,
+    new: &'a Crate,
+    changes: Vec<Change>,
+}
+
+impl<'a> Differ<'a> {
+    fn push(&mut self, level: Level, path: &str, message: String) {
+        self.changes.push(Change { level, path: path.to_owned(), message });
+    }
+
+    fn compare_entries(&mut self, path: &str, old: Entry<'a>, new: Entry<'a>) {
+        match (old, new) {
+            (Entry::Local(old), Entry::Local(new)) => self.compare_items(path, old, new),
+            (Entry::External(old), Entry::External(new)) => {
+                if old.path != new.path {
+                    let message = format!(
+                        "now re-exports `{}` instead of `{}`",
+                        new.path.join("::"),
+                        old.path.join("::"),
+                    );
+                    self.push(Level::Major, path, message);
+                }
+            }
+            (Entry::Local(_), Entry::External(new)) => {
+                let message = format!("now re-exports `{}`", new.path.join("::"));
+                self.push(Level::Major, path, message);
+            }
+            (Entry::External(old), Entry::Local(_)) => {
+                let message = format!("no longer re-exports `{}`", old.path.join("::"));
+                self.push(Level::Major, path, message);
+            }
+        }
+    }
+
+    fn compare_items(&mut self, path: &str, old: &'a Item, new: &'a Item) {
+        let (old_kind, new_kind) = (item_kind(old), item_kind(new));
+        if old_kind != new_kind {
+            let message =
+                format!("changed from a {} to a {}", kind_name(old_kind), kind_name(new_kind));
+            self.push(Level::Major, path, message);
+            return;
+        }
+        self.compare_attrs(path, old, new);
+
+        match (&old.inner, &new.inner) {
+            (ItemEnum::Function(o), ItemEnum::Function(n)) => self.compare_functions(path, o, n),
+            (ItemEnum::Struct(o), ItemEnum::Struct(n)) => {
+                self.compare_generics(path, &o.generics, &n.generics);
+                self.compare_structs(path, (old, o), (new, n));
+                self.compare_impls(path, &o.impls, &n.impls);
+            }
+            (ItemEnum::Union(o), ItemEnum::Union(n)) => {
+                self.compare_generics(path, &o.generics, &n.generics);
+                let old_fields = Fields::named(self.old, old, &o.fields, o.has_stripped_fields);
+                let new_fields = Fields::named(self.new, new, &n.fields, n.has_stripped_fields);
+                self.compare_fields(path, old_fields, new_fields, true);
+                self.compare_impls(path, &o.impls, &n.impls);
+            }
+            (ItemEnum::Enum(o), ItemEnum::Enum(n)) => {
+                self.compare_generics(path, &o.generics, &n.generics);
+                self.compare_variants(path, (old, o), n);
+                self.compare_impls(path, &o.impls, &n.impls);
+            }
+            (ItemEnum::Trait(o), ItemEnum::Trait(n)) => self.compare_traits(path, o, n),
+            (ItemEnum::TraitAlias(o), ItemEnum::TraitAlias(n)) => {
+                self.compare_generics(path, &o.generics, &n.generics);
+                let old_bounds = render::bounds(self.old, &o.params);
+                let new_bounds = render::bounds(self.new, &n.params);
+                if old_bounds != new_bounds {
+                    let message = format!("bounds changed from `{old_bounds}` to `{new_bounds}`");
+                    self.push(Level::Major, path, message);
+                }
+            }
+            (ItemEnum::TypeAlias(o), ItemEnum::TypeAlias(n)) => {
+                self.compare_generics(path, &o.generics, &n.generics);
+                self.compare_types(path, "aliased type", &o.type_, &n.type_);
+            }
+            (
+                ItemEnum::Constant { type_: old_type, const_: old_const },
+                ItemEnum::Constant { type_: new_type, const_: new_const },
+            ) => {
+                self.compare_types(path, "type", old_type, new_type);
+                if old_const.expr != new_const.expr {
+                    let message =
+                        format!("value changed from `{}` to `{}`", old_const.expr, new_const.expr);
+                    self.push(Level::Patch, path, message);
+                }
+            }
+            (ItemEnum::Static(o), ItemEnum::Static(n)) => {
+                self.compare_types(path, "type", &o.type_, &n.type_);
+                if o.is_mutable != n.is_mutable {
+                    let message = if n.is_mutable { "now mutable" } else { "no longer mutable" };
+                    self.push(Level::Major, path, message.to_owned());
+                }
+                if o.is_unsafe != n.is_unsafe {
+                    let message = if n.is_unsafe { "now `unsafe`" } else { "no longer `unsafe`" };
+                    self.push(Level::Major, path, message.to_owned());
+                }
+                if o.expr != n.expr {
+                    self.push(Level::Patch, path, "initializer changed".to_owned());
+                }
+            }
+            (ItemEnum::Macro(o), ItemEnum::Macro(n)) => {
+                if o != n {
+                    self.push(Level::Patch, path, "definition changed".to_owned());
+                }
+            }
+            (ItemEnum::ProcMacro(o), ItemEnum::ProcMacro(n)) => {
+                for helper in o.helpers.iter().filter(|h| !n.helpers.contains(h)) {
+                    self.push(Level::Major, path, format!("helper attribute `{helper}` removed"));
+                }
+                for helper in n.helpers.iter().filter(|h| !o.helpers.contains(h)) {
+                    self.push(Level::Minor, path, format!("helper attribute `{helper}` added"));
+                }
+            }
+            (
+                ItemEnum::AssocConst { type_: old_type, value: old_value },
+                ItemEnum::AssocConst { type_: new_type, value: new_value },
+            ) => {
+                self.compare_types(path, "type", old_type, new_type);
+                if let (Some(old_value), Some(new_value)) = (old_value, new_value)
+                    && old_value != new_value
+                {
+                    let message = format!("value changed from `{old_value}` to `{new_value}`");
+                    self.push(Level::Patch, path, message);
+                }
+            }
+            (
+                ItemEnum::AssocType { generics: old_generics, bounds: old_bounds, type_: old_type },
+                ItemEnum::AssocType { generics: new_generics, bounds: new_bounds, type_: new_type },
+            ) => {
+                self.compare_generics(path, old_generics, new_generics);
+                // Implementations have to satisfy the bounds and users can rely on them, so any
+                // change to them can break code.
+                let old_bounds = bound_set(self.old, old_bounds);
+                let new_bounds = bound_set(self.new, new_bounds);
+                for bound in new_bounds.difference(&old_bounds) {
+                    self.push(Level::Major, path, format!("bound `{bound}` added"));
+                }
+                for bound in old_bounds.difference(&new_bounds) {
+                    self.push(Level::Major, path, format!("bound `{bound}` removed"));
+                }
+                if let (Some(old_type), Some(new_type)) = (old_type, new_type) {
+                    self.compare_types(path, "type", old_type, new_type);
+                }
+            }
+            (ItemEnum::Primitive(o), ItemEnum::Primitive(n)) => {
+                self.compare_impls(path, &o.impls, &n.impls)
+            }
+            // Modules are compared through the items they contain.
+            _ => {}
+        }
+    }
+
+    fn compare_attrs(&mut self, path: &str, old: &Item, new: &Item) {
+        match (is_non_exhaustive(old), is_non_exhaustive(new)) {
+            (false, true) => {
+                self.push(Level::Major, path, "marked `#[non_exhaustive]`".to_owned());
+            }
+            (true, false) => {
+                self.push(Level::Minor, path, "no longer `#[non_exhaustive]`".to_owned());
+            }
+            _ => {}
+        }
+
+        match (repr(old), repr(new)) {
+            (Some(old_repr), new_repr) if Some(old_repr) != new_repr => {
+                let message = format!(
+                    "representation changed from `{}` to `{}`",
+                    print_repr(Some(old_repr)),
+                    print_repr(new_repr),
+                );
+                self.push(Level::Major, path, message);
+            }
+            (None, Some(new_repr)) => {
+                let message = format!("marked `{}`", print_repr(Some(new_repr)));
+                self.push(Level::Minor, path, message);
+            }
+            _ => {}
+        }
+
+        let is_must_use =
+            |item: &Item| item.attrs.iter().any(|attr| matches!(attr, Attribute::MustUse { .. }));
+        if !is_must_use(old) && is_must_use(new) {
+            self.push(Level::Minor, path, "marked `#[must_use]`".to_owned());
+        }
+
+        match (&old.deprecation, &new.deprecation) {
+            (None, Some(_)) => self.push(Level::Minor, path, "deprecated".to_owned()),
+            (Some(_), None) => self.push(Level::Patch, path, "no longer deprecated".to_owned()),
+            _ => {}
+        }
+    }
+
+    fn compare_types(&mut self, path: &str, what: &str, old: &Type, new: &Type) {
+        let (old, new) = (render::ty(self.old, old), render::ty(self.new, new));
+        if old != new {
+            self.push(Level::Major, path, format!("{what} changed from `{old}` to `{new}`"));
+        }
+    }
+
+    fn compare_functions(&mut self, path: &str, old: &Function, new: &Function) {
+        let (o, n) = (&old.header, &new.header);
+        match (o.is_const, n.is_const) {
+            (true, false) => self.push(Level::Major, path, "no longer `const`".to_owned()),
+            (false, true) => self.push(Level::Minor, path, "now `const`".to_owned()),
+            _ => {}
+        }
+        match (o.is_unsafe, n.is_unsafe) {
+            (false, true) => self.push(Level::Major, path, "now `unsafe`".to_owned()),
+            (true, false) => self.push(Level::Minor, path, "no longer `unsafe`".to_owned()),
+            _ => {}
+        }
+        if o.is_async != n.is_async {
+            let message = if n.is_async { "now `async`" } else { "no longer `async`" };
+            self.push(Level::Major, path, message.to_owned());
+        }
+        if o.abi != n.abi {
+            let message =
+                format!("ABI changed from `{}` to `{}`", render::abi(&o.abi), render::abi(&n.abi));
+            self.push(Level::Major, path, message);
+        }
+
+        self.compare_generics(path, &old.generics, &new.generics);
+        let old_sig = render::signature(self.old, &old.sig);
+        let new_sig = render::signature(self.new, &new.sig);
+        if old_sig != new_sig {
+            let message = format!("signature changed from `fn{old_sig}` to `fn{new_sig}`");
+            self.push(Level::Major, path, message);
+        }
+    }
+
+    fn compare_generics(&mut self, path: &str, old: &Generics, new: &Generics) {
+        let (old_params, new_params) = (generic_params(old), generic_params(new));
+        for name in old_params.keys().filter(|name| !new_params.contains_key(*name)) {
+            self.push(Level::Major, path, format!("generic parameter `{name}` removed"));
+        }
+        for (name, kind) in new_params.iter().filter(|(name, _)| !old_params.contains_key(*name)) {
+            let has_default = match kind {
+                GenericParamDefKind::Type { default, .. } => default.is_some(),
+                GenericParamDefKind::Const { default, .. } => default.is_some(),
+                GenericParamDefKind::Lifetime { .. } => false,
+            };
+            if has_default {
+                let message = format!("generic parameter `{name}` added with a default");
+                self.push(Level::Minor, path, message);
+            } else {
+                self.push(Level::Major, path, format!("generic parameter `{name}` added"));
+            }
+        }
+
+        let old_requirements = requirements(self.old, old);
+        let new_requirements = requirements(self.new, new);
+        for requirement in new_requirements.difference(&old_requirements) {
+            self.push(Level::Major, path, format!("bound `{requirement}` added"));
+        }
+        for requirement in old_requirements.difference(&new_requirements) {
+            self.push(Level::Minor, path, format!("bound `{requirement}` removed"));
+        }
+    }
+
+    fn compare_structs(
+        &mut self,
+        path: &str,
+        (old_item, old): (&'a Item, &'a Struct),
+        (new_item, new): (&'a Item, &'a Struct),
+    ) {
+        let (old_fields, new_fields) = match (&old.kind, &new.kind) {
+            (StructKind::Unit, StructKind::Unit) => return,
+            (StructKind::Tuple(o), StructKind::Tuple(n)) => (
+                Fields::positional(self.old, old_item, o),
+                Fields::positional(self.new, new_item, n),
+            ),
+            (
+                StructKind::Plain { fields: o, has_stripped_fields: old_stripped },
+                StructKind::Plain { fields: n, has_stripped_fields: new_stripped },
+            ) => (
+                Fields::named(self.old, old_item, o, *old_stripped),
+                Fields::named(self.new, new_item, n, *new_stripped),
+            ),
+            (o, n) => {
+                let message =
+                    format!("changed from a {} to a {}", struct_kind_name(o), struct_kind_name(n));
+                self.push(Level::Major, path, message);
+                return;
+            }
+        };
+        self.compare_fields(path, old_fields, new_fields, false);
+    }
+
+    fn compare_fields(&mut self, path: &str, old: Fields<'a>, new: Fields<'a>, is_union: bool) {
+        for (name, old_type) in &old.fields {
+            let field_path = format!("{path}::{name}");
+            match new.fields.get(name) {
+                Some(new_type) => self.compare_types(&field_path, "type", old_type, new_type),
+                None => self.push(Level::Major, &field_path, "field removed".to_owned()),
+            }
+        }
+        for name in new.fields.keys().filter(|name| !old.fields.contains_key(*name)) {
+            let field_path = format!("{path}::{name}");
+            // Struct literals and patterns have to name every field of an exhaustive struct, while
+            // union literals only name one.
+            if old.is_exhaustive && !is_union {
+                let message = "field added to a type without private fields".to_owned();
+                self.push(Level::Major, &field_path, message);
+            } else {
+                self.push(Level::Minor, &field_path, "field added".to_owned());
+            }
+        }
+        if !old.has_private && new.has_private {
+            self.push(Level::Major, path, "private field added".to_owned());
+        }
+    }
+
+    fn compare_variants(
+        &mut self,
+        path: &str,
+        (old_item, old): (&'a Item, &'a Enum),
+        new: &'a Enum,
+    ) {
+        let old_variants = named_items(self.old, &old.variants);
+        let new_variants = named_items(self.new, &new.variants);
+        let is_exhaustive = !old.has_stripped_variants && !is_non_exhaustive(old_item);
+
+        for (name, old_variant) in &old_variants {
+            let variant_path = format!("{path}::{name}");
+            match new_variants.get(name) {
+                Some(new_variant) => self.compare_variant(&variant_path, old_variant, new_variant),
+                None => self.push(Level::Major, &variant_path, "variant removed".to_owned()),
+            }
+        }
+        for name in new_variants.keys().filter(|name| !old_variants.contains_key(*name)) {
+            let variant_path = format!("{path}::{name}");
+            if is_exhaustive {
+                let message = "variant added to an exhaustive enum".to_owned();
+                self.push(Level::Major, &variant_path, message);
+            } else {
+                self.push(Level::Minor, &variant_path, "variant added".to_owned());
+            }
+        }
+    }
+
+    fn compare_variant(&mut self, path: &str, old_item: &'a Item, new_item: &'a Item) {
+        self.compare_attrs(path, old_item, new_item);
+        let (ItemEnum::Variant(old), ItemEnum::Variant(new)) = (&old_item.inner, &new_item.inner)
+        else {
+            return;
+        };
+
+        if let (Some(old_discr), Some(new_discr)) = (&old.discriminant, &new.discriminant)
+            && old_discr.value != new_discr.value
+        {
+            let message =
+                format!("discriminant changed from `{}` to `{}`", old_discr.value, new_discr.value);
+            self.push(Level::Major, path, message);
+        }
+
+        let (old_fields, new_fields) = match (&old.kind, &new.kind) {
+            (VariantKind::Plain, VariantKind::Plain) => return,
+            (VariantKind::Tuple(o), VariantKind::Tuple(n)) => (
+                Fields::positional(self.old, old_item, o),
+                Fields::positional(self.new, new_item, n),
+            ),
+            (
+                VariantKind::Struct { fields: o, has_stripped_fields: old_stripped },
+                VariantKind::Struct { fields: n, has_stripped_fields: new_stripped },
+            ) => (
+                Fields::named(self.old, old_item, o, *old_stripped),
+                Fields::named(self.new, new_item, n, *new_stripped),
+            ),
+            (o, n) => {
+                let message = format!(
+                    "changed from a {} to a {}",
+                    variant_kind_name(o),
+                    variant_kind_name(n)
+                );
+                self.push(Level::Major, path, message);
+                return;
+            }
+        };
+        self.compare_fields(path, old_fields, new_fields, false);
+    }
+
+    fn compare_traits(&mut self, path: &str, old: &'a Trait, new: &'a Trait) {
+        if old.is_unsafe != new.is_unsafe {
+            let message = if new.is_unsafe { "now `unsafe`" } else { "no longer `unsafe`" };
+            self.push(Level::Major, path, message.to_owned());
+        }
+        match (old.is_dyn_compatible, new.is_dyn_compatible) {
+            (true, false) => self.push(Level::Major, path, "no longer dyn compatible".to_owned()),
+            (false, true) => self.push(Level::Minor, path, "now dyn compatible".to_owned()),
+            _ => {}
+        }
+        self.compare_generics(path, &old.generics, &new.generics);
+
+        // Implementations have to satisfy new supertraits, and users can rely on the old ones.
+        let old_bounds = bound_set(self.old, &old.bounds);
+        let new_bounds = bound_set(self.new, &new.bounds);
+        for bound in new_bounds.difference(&old_bounds) {
+            self.push(Level::Major, path, format!("supertrait `{bound}` added"));
+        }
+        for bound in old_bounds.difference(&new_bounds) {
+            self.push(Level::Major, path, format!("supertrait `{bound}` removed"));
+        }
+
+        let old_items = named_items(self.old, &old.items);
+        let new_items = named_items(self.new, &new.items);
+        for (name, old_item) in &old_items {
+            let item_path = format!("{path}::{name}");
+            let Some(new_item) = new_items.get(name) else {
+                self.push(Level::Major, &item_path, format!("{} removed", member_kind(old_item)));
+                continue;
+            };
+            self.compare_items(&item_path, old_item, new_item);
+            if !is_required(old_item) && is_required(new_item) {
+                self.push(Level::Major, &item_path, "default removed".to_owned());
+            }
+        }
+        for (name, new_item) in new_items.iter().filter(|(name, _)| !old_items.contains_key(*name))
+        {
+            let item_path = format!("{path}::{name}");
+            if is_required(new_item) {
+                let message = format!("required {} added", member_kind(new_item));
+                self.push(Level::Major, &item_path, message);
+            } else {
+                let message = format!("provided {} added", member_kind(new_item));
+                self.push(Level::Minor, &item_path, message);
+            }
+        }
+    }
+
+    /// Compares the implementations of a type: the associated items of its inherent impls, and
+    /// the traits that it implements, including the auto traits.
+    fn compare_impls(&mut self, path: &str, old: &[Id], new: &[Id]) {
+        let old = Impls::new(self.old, old);
+        let new = Impls::new(self.new, new);
+
+        for (name, old_item) in &old.inherent {
+            let item_path = format!("{path}::{name}");
+            match new.inherent.get(name) {
+                Some(new_item) => self.compare_items(&item_path, old_item, new_item),
+                None => {
+                    let message = format!("{} removed", member_kind(old_item));
+                    self.push(Level::Major, &item_path, message);
+                }
+            }
+        }
+        for (name, new_item) in
+            new.inherent.iter().filter(|(name, _)| !old.inherent.contains_key(*name))
+        {
+            let item_path = format!("{path}::{name}");
+            self.push(Level::Minor, &item_path, format!("{} added", member_kind(new_item)));
+        }
+
+        for (trait_, old_impl) in &old.traits {
+            match new.traits.get(trait_) {
+                Some(new_impl) if !new_impl.is_negative => {
+                    if old_impl.is_negative {
+                        self.push(Level::Minor, path, format!("now implements `{trait_}`"));
+                        continue;
+                    }
+                    let old_requirements = requirements(self.old, &old_impl.generics);
+                    let new_requirements = requirements(self.new, &new_impl.generics);
+                    for requirement in new_requirements.difference(&old_requirements) {
+                        let message =
+                            format!("implementation of `{trait_}` now requires `{requirement}`");
+                        self.push(Level::Major, path, message);
+                    }
+                    for requirement in old_requirements.difference(&new_requirements) {
+                        let message = format!(
+                            "implementation of `{trait_}` no longer requires `{requirement}`"
+                        );
+                        self.push(Level::Minor, path, message);
+                    }
+                }
+                _ if old_impl.is_negative => {}
+                _ => self.push(Level::Major, path, format!("no longer implements `{trait_}`")),
+            }
+        }
+        for (trait_, new_impl) in &new.traits {
+            if !old.traits.contains_key(trait_) && !new_impl.is_negative {
+                self.push(Level::Minor, path, format!("now implements `{trait_}`"));
+            }
+        }
+    }
+}
+
+/// The public fields of a struct, union or variant.
+struct Fields<'a> {
+    /// The types of the fields, by name or position.
+    fields: BTreeMap<String, &'a Type>,
+    has_private: bool,
+    /// Whether the fields can be listed exhaustively in a literal or a pattern outside of the
+    /// crate.
+    is_exhaustive: bool,
+}
+
+impl<'a> Fields<'a> {
+    fn named(krate: &'a Crate, parent: &Item, ids: &[Id], has_stripped_fields: bool) -> Self {
+        let fields = ids
+            .iter()
+            .filter_map(|id| krate.index.get(id))
+            .filter_map(|field| match (&field.name, &field.inner) {
+                (Some(name), ItemEnum::StructField(ty)) => Some((name.clone(), ty)),
+                _ => None,
+            })
+            .collect();
+        Fields::new(parent, fields, has_stripped_fields)
+    }
+
+    /// The fields of a tuple struct or variant, where private fields are `None`.
+    fn positional(krate: &'a Crate, parent: &Item, ids: &[Option<Id>]) -> Self {
+        let fields = ids
+            .iter()
+            .enumerate()
+            .filter_map(|(i, id)| Some((i, krate.index.get(id.as_ref()?)?)))
+            .filter_map(|(i, field)| match &field.inner {
+                ItemEnum::StructField(ty) => Some((i.to_string(), ty)),
+                _ => None,
+            })
+            .collect();
+        Fields::new(parent, fields, ids.iter().any(Option::is_none))
+    }
+
+    fn new(parent: &Item, fields: BTreeMap<String, &'a Type>, has_private: bool) -> Self {
+        Fields { fields, has_private, is_exhaustive: !has_private && !is_non_exhaustive(parent) }
+    }
+}
+
+/// The implementations of a type.
+struct Impls<'a> {
+    /// The public associated items of the inherent impls, by name.
+    inherent: BTreeMap<&'a str, &'a Item>,
+    /// The trait impls, by the path of the trait with its generic arguments.
+    traits: BTreeMap<String, &'a Impl>,
+}
+
+impl<'a> Impls<'a> {
+    fn new(krate: &'a Crate, ids: &[Id]) -> Self {
+        let mut impls = Impls { inherent: BTreeMap::new(), traits: BTreeMap::new() };
+        for item in ids.iter().filter_map(|id| krate.index.get(id)) {
+            let ItemEnum::Impl(impl_) = &item.inner else { continue };
+            match &impl_.trait_ {
+                Some(trait_) => {
+                    impls.traits.entry(render::path(krate, trait_)).or_insert(impl_);
+                }
+                None => {
+                    for item in impl_.items.iter().filter_map(|id| krate.index.get(id)) {
+                        if item.visibility == Visibility::Public
+                            && let Some(name) = &item.name
+                        {
+                            impls.inherent.entry(name.as_str()).or_insert(item);
+                        }
+                    }
+                }
+            }
+        }
+        impls
+    }
+}
+
+fn named_items<'a>(krate: &'a Crate, ids: &[Id]) -> BTreeMap<&'a str, &'a Item> {
+    ids.iter()
+        .filter_map(|id| krate.index.get(id))
+        .filter_map(|item| Some((item.name.as_deref()?, item)))
+        .collect()
+}
+
+fn generic_params(generics: &Generics) -> BTreeMap<&str, &GenericParamDefKind> {
+    generics
+        .params
+        .iter()
+        .filter(|param| match &param.kind {
+            GenericParamDefKind::Lifetime { .. } => false,
+            GenericParamDefKind::Type { is_synthetic, .. } => !is_synthetic,
+            GenericParamDefKind::Const { .. } => true,
+        })
+        .map(|param| (param.name.as_str(), &param.kind))
+        .collect()
+}
+
+/// Lists the bounds that `generics` puts on its parameters, one per entry.
+///
+/// Type parameters are `Sized` unless they are relaxed with `?Sized`, so that relaxing that bound
+/// is reported like removing any other one.
+fn requirements(krate: &Crate, generics: &Generics) -> BTreeSet<String> {
+    let mut requirements = BTreeSet::new();
+    let mut bounds: Vec<(String, &GenericBound)> = Vec::new();
+    let mut sized = BTreeSet::new();
+
+    for param in &generics.params {
+        match &param.kind {
+            GenericParamDefKind::Lifetime { outlives } => {
+                requirements.extend(outlives.iter().map(|o| format!("{}: {o}", param.name)));
+            }
+            // Synthetic parameters come from `impl Trait` arguments, which are compared as part
+            // of the signature.
+            GenericParamDefKind::Type { is_synthetic: true, .. } => {}
+            GenericParamDefKind::Type { bounds: b, .. } => {
+                sized.insert(param.name.clone());
+                bounds.extend(b.iter().map(|b| (param.name.clone(), b)));
+            }
+            GenericParamDefKind::Const { .. } => {}
+        }
+    }
+    for predicate in &generics.where_predicates {
+        match predicate {
+            WherePredicate::BoundPredicate { type_, bounds: b, generic_params } => {
+                let subject = format!(
+                    "{}{}",
+                    render::higher_ranked(krate, generic_params),
+                    render::ty(krate, type_)
+                );
+                bounds.extend(b.iter().map(|b| (subject.clone(), b)));
+            }
+            WherePredicate::LifetimePredicate { lifetime, outlives } => {
+                requirements.extend(outlives.iter().map(|o| format!("{lifetime}: {o}")));
+            }
+            WherePredicate::EqPredicate { .. } => {
+                requirements.insert(render::where_predicate(krate, predicate));
+            }
+        }
+    }
+
+    for (subject, bound) in bounds {
+        if let GenericBound::TraitBound { modifier: TraitBoundModifier::Maybe, .. } = bound {
+            sized.remove(&subject);
+        } else {
+            requirements.insert(format!("{subject}: {}", render::bound(krate, bound)));
+        }
+    }
+    requirements.extend(sized.into_iter().map(|name| format!("{name}: Sized")));
+    requirements
+}
+
+fn bound_set(krate: &Crate, bounds: &[GenericBound]) -> BTreeSet<String> {
+    bounds.iter().map(|bound| render::bound(krate, bound)).collect()
+}
+
+fn is_non_exhaustive(item: &Item) -> bool {
+    item.attrs.contains(&Attribute::NonExhaustive)
+}
+
+/// Whether implementations of a trait have to define this trait item.
+fn is_required(item: &Item) -> bool {
+    match &item.inner {
+        ItemEnum::Function(function) => !function.has_body,
+        ItemEnum::AssocConst { value, .. } => value.is_none(),
+        ItemEnum::AssocType { type_, .. } => type_.is_none(),
+        _ => false,
+    }
+}
+
+fn member_kind(item: &Item) -> &'static str {
+    match &item.inner {
+        ItemEnum::Function(_) => "method",
+        _ => kind_name(item_kind(item)),
+    }
+}
+
+fn repr(item: &Item) -> Option<&AttributeRepr> {
+    item.attrs.iter().find_map(|attr| match attr {
+        Attribute::Repr(repr) => Some(repr),
+        _ => None,
+    })
+}
+
+fn print_repr(repr: Option<&AttributeRepr>) -> String {
+    let Some(repr) = repr else { return "#[repr(Rust)]".to_owned() };
+    let mut parts = Vec::new();
+    match repr.kind {
+        ReprKind::Rust => {}
+        ReprKind::C => parts.push("C".to_owned()),
+        ReprKind::Transparent => parts.push("transparent".to_owned()),
+        ReprKind::Simd => parts.push("simd".to_owned()),
+    }
+    parts.extend(repr.int.clone());
+    parts.extend(repr.align.map(|align| format!("align({align})")));
+    parts.extend(repr.packed.map(|packed| format!("packed({packed})")));
+    if parts.is_empty() {
+        parts.push("Rust".to_owned());
+    }
+    format!("#[repr({})]", parts.join(", "))
+}
+
+fn struct_kind_name(kind: &StructKind) -> &'static str {
+    match kind {
+        StructKind::Unit => "unit struct",
+        StructKind::Tuple(_) => "tuple struct",
+        StructKind::Plain { .. } => "struct with named fields",
+    }
+}
+
+fn variant_kind_name(kind: &VariantKind) -> &'static str {
+    match kind {
+        VariantKind::Plain => "unit variant",
+        VariantKind::Tuple(_) => "tuple variant",
+        VariantKind::Struct { .. } => "struct variant",
+    }
+}
@@ -0,0 +1,318 @@
+use rustdoc_json_types::{
+    Abi, FORMAT_VERSION, FunctionHeader, FunctionSignature, FxHashMap, GenericParamDef, ItemKind,
+    ItemSummary, Module, Path, Target,
+};
+
+use super::*;
+
+/// Builds a crate whose root module contains the added items.
+struct Builder {
+    krate: Crate,
+    next_id: u32,
+}
+
+impl Builder {
+    fn new() -> Self {
+        let root = item(Id(0), "krate", ItemEnum::Module(module(vec![])));
+        let krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: FxHashMap::from_iter([(Id(0), root)]),
+            paths: FxHashMap::default(),
+            external_crates: FxHashMap::default(),
+            target: Target { triple: String::new(), target_features: vec![] },
+            format_version: FORMAT_VERSION,
+        };
+        Builder { krate, next_id: 1 }
+    }
+
+    /// Adds an item that is not in any module, like a field or an impl.
+    fn add(&mut self, name: &str, inner: ItemEnum) -> Id {
+        let id = Id(self.next_id);
+        self.next_id += 1;
+        self.krate.index.insert(id, item(id, name, inner));
+        id
+    }
+
+    fn add_to_root(&mut self, name: &str, inner: ItemEnum) -> Id {
+        let id = self.add(name, inner);
+        let ItemEnum::Module(root) = &mut self.item_mut(Id(0)).inner else { unreachable!() };
+        root.items.push(id);
+        id
+    }
+
+    fn item_mut(&mut self, id: Id) -> &mut Item {
+        self.krate.index.get_mut(&id).unwrap()
+    }
+
+    /// Adds an external trait, like `core::marker::Send`.
+    fn add_external_trait(&mut self, path: &[&str]) -> Path {
+        let id = Id(self.next_id);
+        self.next_id += 1;
+        let path = path.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let name = path.last().unwrap().clone();
+        self.krate.paths.insert(id, ItemSummary { crate_id: 1, path, kind: ItemKind::Trait });
+        Path { path: name, id, args: None }
+    }
+
+    fn build(self) -> Crate {
+        self.krate
+    }
+}
+
+fn item(id: Id, name: &str, inner: ItemEnum) -> Item {
+    Item {
+        id,
+        crate_id: 0,
+        name: Some(name.to_owned()),
+        span: None,
+        visibility: Visibility::Public,
+        docs: None,
+        links: FxHashMap::default(),
+        attrs: vec![],
+        deprecation: None,
+        inner,
+    }
+}
+
+fn module(items: Vec<Id>) -> Module {
+    Module { is_crate: false, items, is_stripped: false }
+}
+
+fn generics(params: Vec<GenericParamDef>) -> Generics {
+    Generics { params, where_predicates: vec![] }
+}
+
+fn type_param(name: &str, bounds: Vec<GenericBound>) -> GenericParamDef {
+    GenericParamDef {
+        name: name.to_owned(),
+        kind: GenericParamDefKind::Type { bounds, default: None, is_synthetic: false },
+    }
+}
+
+fn trait_bound(trait_: Path) -> GenericBound {
+    GenericBound::TraitBound { trait_, generic_params: vec![], modifier: TraitBoundModifier::None }
+}
+
+fn primitive(name: &str) -> Type {
+    Type::Primitive(name.to_owned())
+}
+
+fn function(inputs: Vec<Type>, output: Option<Type>, generics: Generics) -> ItemEnum {
+    ItemEnum::Function(Function {
+        sig: FunctionSignature {
+            inputs: inputs.into_iter().map(|ty| ("x".to_owned(), ty)).collect(),
+            output,
+            is_c_variadic: false,
+        },
+        generics,
+        header: FunctionHeader {
+            is_const: false,
+            is_unsafe: false,
+            is_async: false,
+            abi: Abi::Rust,
+        },
+        has_body: true,
+    })
+}
+
+fn plain_struct(b: &mut Builder, fields: &[(&str, Type)], impls: Vec<Id>) -> ItemEnum {
+    let fields =
+        fields.iter().map(|(name, ty)| b.add(name, ItemEnum::StructField(ty.clone()))).collect();
+    ItemEnum::Struct(Struct {
+        kind: StructKind::Plain { fields, has_stripped_fields: false },
+        generics: generics(vec![]),
+        impls,
+    })
+}
+
+fn enum_(b: &mut Builder, variants: &[&str]) -> ItemEnum {
+    let variants = variants
+        .iter()
+        .map(|name| {
+            let variant =
+                rustdoc_json_types::Variant { kind: VariantKind::Plain, discriminant: None };
+            b.add(name, ItemEnum::Variant(variant))
+        })
+        .collect();
+    ItemEnum::Enum(Enum {
+        generics: generics(vec![]),
+        has_stripped_variants: false,
+        variants,
+        impls: vec![],
+    })
+}
+
+fn trait_impl(trait_: Path, for_: Type, is_negative: bool) -> ItemEnum {
+    ItemEnum::Impl(Impl {
+        is_unsafe: false,
+        generics: generics(vec![]),
+        provided_trait_methods: vec![],
+        trait_: Some(trait_),
+        for_,
+        items: vec![],
+        is_negative,
+        is_synthetic: true,
+        blanket_impl: None,
+    })
+}
+
+#[track_caller]
+fn check(old: &Crate, new: &Crate, expected: &[(Level, &str, &str)]) {
+    let changes = diff(old, new);
+    let changes: Vec<_> =
+        changes.iter().map(|c| (c.level, c.path.as_str(), c.message.as_str())).collect();
+    assert_eq!(changes, expected);
+}
+
+#[test]
+fn no_changes() {
+    let mut b = Builder::new();
+    b.add_to_root("f", function(vec![primitive("u32")], None, generics(vec![])));
+    let krate = b.build();
+    check(&krate, &krate, &[]);
+}
+
+#[test]
+fn removed_and_added_items() {
+    let mut old = Builder::new();
+    old.add_to_root("f", function(vec![], None, generics(vec![])));
+    let mut new = Builder::new();
+    new.add_to_root("g", function(vec![], None, generics(vec![])));
+    check(
+        &old.build(),
+        &new.build(),
+        &[
+            (Level::Major, "krate::f", "function removed"),
+            (Level::Minor, "krate::g", "function added"),
+        ],
+    );
+}
+
+#[test]
+fn items_are_found_through_reexports() {
+    let mut old = Builder::new();
+    let f = old.add("f", function(vec![], None, generics(vec![])));
+    let inner = old.add_to_root("inner", ItemEnum::Module(module(vec![f])));
+    old.item_mut(inner).visibility = Visibility::Crate;
+    old.add_to_root(
+        "f",
+        ItemEnum::Use(rustdoc_json_types::Use {
+            source: "inner::f".to_owned(),
+            name: "f".to_owned(),
+            id: Some(f),
+            is_glob: false,
+        }),
+    );
+    check(&old.build(), &Builder::new().build(), &[(Level::Major, "krate::f", "function removed")]);
+}
+
+#[test]
+fn changed_signature() {
+    let mut old = Builder::new();
+    old.add_to_root("f", function(vec![primitive("u32")], None, generics(vec![])));
+    let mut new = Builder::new();
+    new.add_to_root(
+        "f",
+        function(vec![primitive("u64")], Some(primitive("bool")), generics(vec![])),
+    );
+    check(
+        &old.build(),
+        &new.build(),
+        &[(Level::Major, "krate::f", "signature changed from `fn(u32)` to `fn(u64) -> bool`")],
+    );
+}
+
+#[test]
+fn trait_bounds() {
+    let generic = || vec![Type::Generic("T".to_owned())];
+    let mut old = Builder::new();
+    let clone = old.add_external_trait(&["core", "clone", "Clone"]);
+    old.add_to_root(
+        "f",
+        function(generic(), None, generics(vec![type_param("T", vec![trait_bound(clone)])])),
+    );
+    let mut new = Builder::new();
+    let debug = new.add_external_trait(&["core", "fmt", "Debug"]);
+    new.add_to_root(
+        "f",
+        function(generic(), None, generics(vec![type_param("T", vec![trait_bound(debug)])])),
+    );
+    check(
+        &old.build(),
+        &new.build(),
+        &[
+            (Level::Major, "krate::f", "bound `T: core::fmt::Debug` added"),
+            (Level::Minor, "krate::f", "bound `T: core::clone::Clone` removed"),
+        ],
+    );
+}
+
+#[test]
+fn lost_auto_trait_impl() {
+    let self_ty = || Type::ResolvedPath(Path { path: "S".to_owned(), id: Id(100), args: None });
+
+    let mut old = Builder::new();
+    let send = old.add_external_trait(&["core", "marker", "Send"]);
+    let send_impl = old.add("", trait_impl(send, self_ty(), false));
+    let s = plain_struct(&mut old, &[], vec![send_impl]);
+    old.add_to_root("S", s);
+
+    let mut new = Builder::new();
+    let send = new.add_external_trait(&["core", "marker", "Send"]);
+    let send_impl = new.add("", trait_impl(send, self_ty(), true));
+    let s = plain_struct(&mut new, &[], vec![send_impl]);
+    new.add_to_root("S", s);
+
+    let (old, new) = (old.build(), new.build());
+    check(&old, &new, &[(Level::Major, "krate::S", "no longer implements `core::marker::Send`")]);
+    check(&new, &old, &[(Level::Minor, "krate::S", "now implements `core::marker::Send`")]);
+}
+
+#[test]
+fn struct_fields() {
+    let mut old = Builder::new();
+    let s = plain_struct(&mut old, &[("x", primitive("u32")), ("y", primitive("u32"))], vec![]);
+    old.add_to_root("S", s);
+    let mut new = Builder::new();
+    let s = plain_struct(&mut new, &[("x", primitive("u64")), ("z", primitive("u32"))], vec![]);
+    new.add_to_root("S", s);
+    check(
+        &old.build(),
+        &new.build(),
+        &[
+            (Level::Major, "krate::S::x", "type changed from `u32` to `u64`"),
+            (Level::Major, "krate::S::y", "field removed"),
+            (Level::Major, "krate::S::z", "field added to a type without private fields"),
+        ],
+    );
+}
+
+#[test]
+fn non_exhaustive_enum() {
+    let build = |variants: &[&str], non_exhaustive: bool| {
+        let mut b = Builder::new();
+        let e = enum_(&mut b, variants);
+        let e = b.add_to_root("E", e);
+        if non_exhaustive {
+            b.item_mut(e).attrs.push(Attribute::NonExhaustive);
+        }
+        b.build()
+    };
+    check(
+        &build(&["A"], false),
+        &build(&["A", "B"], false),
+        &[(Level::Major, "krate::E::B", "variant added to an exhaustive enum")],
+    );
+    check(
+        &build(&["A"], true),
+        &build(&["A", "B"], true),
+        &[(Level::Minor, "krate::E::B", "variant added")],
+    );
+    check(
+        &build(&["A"], false),
+        &build(&["A"], true),
+        &[(Level::Major, "krate::E", "marked `#[non_exhaustive]`")],
+    );
+}
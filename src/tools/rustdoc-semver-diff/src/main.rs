@@ -0,0 +1,90 @@
+//! Reports the changes between the public API of two versions of a crate, and the version bump
+//! that they require, from the rustdoc JSON output of each version.
+//!
+//! Both outputs have to use the format version that this tool is built with, which is always the
+//! one of the rustdoc it ships with.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+use clap::Parser;
+use fs_err as fs;
+use rustdoc_json_types::{Crate, FORMAT_VERSION};
+use serde::{Deserialize, Serialize};
+
+use crate::diff::{Change, Level};
+
+mod api;
+mod diff;
+mod render;
+
+#[derive(Parser)]
+struct Cli {
+    /// The JSON output of the old version of the crate
+    old: PathBuf,
+
+    /// The JSON output of the new version of the crate
+    new: PathBuf,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Fail if the changes require a bigger version bump than this
+    #[arg(long, value_enum)]
+    max_bump: Option<Level>,
+}
+
+#[derive(Serialize)]
+struct JsonOutput<'a> {
+    format_version: u32,
+    bump: Level,
+    changes: &'a [Change],
+}
+
+fn main() -> Result<()> {
+    let Cli { old, new, json, max_bump } = Cli::parse();
+    let old = load(&old)?;
+    let new = load(&new)?;
+
+    let changes = diff::diff(&old, &new);
+    let bump = changes.iter().map(|change| change.level).max().unwrap_or(Level::Patch);
+
+    let mut stdout = io::stdout().lock();
+    if json {
+        let output = JsonOutput { format_version: FORMAT_VERSION, bump, changes: &changes };
+        serde_json::to_writer_pretty(&mut stdout, &output)?;
+        writeln!(stdout)?;
+    } else {
+        for change in &changes {
+            writeln!(stdout, "{change}")?;
+        }
+        writeln!(stdout, "required version bump: {bump}")?;
+    }
+
+    if let Some(max_bump) = max_bump
+        && bump > max_bump
+    {
+        bail!("the changes require a {bump} version bump, but at most {max_bump} is allowed");
+    }
+    Ok(())
+}
+
+fn load(path: &Path) -> Result<Crate> {
+    #[derive(Deserialize)]
+    struct Version {
+        format_version: u32,
+    }
+
+    let contents = fs::read_to_string(path)?;
+    // Other versions of the format may not deserialize at all, so the version is checked first.
+    let Version { format_version } = serde_json::from_str(&contents)?;
+    if format_version != FORMAT_VERSION {
+        bail!(
+            "{} uses version {format_version} of the rustdoc JSON format, but this tool only supports version {FORMAT_VERSION}",
+            path.display()
+        );
+    }
+    Ok(serde_json::from_str(&contents)?)
+}
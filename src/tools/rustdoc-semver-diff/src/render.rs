@@ -0,0 +1,253 @@
+//! Printing of types and signatures, used both to compare them and to report changes.
+//!
+//! The ids in two JSON outputs are unrelated, so paths are printed as the full path of the item
+//! they resolve to rather than as written in the source.
+
+use rustdoc_json_types::{
+    Abi, AssocItemConstraint, AssocItemConstraintKind, Crate, FunctionHeader, FunctionSignature,
+    GenericArg, GenericArgs, GenericBound, GenericParamDef, GenericParamDefKind, Path,
+    PreciseCapturingArg, Term, TraitBoundModifier, Type, WherePredicate,
+};
+
+fn join(parts: impl Iterator<Item = String>, separator: &str) -> String {
+    parts.collect::<Vec<_>>().join(separator)
+}
+
+pub(crate) fn path(krate: &Crate, path: &Path) -> String {
+    let mut s = match krate.paths.get(&path.id) {
+        Some(summary) => summary.path.join("::"),
+        None => path.path.clone(),
+    };
+    if let Some(args) = &path.args {
+        s.push_str(&generic_args(krate, args));
+    }
+    s
+}
+
+fn generic_args(krate: &Crate, args: &GenericArgs) -> String {
+    match args {
+        GenericArgs::AngleBracketed { args, constraints } => {
+            if args.is_empty() && constraints.is_empty() {
+                return String::new();
+            }
+            let args = args.iter().map(|arg| generic_arg(krate, arg));
+            let constraints = constraints.iter().map(|c| assoc_item_constraint(krate, c));
+            format!("<{}>", join(args.chain(constraints), ", "))
+        }
+        GenericArgs::Parenthesized { inputs, output } => {
+            let mut s = format!("({})", join(inputs.iter().map(|t| ty(krate, t)), ", "));
+            if let Some(output) = output {
+                s.push_str(" -> ");
+                s.push_str(&ty(krate, output));
+            }
+            s
+        }
+        GenericArgs::ReturnTypeNotation => "(..)".to_owned(),
+    }
+}
+
+fn generic_arg(krate: &Crate, arg: &GenericArg) -> String {
+    match arg {
+        GenericArg::Lifetime(lifetime) => lifetime.clone(),
+        GenericArg::Type(t) => ty(krate, t),
+        GenericArg::Const(c) => c.expr.clone(),
+        GenericArg::Infer => "_".to_owned(),
+    }
+}
+
+fn assoc_item_constraint(krate: &Crate, constraint: &AssocItemConstraint) -> String {
+    let mut s = constraint.name.clone();
+    if let Some(args) = &constraint.args {
+        s.push_str(&generic_args(krate, args));
+    }
+    match &constraint.binding {
+        AssocItemConstraintKind::Equality(t) => format!("{s} = {}", term(krate, t)),
+        AssocItemConstraintKind::Constraint(b) => format!("{s}: {}", bounds(krate, b)),
+    }
+}
+
+fn term(krate: &Crate, term: &Term) -> String {
+    match term {
+        Term::Type(t) => ty(krate, t),
+        Term::Constant(c) => c.expr.clone(),
+    }
+}
+
+pub(crate) fn ty(krate: &Crate, ty_: &Type) -> String {
+    match ty_ {
+        Type::ResolvedPath(p) => path(krate, p),
+        Type::DynTrait(dyn_trait) => {
+            let traits = dyn_trait.traits.iter().map(|t| {
+                format!("{}{}", higher_ranked(krate, &t.generic_params), path(krate, &t.trait_))
+            });
+            let lifetime = dyn_trait.lifetime.iter().cloned();
+            format!("dyn {}", join(traits.chain(lifetime), " + "))
+        }
+        Type::Generic(name) | Type::Primitive(name) => name.clone(),
+        Type::FunctionPointer(f) => format!(
+            "{}{}fn{}",
+            higher_ranked(krate, &f.generic_params),
+            header(&f.header),
+            signature(krate, &f.sig),
+        ),
+        Type::Tuple(types) if types.len() == 1 => format!("({},)", ty(krate, &types[0])),
+        Type::Tuple(types) => format!("({})", join(types.iter().map(|t| ty(krate, t)), ", ")),
+        Type::Slice(t) => format!("[{}]", ty(krate, t)),
+        Type::Array { type_, len } => format!("[{}; {len}]", ty(krate, type_)),
+        Type::Pat { type_, __pat_unstable_do_not_use: pat } => {
+            format!("{} is {pat}", ty(krate, type_))
+        }
+        Type::ImplTrait(b) => format!("impl {}", bounds(krate, b)),
+        Type::Infer => "_".to_owned(),
+        Type::RawPointer { is_mutable, type_ } => {
+            format!("*{} {}", if *is_mutable { "mut" } else { "const" }, ty(krate, type_))
+        }
+        Type::BorrowedRef { lifetime, is_mutable, type_ } => {
+            let mut s = "&".to_owned();
+            if let Some(lifetime) = lifetime {
+                s.push_str(lifetime);
+                s.push(' ');
+            }
+            if *is_mutable {
+                s.push_str("mut ");
+            }
+            s.push_str(&ty(krate, type_));
+            s
+        }
+        Type::QualifiedPath { name, args, self_type, trait_ } => {
+            let args = args.as_ref().map(|args| generic_args(krate, args)).unwrap_or_default();
+            match trait_ {
+                Some(trait_) => {
+                    format!("<{} as {}>::{name}{args}", ty(krate, self_type), path(krate, trait_))
+                }
+                None => format!("{}::{name}{args}", ty(krate, self_type)),
+            }
+        }
+    }
+}
+
+pub(crate) fn bounds(krate: &Crate, bounds: &[GenericBound]) -> String {
+    join(bounds.iter().map(|b| bound(krate, b)), " + ")
+}
+
+pub(crate) fn bound(krate: &Crate, bound: &GenericBound) -> String {
+    match bound {
+        GenericBound::TraitBound { trait_, generic_params, modifier } => {
+            let modifier = match modifier {
+                TraitBoundModifier::None => "",
+                TraitBoundModifier::Maybe => "?",
+                TraitBoundModifier::MaybeConst => "[const] ",
+            };
+            format!("{}{modifier}{}", higher_ranked(krate, generic_params), path(krate, trait_))
+        }
+        GenericBound::Outlives(lifetime) => lifetime.clone(),
+        GenericBound::Use(args) => {
+            let args = args.iter().map(|arg| match arg {
+                PreciseCapturingArg::Lifetime(name) | PreciseCapturingArg::Param(name) => {
+                    name.clone()
+                }
+            });
+            format!("use<{}>", join(args, ", "))
+        }
+    }
+}
+
+pub(crate) fn higher_ranked(krate: &Crate, params: &[GenericParamDef]) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    format!("for<{}> ", join(params.iter().map(|p| generic_param(krate, p)), ", "))
+}
+
+pub(crate) fn generic_param(krate: &Crate, param: &GenericParamDef) -> String {
+    let name = &param.name;
+    match &param.kind {
+        GenericParamDefKind::Lifetime { outlives } if outlives.is_empty() => name.clone(),
+        GenericParamDefKind::Lifetime { outlives } => format!("{name}: {}", outlives.join(" + ")),
+        GenericParamDefKind::Type { bounds: b, default, is_synthetic: _ } => {
+            let mut s = name.clone();
+            if !b.is_empty() {
+                s.push_str(": ");
+                s.push_str(&bounds(krate, b));
+            }
+            if let Some(default) = default {
+                s.push_str(" = ");
+                s.push_str(&ty(krate, default));
+            }
+            s
+        }
+        GenericParamDefKind::Const { type_, default } => {
+            let mut s = format!("const {name}: {}", ty(krate, type_));
+            if let Some(default) = default {
+                s.push_str(" = ");
+                s.push_str(default);
+            }
+            s
+        }
+    }
+}
+
+pub(crate) fn where_predicate(krate: &Crate, predicate: &WherePredicate) -> String {
+    match predicate {
+        WherePredicate::BoundPredicate { type_, bounds: b, generic_params } => format!(
+            "{}{}: {}",
+            higher_ranked(krate, generic_params),
+            ty(krate, type_),
+            bounds(krate, b),
+        ),
+        WherePredicate::LifetimePredicate { lifetime, outlives } => {
+            format!("{lifetime}: {}", outlives.join(" + "))
+        }
+        WherePredicate::EqPredicate { lhs, rhs } => {
+            format!("{} == {}", ty(krate, lhs), term(krate, rhs))
+        }
+    }
+}
+
+pub(crate) fn abi(abi: &Abi) -> String {
+    let (name, unwind) = match abi {
+        Abi::Rust => return "Rust".to_owned(),
+        Abi::C { unwind } => ("C", unwind),
+        Abi::Cdecl { unwind } => ("cdecl", unwind),
+        Abi::Stdcall { unwind } => ("stdcall", unwind),
+        Abi::Fastcall { unwind } => ("fastcall", unwind),
+        Abi::Aapcs { unwind } => ("aapcs", unwind),
+        Abi::Win64 { unwind } => ("win64", unwind),
+        Abi::SysV64 { unwind } => ("sysv64", unwind),
+        Abi::System { unwind } => ("system", unwind),
+        Abi::Other(name) => return name.trim_matches('"').to_owned(),
+    };
+    if *unwind { format!("{name}-unwind") } else { name.to_owned() }
+}
+
+fn header(header: &FunctionHeader) -> String {
+    let mut s = String::new();
+    if header.is_const {
+        s.push_str("const ");
+    }
+    if header.is_async {
+        s.push_str("async ");
+    }
+    if header.is_unsafe {
+        s.push_str("unsafe ");
+    }
+    if header.abi != Abi::Rust {
+        s.push_str(&format!("extern \"{}\" ", abi(&header.abi)));
+    }
+    s
+}
+
+/// Prints the parameter and return types of a function. The names of the parameters are not part
+/// of its API, so they are left out.
+pub(crate) fn signature(krate: &Crate, sig: &FunctionSignature) -> String {
+    let mut inputs: Vec<String> = sig.inputs.iter().map(|(_, t)| ty(krate, t)).collect();
+    if sig.is_c_variadic {
+        inputs.push("...".to_owned());
+    }
+    let mut s = format!("({})", inputs.join(", "));
+    if let Some(output) = &sig.output {
+        s.push_str(" -> ");
+        s.push_str(&ty(krate, output));
+    }
+    s
+}
@@ -0,0 +1,24 @@
+//! A crate to search.
+
+/// A widget with a size.
+pub struct Widget {
+    size: usize,
+}
+
+impl Widget {
+    /// Returns the size of the widget.
+    #[doc(alias = "len")]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+/// Makes a widget of the given size.
+pub fn make_widget(size: usize) -> Widget {
+    Widget { size }
+}
+
+/// Adds up the bytes of an iterator.
+pub fn sum_bytes<I: Iterator<Item = u8>>(iter: I) -> usize {
+    iter.map(usize::from).sum()
+}
@@ -0,0 +1,47 @@
+//! Checks that the search index exported by `rustdoc --emit=search-index` can be read by the
+//! `rustdoc-search` tool, and that searches by name, alias and type find the documented items.
+
+use run_make_support::serde_json::{self, Value};
+use run_make_support::{cargo, path, rfs, rustc_path, rustdoc, source_root};
+
+fn main() {
+    rustdoc()
+        .input("lib.rs")
+        .crate_name("widgets")
+        .out_dir("doc")
+        .arg("-Zunstable-options")
+        .arg("--emit=search-index")
+        .run();
+    // When the export is the only output, the HTML documentation is not written.
+    assert!(path("doc/search-index.json").exists());
+    assert!(!path("doc/widgets/index.html").exists());
+
+    let index: Value = serde_json::from_str(&rfs::read_to_string("doc/search-index.json")).unwrap();
+    assert_eq!(index["crates"], serde_json::json!(["widgets"]));
+
+    assert_eq!(search("Widget::size"), ["widgets::Widget::size"]);
+    assert_eq!(search("len"), ["widgets::Widget::size"]);
+    assert_eq!(search("usize -> Widget"), ["widgets::make_widget"]);
+    assert_eq!(search("Widget -> usize"), ["widgets::Widget::size"]);
+    assert_eq!(search("Iterator<Item = u8> -> usize"), ["widgets::sum_bytes"]);
+}
+
+/// Runs `rustdoc-search` on the exported index and returns the paths of the results.
+fn search(query: &str) -> Vec<String> {
+    // Use the stage0 beta cargo to build the tool, like the other tools of the repository.
+    let output = cargo()
+        // Ensure `proc-macro2`'s nightly detection is disabled
+        .env("RUSTC_STAGE", "0")
+        .env("RUSTC", rustc_path())
+        .arg("run")
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(source_root().join("Cargo.toml"))
+        .args(&["-p", "rustdoc-search", "--target-dir", "target", "--"])
+        .arg(path("doc"))
+        .arg("--json")
+        .arg(query)
+        .run();
+    let results: Vec<Value> = serde_json::from_str(&output.stdout_utf8()).unwrap();
+    results.iter().map(|result| result["path"].as_str().unwrap().to_owned()).collect()
+}
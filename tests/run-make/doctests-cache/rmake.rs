@@ -0,0 +1,47 @@
+//@ ignore-cross-compile attempts to run the doctests
+
+// Check that `--doctest-cache-dir` skips doctests that did not change since their last run, and
+// that the results are invalidated when a crate that the doctests use changes.
+
+use run_make_support::rfs::read_to_string;
+use run_make_support::{Rustdoc, rustc, rustdoc};
+
+fn test_with_cache(edition: &str) -> Rustdoc {
+    let mut rustdoc = rustdoc();
+    rustdoc
+        .input("t.rs")
+        .arg("-Zunstable-options")
+        .arg("--test")
+        .arg("--doctest-cache-dir")
+        .arg("cache")
+        .edition(edition)
+        .extern_("t", "libt.rlib");
+    rustdoc
+}
+
+fn assert_runs(expected: usize) {
+    assert_eq!(read_to_string("runs").len(), expected);
+}
+
+fn main() {
+    rustc().input("t.rs").crate_type("rlib").run();
+    test_with_cache("2021").run_fail().assert_stdout_not_contains("cached by a previous run");
+    assert_runs(1);
+
+    // Nothing changed, so the failure is reported again without running anything.
+    test_with_cache("2021").run_fail().assert_stdout_contains("cached by a previous run");
+    assert_runs(1);
+
+    // The doctests depend on the contents of the crate, which now makes them pass.
+    rustc().input("t.rs").crate_type("rlib").cfg("fixed").run();
+    test_with_cache("2021").run();
+    assert_runs(2);
+    test_with_cache("2021").run();
+    assert_runs(2);
+
+    // The results of merged doctests are cached as well.
+    test_with_cache("2024").run();
+    assert_runs(3);
+    test_with_cache("2024").run();
+    assert_runs(3);
+}
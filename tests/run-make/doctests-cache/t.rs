@@ -0,0 +1,21 @@
+/// Counts how often the doctests run.
+/// ```
+/// use std::io::Write;
+///
+/// let mut runs = std::fs::OpenOptions::new().create(true).append(true).open("runs").unwrap();
+/// runs.write_all(b"x").unwrap();
+/// ```
+pub fn count() {}
+
+/// ```
+/// assert_eq!(t::answer(), 42);
+/// ```
+#[cfg(not(fixed))]
+pub fn answer() -> u32 {
+    41
+}
+
+#[cfg(fixed)]
+pub fn answer() -> u32 {
+    42
+}
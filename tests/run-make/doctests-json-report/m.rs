@@ -0,0 +1,13 @@
+/// ```
+/// assert_eq!(m::double(2), 4);
+/// ```
+pub fn double(x: u32) -> u32 {
+    x * 2
+}
+
+/// ```
+/// assert_eq!(m::half(4), 2);
+/// ```
+pub fn half(x: u32) -> u32 {
+    x / 2
+}
@@ -0,0 +1,84 @@
+//@ ignore-cross-compile attempts to run the doctests
+
+// Check that doctests are described by `doctest` events when libtest prints JSON, with the item
+// they document and how long they took to compile and run.
+
+use run_make_support::serde_json::{self, Value};
+use run_make_support::{rustc, rustdoc};
+
+/// Runs the doctests of `<krate>.rs` with the test arguments `extra_test_args` and returns
+/// whether they passed, and their events by the path of the item they document.
+fn doctest_events(
+    krate: &str,
+    edition: &str,
+    extra_test_args: &str,
+) -> (bool, Vec<(String, Value)>) {
+    let output = rustdoc()
+        .input(format!("{krate}.rs"))
+        .arg("--test")
+        .arg(format!("--test-args=-Zunstable-options --format json {extra_test_args}"))
+        .edition(edition)
+        .extern_(krate, format!("lib{krate}.rlib"))
+        .run_unchecked();
+    let mut events: Vec<_> = output
+        .stdout_utf8()
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .filter(|event| event["type"] == "doctest")
+        .map(|event| (event["item"].as_str().unwrap().to_owned(), event))
+        .collect();
+    events.sort_by(|(a, _), (b, _)| a.cmp(b));
+    (output.status().success(), events)
+}
+
+fn main() {
+    rustc().input("t.rs").crate_type("rlib").run();
+    rustc().input("m.rs").crate_type("rlib").run();
+
+    let (passed, events) = doctest_events("t", "2021", "");
+    assert!(!passed);
+    let items: Vec<_> = events.iter().map(|(item, _)| item.as_str()).collect();
+    assert_eq!(items, ["Foo", "Foo::new", "broken"]);
+    for (item, event) in &events {
+        assert_eq!(event["event"], "finished");
+        assert_eq!(event["file"], "t.rs");
+        assert_eq!(event["edition"], "2021");
+        assert_eq!(event["merged"], false);
+        assert_eq!(event["cached"], false);
+        assert!(event["compile_time"].is_f64());
+        // Only the doctest of `Foo` compiled and ran.
+        assert_eq!(event["run_time"].is_f64(), item == "Foo", "{event}");
+    }
+    assert_eq!(events[0].1["name"], "t.rs - Foo (line 1)");
+    assert_eq!(events[0].1["line"], 1);
+
+    // The doctests that can be merged are, except for the one that fails to compile, which makes
+    // them all fall back to being compiled on their own.
+    let (passed, events) = doctest_events("t", "2024", "");
+    assert!(!passed);
+    assert_eq!(events.len(), 3);
+    assert!(events.iter().all(|(_, event)| event["edition"] == "2024"));
+
+    // All the doctests of `m.rs` can be merged, and were compiled together.
+    let (passed, events) = doctest_events("m", "2024", "");
+    assert!(passed);
+    let items: Vec<_> = events.iter().map(|(item, _)| item.as_str()).collect();
+    assert_eq!(items, ["double", "half"]);
+    for (_, event) in &events {
+        assert_eq!(event["merged"], true);
+        assert_eq!(event["cached"], false);
+        assert!(event["compile_time"].is_f64());
+        assert_eq!(event["run_time"], Value::Null);
+    }
+    assert_eq!(events[0].1["compile_time"], events[1].1["compile_time"]);
+
+    // Merged doctests that the test arguments filter out did not run, and have no event.
+    let (passed, events) = doctest_events("m", "2024", "half");
+    assert!(passed);
+    let items: Vec<_> = events.iter().map(|(item, _)| item.as_str()).collect();
+    assert_eq!(items, ["half"]);
+    let (passed, events) = doctest_events("m", "2024", "--skip half");
+    assert!(passed);
+    let items: Vec<_> = events.iter().map(|(item, _)| item.as_str()).collect();
+    assert_eq!(items, ["double"]);
+}
@@ -0,0 +1,18 @@
+/// ```
+/// assert_eq!(1 + 1, 2);
+/// ```
+pub struct Foo;
+
+impl Foo {
+    /// ```no_run
+    /// let _ = t::Foo::new();
+    /// ```
+    pub fn new() -> Foo {
+        Foo
+    }
+}
+
+/// ```
+/// let x: u8 = "not a number";
+/// ```
+pub fn broken() {}
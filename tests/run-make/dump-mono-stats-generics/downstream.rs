@@ -0,0 +1,3 @@
+pub fn instantiate() -> (Vec<u8>, Vec<String>) {
+    (upstream::generic(1), upstream::generic(String::new()))
+}
@@ -0,0 +1,33 @@
+//! Checks that `-Z dump-mono-stats` attributes the instances of a generic function, and of its
+//! closures, to the generic function, with the crate that instantiated them and the size of their
+//! LLVM IR.
+
+//@ needs-target-std
+
+use run_make_support::serde_json::{self, Value};
+use run_make_support::{cwd, rfs, rustc};
+
+fn main() {
+    rustc().input("upstream.rs").crate_type("rlib").run();
+    rustc()
+        .input("downstream.rs")
+        .crate_type("rlib")
+        .extern_("upstream", "libupstream.rlib")
+        .arg(format!("-Zdump-mono-stats={}", cwd().display()))
+        .arg("-Zdump-mono-stats-format=json")
+        .run();
+
+    let stats: Value =
+        serde_json::from_str(&rfs::read_to_string("downstream.mono_items.json")).unwrap();
+    let generic = stats
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["name"] == "upstream::generic")
+        .expect("no stats for `upstream::generic`");
+
+    assert_eq!(generic["instantiating_crate"], "downstream");
+    // Two instances of the function, and two of its closure.
+    assert_eq!(generic["instantiation_count"], 4);
+    assert!(generic["llvm_ir_size"].as_u64().unwrap() > 0);
+}
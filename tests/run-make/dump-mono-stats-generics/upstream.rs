@@ -0,0 +1,4 @@
+pub fn generic<T: Clone>(value: T) -> Vec<T> {
+    let make = || value.clone();
+    vec![make(), make()]
+}
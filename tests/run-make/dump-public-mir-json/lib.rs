@@ -0,0 +1,18 @@
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn origin() -> Point {
+        Point { x: 0, y: 0 }
+    }
+}
+
+pub fn add(a: Point, b: Point) -> Point {
+    Point { x: a.x + b.x, y: a.y + b.y }
+}
+
+pub fn greeting() -> &'static str {
+    "hello"
+}
@@ -0,0 +1,100 @@
+//! Checks that `-Zdump-public-mir=json` writes `<crate>.public_mir.json` next to the other
+//! outputs, and that its tables describe every type, span and definition the bodies refer to.
+
+//@ ignore-cross-compile
+
+use std::collections::HashSet;
+
+use run_make_support::serde_json::{self, Value};
+use run_make_support::{rfs, rustc};
+
+/// Keep in sync with `SCHEMA_VERSION` in `compiler/rustc_public/src/mir/interchange.rs`.
+const SCHEMA_VERSION: u64 = 1;
+
+fn main() {
+    rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .crate_name("points")
+        .arg("-Zdump-public-mir=json")
+        .run();
+    let mir: Value = serde_json::from_str(&rfs::read_to_string("points.public_mir.json")).unwrap();
+
+    assert_eq!(mir["schema_version"], SCHEMA_VERSION);
+    assert_eq!(mir["crate_name"], "points");
+
+    let items = mir["items"].as_array().unwrap();
+    let names: HashSet<&str> = items.iter().map(|item| item["name"].as_str().unwrap()).collect();
+    for name in ["points::add", "points::greeting", "points::Point::origin"] {
+        assert!(names.contains(name), "missing item `{name}` in {names:?}");
+    }
+
+    let types = ids(&mir["types"]);
+    let spans = ids(&mir["spans"]);
+    let defs = ids(&mir["defs"]);
+    ids(&mir["allocations"]);
+
+    // Every type and span that the bodies refer to has an entry in the tables.
+    for item in items {
+        let mut referenced_types = vec![];
+        let mut referenced_spans = vec![];
+        collect_ids(&item["body"], "ty", &mut referenced_types);
+        collect_ids(&item["body"], "span", &mut referenced_spans);
+        assert!(!referenced_types.is_empty() && !referenced_spans.is_empty());
+        for ty in referenced_types {
+            assert!(types.contains(&ty), "missing type {ty} of `{}`", item["name"]);
+        }
+        for span in referenced_spans {
+            assert!(spans.contains(&span), "missing span {span} of `{}`", item["name"]);
+        }
+    }
+
+    // Every span entry has a file and a line range.
+    for span in mir["spans"].as_array().unwrap() {
+        assert!(span["file"].as_str().unwrap().ends_with("lib.rs"), "{span}");
+        assert!(span["lines"]["start_line"].as_u64().unwrap() >= 1, "{span}");
+    }
+
+    // The ADTs in the type table are described in the definition table.
+    let mut adts = 0;
+    for ty in mir["types"].as_array().unwrap() {
+        if let Some(adt) = ty["kind"]["RigidTy"].get("Adt") {
+            let def = adt[0].as_u64().unwrap();
+            assert!(defs.contains(&def), "missing definition {def} of type {}", ty["id"]);
+            adts += 1;
+        }
+    }
+    assert!(adts > 0);
+    let def_names: HashSet<&str> =
+        mir["defs"].as_array().unwrap().iter().map(|def| def["name"].as_str().unwrap()).collect();
+    assert!(def_names.contains("points::Point"), "{def_names:?}");
+
+    // The string literal lives in its own allocation.
+    assert!(!mir["allocations"].as_array().unwrap().is_empty());
+}
+
+/// Returns the ids of the entries of `table`, checking that no id appears twice.
+fn ids(table: &Value) -> HashSet<u64> {
+    let mut ids = HashSet::new();
+    for entry in table.as_array().unwrap() {
+        let id = entry["id"].as_u64().unwrap();
+        assert!(ids.insert(id), "id {id} appears twice in {entry}");
+    }
+    ids
+}
+
+/// Collects the numbers stored under `key` anywhere in `value`.
+fn collect_ids(value: &Value, key: &str, ids: &mut Vec<u64>) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map {
+                match v.as_u64() {
+                    Some(id) if k == key => ids.push(id),
+                    _ => collect_ids(v, key, ids),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|v| collect_ids(v, key, ids)),
+        _ => {}
+    }
+}
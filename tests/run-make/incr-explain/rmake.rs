@@ -0,0 +1,56 @@
+//! Checks that `-Z incremental-explain` reports which changes caused queries and codegen units
+//! to be re-executed by an incremental compilation.
+
+//@ ignore-cross-compile
+
+use run_make_support::{rfs, rustc};
+
+fn compile(opt_level: &str) -> String {
+    rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .incremental("incr")
+        .opt_level(opt_level)
+        .arg("-Zincremental-explain")
+        .run()
+        .stderr_utf8()
+}
+
+fn compile_without_explain(opt_level: &str) {
+    rustc().input("lib.rs").crate_type("lib").incremental("incr").opt_level(opt_level).run();
+}
+
+fn main() {
+    rfs::write("lib.rs", "pub fn changed() -> u32 { 1 }\npub fn unchanged() -> u32 { 2 }\n");
+    let stderr = compile("0");
+    assert!(stderr.contains("all queries are executed because there is no previous session"));
+
+    // The body of `changed` is edited without changing its span.
+    rfs::write("lib.rs", "pub fn changed() -> u32 { 3 }\npub fn unchanged() -> u32 { 2 }\n");
+    let stderr = compile("0");
+    assert!(stderr.contains("in `lib.rs`, the HIR of `changed` changed"), "{stderr}");
+    assert!(stderr.contains("typeck (1)"), "{stderr}");
+    assert!(stderr.contains("codegen units: `"), "{stderr}");
+    assert!(!stderr.contains("the HIR of `unchanged`"), "{stderr}");
+
+    // Changing the optimization level also changes whether debug assertions are enabled.
+    let stderr = compile("1");
+    assert!(
+        stderr.contains(
+            "all queries are executed because these command-line options changed: \
+             `cg.opt_level`, `debug_assertions`, `optimize`"
+        ),
+        "{stderr}"
+    );
+
+    // The options are only recorded by a compilation that explains itself.
+    compile_without_explain("2");
+    let stderr = compile("3");
+    assert!(
+        stderr.contains(
+            "all queries are executed because the command-line options changed, which are only \
+             named if the previous session also used `-Zincremental-explain`"
+        ),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,63 @@
+//! Checks that `-Z incremental-shared-cache` lets a checkout of a crate without incremental state
+//! of its own start from the incremental state of another checkout, in another directory, when
+//! both are remapped to the same path, and that the shared cache is left as it is.
+
+//@ ignore-cross-compile
+
+use std::path::Path;
+
+use run_make_support::{cwd, rfs, rustc};
+
+fn session_dirs(crate_dirs: &Path) -> Vec<String> {
+    let mut dirs = Vec::new();
+    for crate_dir in rfs::read_dir(crate_dirs) {
+        for entry in rfs::read_dir(crate_dir.unwrap().path()) {
+            dirs.push(entry.unwrap().file_name().into_string().unwrap());
+        }
+    }
+    dirs.sort();
+    dirs
+}
+
+fn main() {
+    for checkout in ["ci", "local"] {
+        rfs::create_dir(checkout);
+        rfs::copy("lib.rs", format!("{checkout}/lib.rs"));
+    }
+    let shared_cache = cwd().join("shared-incr");
+
+    // The shared cache is populated from one checkout...
+    rustc()
+        .current_dir("ci")
+        .input("lib.rs")
+        .crate_type("lib")
+        .incremental(&shared_cache)
+        .remap_path_prefix(cwd().join("ci"), "/project")
+        .arg("-Zassert-incr-state=not-loaded")
+        .run();
+    let shared_session_dirs = session_dirs(&shared_cache);
+
+    // ...and another one starts from it.
+    rustc()
+        .current_dir("local")
+        .input("lib.rs")
+        .crate_type("lib")
+        .incremental(cwd().join("local-incr"))
+        .arg(format!("-Zincremental-shared-cache={}", shared_cache.display()))
+        .remap_path_prefix(cwd().join("local"), "/project")
+        .arg("-Zassert-incr-state=loaded")
+        .run();
+    assert_eq!(session_dirs(&shared_cache), shared_session_dirs);
+
+    // Once it has incremental state of its own, it starts from it.
+    rfs::remove_dir_all(&shared_cache);
+    rustc()
+        .current_dir("local")
+        .input("lib.rs")
+        .crate_type("lib")
+        .incremental(cwd().join("local-incr"))
+        .arg(format!("-Zincremental-shared-cache={}", shared_cache.display()))
+        .remap_path_prefix(cwd().join("local"), "/project")
+        .arg("-Zassert-incr-state=loaded")
+        .run();
+}
@@ -0,0 +1,10 @@
+unsafe fn dangerous() {}
+
+unsafe fn wrapper() {
+    dangerous();
+}
+
+fn main() {
+    let unused = 1;
+    unsafe { wrapper() };
+}
@@ -0,0 +1,41 @@
+//! Checks that the diagnostics of lints in the JSON output describe the lint: its groups, its
+//! default level, the edition that changes it, its documentation and whether it is a
+//! future-incompatibility lint.
+
+//@ ignore-cross-compile
+
+use run_make_support::rustc;
+use run_make_support::serde_json::{self, Value, json};
+
+fn main() {
+    let output = rustc().input("main.rs").edition("2024").error_format("json").run();
+    let mut lints: Vec<Value> = output
+        .stderr_utf8()
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .filter_map(|diagnostic| diagnostic["code"].get("lint").cloned())
+        .collect();
+    lints.sort_by_key(|lint| lint["name"].as_str().unwrap().to_owned());
+
+    assert_eq!(
+        lints,
+        [
+            json!({
+                "name": "unsafe_op_in_unsafe_fn",
+                "groups": ["rust_2024_compatibility"],
+                "default_level": "allow",
+                "edition": { "edition": "2024", "level": "warn" },
+                "docs_url": "https://doc.rust-lang.org/rustc/lints/listing/allowed-by-default.html#unsafe-op-in-unsafe-fn",
+                "future_incompat": true,
+            }),
+            json!({
+                "name": "unused_variables",
+                "groups": ["unused"],
+                "default_level": "warn",
+                "edition": null,
+                "docs_url": "https://doc.rust-lang.org/rustc/lints/listing/warn-by-default.html#unused-variables",
+                "future_incompat": false,
+            }),
+        ]
+    );
+}
@@ -0,0 +1,71 @@
+// Check that `--json=timings` reports the start and the end of each compilation section, and that
+// the sections that run once per codegen unit say which codegen unit they belong to.
+
+//@ ignore-cross-compile
+
+use std::collections::HashMap;
+
+use run_make_support::{rustc, serde_json};
+
+/// The start and end events of each section, along with their codegen units.
+fn timings(lto: &str) -> HashMap<String, Vec<(String, Option<String>)>> {
+    let output = rustc()
+        .input("main.rs")
+        .arg("-Zunstable-options")
+        .arg("--json=timings")
+        .arg("--error-format=json")
+        .arg(format!("-Clto={lto}"))
+        .arg("-Ccodegen-units=2")
+        .run();
+    let mut sections: HashMap<String, Vec<_>> = HashMap::new();
+    for line in output.stderr_utf8().lines() {
+        let message: serde_json::Value = serde_json::from_str(line).unwrap();
+        if message["$message_type"] != "section_timing" {
+            continue;
+        }
+        assert!(message["timestamp"].is_u64(), "{message}");
+        let name = message["name"].as_str().unwrap().to_owned();
+        let event = message["event"].as_str().unwrap().to_owned();
+        let codegen_unit = message.get("codegen_unit").map(|cgu| cgu.as_str().unwrap().to_owned());
+        sections.entry(name).or_default().push((event, codegen_unit));
+    }
+    sections
+}
+
+/// Check that every start of `section` is followed by its end.
+#[track_caller]
+fn check_pairs(events: &[(String, Option<String>)], section: &str) {
+    let starts: Vec<_> = events.iter().filter(|(event, _)| event == "start").collect();
+    let ends: Vec<_> = events.iter().filter(|(event, _)| event == "end").collect();
+    assert!(!starts.is_empty(), "section `{section}` was not started");
+    assert_eq!(starts.len(), ends.len(), "unbalanced events for `{section}`: {events:?}");
+}
+
+fn main() {
+    let sections = timings("thin");
+    for section in [
+        "parse",
+        "expand",
+        "resolve",
+        "typeck",
+        "borrowck",
+        "mir_opt",
+        "mono_items",
+        "codegen",
+        "lto",
+        "link",
+    ] {
+        let events = sections.get(section).map(Vec::as_slice).unwrap_or_default();
+        check_pairs(events, section);
+        if section != "lto" {
+            assert!(events.iter().all(|(_, cgu)| cgu.is_none()), "{section}: {events:?}");
+        }
+    }
+    // Thin LTO runs once per codegen unit.
+    assert!(sections["lto"].iter().all(|(_, cgu)| cgu.is_some()), "{:?}", sections["lto"]);
+
+    // Fat LTO runs once for all codegen units.
+    let sections = timings("fat");
+    check_pairs(&sections["lto"], "lto");
+    assert_eq!(sections["lto"], [("start".to_owned(), None), ("end".to_owned(), None)]);
+}
@@ -0,0 +1,13 @@
+#![deny(unused_variables, unused_comparisons)]
+
+pub fn first() {
+    let unused = 1;
+}
+
+pub fn second() {
+    let unused = 2;
+}
+
+pub fn positive(x: u32) -> bool {
+    x >= 0
+}
@@ -0,0 +1,66 @@
+//! Checks that `-Z write-lint-baseline` records the lint diagnostics of a crate instead of
+//! reporting them, and that `-Z lint-baseline` then only reports the diagnostics that are not in
+//! the baseline, even when the known ones moved or were reformatted.
+
+//@ ignore-cross-compile
+
+use run_make_support::serde_json::{self, Value};
+use run_make_support::{Rustc, rfs, rustc};
+
+fn main() {
+    rustc()
+        .input("lib.rs")
+        .crate_type("lib")
+        .arg("-Zlint-baseline=baseline.json")
+        .arg("-Zwrite-lint-baseline")
+        .run()
+        .assert_stderr_equals("");
+
+    let baseline: Value = serde_json::from_str(&rfs::read_to_string("baseline.json")).unwrap();
+    assert_eq!(baseline["version"], 1);
+    let diagnostics = baseline["diagnostics"].as_array().unwrap();
+    let mut lints: Vec<_> =
+        diagnostics.iter().map(|diagnostic| diagnostic["lint"].as_str().unwrap()).collect();
+    lints.sort();
+    assert_eq!(lints, ["unused_comparisons", "unused_variables", "unused_variables"]);
+    for diagnostic in diagnostics {
+        assert_eq!(diagnostic["file"], "lib.rs");
+    }
+
+    // The known diagnostics are denied, but not reported.
+    check().run().assert_stderr_equals("");
+
+    // Moving the known diagnostics and reformatting the code that they point to does not make
+    // them reported, but new ones are.
+    edit(|source| {
+        source
+            .replace(
+                "#![deny(unused_variables, unused_comparisons)]\n",
+                "#![deny(unused_variables, unused_comparisons)]\n\n\
+                 pub fn third() {\n    let new_unused = 3;\n}\n",
+            )
+            .replace("x >= 0", "x>=\n        0")
+    });
+    check()
+        .run_fail()
+        .assert_stderr_contains("unused variable: `new_unused`")
+        .assert_stderr_not_contains("unused variable: `unused`")
+        .assert_stderr_not_contains("comparison is useless");
+
+    // Editing the code that a known diagnostic points to makes it reported again.
+    edit(|source| source.replace("x>=\n        0", "x >= 0u32"));
+    check()
+        .run_fail()
+        .assert_stderr_contains("comparison is useless")
+        .assert_stderr_not_contains("unused variable: `unused`");
+}
+
+fn check() -> Rustc {
+    let mut rustc = rustc();
+    rustc.input("lib.rs").crate_type("lib").arg("-Zlint-baseline=baseline.json");
+    rustc
+}
+
+fn edit(f: impl FnOnce(String) -> String) {
+    rfs::write("lib.rs", f(rfs::read_to_string("lib.rs")));
+}
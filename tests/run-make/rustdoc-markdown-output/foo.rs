@@ -0,0 +1,42 @@
+//! The crate documentation, which links to [`Point`].
+
+pub mod shapes {
+    /// A shape that can be drawn.
+    pub trait Shape {
+        /// Returns the area of the shape.
+        fn area(&self) -> f64;
+    }
+}
+
+/// A point in the plane.
+///
+/// See [`Point::origin`] and [`shapes::Shape`].
+///
+/// # Examples
+///
+/// ```
+/// # use foo::Point;
+/// let p = Point::origin();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Point {
+    /// The horizontal coordinate.
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    /// Returns the point at `(0, 0)`.
+    pub fn origin() -> Point {
+        Point { x: 0.0, y: 0.0 }
+    }
+}
+
+impl shapes::Shape for Point {
+    fn area(&self) -> f64 {
+        0.0
+    }
+}
+
+#[deprecated(since = "1.2.0", note = "use `Point` instead")]
+pub struct OldPoint;
@@ -0,0 +1,49 @@
+// Checks that `--output-format markdown` writes one file per module and item, with signatures,
+// resolved intra-doc links and impl lists.
+
+//@ needs-target-std
+
+use run_make_support::rfs::read_to_string;
+use run_make_support::{path, rustdoc};
+
+fn main() {
+    let out_dir = path("out");
+    rustdoc()
+        .input("foo.rs")
+        .arg("-Zunstable-options")
+        .arg("--output-format=markdown")
+        .out_dir(&out_dir)
+        .run();
+
+    let index = read_to_string(out_dir.join("foo/index.md"));
+    assert!(index.starts_with("# Crate `foo`\n"), "{index}");
+    assert!(index.contains("[`Point`](struct.Point.md)"), "{index}");
+    assert!(index.contains("## Modules\n\n- [`shapes`](shapes/index.md)"), "{index}");
+    assert!(index.contains("- [`Point`](struct.Point.md): A point in the plane."), "{index}");
+
+    let point = read_to_string(out_dir.join("foo/struct.Point.md"));
+    assert!(
+        point.contains("```rust\npub struct Point {\n    pub x: f64,\n    pub y: f64,\n}\n```")
+    );
+    // Intra-doc links point to the generated files.
+    assert!(point.contains("[`Point::origin`](struct.Point.md#method.origin)"), "{point}");
+    assert!(point.contains("[`shapes::Shape`](shapes/trait.Shape.md)"), "{point}");
+    // Headings are nested under the title, and hidden lines are removed from examples.
+    assert!(point.contains("\n## Examples\n"), "{point}");
+    assert!(point.contains("```rust\nlet p = Point::origin();\n```"), "{point}");
+    assert!(!point.contains("use foo::Point"), "{point}");
+    assert!(point.contains("<a id=\"structfield.x\"></a>"), "{point}");
+    assert!(point.contains("<a id=\"method.origin\"></a>\n\n#### `pub fn origin() -> Point`"));
+    assert!(point.contains("## Trait Implementations\n"), "{point}");
+    assert!(point.contains("[`impl Shape for Point`](shapes/trait.Shape.md)"), "{point}");
+    assert!(point.contains("## Auto Trait Implementations\n"), "{point}");
+    assert!(point.contains("`impl Send for Point`"), "{point}");
+
+    let shape = read_to_string(out_dir.join("foo/shapes/trait.Shape.md"));
+    assert!(shape.contains("## Required Methods\n"), "{shape}");
+    assert!(shape.contains("## Implementors\n"), "{shape}");
+    assert!(shape.contains("[`impl Shape for Point`](../struct.Point.md)"), "{shape}");
+
+    let old = read_to_string(out_dir.join("foo/struct.OldPoint.md"));
+    assert!(old.contains("> Deprecated since 1.2.0: use `Point` instead\n"), "{old}");
+}
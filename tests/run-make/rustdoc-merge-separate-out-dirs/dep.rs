@@ -0,0 +1,3 @@
+pub trait Trait {}
+
+pub struct Dep;
@@ -0,0 +1,4 @@
+// An older version of `dep`, whose parts are replaced by the ones of `dep.rs`.
+pub trait Trait {}
+
+pub struct Stale;
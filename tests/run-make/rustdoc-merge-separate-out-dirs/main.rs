@@ -0,0 +1,13 @@
+//@ has index.html '//h1' 'List of all crates'
+//@ has index.html '//ul[@class="all-items"]//a[@href="dep/index.html"]' 'dep'
+//@ has index.html '//ul[@class="all-items"]//a[@href="main/index.html"]' 'main'
+//@ has main/struct.Main.html '//a[@href="../dep/trait.Trait.html"]' 'Trait'
+//@ hasraw trait.impl/dep/trait.Trait.js 'struct.Main.html'
+//@ hasraw search.index/name/*.js 'Dep'
+//@ hasraw search.index/name/*.js 'Main'
+//@ !hasraw search.index/name/*.js 'Stale'
+extern crate dep;
+
+pub struct Main;
+
+impl dep::Trait for Main {}
@@ -0,0 +1,56 @@
+// Crates documented in separate output directories, like in separate jobs of a CI, can be merged
+// into one site without documenting them again. Links between them work because the crates whose
+// parts are included with `--merge=none` are taken to be documented in the same site.
+
+//@ needs-target-std
+
+use run_make_support::{htmldocck, path, rfs, rust_lib_name, rustc, rustdoc};
+
+fn main() {
+    rustc().input("dep.rs").crate_type("lib").run();
+
+    rustdoc()
+        .input("dep_stale.rs")
+        .crate_name("dep")
+        .out_dir(path("stale/doc"))
+        .arg("-Zunstable-options")
+        .arg("--merge=none")
+        .arg("--parts-out-dir=stale/parts")
+        .run();
+    assert!(path("stale/parts/dep.json").exists());
+
+    rustdoc()
+        .input("dep.rs")
+        .out_dir(path("dep/doc"))
+        .arg("-Zunstable-options")
+        .arg("--merge=none")
+        .arg("--parts-out-dir=dep/parts")
+        .run();
+
+    rustdoc()
+        .input("main.rs")
+        .extern_("dep", rust_lib_name("dep"))
+        .out_dir(path("main/doc"))
+        .arg("-Zunstable-options")
+        .arg("--merge=none")
+        .arg("--parts-out-dir=main/parts")
+        .arg("--include-parts-dir=dep/parts")
+        .run();
+    // Nothing from the included parts is written with `--merge=none`.
+    assert!(!path("main/doc/dep").exists());
+    assert!(!path("main/doc/search.index").exists());
+
+    rfs::copy_dir_all(path("dep/doc"), path("site"));
+    rfs::copy_dir_all(path("main/doc"), path("site"));
+    rustdoc()
+        .out_dir(path("site"))
+        .arg("-Zunstable-options")
+        .arg("--merge=finalize")
+        .arg("--include-parts-dir=stale/parts")
+        .arg("--include-parts-dir=dep/parts")
+        .arg("--include-parts-dir=main/parts")
+        .arg("--enable-index-page")
+        .run();
+
+    htmldocck().arg(path("site")).arg("main.rs").run();
+}
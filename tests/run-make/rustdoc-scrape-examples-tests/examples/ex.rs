@@ -0,0 +1,11 @@
+fn main() {
+    foobar::ok(0);
+
+    // this is a
+
+    //  ..
+
+    // BIG
+
+    // item
+}
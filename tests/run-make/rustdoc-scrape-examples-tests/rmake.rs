@@ -0,0 +1,58 @@
+//! Checks that examples can be scraped from tests, which are shown after the examples of
+//! `examples/` unless they are pinned with `#[doc(scrape_examples = "pin")]`, and that calls in
+//! items with `#[doc(scrape_examples = "exclude")]` are not scraped.
+
+//@ needs-target-std
+
+use std::path::Path;
+
+use run_make_support::{htmldocck, rustc, rustdoc};
+
+fn main() {
+    let out_dir = Path::new("rustdoc");
+    let crate_name = "foobar";
+
+    rustc().input("src/lib.rs").crate_name(crate_name).crate_type("lib").emit("metadata").run();
+
+    let scraped = [
+        ("examples/ex.rs", false),
+        ("tests/small.rs", true),
+        ("tests/pinned.rs", true),
+        ("tests/excluded.rs", true),
+    ];
+    let mut calls = Vec::new();
+    for (input, is_test) in scraped {
+        let name = Path::new(input).file_stem().unwrap().to_str().unwrap();
+        let out_calls = out_dir.join(format!("{name}.calls"));
+        let mut scrape = rustdoc();
+        scrape
+            .input(input)
+            .crate_name(name)
+            .crate_type("bin")
+            .out_dir(out_dir)
+            .extern_(crate_name, format!("lib{crate_name}.rmeta"))
+            .arg("-Zunstable-options")
+            .arg("--scrape-examples-output-path")
+            .arg(&out_calls)
+            .arg("--scrape-examples-target-crate")
+            .arg(crate_name);
+        if is_test {
+            scrape.arg("--scrape-tests");
+        }
+        scrape.run();
+        calls.push(out_calls);
+    }
+
+    let mut doc = rustdoc();
+    doc.input("src/lib.rs")
+        .out_dir(out_dir)
+        .crate_name(crate_name)
+        .crate_type("lib")
+        .arg("-Zunstable-options");
+    for calls in calls {
+        doc.arg("--with-examples").arg(calls);
+    }
+    doc.run();
+
+    htmldocck().arg(out_dir).arg("src/lib.rs").run();
+}
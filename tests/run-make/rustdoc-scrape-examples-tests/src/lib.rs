@@ -0,0 +1,7 @@
+// The pinned test is shown first, then the examples of `examples/` come before the other tests.
+//@ has foobar/fn.ok.html '//*[@class="docblock scraped-example-list"]/div[1]/div[1]' 'tests/pinned.rs'
+//@ has foobar/fn.ok.html '//*[@class="more-scraped-examples"]/div[2]/div[1]' 'examples/ex.rs'
+//@ has foobar/fn.ok.html '//*[@class="more-scraped-examples"]/div[3]/div[1]' 'tests/small.rs'
+//@ !has foobar/fn.ok.html '//*[@class="docblock scraped-example-list"]' 'tests/excluded.rs'
+
+pub fn ok(_x: i32) {}
@@ -0,0 +1,9 @@
+#![feature(doc_scrape_examples)]
+
+#[doc(scrape_examples = "exclude")]
+mod excluded {
+    #[test]
+    fn tiny() {
+        foobar::ok(3);
+    }
+}
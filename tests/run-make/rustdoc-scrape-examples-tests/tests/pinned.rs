@@ -0,0 +1,19 @@
+#![feature(doc_scrape_examples)]
+
+#[test]
+#[doc(scrape_examples = "pin")]
+fn pinned() {
+    foobar::ok(2);
+
+    // this is a
+
+    //  ..
+
+    // BIGGER
+
+    // item
+
+    //  ..
+
+    // than the others
+}
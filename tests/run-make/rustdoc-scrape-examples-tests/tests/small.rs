@@ -0,0 +1,4 @@
+#[test]
+fn small() {
+    foobar::ok(1);
+}
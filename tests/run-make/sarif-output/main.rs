@@ -0,0 +1,4 @@
+fn main() {
+    let _x: u32 = "not a number";
+    let _y = (1);
+}
@@ -0,0 +1,71 @@
+//! Checks that `--error-format=sarif` prints a single SARIF 2.1.0 log whose results point to
+//! the right source regions and refer to rules for error codes and lints.
+
+//@ ignore-cross-compile
+
+use run_make_support::rustc;
+use run_make_support::serde_json::{self, Value};
+
+fn main() {
+    let output =
+        rustc().input("main.rs").arg("-Zunstable-options").error_format("sarif").run_fail();
+    let log: Value = serde_json::from_str(&output.stderr_utf8()).unwrap();
+
+    assert_eq!(log["version"], "2.1.0");
+    assert_eq!(log["$schema"], "https://json.schemastore.org/sarif-2.1.0.json");
+    let runs = log["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 1);
+    let run = &runs[0];
+    assert_eq!(run["tool"]["driver"]["name"], "rustc");
+    assert_eq!(run["columnKind"], "unicodeCodePoints");
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    let results = run["results"].as_array().unwrap();
+
+    // Every result that names a rule points to that rule in the rules table.
+    for result in results {
+        if let Some(index) = result["ruleIndex"].as_u64() {
+            assert_eq!(rules[index as usize]["id"], result["ruleId"]);
+        }
+    }
+
+    let mismatch = results.iter().find(|result| result["ruleId"] == "E0308").unwrap();
+    assert_eq!(mismatch["level"], "error");
+    assert!(mismatch["message"]["text"].as_str().unwrap().starts_with("mismatched types"));
+    let rule = &rules[mismatch["ruleIndex"].as_u64().unwrap() as usize];
+    assert_eq!(rule["helpUri"], "https://doc.rust-lang.org/error_codes/E0308.html");
+    assert!(rule["fullDescription"]["text"].is_string());
+
+    let locations = mismatch["locations"].as_array().unwrap();
+    assert_eq!(locations.len(), 1);
+    let location = &locations[0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "main.rs");
+    let region = &location["region"];
+    assert_eq!(region["startLine"], 2);
+    assert_eq!(region["startColumn"], 19);
+    assert_eq!(region["endLine"], 2);
+    assert_eq!(region["endColumn"], 33);
+    assert_eq!(region["snippet"]["text"], "\"not a number\"");
+
+    // The "expected due to this" label on the type annotation is a related location.
+    let related = mismatch["relatedLocations"].as_array().unwrap();
+    assert!(
+        related
+            .iter()
+            .any(|location| location["physicalLocation"]["region"]["snippet"]["text"] == "u32")
+    );
+
+    let parens = results.iter().find(|result| result["ruleId"] == "unused_parens").unwrap();
+    assert_eq!(parens["level"], "warning");
+    assert_eq!(parens["locations"][0]["physicalLocation"]["region"]["startLine"], 3);
+    // Both parentheses are removed by a single fix.
+    let fix = &parens["fixes"][0];
+    assert_eq!(fix["properties"]["applicability"], "MachineApplicable");
+    let replacements = fix["artifactChanges"][0]["replacements"].as_array().unwrap();
+    assert_eq!(replacements.len(), 2);
+    for replacement in replacements {
+        assert_eq!(replacement["insertedContent"]["text"], "");
+    }
+
+    // Failure notes such as "aborting due to 1 previous error" are not results.
+    assert_eq!(results.len(), 2);
+}
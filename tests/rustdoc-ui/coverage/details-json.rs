@@ -0,0 +1,27 @@
+//@ check-pass
+//@ compile-flags:-Z unstable-options --output-format json --show-coverage --coverage-details
+
+//! Crate docs.
+
+/// # Examples
+///
+/// ```
+/// let x = 1;
+/// ```
+pub fn documented() {}
+
+pub fn undocumented() {}
+
+/// Returns the value.
+pub fn may_panic(x: Option<u32>) -> u32 {
+    x.unwrap()
+}
+
+/// Does something unsafe.
+///
+/// # Examples
+///
+/// ```
+/// let x = 1;
+/// ```
+pub unsafe fn unsafe_fn() {}
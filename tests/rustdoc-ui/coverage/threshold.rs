@@ -0,0 +1,14 @@
+//@ compile-flags:-Z unstable-options --show-coverage
+//@ compile-flags:--coverage-threshold=90 --coverage-threshold=inner=50
+
+//! Crate docs.
+
+pub mod inner {
+    //! Documented module.
+
+    pub fn undocumented() {}
+}
+
+pub fn undocumented() {}
+
+//~? ERROR 50.0% of the items of the crate are documented, which is below the threshold of 90%
@@ -0,0 +1,105 @@
+//@ run-pass
+//! Test that the MIR of a crate can be exported in the interchange format, and that the tables
+//! describe everything the bodies refer to.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate serde_json;
+#[macro_use]
+extern crate rustc_public;
+
+use rustc_public::mir::interchange::{CrateMir, SCHEMA_VERSION};
+use rustc_public::ty::Ty;
+use std::collections::HashSet;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_interchange() -> ControlFlow<()> {
+    let mir = CrateMir::collect();
+    assert_eq!(mir.schema_version, SCHEMA_VERSION);
+    assert_eq!(mir.crate_name, CRATE_NAME);
+
+    let names: HashSet<&str> = mir.items.iter().map(|item| item.name.as_str()).collect();
+    assert!(names.contains("input::add"), "{names:?}");
+    assert!(names.contains("input::greeting"), "{names:?}");
+    assert!(names.contains("input::Point::origin"), "{names:?}");
+
+    // Every type and span that appears in a body has an entry in the tables.
+    let types: HashSet<Ty> = mir.types.iter().map(|entry| entry.id).collect();
+    let spans: HashSet<_> = mir.spans.iter().map(|entry| entry.id).collect();
+    for item in &mir.items {
+        for local in item.body.locals() {
+            assert!(types.contains(&local.ty), "missing type `{}`", local.ty);
+        }
+        assert!(spans.contains(&item.body.span));
+    }
+    let defs: HashSet<&str> = mir.defs.iter().map(|entry| entry.name.as_str()).collect();
+    assert!(defs.contains("input::Point"), "{defs:?}");
+
+    // The string literal lives in its own allocation.
+    assert!(!mir.allocations.is_empty());
+
+    let mut json = Vec::new();
+    mir.write_json(&mut json).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(value["schema_version"], SCHEMA_VERSION);
+    assert_eq!(value["items"].as_array().unwrap().len(), mir.items.len());
+    assert_eq!(value["types"].as_array().unwrap().len(), mir.types.len());
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `RustcPublic` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "interchange_input.rs";
+    generate_input(&path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run!(args, test_interchange).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub struct Point {{
+            pub x: i32,
+            pub y: i32,
+        }}
+
+        impl Point {{
+            pub fn origin() -> Point {{
+                Point {{ x: 0, y: 0 }}
+            }}
+        }}
+
+        pub fn add(a: Point, b: Point) -> Point {{
+            Point {{ x: a.x + b.x, y: a.y + b.y }}
+        }}
+
+        pub fn greeting() -> &'static str {{
+            "hello"
+        }}
+    "#
+    )?;
+    Ok(())
+}
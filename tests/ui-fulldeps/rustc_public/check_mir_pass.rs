@@ -0,0 +1,132 @@
+//@ run-pass
+//! Test that users can run a pass over every body and report diagnostics from it.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+#[macro_use]
+extern crate rustc_public;
+
+use rustc_public::diagnostic::Diagnostic;
+use rustc_public::mir::TerminatorKind;
+use rustc_public::{CompilerError, CrateDef};
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// Every item with a body is visited, including closures.
+fn test_visit_all(args: &[String]) {
+    let mut names = BTreeSet::new();
+    let result = run_mir_pass!(args, |item, body| {
+        assert!(!body.blocks.is_empty());
+        names.insert(item.name());
+        ControlFlow::<()>::Continue(())
+    });
+    assert_eq!(result, Ok(()));
+    assert_eq!(
+        names,
+        BTreeSet::from(
+            [
+                "input::calls_panic",
+                "input::no_panic",
+                "input::with_closure",
+                "input::with_closure::{closure#0}",
+            ]
+            .map(String::from)
+        )
+    );
+}
+
+/// Warnings are reported, but do not fail the compilation.
+fn test_warning(args: &[String]) {
+    let result = run_mir_pass!(args, |item, body| {
+        Diagnostic::warning(format!("visited `{}`", item.name()))
+            .with_span(body.span)
+            .with_note("this is only a test")
+            .emit();
+        ControlFlow::<()>::Continue(())
+    });
+    assert_eq!(result, Ok(()));
+}
+
+/// A pass that reports an error fails the compilation.
+fn test_error(args: &[String]) {
+    let result = run_mir_pass!(args, |item, body| {
+        for block in &body.blocks {
+            if let TerminatorKind::Call { .. } = block.terminator.kind
+                && item.name() == "input::calls_panic"
+            {
+                Diagnostic::error("calls are forbidden")
+                    .with_span(block.terminator.span)
+                    .with_span_help(item.span(), "in this function")
+                    .emit();
+            }
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    assert_eq!(result, Err(CompilerError::Failed));
+}
+
+/// Breaking out of the pass stops the compilation right away.
+fn test_break(args: &[String]) {
+    let mut visited = 0;
+    let result = run_mir_pass!(args, |_, _| {
+        visited += 1;
+        ControlFlow::Break(visited)
+    });
+    assert_eq!(result, Err(CompilerError::Interrupted(1)));
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will run the compiler with a pass over every body.
+fn main() {
+    let path = "mir_pass_input.rs";
+    generate_input(&path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--emit=metadata".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    test_visit_all(args);
+    test_warning(args);
+    test_error(args);
+    test_break(args);
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub const LIMIT: usize = 10;
+
+    pub fn calls_panic() {{
+        panic!("oops");
+    }}
+
+    pub fn no_panic(x: usize) -> bool {{
+        x < LIMIT
+    }}
+
+    pub fn with_closure() -> usize {{
+        let f = |x: usize| x + 1;
+        f(LIMIT)
+    }}
+    "#
+    )?;
+    Ok(())
+}
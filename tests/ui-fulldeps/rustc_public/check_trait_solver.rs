@@ -0,0 +1,156 @@
+//@ run-pass
+//! Test that users are able to ask the trait solver about implementations, normalize types, and
+//! resolve trait method calls in a generic context.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+
+extern crate rustc_middle;
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+#[macro_use]
+extern crate rustc_public;
+
+use rustc_public::mir::mono::{Instance, InstanceKind};
+use rustc_public::mir::{Operand, TerminatorKind};
+use rustc_public::ty::{
+    AliasKind, FnDef, GenericArgKind, GenericArgs, ParamEnv, RigidTy, TraitDef, Ty, TyKind, UintTy,
+};
+use rustc_public::CrateDef;
+use std::assert_matches::assert_matches;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_trait_solver() -> ControlFlow<()> {
+    let shape = find_trait(rustc_public::local_crate().trait_decls(), "Shape");
+    let no_args = GenericArgs(vec![]);
+
+    let concrete = find_fn("concrete");
+    let square = concrete.fn_sig().skip_binder().inputs()[0];
+    assert!(square.implements_trait(shape, &no_args, ParamEnv::Empty));
+    assert!(!Ty::bool_ty().implements_trait(shape, &no_args, ParamEnv::Empty));
+    assert!(square.is_send(ParamEnv::Empty));
+    assert!(square.is_freeze(ParamEnv::Empty));
+
+    let not_freeze = find_fn("not_freeze").fn_sig().skip_binder().inputs()[0];
+    assert!(not_freeze.is_send(ParamEnv::Empty));
+    assert!(!not_freeze.is_freeze(ParamEnv::Empty));
+
+    // The bounds of a generic function are only known in its own environment.
+    let generic = find_fn("generic");
+    let param = generic.fn_sig().skip_binder().inputs()[0];
+    assert_matches!(param.kind(), TyKind::Param(_));
+    assert!(param.implements_trait(shape, &no_args, ParamEnv::of(generic)));
+    assert!(param.is_send(ParamEnv::of(generic)));
+    assert!(!param.is_freeze(ParamEnv::of(generic)));
+
+    // Projections on concrete types can be normalized, but not the ones on generic parameters.
+    let area = find_fn("area_of").fn_sig().skip_binder().output();
+    assert_matches!(area.kind(), TyKind::Alias(AliasKind::Projection, _));
+    let normalized = area.normalize(ParamEnv::Empty).unwrap();
+    assert_eq!(normalized.kind().rigid(), Some(&RigidTy::Uint(UintTy::U64)));
+    let generic_area = generic.fn_sig().skip_binder().output();
+    assert_matches!(
+        generic_area.normalize(ParamEnv::of(generic)).unwrap().kind(),
+        TyKind::Alias(AliasKind::Projection, _)
+    );
+
+    // A call to a trait method can only be resolved once the self type is known.
+    let (method, args) = called_fn(generic);
+    assert!(Instance::resolve_in(method, &args, ParamEnv::of(generic)).is_err());
+    let args = GenericArgs(vec![GenericArgKind::Type(square)]);
+    let instance = Instance::resolve_in(method, &args, ParamEnv::Empty).unwrap();
+    assert_eq!(instance.kind, InstanceKind::Item);
+    assert!(instance.has_body());
+    let (method, args) = called_fn(concrete);
+    assert_eq!(Instance::resolve_in(method, &args, ParamEnv::Empty).unwrap(), instance);
+    assert_eq!(Instance::resolve(method, &args).unwrap(), instance);
+
+    ControlFlow::Continue(())
+}
+
+fn find_trait(traits: Vec<TraitDef>, name: &str) -> TraitDef {
+    traits.into_iter().find(|t| t.trimmed_name() == name).unwrap()
+}
+
+fn find_fn(name: &str) -> FnDef {
+    let fns = rustc_public::local_crate().fn_defs();
+    fns.into_iter().find(|f| f.trimmed_name() == name).unwrap()
+}
+
+/// Find the function that is called by the first call in the body of `def`.
+fn called_fn(def: FnDef) -> (FnDef, GenericArgs) {
+    let body = def.body().unwrap();
+    body.blocks
+        .iter()
+        .find_map(|block| match &block.terminator.kind {
+            TerminatorKind::Call { func: Operand::Constant(func), .. } => {
+                let (def, args) = func.ty().kind().fn_def().map(|(d, a)| (d, a.clone()))?;
+                Some((def, args))
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `RustcPublic` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "trait_solver_input.rs";
+    generate_input(&path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run!(args, test_trait_solver).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        use std::cell::Cell;
+
+        pub trait Shape {{
+            type Area;
+            fn area(&self) -> Self::Area;
+        }}
+
+        pub struct Square(pub u32);
+
+        impl Shape for Square {{
+            type Area = u64;
+            fn area(&self) -> u64 {{ (self.0 as u64).pow(2) }}
+        }}
+
+        pub fn generic<T: Shape + Send>(shape: T) -> T::Area {{
+            shape.area()
+        }}
+
+        pub fn concrete(square: Square) -> u64 {{
+            square.area()
+        }}
+
+        pub fn area_of(square: Square) -> <Square as Shape>::Area {{
+            concrete(square)
+        }}
+
+        pub fn not_freeze(_cell: Cell<u8>) {{}}
+    "#
+    )?;
+    Ok(())
+}
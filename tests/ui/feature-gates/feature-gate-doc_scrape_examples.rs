@@ -0,0 +1,2 @@
+#[doc(scrape_examples = "pin")] //~ ERROR: `#[doc(scrape_examples)]` is experimental
+fn main() {}
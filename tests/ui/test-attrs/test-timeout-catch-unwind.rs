@@ -0,0 +1,27 @@
+//@ no-prefer-dynamic
+//@ compile-flags: --test
+//@ run-flags: --test-threads=1 -Zunstable-options --test-timeout 600
+//@ run-pass
+//@ check-run-results
+//@ exec-env:RUST_BACKTRACE=0
+//@ normalize-stdout: "finished in \d+\.\d+s" -> "finished in $$TIME"
+//@ needs-subprocess
+
+// Tests that the tests run in their own process by `--test-timeout` can catch their own panics.
+
+#![cfg(test)]
+
+use std::panic;
+
+#[test]
+fn catches_panic() {
+    let result = panic::catch_unwind(|| panic!("caught"));
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "not caught")]
+fn panics() {
+    let _ = panic::catch_unwind(|| panic!("caught"));
+    panic!("not caught");
+}
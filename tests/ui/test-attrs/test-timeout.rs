@@ -0,0 +1,27 @@
+//@ no-prefer-dynamic
+//@ compile-flags: --test
+//@ run-flags: --test-threads=1 -Zunstable-options --test-timeout 0.5
+//@ run-fail
+//@ check-run-results
+//@ exec-env:RUST_BACKTRACE=0
+//@ normalize-stdout: "finished in \d+\.\d+s" -> "finished in $$TIME"
+//@ needs-subprocess
+
+// Tests that `--test-timeout` kills a test that doesn't finish in time, keeps what it printed, and
+// reports it as failed while the other tests still run.
+
+#![cfg(test)]
+
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn finishes() {}
+
+#[test]
+fn loops_forever() {
+    println!("looping");
+    loop {
+        thread::sleep(Duration::from_millis(10));
+    }
+}